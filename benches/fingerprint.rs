@@ -0,0 +1,44 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use frame_header::{EncodingFlag, Endianness, FrameHeader};
+
+// A second of 10M headers would take too long per sample, so measure a
+// batch and let criterion report headers per second.
+const HEADERS: usize = 100_000;
+
+fn headers() -> Vec<FrameHeader> {
+    (0..HEADERS as u64)
+        .map(|i| {
+            FrameHeader::new(
+                EncodingFlag::Opus,
+                960,
+                48000,
+                2,
+                16,
+                Endianness::LittleEndian,
+                Some(i),
+                Some(i * 20_000),
+            )
+            .unwrap()
+        })
+        .collect()
+}
+
+fn fingerprints(c: &mut Criterion) {
+    let headers = headers();
+    let mut group = c.benchmark_group("stream_fingerprint");
+    group.throughput(Throughput::Elements(HEADERS as u64));
+    group.bench_function("fnv1a", |b| {
+        b.iter(|| {
+            black_box(&headers)
+                .iter()
+                .fold(0, |hash, header| hash ^ header.config_fingerprint())
+        })
+    });
+    group.bench_function("xxh3", |b| {
+        b.iter(|| FrameHeader::bulk_fingerprint(black_box(&headers)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, fingerprints);
+criterion_main!(benches);
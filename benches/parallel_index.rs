@@ -0,0 +1,50 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use frame_header::{EncodingFlag, Endianness, FrameHeaderV2, FrameWriter, IndexFile};
+use std::io::Cursor;
+
+// Large enough that the sequential scan is dominated by the stream itself
+// rather than by setup, as in a recording loaded into memory.
+const STREAM_BYTES: usize = 512 * 1024 * 1024;
+const PAYLOAD_BYTES: usize = 3840;
+const CHUNK_BYTES: usize = 4 * 1024 * 1024;
+
+fn stream() -> Vec<u8> {
+    let payload = vec![0x5A; PAYLOAD_BYTES];
+    let mut writer = FrameWriter::new(Vec::with_capacity(STREAM_BYTES + PAYLOAD_BYTES));
+    let mut index = 0;
+    while writer.get_ref().len() < STREAM_BYTES {
+        let header = FrameHeaderV2::new(
+            EncodingFlag::PCMSigned,
+            PAYLOAD_BYTES as u32,
+            960,
+            48000,
+            2,
+            16,
+            Endianness::LittleEndian,
+            Some(index),
+            Some(index * 20_000),
+            None,
+        )
+        .unwrap();
+        writer.write_frame(&header, &payload).unwrap();
+        index += 1;
+    }
+    writer.into_inner()
+}
+
+fn index(c: &mut Criterion) {
+    let stream = stream();
+    let mut group = c.benchmark_group("index");
+    group.sample_size(10);
+    group.throughput(Throughput::Bytes(stream.len() as u64));
+    group.bench_function("sequential", |b| {
+        b.iter(|| IndexFile::build_from_seekable_stream(Cursor::new(black_box(&stream[..]))))
+    });
+    group.bench_function("parallel", |b| {
+        b.iter(|| IndexFile::build_parallel(black_box(&stream), CHUNK_BYTES))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, index);
+criterion_main!(benches);
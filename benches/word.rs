@@ -0,0 +1,106 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use frame_header::{EncodingFlag, Endianness, FrameHeader, RawHeaderWord};
+
+const WORDS: usize = 65_536;
+
+// Half valid headers and half random words, shuffled, as a classifier sees
+// on a busy UDP port.
+fn workload() -> Vec<u32> {
+    let mut state = 0x2545_F491_4F6C_DD1Du64;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    let encodings = [
+        EncodingFlag::PCMSigned,
+        EncodingFlag::PCMFloat,
+        EncodingFlag::Opus,
+        EncodingFlag::FLAC,
+        EncodingFlag::AAC,
+    ];
+    (0..WORDS)
+        .map(|_| {
+            let random = next();
+            if random & 1 == 0 {
+                return (random >> 32) as u32;
+            }
+            let header = FrameHeader::new(
+                encodings[(random >> 8) as usize % encodings.len()],
+                (random >> 16) as u16 & 0x3FF,
+                [16000, 44100, 48000, 96000][(random >> 32) as usize % 4],
+                (random >> 40) as u8 % 16 + 1,
+                [16, 24, 32][(random >> 48) as usize % 3],
+                Endianness::LittleEndian,
+                None,
+                None,
+            )
+            .unwrap();
+            u32::from_be_bytes(RawHeaderWord::from(&header).0)
+        })
+        .collect()
+}
+
+// The field-by-field checks `validate_header` made before `validate_word`.
+// Its sample rate and channel checks can never fail and are left out.
+fn validate_branching(word: u32) -> bool {
+    if word >> 26 != 0x2A {
+        return false;
+    }
+    if (word >> 17) & 0x7 > 5 {
+        return false;
+    }
+    (word >> 22) & 0x3 <= 2
+}
+
+fn validate(c: &mut Criterion) {
+    let words = workload();
+    let mut group = c.benchmark_group("validate_word");
+    group.throughput(Throughput::Elements(WORDS as u64));
+    group.bench_function("branching", |b| {
+        b.iter(|| {
+            black_box(&words)
+                .iter()
+                .filter(|&&word| validate_branching(word))
+                .count()
+        })
+    });
+    group.bench_function("validate_word", |b| {
+        b.iter(|| {
+            black_box(&words)
+                .iter()
+                .filter(|&&word| FrameHeader::validate_word(word))
+                .count()
+        })
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("classify_word");
+    group.throughput(Throughput::Elements(WORDS as u64));
+    group.bench_function("decode", |b| {
+        b.iter(|| {
+            black_box(&words)
+                .iter()
+                .filter_map(|&word| {
+                    let base = word & !(0x3 << 20);
+                    FrameHeader::decode(&mut &base.to_be_bytes()[..]).ok()
+                })
+                .map(|header| header.sample_rate())
+                .sum::<u32>()
+        })
+    });
+    group.bench_function("classify_word", |b| {
+        b.iter(|| {
+            black_box(&words)
+                .iter()
+                .filter_map(|&word| FrameHeader::classify_word(word))
+                .map(|fields| fields.sample_rate)
+                .sum::<u32>()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, validate);
+criterion_main!(benches);
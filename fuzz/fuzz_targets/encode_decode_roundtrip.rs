@@ -0,0 +1,7 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    frame_header::fuzz_targets::fuzz_encode_decode_roundtrip(data);
+});
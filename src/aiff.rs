@@ -0,0 +1,202 @@
+use crate::{EncodingFlag, Endianness, FrameHeader, FrameHeaderError};
+
+const EXTENDED_BIAS: i32 = 16383;
+
+/// Converts to the 80-bit IEEE 754 extended format AIFF uses for sample
+/// rates, big-endian. Every `f64`, including subnormals, is exact in it.
+pub fn f64_to_extended(f: f64) -> [u8; 10] {
+    let bits = f.to_bits();
+    let sign = ((bits >> 63) as u16) << 15;
+    let exponent = ((bits >> 52) & 0x7FF) as i32;
+    let fraction = bits & ((1 << 52) - 1);
+
+    let (exponent, mantissa) = match (exponent, fraction) {
+        (0, 0) => (0, 0),
+        (0x7FF, _) => (0x7FFF, 1 << 63 | fraction << 11),
+        // Subnormal: normalize, since the extended format has the range.
+        (0, _) => {
+            let shift = fraction.leading_zeros();
+            (EXTENDED_BIAS - 1011 - shift as i32, fraction << shift)
+        }
+        _ => (exponent - 1023 + EXTENDED_BIAS, 1 << 63 | fraction << 11),
+    };
+
+    let mut bytes = [0u8; 10];
+    bytes[..2].copy_from_slice(&(sign | exponent as u16).to_be_bytes());
+    bytes[2..].copy_from_slice(&mantissa.to_be_bytes());
+    bytes
+}
+
+/// Converts from 80-bit extended, rounding to the nearest `f64`. Values out
+/// of its range become infinity or zero.
+pub fn extended_to_f64(b: &[u8; 10]) -> f64 {
+    let sign_exponent = u16::from_be_bytes([b[0], b[1]]);
+    let exponent = (sign_exponent & 0x7FFF) as i32;
+    let mantissa = u64::from_be_bytes(b[2..].try_into().unwrap());
+    let sign = if sign_exponent & 0x8000 != 0 {
+        -1.0
+    } else {
+        1.0
+    };
+
+    if exponent == 0x7FFF {
+        return if mantissa << 1 == 0 {
+            sign * f64::INFINITY
+        } else {
+            f64::NAN
+        };
+    }
+    // The mantissa has an explicit integer bit, so it is an integer scaled
+    // by 2^(exponent - bias - 63).
+    sign * scale(mantissa as f64, exponent - EXTENDED_BIAS - 63)
+}
+
+// x * 2^n without overflowing the intermediate power of two.
+fn scale(mut x: f64, mut n: i32) -> f64 {
+    while n > 1000 {
+        x *= 2f64.powi(1000);
+        n -= 1000;
+    }
+    while n < -1000 {
+        x *= 2f64.powi(-1000);
+        n += 1000;
+    }
+    x * 2f64.powi(n)
+}
+
+impl FrameHeader {
+    /// The AIFF `COMM` chunk for this header's format. AIFF holds signed
+    /// big-endian PCM only; other encodings need AIFF-C.
+    pub fn to_aiff_comm_chunk(&self, total_frames: u32) -> Result<Vec<u8>, FrameHeaderError> {
+        if self.encoding != EncodingFlag::PCMSigned {
+            return Err(FrameHeaderError::InvalidEncoding(
+                self.encoding.code() as u32
+            ));
+        }
+        if self.endianness != Endianness::BigEndian {
+            return Err(FrameHeaderError::FieldOutOfRange("endianness"));
+        }
+
+        let mut chunk = Vec::with_capacity(26);
+        chunk.extend_from_slice(b"COMM");
+        chunk.extend_from_slice(&18u32.to_be_bytes());
+        chunk.extend_from_slice(&(self.channels as u16).to_be_bytes());
+        chunk.extend_from_slice(&total_frames.to_be_bytes());
+        chunk.extend_from_slice(&(self.bits_per_sample as u16).to_be_bytes());
+        chunk.extend_from_slice(&f64_to_extended(self.sample_rate as f64));
+        Ok(chunk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Sample rates as they appear in the COMM chunks of AIFF files.
+    const RATES: [(f64, [u8; 10]); 6] = [
+        (8000.0, [0x40, 0x0B, 0xFA, 0, 0, 0, 0, 0, 0, 0]),
+        (16000.0, [0x40, 0x0C, 0xFA, 0, 0, 0, 0, 0, 0, 0]),
+        (22050.0, [0x40, 0x0D, 0xAC, 0x44, 0, 0, 0, 0, 0, 0]),
+        (44100.0, [0x40, 0x0E, 0xAC, 0x44, 0, 0, 0, 0, 0, 0]),
+        (48000.0, [0x40, 0x0E, 0xBB, 0x80, 0, 0, 0, 0, 0, 0]),
+        (96000.0, [0x40, 0x0F, 0xBB, 0x80, 0, 0, 0, 0, 0, 0]),
+    ];
+
+    #[test]
+    fn test_extended_sample_rates() {
+        for (rate, bytes) in RATES {
+            assert_eq!(f64_to_extended(rate), bytes, "{}", rate);
+            assert_eq!(extended_to_f64(&bytes), rate);
+        }
+    }
+
+    #[test]
+    fn test_extended_roundtrip() {
+        for value in [
+            0.0,
+            -0.0,
+            1.0,
+            -2.5,
+            0.1,
+            f64::MAX,
+            f64::MIN_POSITIVE,
+            f64::MIN_POSITIVE / 3.0,
+            f64::from_bits(1),
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+        ] {
+            let extended = f64_to_extended(value);
+            assert_eq!(
+                extended_to_f64(&extended).to_bits(),
+                value.to_bits(),
+                "{}",
+                value
+            );
+        }
+        assert_eq!(
+            f64_to_extended(1.0),
+            [0x3F, 0xFF, 0x80, 0, 0, 0, 0, 0, 0, 0]
+        );
+        assert_eq!(f64_to_extended(-0.0)[0], 0x80);
+        assert!(extended_to_f64(&f64_to_extended(f64::NAN)).is_nan());
+
+        // Beyond the f64 range.
+        assert_eq!(
+            extended_to_f64(&[0x7F, 0xFE, 0x80, 0, 0, 0, 0, 0, 0, 0]),
+            f64::INFINITY
+        );
+        assert_eq!(extended_to_f64(&[0, 1, 0x80, 0, 0, 0, 0, 0, 0, 0]), 0.0);
+    }
+
+    #[test]
+    fn test_aiff_comm_chunk() {
+        let header = FrameHeader::new(
+            EncodingFlag::PCMSigned,
+            1024,
+            44100,
+            2,
+            16,
+            Endianness::BigEndian,
+            None,
+            None,
+        )
+        .unwrap();
+        // COMM chunk of a one-second 16-bit stereo 44.1kHz AIFF.
+        let expected = [
+            b'C', b'O', b'M', b'M', 0x00, 0x00, 0x00, 0x12, 0x00, 0x02, 0x00, 0x00, 0xAC, 0x44,
+            0x00, 0x10, 0x40, 0x0E, 0xAC, 0x44, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ];
+        assert_eq!(header.to_aiff_comm_chunk(44100).unwrap(), expected);
+
+        let little = FrameHeader::new(
+            EncodingFlag::PCMSigned,
+            1024,
+            48000,
+            1,
+            24,
+            Endianness::LittleEndian,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            little.to_aiff_comm_chunk(0),
+            Err(FrameHeaderError::FieldOutOfRange("endianness"))
+        );
+        let float = FrameHeader::new(
+            EncodingFlag::PCMFloat,
+            1024,
+            48000,
+            1,
+            32,
+            Endianness::BigEndian,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            float.to_aiff_comm_chunk(0),
+            Err(FrameHeaderError::InvalidEncoding(1))
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use crate::{ChannelCount, EncodingFlag, Endianness, FrameHeader, FrameHeaderError, SampleRate};
+use Endianness::{BigEndian, LittleEndian};
+
+// ALSA `snd_pcm_format_name` names by (encoding, bits, endianness). Headers
+// treat 24-bit samples as packed into 3 bytes, which is what `bytes_per_sample`
+// and the other payload helpers assume, so 24 bits maps to `S24_3LE`/`S24_3BE`.
+// ALSA's `S24_LE`/`S24_BE` put 24 bits in a 4-byte container and are rejected.
+const ALSA_FORMATS: [(EncodingFlag, u8, Endianness, &str); 8] = [
+    (EncodingFlag::PCMSigned, 16, LittleEndian, "S16_LE"),
+    (EncodingFlag::PCMSigned, 16, BigEndian, "S16_BE"),
+    (EncodingFlag::PCMSigned, 24, LittleEndian, "S24_3LE"),
+    (EncodingFlag::PCMSigned, 24, BigEndian, "S24_3BE"),
+    (EncodingFlag::PCMSigned, 32, LittleEndian, "S32_LE"),
+    (EncodingFlag::PCMSigned, 32, BigEndian, "S32_BE"),
+    (EncodingFlag::PCMFloat, 32, LittleEndian, "FLOAT_LE"),
+    (EncodingFlag::PCMFloat, 32, BigEndian, "FLOAT_BE"),
+];
+
+impl FrameHeader {
+    /// Accepts the format names `to_alsa_format` produces. `S24_LE` and
+    /// `S24_BE` (24 bits in 4 bytes) are rejected, as headers describe 24-bit
+    /// samples as 3 bytes each.
+    pub fn from_alsa_format(
+        name: &str,
+        rate: u32,
+        channels: u8,
+        sample_size: u16,
+    ) -> Result<FrameHeader, FrameHeaderError> {
+        let (encoding, bits_per_sample, endianness, _) = ALSA_FORMATS
+            .iter()
+            .find(|(.., format)| *format == name)
+            .ok_or(FrameHeaderError::FieldOutOfRange("format"))?;
+        Ok(FrameHeader::new_typed(
+            *encoding,
+            sample_size,
+            SampleRate::try_from(rate)?,
+            ChannelCount::try_from(channels)?,
+            *bits_per_sample,
+            *endianness,
+            None,
+            None,
+        )?)
+    }
+
+    pub fn to_alsa_format(&self) -> Result<&'static str, FrameHeaderError> {
+        match self.encoding {
+            EncodingFlag::PCMSigned | EncodingFlag::PCMFloat => ALSA_FORMATS
+                .iter()
+                .find(|(encoding, bits, endianness, _)| {
+                    (*encoding, *bits, *endianness)
+                        == (self.encoding, self.bits_per_sample, self.endianness)
+                })
+                .map(|(.., format)| *format)
+                .ok_or(FrameHeaderError::FieldOutOfRange("bits_per_sample")),
+            encoding => Err(FrameHeaderError::InvalidEncoding(encoding.code() as u32)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use EncodingFlag::{PCMFloat, PCMSigned};
+
+    #[test]
+    fn test_alsa_formats() {
+        for (name, encoding, bits, endianness, bytes_per_sample) in [
+            ("S16_LE", PCMSigned, 16, LittleEndian, 2),
+            ("S16_BE", PCMSigned, 16, BigEndian, 2),
+            ("S24_3LE", PCMSigned, 24, LittleEndian, 3),
+            ("S24_3BE", PCMSigned, 24, BigEndian, 3),
+            ("S32_LE", PCMSigned, 32, LittleEndian, 4),
+            ("S32_BE", PCMSigned, 32, BigEndian, 4),
+            ("FLOAT_LE", PCMFloat, 32, LittleEndian, 4),
+            ("FLOAT_BE", PCMFloat, 32, BigEndian, 4),
+        ] {
+            let header = FrameHeader::from_alsa_format(name, 48000, 2, 480).unwrap();
+            assert_eq!(header.encoding(), encoding, "{}", name);
+            assert_eq!(header.bits_per_sample(), bits);
+            assert_eq!(header.endianness(), endianness);
+            assert_eq!(header.bytes_per_sample(), bytes_per_sample);
+            assert_eq!(
+                header.total_pcm_frame_bytes(),
+                480 * 2 * bytes_per_sample as u32
+            );
+            assert_eq!(header.to_alsa_format(), Ok(name));
+        }
+    }
+
+    #[test]
+    fn test_alsa_rejects_unsupported_formats() {
+        for name in ["S24_LE", "S24_BE", "U8", "S8", "FLOAT64_LE", "s16_le", ""] {
+            assert_eq!(
+                FrameHeader::from_alsa_format(name, 48000, 2, 480),
+                Err(FrameHeaderError::FieldOutOfRange("format")),
+                "{}",
+                name
+            );
+        }
+        assert!(FrameHeader::from_alsa_format("S16_LE", 22050, 2, 480).is_err());
+
+        let header = |encoding, bits| {
+            FrameHeader::new(encoding, 480, 48000, 2, bits, LittleEndian, None, None).unwrap()
+        };
+        assert_eq!(
+            header(PCMFloat, 16).to_alsa_format(),
+            Err(FrameHeaderError::FieldOutOfRange("bits_per_sample"))
+        );
+        assert_eq!(
+            header(EncodingFlag::AAC, 16).to_alsa_format(),
+            Err(FrameHeaderError::InvalidEncoding(4))
+        );
+    }
+}
@@ -0,0 +1,230 @@
+use crate::{DecodeError, FrameHeader};
+use std::future::poll_fn;
+use std::io;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+use tokio::io::{AsyncRead, ReadBuf};
+
+// Base word plus ID and PTS.
+const MAX_HEADER_SIZE: usize = 20;
+
+/// Incremental v1 header decoder for async readers.
+///
+/// The bytes of a partly read header live in the decoder, not in the future
+/// reading them, so `decode` is cancel safe: dropping it (for example when it
+/// loses a `tokio::select!` race) loses no input, and the next call carries
+/// on where it stopped. Reuse the same decoder for the same reader.
+///
+/// Errors carry the header's position as a `DecodeError`. The decoder only
+/// sees header bytes, so call `advance` for payloads read past it.
+#[derive(Debug, Default, Clone)]
+pub struct AsyncHeaderDecoder {
+    buf: [u8; MAX_HEADER_SIZE],
+    filled: usize,
+    frames: u64,
+    position: u64,
+}
+
+impl AsyncHeaderDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_position(position: u64) -> Self {
+        AsyncHeaderDecoder {
+            position,
+            ..Self::default()
+        }
+    }
+
+    /// Counts bytes read from the stream outside the decoder.
+    pub fn advance(&mut self, bytes: u64) {
+        self.position += bytes;
+    }
+
+    /// Offset of the header being decoded, or of the next one.
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Bytes of the current header read so far.
+    pub fn buffered(&self) -> usize {
+        self.filled
+    }
+
+    // Bytes needed for the whole header, known once the base word is in.
+    fn needed(&self) -> usize {
+        if self.filled < 4 {
+            return 4;
+        }
+        let word = u32::from_be_bytes(self.buf[..4].try_into().unwrap());
+        4 + 8 * ((word & FrameHeader::ID_MASK != 0) as usize)
+            + 8 * ((word & FrameHeader::PTS_MASK != 0) as usize)
+    }
+
+    pub fn poll_decode<R: AsyncRead + ?Sized>(
+        &mut self,
+        cx: &mut Context<'_>,
+        reader: Pin<&mut R>,
+    ) -> Poll<io::Result<FrameHeader>> {
+        let result = ready!(self.poll_header(cx, reader));
+        Poll::Ready(match result {
+            Ok(header) => {
+                self.position += header.size() as u64;
+                self.frames += 1;
+                Ok(header)
+            }
+            Err(err) => Err(DecodeError::wrap(err, self.frames, self.position, None)),
+        })
+    }
+
+    fn poll_header<R: AsyncRead + ?Sized>(
+        &mut self,
+        cx: &mut Context<'_>,
+        mut reader: Pin<&mut R>,
+    ) -> Poll<io::Result<FrameHeader>> {
+        loop {
+            let needed = self.needed();
+            if self.filled == needed {
+                self.filled = 0;
+                return Poll::Ready(FrameHeader::decode(&mut &self.buf[..needed]));
+            }
+
+            let mut read_buf = ReadBuf::new(&mut self.buf[self.filled..needed]);
+            ready!(reader.as_mut().poll_read(cx, &mut read_buf))?;
+            let read = read_buf.filled().len();
+            if read == 0 {
+                return Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    format!("Stream ended {} bytes into a header", self.filled),
+                )));
+            }
+            self.filled += read;
+            // Reject a bad base word before waiting for the fields it announces.
+            if self.filled == 4 {
+                if let Err(err) = FrameHeader::validate_header_with_reason(&self.buf[..4]) {
+                    self.filled = 0;
+                    return Poll::Ready(Err(err.into()));
+                }
+            }
+        }
+    }
+
+    pub async fn decode<R: AsyncRead + Unpin + ?Sized>(
+        &mut self,
+        reader: &mut R,
+    ) -> io::Result<FrameHeader> {
+        poll_fn(|cx| self.poll_decode(cx, Pin::new(&mut *reader))).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EncodingFlag, Endianness};
+    use std::time::Duration;
+    use tokio::io::AsyncWriteExt;
+
+    fn headers() -> Vec<FrameHeader> {
+        [
+            (None, None),
+            (Some(7), None),
+            (None, Some(20_000)),
+            (Some(8), Some(40_000)),
+        ]
+        .into_iter()
+        .map(|(id, pts)| {
+            FrameHeader::new(
+                EncodingFlag::Opus,
+                960,
+                48000,
+                2,
+                16,
+                Endianness::LittleEndian,
+                id,
+                pts,
+            )
+            .unwrap()
+        })
+        .collect()
+    }
+
+    #[tokio::test]
+    async fn test_cancel_at_every_byte_boundary() {
+        let headers = headers();
+        let mut bytes = Vec::new();
+        for header in &headers {
+            header.encode(&mut bytes).unwrap();
+        }
+
+        let (mut writer, mut reader) = tokio::io::duplex(64);
+        let mut decoder = AsyncHeaderDecoder::new();
+        let mut decoded = Vec::new();
+        let mut cancelled = 0;
+        for byte in bytes {
+            writer.write_all(&[byte]).await.unwrap();
+            // Each byte completes at most one header; otherwise the decode
+            // future times out waiting for the next byte and is dropped.
+            tokio::select! {
+                biased;
+                header = decoder.decode(&mut reader) => decoded.push(header.unwrap()),
+                _ = tokio::time::sleep(Duration::from_millis(1)) => cancelled += 1,
+            }
+        }
+        assert_eq!(decoded, headers);
+        assert_eq!(cancelled, 4 + 12 + 12 + 20 - headers.len());
+        assert_eq!(decoder.buffered(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_errors_carry_header_positions() {
+        let mut bytes = Vec::new();
+        for header in headers() {
+            header.encode(&mut bytes).unwrap();
+            bytes.extend_from_slice(&[0; 10]);
+        }
+        // Bits per sample code 3 in the third header.
+        bytes[4 + 10 + 12 + 10 + 1] |= 0xC0;
+
+        let mut decoder = AsyncHeaderDecoder::with_position(100);
+        let mut reader = &bytes[..];
+        for _ in 0..2 {
+            decoder.decode(&mut reader).await.unwrap();
+            reader = &reader[10..];
+            decoder.advance(10);
+        }
+        let err = decoder.decode(&mut reader).await.unwrap_err();
+        let position = DecodeError::find(&err).unwrap();
+        assert_eq!((position.frame, position.offset), (2, 136));
+        assert_eq!(
+            err.to_string(),
+            "frame 2 at offset 0x88: bits_per_sample: Invalid bits per sample code: 3"
+        );
+        assert_eq!(decoder.position(), 136);
+    }
+
+    #[tokio::test]
+    async fn test_decode_errors() {
+        let mut decoder = AsyncHeaderDecoder::new();
+        let mut truncated = &[0xA8, 0x13][..];
+        let err = decoder.decode(&mut truncated).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+
+        let mut invalid = &[0u8; 4][..];
+        let err = AsyncHeaderDecoder::new()
+            .decode(&mut invalid)
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        // An ID flag with the stream ending inside the ID.
+        let mut bytes = Vec::new();
+        headers()[1].encode(&mut bytes).unwrap();
+        let mut short = &bytes[..9];
+        let err = AsyncHeaderDecoder::new()
+            .decode(&mut short)
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+}
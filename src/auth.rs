@@ -0,0 +1,302 @@
+use crate::hmac_auth::{keyed_mac, HmacSha256};
+use crate::{packet_crc32, Extension, FrameHeaderV2};
+use hmac::Mac;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuthError {
+    InvalidHeader(String),
+    MissingTag,
+    TagMismatch,
+}
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuthError::InvalidHeader(message) => write!(f, "Invalid v2 header: {}", message),
+            AuthError::MissingTag => write!(f, "v2 header has no auth tag"),
+            AuthError::TagMismatch => write!(f, "v2 auth tag verification failed"),
+        }
+    }
+}
+
+impl std::error::Error for AuthError {}
+
+/// What `FrameReader::with_auth_key` does with a frame that fails
+/// verification, including one without a tag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthPolicy {
+    /// Consume the frame and return an `InvalidData` error wrapping the
+    /// `AuthError`.
+    Reject,
+    /// Return the frame marked corrupt, so it is concealed.
+    Mark,
+}
+
+// The HMAC of one header copy, with its tag and any CRC zeroed, followed by
+// the payload. The CRC covers the tag, so it is set after signing.
+fn mac(copy: &[u8], tag_offset: usize, has_crc: bool, payload: &[u8], key: &[u8]) -> HmacSha256 {
+    let mut bytes = copy.to_vec();
+    bytes[tag_offset..tag_offset + FrameHeaderV2::AUTH_TAG_BYTES].fill(0);
+    if has_crc {
+        let len = bytes.len();
+        bytes[len - 4..].fill(0);
+    }
+    let mut mac = keyed_mac(key);
+    mac.update(&bytes);
+    mac.update(payload);
+    mac
+}
+
+impl FrameHeaderV2 {
+    /// Signs the header at the start of `frame_bytes` with a 16-byte
+    /// truncated HMAC-SHA256 over the header and `payload`, adding the tag
+    /// field if the header has none; a payload after the header moves with
+    /// it. A packet CRC is recomputed to cover the tag.
+    ///
+    /// As with any MAC, a recorded frame verifies again when replayed.
+    pub fn sign(frame_bytes: &mut Vec<u8>, payload: &[u8], key: &[u8]) -> Result<(), AuthError> {
+        let invalid = AuthError::InvalidHeader;
+        let header = Self::decode(&mut &frame_bytes[..]).map_err(|err| invalid(err.to_string()))?;
+        if header.auth_tag.is_none() {
+            let header = header.with_auth_tag(Some([0; 16]));
+            let mut encoded = Vec::with_capacity(header.size());
+            header
+                .encode(&mut encoded)
+                .map_err(|err| invalid(err.to_string()))?;
+            frame_bytes.splice(..Self::header_size(frame_bytes).map_err(invalid)?, encoded);
+        }
+
+        let tag_offset = Self::extension_offset(frame_bytes, Extension::AuthTag)
+            .map_err(invalid)?
+            .ok_or(AuthError::MissingTag)?;
+        let has_crc = Self::has_packet_crc32(frame_bytes).map_err(invalid)?;
+        let copy_size = Self::header_copy_size(frame_bytes).map_err(invalid)?;
+        let header_size = Self::header_size(frame_bytes).map_err(invalid)?;
+
+        let tag = mac(&frame_bytes[..copy_size], tag_offset, has_crc, payload, key)
+            .finalize()
+            .into_bytes();
+        frame_bytes[tag_offset..tag_offset + Self::AUTH_TAG_BYTES]
+            .copy_from_slice(&tag[..Self::AUTH_TAG_BYTES]);
+        if has_crc {
+            let crc = packet_crc32(&frame_bytes[..copy_size - 4], payload);
+            frame_bytes[copy_size - 4..copy_size].copy_from_slice(&crc.to_be_bytes());
+        }
+        if header_size > copy_size {
+            frame_bytes.copy_within(..copy_size, copy_size);
+        }
+        Ok(())
+    }
+
+    /// Checks the auth tag of the encoded header at the start of
+    /// `header_bytes` against `payload` in constant time. A redundant header
+    /// is checked from its first copy, so one recovered from its second
+    /// fails.
+    pub fn verify(header_bytes: &[u8], payload: &[u8], key: &[u8]) -> Result<(), AuthError> {
+        let invalid = AuthError::InvalidHeader;
+        let tag_offset = Self::extension_offset(header_bytes, Extension::AuthTag)
+            .map_err(invalid)?
+            .ok_or(AuthError::MissingTag)?;
+        let has_crc = Self::has_packet_crc32(header_bytes).map_err(invalid)?;
+        let copy_size = Self::header_copy_size(header_bytes).map_err(invalid)?;
+        mac(
+            &header_bytes[..copy_size],
+            tag_offset,
+            has_crc,
+            payload,
+            key,
+        )
+        .verify_truncated_left(&header_bytes[tag_offset..tag_offset + Self::AUTH_TAG_BYTES])
+        .map_err(|_| AuthError::TagMismatch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EncodingFlag, Endianness, FrameReader, FrameWriter};
+    use std::io;
+
+    const KEY: &[u8] = b"relay key";
+
+    fn opus(payload: &[u8]) -> FrameHeaderV2 {
+        FrameHeaderV2::new(
+            EncodingFlag::Opus,
+            payload.len() as u32,
+            960,
+            48000,
+            2,
+            0,
+            Endianness::LittleEndian,
+            Some(9),
+            Some(48_000),
+            None,
+        )
+        .unwrap()
+    }
+
+    fn signed(header: &FrameHeaderV2, payload: &[u8]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        header.encode(&mut bytes).unwrap();
+        FrameHeaderV2::sign(&mut bytes, payload, KEY).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_sign_verify_roundtrip() {
+        let payload = [7u8; 40];
+        let plain = opus(&payload);
+        let crc = plain.clone().with_packet_crc32(&payload).unwrap();
+        let redundant = crc.clone().with_redundant_copy(true);
+        for header in [plain, crc, redundant] {
+            let bytes = signed(&header, &payload);
+            // A tag adds the extension word as well as its own 16 bytes.
+            let tagged = header.clone().with_auth_tag(Some([0; 16]));
+            assert_eq!(bytes.len(), tagged.size());
+            assert_eq!(FrameHeaderV2::verify(&bytes, &payload, KEY), Ok(()));
+
+            let decoded = FrameHeaderV2::decode(&mut &bytes[..]).unwrap();
+            assert_eq!(
+                decoded.auth_tag(),
+                FrameHeaderV2::extract_auth_tag(&bytes).unwrap()
+            );
+            let mut expected = header.clone().with_auth_tag(decoded.auth_tag());
+            if expected.packet_crc32_value().is_some() {
+                expected = expected.with_packet_crc32(&payload).unwrap();
+            }
+            assert_eq!(decoded, expected);
+            if header.packet_crc32_value().is_some() {
+                assert_eq!(decoded.verify_packet_crc32(&bytes, &payload), Ok(true));
+            }
+            let mut encoded = Vec::new();
+            decoded.encode(&mut encoded).unwrap();
+            assert_eq!(encoded, bytes);
+
+            // Signing again replaces the tag in place.
+            let mut resigned = bytes.clone();
+            FrameHeaderV2::sign(&mut resigned, &payload, KEY).unwrap();
+            assert_eq!(resigned, bytes);
+        }
+
+        // A payload after the header moves with it.
+        let header = opus(&payload);
+        let mut frame = Vec::new();
+        header.encode(&mut frame).unwrap();
+        frame.extend_from_slice(&payload);
+        FrameHeaderV2::sign(&mut frame, &payload, KEY).unwrap();
+        let header_size = FrameHeaderV2::header_size(&frame).unwrap();
+        assert_eq!(frame[header_size..], payload);
+        assert_eq!(FrameHeaderV2::verify(&frame, &payload, KEY), Ok(()));
+    }
+
+    #[test]
+    fn test_verify_rejects_tampering() {
+        let payload = [7u8; 40];
+        let header = opus(&payload).with_packet_crc32(&payload).unwrap();
+        let bytes = signed(&header, &payload);
+
+        let mut tampered = payload;
+        tampered[39] ^= 1;
+        assert_eq!(
+            FrameHeaderV2::verify(&bytes, &tampered, KEY),
+            Err(AuthError::TagMismatch)
+        );
+        assert_eq!(
+            FrameHeaderV2::verify(&bytes, &payload, b"other key"),
+            Err(AuthError::TagMismatch)
+        );
+
+        // A changed PTS, whether or not the CRC is fixed up to match.
+        let pts_offset = FrameHeaderV2::pts_offset(&bytes).unwrap().unwrap();
+        let mut modified = bytes.clone();
+        modified[pts_offset + 7] ^= 1;
+        assert_eq!(
+            FrameHeaderV2::verify(&modified, &payload, KEY),
+            Err(AuthError::TagMismatch)
+        );
+        let copy_size = modified.len();
+        let crc = packet_crc32(&modified[..copy_size - 4], &payload);
+        modified[copy_size - 4..].copy_from_slice(&crc.to_be_bytes());
+        assert_eq!(
+            FrameHeaderV2::verify(&modified, &payload, KEY),
+            Err(AuthError::TagMismatch)
+        );
+
+        // The tag itself, and the CRC, which the tag does not cover.
+        let tag_offset = copy_size - 4 - FrameHeaderV2::AUTH_TAG_BYTES;
+        let mut modified = bytes.clone();
+        modified[tag_offset] ^= 1;
+        assert_eq!(
+            FrameHeaderV2::verify(&modified, &payload, KEY),
+            Err(AuthError::TagMismatch)
+        );
+        let mut modified = bytes.clone();
+        modified[copy_size - 1] ^= 1;
+        assert_eq!(FrameHeaderV2::verify(&modified, &payload, KEY), Ok(()));
+        let decoded = FrameHeaderV2::decode(&mut &modified[..]).unwrap();
+        assert_eq!(decoded.verify_packet_crc32(&modified, &payload), Ok(false));
+
+        let mut unsigned = Vec::new();
+        header.encode(&mut unsigned).unwrap();
+        assert_eq!(
+            FrameHeaderV2::verify(&unsigned, &payload, KEY),
+            Err(AuthError::MissingTag)
+        );
+        assert!(matches!(
+            FrameHeaderV2::verify(&[0xFF; 8], &payload, KEY),
+            Err(AuthError::InvalidHeader(_))
+        ));
+    }
+
+    fn stream(key: &[u8]) -> Vec<u8> {
+        let mut writer = FrameWriter::new(Vec::new());
+        for index in 0..3u8 {
+            let payload = [index; 12];
+            let mut frame = Vec::new();
+            opus(&payload).encode(&mut frame).unwrap();
+            if index != 2 {
+                FrameHeaderV2::sign(&mut frame, &payload, key).unwrap();
+            }
+            let header = FrameHeaderV2::decode(&mut &frame[..]).unwrap();
+            writer.write_frame(&header, &payload).unwrap();
+        }
+        writer.into_inner()
+    }
+
+    #[test]
+    fn test_reader_auth_policies() {
+        // The last frame is unsigned.
+        let bytes = stream(KEY);
+        let frames: Vec<_> = FrameReader::new(&bytes[..])
+            .with_auth_key(KEY, AuthPolicy::Reject)
+            .collect();
+        assert_eq!(frames.len(), 3);
+        assert!(frames[0].is_ok() && frames[1].is_ok());
+        let err = frames[2].as_ref().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("no auth tag"));
+
+        let mut reader = FrameReader::new(&bytes[..]).with_auth_key(b"other key", AuthPolicy::Mark);
+        let corrupt: Vec<_> = reader
+            .by_ref()
+            .map(|frame| frame.unwrap().0.is_payload_corrupt())
+            .collect();
+        assert_eq!(corrupt, [true, true, true]);
+        assert_eq!(reader.auth_failures(), 3);
+
+        let mut reader = FrameReader::new(&bytes[..]).with_auth_key(KEY, AuthPolicy::Mark);
+        let corrupt: Vec<_> = reader
+            .by_ref()
+            .map(|frame| frame.unwrap().0.is_payload_corrupt())
+            .collect();
+        assert_eq!(corrupt, [false, false, true]);
+        assert_eq!(reader.auth_failures(), 1);
+
+        // Without a key, tags are carried but not checked.
+        let headers: Vec<_> = FrameReader::new(&bytes[..])
+            .map(|frame| frame.unwrap().0.auth_tag().is_some())
+            .collect();
+        assert_eq!(headers, [true, true, false]);
+    }
+}
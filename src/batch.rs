@@ -0,0 +1,168 @@
+use crate::FrameHeader;
+use std::io::{self, Read, Write};
+
+impl FrameHeader {
+    /// Largest header count a batch may announce.
+    pub const MAX_BATCH_SIZE: usize = 65536;
+
+    /// Reads a batch: a big-endian u32 header count, then each header as a
+    /// record prefixed with its big-endian u16 length. A record must hold
+    /// exactly one header.
+    pub fn decode_size_prefixed_batch<R: Read>(reader: &mut R) -> io::Result<Vec<FrameHeader>> {
+        let mut count = [0u8; 4];
+        reader.read_exact(&mut count)?;
+        let count = u32::from_be_bytes(count) as usize;
+        if count > Self::MAX_BATCH_SIZE {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "Batch of {} headers exceeds maximum ({})",
+                    count,
+                    Self::MAX_BATCH_SIZE
+                ),
+            ));
+        }
+
+        // The count is untrusted until the records arrive, so grow as they do.
+        let mut headers = Vec::with_capacity(count.min(1024));
+        let mut record = [0u8; 20];
+        for _ in 0..count {
+            let mut len = [0u8; 2];
+            reader.read_exact(&mut len)?;
+            let len = u16::from_be_bytes(len) as usize;
+            if len > record.len() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Record of {} bytes is larger than any header", len),
+                ));
+            }
+            reader.read_exact(&mut record[..len])?;
+
+            let mut bytes = &record[..len];
+            let header = FrameHeader::decode(&mut bytes)?;
+            if !bytes.is_empty() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "Record has bytes after its header",
+                ));
+            }
+            headers.push(header);
+        }
+        Ok(headers)
+    }
+
+    pub fn encode_size_prefixed_batch<W: Write>(
+        headers: &[FrameHeader],
+        writer: &mut W,
+    ) -> io::Result<()> {
+        if headers.len() > Self::MAX_BATCH_SIZE {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "Batch of {} headers exceeds maximum ({})",
+                    headers.len(),
+                    Self::MAX_BATCH_SIZE
+                ),
+            ));
+        }
+
+        writer.write_all(&(headers.len() as u32).to_be_bytes())?;
+        let mut record = Vec::with_capacity(22);
+        for header in headers {
+            record.clear();
+            record.extend_from_slice(&(header.size() as u16).to_be_bytes());
+            header.encode(&mut record)?;
+            writer.write_all(&record)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EncodingFlag, Endianness};
+
+    fn batch() -> (Vec<FrameHeader>, Vec<u8>) {
+        let headers: Vec<_> = (0..6u64)
+            .map(|i| {
+                FrameHeader::new(
+                    EncodingFlag::Opus,
+                    960,
+                    48000,
+                    2,
+                    16,
+                    Endianness::LittleEndian,
+                    (i % 2 == 1).then_some(i),
+                    (i % 3 != 0).then_some(i * 20_000),
+                )
+                .unwrap()
+            })
+            .collect();
+        let mut bytes = Vec::new();
+        FrameHeader::encode_size_prefixed_batch(&headers, &mut bytes).unwrap();
+        (headers, bytes)
+    }
+
+    #[test]
+    fn test_batch_roundtrip() {
+        let (headers, bytes) = batch();
+        let expected_len = 4 + headers.iter().map(|h| 2 + h.size()).sum::<usize>();
+        assert_eq!(bytes.len(), expected_len);
+        assert_eq!(
+            FrameHeader::decode_size_prefixed_batch(&mut &bytes[..]).unwrap(),
+            headers
+        );
+
+        let mut empty = Vec::new();
+        FrameHeader::encode_size_prefixed_batch(&[], &mut empty).unwrap();
+        assert_eq!(empty, [0, 0, 0, 0]);
+        assert!(FrameHeader::decode_size_prefixed_batch(&mut &empty[..])
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_batch_rejects_malicious_lengths() {
+        let huge = (FrameHeader::MAX_BATCH_SIZE as u32 + 1).to_be_bytes();
+        let err = FrameHeader::decode_size_prefixed_batch(&mut &huge[..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        // A count far above the records that follow fails on the missing data.
+        let mut bytes = (FrameHeader::MAX_BATCH_SIZE as u32).to_be_bytes().to_vec();
+        bytes.extend_from_slice(&batch().1[4..]);
+        let err = FrameHeader::decode_size_prefixed_batch(&mut &bytes[..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+
+        let mut bytes = vec![0, 0, 0, 1, 0xFF, 0xFF];
+        bytes.extend_from_slice(&[0xAA; 64]);
+        assert!(FrameHeader::decode_size_prefixed_batch(&mut &bytes[..]).is_err());
+    }
+
+    #[test]
+    fn test_batch_partial_and_corrupted_input() {
+        let (_, bytes) = batch();
+        for len in 0..bytes.len() {
+            assert!(
+                FrameHeader::decode_size_prefixed_batch(&mut &bytes[..len]).is_err(),
+                "truncated to {}",
+                len
+            );
+        }
+
+        let mut state = 0x9E37_79B9u32;
+        for _ in 0..2000 {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            let mut corrupted = bytes.clone();
+            corrupted[state as usize % bytes.len()] ^= (state >> 24) as u8 | 1;
+            if let Ok(decoded) = FrameHeader::decode_size_prefixed_batch(&mut &corrupted[..]) {
+                // Whatever still decodes must be exactly what was read.
+                let mut reencoded = Vec::new();
+                FrameHeader::encode_size_prefixed_batch(&decoded, &mut reencoded).unwrap();
+                assert_eq!(reencoded, corrupted[..reencoded.len()]);
+            }
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use crate::{FrameHeader, FrameHeaderError};
+use binrw::io::{Read, Seek, SeekFrom, Write};
+use binrw::meta::{EndianKind, ReadEndian, WriteEndian};
+use binrw::{BinRead, BinResult, BinWrite, Endian};
+
+// The header words are always big-endian; `Endianness` describes the
+// payload samples, so the endian binrw passes in is ignored.
+impl ReadEndian for FrameHeader {
+    const ENDIAN: EndianKind = EndianKind::None;
+}
+
+impl WriteEndian for FrameHeader {
+    const ENDIAN: EndianKind = EndianKind::None;
+}
+
+impl BinRead for FrameHeader {
+    type Args<'a> = ();
+
+    fn read_options<R: Read + Seek>(
+        reader: &mut R,
+        _endian: Endian,
+        _args: Self::Args<'_>,
+    ) -> BinResult<Self> {
+        let pos = reader.stream_position()?;
+        let result = read_header(reader, pos);
+        if result.is_err() {
+            // Rewind like derived readers do, so alternatives can be tried.
+            reader.seek(SeekFrom::Start(pos))?;
+        }
+        result
+    }
+}
+
+fn read_header<R: Read>(reader: &mut R, pos: u64) -> BinResult<FrameHeader> {
+    let mut bytes = [0u8; 20];
+    reader.read_exact(&mut bytes[..4])?;
+
+    match FrameHeader::validate_header_with_reason(&bytes[..4]) {
+        Ok(()) => {}
+        Err(FrameHeaderError::InvalidMagic(magic)) => {
+            return Err(binrw::Error::BadMagic {
+                pos,
+                found: Box::new(magic),
+            })
+        }
+        Err(err) => {
+            return Err(binrw::Error::Custom {
+                pos,
+                err: Box::new(err),
+            })
+        }
+    }
+
+    let size = FrameHeader::encoded_size(&bytes[..4]).expect("four bytes were read");
+    reader.read_exact(&mut bytes[4..size])?;
+    FrameHeader::decode(&mut &bytes[..size]).map_err(|err| binrw::Error::Custom {
+        pos,
+        err: Box::new(FrameHeaderError::from(err)),
+    })
+}
+
+impl BinWrite for FrameHeader {
+    type Args<'a> = ();
+
+    fn write_options<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        _endian: Endian,
+        _args: Self::Args<'_>,
+    ) -> BinResult<()> {
+        let mut bytes = Vec::with_capacity(self.size());
+        self.encode(&mut bytes)?;
+        writer.write_all(&bytes)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EncodingFlag, Endianness};
+    use binrw::io::Cursor;
+    use binrw::{binrw, BinReaderExt, BinWriterExt};
+
+    #[binrw]
+    #[brw(big, magic = b"PKT")]
+    #[derive(Debug, PartialEq)]
+    struct Packet {
+        stream_id: u16,
+        header: FrameHeader,
+        #[bw(calc = payload.len() as u32)]
+        payload_len: u32,
+        #[br(count = payload_len)]
+        payload: Vec<u8>,
+    }
+
+    fn header(id: Option<u64>, pts: Option<u64>) -> FrameHeader {
+        FrameHeader::new(
+            EncodingFlag::Opus,
+            960,
+            48000,
+            2,
+            16,
+            Endianness::LittleEndian,
+            id,
+            pts,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_binrw_matches_encode() {
+        for header in [
+            header(None, None),
+            header(Some(42), None),
+            header(None, Some(20_000)),
+            header(Some(u64::MAX), Some(1)),
+        ] {
+            let mut golden = Vec::new();
+            header.encode(&mut golden).unwrap();
+
+            let mut cursor = Cursor::new(Vec::new());
+            cursor.write_be(&header).unwrap();
+            assert_eq!(cursor.get_ref(), &golden);
+
+            // The wire layout does not depend on the endian binrw asks for.
+            let mut cursor = Cursor::new(Vec::new());
+            cursor.write_le(&header).unwrap();
+            assert_eq!(cursor.get_ref(), &golden);
+
+            let mut cursor = Cursor::new(&golden);
+            assert_eq!(cursor.read_le::<FrameHeader>().unwrap(), header);
+            assert_eq!(cursor.position(), golden.len() as u64);
+        }
+    }
+
+    #[test]
+    fn test_embedded_in_derived_struct() {
+        let packet = Packet {
+            stream_id: 7,
+            header: header(Some(3), Some(60_000)),
+            payload: vec![1, 2, 3, 4],
+        };
+
+        let mut cursor = Cursor::new(Vec::new());
+        packet.write(&mut cursor).unwrap();
+        let bytes = cursor.into_inner();
+        assert_eq!(&bytes[..3], b"PKT");
+        assert_eq!(bytes.len(), 3 + 2 + 20 + 4 + 4);
+
+        let decoded = Packet::read(&mut Cursor::new(&bytes)).unwrap();
+        assert_eq!(decoded, packet);
+    }
+
+    #[test]
+    fn test_errors_carry_stream_position() {
+        let mut golden = Vec::new();
+        header(None, Some(1)).encode(&mut golden).unwrap();
+
+        let mut bytes = vec![0xFF; 5];
+        bytes.extend_from_slice(&[0, 0, 0, 0]);
+        let mut cursor = Cursor::new(&bytes);
+        cursor.set_position(5);
+        match cursor.read_be::<FrameHeader>() {
+            Err(binrw::Error::BadMagic { pos, .. }) => assert_eq!(pos, 5),
+            other => panic!("expected BadMagic, got {:?}", other),
+        }
+        assert_eq!(cursor.position(), 5);
+
+        let mut cursor = Cursor::new(&golden[..golden.len() - 1]);
+        assert!(matches!(
+            cursor.read_be::<FrameHeader>(),
+            Err(binrw::Error::Io(err)) if err.kind() == std::io::ErrorKind::UnexpectedEof
+        ));
+        assert_eq!(cursor.position(), 0);
+
+        let mut bad_bits = golden.clone();
+        bad_bits[1] |= 0xC0;
+        match Cursor::new(&bad_bits).read_be::<FrameHeader>() {
+            Err(err) => {
+                assert_eq!(
+                    err.custom_err::<FrameHeaderError>(),
+                    Some(&FrameHeaderError::InvalidBitsCode(3))
+                );
+            }
+            Ok(header) => panic!("expected an error, got {:?}", header),
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use crate::{ChannelCount, EncodingFlag, Endianness, FrameHeader, SampleRate};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameHeaderBuilder {
+    encoding: EncodingFlag,
+    sample_size: u16,
+    sample_rate: u32,
+    channels: u8,
+    bits_per_sample: u8,
+    endianness: Endianness,
+    id: Option<u64>,
+    pts: Option<u64>,
+}
+
+impl Default for FrameHeaderBuilder {
+    fn default() -> Self {
+        FrameHeaderBuilder {
+            encoding: EncodingFlag::PCMSigned,
+            sample_size: 0,
+            sample_rate: 48000,
+            channels: 1,
+            bits_per_sample: 16,
+            endianness: Endianness::LittleEndian,
+            id: None,
+            pts: None,
+        }
+    }
+}
+
+impl FrameHeaderBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn encoding(mut self, encoding: EncodingFlag) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Same as `samples_per_channel`.
+    pub fn sample_size(mut self, sample_size: u16) -> Self {
+        self.sample_size = sample_size;
+        self
+    }
+
+    pub fn samples_per_channel(mut self, samples_per_channel: u16) -> Self {
+        self.sample_size = samples_per_channel;
+        self
+    }
+
+    pub fn sample_rate(mut self, sample_rate: u32) -> Self {
+        self.sample_rate = sample_rate;
+        self
+    }
+
+    pub fn typed_sample_rate(mut self, sample_rate: SampleRate) -> Self {
+        self.sample_rate = sample_rate.get();
+        self
+    }
+
+    pub fn channels(mut self, channels: u8) -> Self {
+        self.channels = channels;
+        self
+    }
+
+    pub fn typed_channels(mut self, channels: ChannelCount) -> Self {
+        self.channels = channels.get();
+        self
+    }
+
+    pub fn bits_per_sample(mut self, bits_per_sample: u8) -> Self {
+        self.bits_per_sample = bits_per_sample;
+        self
+    }
+
+    pub fn endianness(mut self, endianness: Endianness) -> Self {
+        self.endianness = endianness;
+        self
+    }
+
+    pub fn id(mut self, id: Option<u64>) -> Self {
+        self.id = id;
+        self
+    }
+
+    pub fn pts(mut self, pts: Option<u64>) -> Self {
+        self.pts = pts;
+        self
+    }
+
+    pub fn build(&self) -> Result<FrameHeader, String> {
+        FrameHeader::new(
+            self.encoding,
+            self.sample_size,
+            self.sample_rate,
+            self.channels,
+            self.bits_per_sample,
+            self.endianness,
+            self.id,
+            self.pts,
+        )
+    }
+}
+
+impl FrameHeader {
+    pub fn builder() -> FrameHeaderBuilder {
+        FrameHeaderBuilder::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder_matches_new() {
+        let built = FrameHeader::builder()
+            .encoding(EncodingFlag::FLAC)
+            .sample_size(4000)
+            .sample_rate(96000)
+            .channels(6)
+            .bits_per_sample(24)
+            .endianness(Endianness::BigEndian)
+            .id(Some(1))
+            .pts(Some(2))
+            .build()
+            .unwrap();
+        let expected = FrameHeader::new(
+            EncodingFlag::FLAC,
+            4000,
+            96000,
+            6,
+            24,
+            Endianness::BigEndian,
+            Some(1),
+            Some(2),
+        )
+        .unwrap();
+        assert_eq!(built, expected);
+        assert!(FrameHeader::builder().channels(0).build().is_err());
+
+        let built = FrameHeader::builder()
+            .encoding(EncodingFlag::FLAC)
+            .samples_per_channel(4000)
+            .sample_rate(96000)
+            .channels(6)
+            .bits_per_sample(24)
+            .endianness(Endianness::BigEndian)
+            .id(Some(1))
+            .pts(Some(2))
+            .build()
+            .unwrap();
+        assert_eq!(built, expected);
+    }
+}
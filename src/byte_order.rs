@@ -0,0 +1,359 @@
+use crate::{FrameHeader, FrameHeaderV2};
+use std::io::{self, Read, Write};
+
+// Byte order of the header's integer fields on the wire. `BigEndian` is the
+// standard layout used by `encode`/`decode`; `LittleEndian` stores the same
+// bit layout with each word and integer field byte-reversed.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum WireByteOrder {
+    #[default]
+    BigEndian,
+    LittleEndian,
+}
+
+impl WireByteOrder {
+    fn swap_fields(self, bytes: &mut [u8], widths: &[usize]) {
+        if self == WireByteOrder::BigEndian {
+            return;
+        }
+        let mut start = 0;
+        for &width in widths {
+            bytes[start..start + width].reverse();
+            start += width;
+        }
+    }
+}
+
+impl FrameHeader {
+    /// Encodes with the base word, ID and PTS each little-endian, for
+    /// transports that put the whole header on the wire that way. This is
+    /// the wire byte order of the header itself; the `Endianness` field is
+    /// the byte order of the payload's samples, and is carried unchanged.
+    pub fn encode_le_wire<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.encode_with(writer, WireByteOrder::LittleEndian)
+    }
+
+    /// Decodes a header written by `encode_le_wire`. The magic is checked
+    /// in the top six bits of the word read as little-endian, which arrive
+    /// in the fourth byte.
+    pub fn decode_le_wire<R: Read>(reader: &mut R) -> io::Result<Self> {
+        Self::decode_with(reader, WireByteOrder::LittleEndian)
+    }
+
+    pub fn encode_with<W: Write>(&self, writer: &mut W, order: WireByteOrder) -> io::Result<()> {
+        let mut bytes = Vec::with_capacity(self.size());
+        self.encode(&mut bytes)?;
+        let widths = Self::wire_field_widths(&bytes);
+        order.swap_fields(&mut bytes, &widths);
+        writer.write_all(&bytes)
+    }
+
+    pub fn decode_with<R: Read>(reader: &mut R, order: WireByteOrder) -> io::Result<Self> {
+        let mut bytes = [0u8; 20];
+        reader.read_exact(&mut bytes[..4])?;
+        order.swap_fields(&mut bytes, &[4]);
+        let widths = Self::wire_field_widths(&bytes);
+        let size = widths.iter().sum();
+        reader.read_exact(&mut bytes[4..size])?;
+        order.swap_fields(&mut bytes[4..], &widths[1..]);
+        Self::decode(&mut &bytes[..size])
+    }
+
+    /// Returns the byte order in which `header_bytes` decodes, or `None` if it
+    /// decodes in neither or both. Every v1 bit is meaningful, so a header whose
+    /// sample size has a low byte of 0xA8..=0xAB can be valid both ways.
+    pub fn sniff_byte_order(header_bytes: &[u8]) -> Option<WireByteOrder> {
+        sniff(|order| Self::decode_with(&mut &header_bytes[..], order).is_ok())
+    }
+
+    // Expects the base word in standard byte order.
+    fn wire_field_widths(bytes: &[u8]) -> Vec<usize> {
+        let word = u32::from_be_bytes(bytes[..4].try_into().unwrap());
+        let mut widths = vec![4];
+        if word & Self::ID_MASK != 0 {
+            widths.push(8);
+        }
+        if word & Self::PTS_MASK != 0 {
+            widths.push(8);
+        }
+        widths
+    }
+}
+
+impl FrameHeaderV2 {
+    pub fn encode_with<W: Write>(&self, writer: &mut W, order: WireByteOrder) -> io::Result<()> {
+        let mut bytes = Vec::with_capacity(self.size());
+        self.encode(&mut bytes)?;
+        let widths = Self::wire_field_widths(&bytes);
+        order.swap_fields(&mut bytes, &widths);
+        writer.write_all(&bytes)
+    }
+
+    pub fn decode_with<R: Read>(reader: &mut R, order: WireByteOrder) -> io::Result<Self> {
+        let mut bytes = [0u8; Self::MAX_SIZE];
+        let invalid = |err: String| io::Error::new(io::ErrorKind::InvalidData, err);
+        reader.read_exact(&mut bytes[..Self::BASE_SIZE])?;
+        order.swap_fields(&mut bytes, &[4, 4]);
+        // The extension word, if any, says which fields follow.
+        let prefix_size = Self::header_prefix_size(&bytes).map_err(invalid)?;
+        reader.read_exact(&mut bytes[Self::BASE_SIZE..prefix_size])?;
+        let prefix_widths = if prefix_size > Self::BASE_SIZE {
+            order.swap_fields(&mut bytes[Self::BASE_SIZE..], &[Self::EXTENSION_WORD_BYTES]);
+            3
+        } else {
+            2
+        };
+        let size = Self::header_size(&bytes).map_err(invalid)?;
+        reader.read_exact(&mut bytes[prefix_size..size])?;
+        let widths = Self::wire_field_widths(&bytes);
+        order.swap_fields(&mut bytes[prefix_size..], &widths[prefix_widths..]);
+        Self::decode(&mut &bytes[..size])
+    }
+
+    /// Returns the byte order in which `header_bytes` decodes. A v2 control
+    /// word read in the wrong order always has an invalid bits-per-sample
+    /// code, so valid headers are never ambiguous.
+    pub fn sniff_byte_order(header_bytes: &[u8]) -> Option<WireByteOrder> {
+        sniff(|order| Self::decode_with(&mut &header_bytes[..], order).is_ok())
+    }
+
+    // Expects the base header and extension word in standard byte order.
+    fn wire_field_widths(bytes: &[u8]) -> Vec<usize> {
+        let word = u32::from_be_bytes(bytes[..4].try_into().unwrap());
+        let flags = ((word & Self::FLAGS_MASK) >> Self::FLAGS_SHIFT) as u8;
+        let layout = Self::copy_layout(bytes).unwrap_or_default();
+        let mut widths = vec![4, 4];
+        if layout.extension_word {
+            widths.push(Self::EXTENSION_WORD_BYTES);
+        }
+        if layout.extended_sizes {
+            widths.extend([4, 4]);
+        }
+        if flags & Self::FLAG_ID_PRESENT != 0 {
+            widths.push(if flags & Self::FLAG_ID_U64 != 0 { 8 } else { 4 });
+        }
+        if flags & Self::FLAG_PTS_PRESENT != 0 {
+            widths.push(8);
+        }
+        widths.extend(layout.extension_widths());
+        if flags & Self::FLAG_PACKET_CRC32_PRESENT != 0 {
+            widths.push(4);
+        }
+        if (word & Self::VERSION_MASK) >> Self::VERSION_SHIFT == Self::VERSION_REDUNDANT {
+            widths.extend_from_within(..);
+        }
+        widths
+    }
+}
+
+fn sniff(decodes: impl Fn(WireByteOrder) -> bool) -> Option<WireByteOrder> {
+    match (
+        decodes(WireByteOrder::BigEndian),
+        decodes(WireByteOrder::LittleEndian),
+    ) {
+        (true, false) => Some(WireByteOrder::BigEndian),
+        (false, true) => Some(WireByteOrder::LittleEndian),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EncodingFlag, Endianness};
+
+    fn encode_v1(header: &FrameHeader, order: WireByteOrder) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        header.encode_with(&mut bytes, order).unwrap();
+        bytes
+    }
+
+    fn encode_v2(header: &FrameHeaderV2, order: WireByteOrder) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        header.encode_with(&mut bytes, order).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_v1_little_endian_golden_vector() {
+        let header = FrameHeader::new(
+            EncodingFlag::Opus,
+            960,
+            48000,
+            2,
+            16,
+            Endianness::LittleEndian,
+            Some(0x0102_0304_0506_0708),
+            Some(0x1122_3344_5566_7788),
+        )
+        .unwrap();
+
+        let big = encode_v1(&header, WireByteOrder::default());
+        let mut standard = Vec::new();
+        header.encode(&mut standard).unwrap();
+        assert_eq!(big, standard);
+
+        let little = encode_v1(&header, WireByteOrder::LittleEndian);
+        assert_eq!(
+            little,
+            [
+                0xC0, 0x13, 0x34, 0xAA, // base word 0xAA3413C0
+                0x08, 0x07, 0x06, 0x05, 0x04, 0x03, 0x02, 0x01, // id
+                0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11, // pts
+            ]
+        );
+        assert_eq!(
+            FrameHeader::decode_with(&mut &little[..], WireByteOrder::LittleEndian).unwrap(),
+            header
+        );
+        assert_eq!(
+            FrameHeader::sniff_byte_order(&little),
+            Some(WireByteOrder::LittleEndian)
+        );
+        assert_eq!(
+            FrameHeader::sniff_byte_order(&big),
+            Some(WireByteOrder::BigEndian)
+        );
+    }
+
+    #[test]
+    fn test_le_wire_roundtrip() {
+        for endianness in [Endianness::LittleEndian, Endianness::BigEndian] {
+            for (id, pts) in [
+                (None, None),
+                (Some(7), None),
+                (None, Some(9)),
+                (Some(7), Some(9)),
+            ] {
+                let header = FrameHeader::new(
+                    EncodingFlag::PCMSigned,
+                    441,
+                    44100,
+                    2,
+                    24,
+                    endianness,
+                    id,
+                    pts,
+                )
+                .unwrap();
+                let mut bytes = Vec::new();
+                header.encode_le_wire(&mut bytes).unwrap();
+                assert_eq!(bytes, encode_v1(&header, WireByteOrder::LittleEndian));
+                assert_eq!(bytes[3] >> 2, 0x2A);
+                // Payload endianness is a field, not the wire order.
+                let decoded = FrameHeader::decode_le_wire(&mut &bytes[..]).unwrap();
+                assert_eq!(decoded, header);
+                assert_eq!(decoded.endianness(), endianness);
+
+                let mut standard = Vec::new();
+                header.encode(&mut standard).unwrap();
+                assert!(FrameHeader::decode_le_wire(&mut &standard[..]).is_err());
+            }
+        }
+    }
+
+    #[test]
+    fn test_v2_little_endian_golden_vector() {
+        let header = FrameHeaderV2::new(
+            EncodingFlag::Opus,
+            0x1_0000,
+            960,
+            48000,
+            2,
+            16,
+            Endianness::LittleEndian,
+            Some(0x0A0B_0C0D),
+            Some(0x1122_3344_5566_7788),
+            Some(0xDEAD_BEEF),
+        )
+        .unwrap();
+
+        let little = encode_v2(&header, WireByteOrder::LittleEndian);
+        assert_eq!(
+            little,
+            [
+                0x0A, 0x26, 0x2D, 0xAE, // control word 0xAE2D260A
+                0xFF, 0xFF, 0xFF, 0xFF, // short-size sentinels
+                0x00, 0x00, 0x01, 0x00, // payload size
+                0xC0, 0x03, 0x00, 0x00, // frame count
+                0x0D, 0x0C, 0x0B, 0x0A, // u32 id
+                0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11, // pts
+                0xEF, 0xBE, 0xAD, 0xDE, // packet crc32
+            ]
+        );
+        assert_eq!(
+            FrameHeaderV2::decode_with(&mut &little[..], WireByteOrder::LittleEndian).unwrap(),
+            header
+        );
+        assert_eq!(
+            FrameHeaderV2::sniff_byte_order(&little),
+            Some(WireByteOrder::LittleEndian)
+        );
+    }
+
+    #[test]
+    fn test_byte_orders_do_not_cross_validate() {
+        for encoding in [
+            EncodingFlag::PCMSigned,
+            EncodingFlag::PCMFloat,
+            EncodingFlag::Opus,
+            EncodingFlag::FLAC,
+            EncodingFlag::AAC,
+            EncodingFlag::H264,
+        ] {
+            for channels in 1..=16 {
+                for sample_rate in [16000, 44100, 48000, 96000] {
+                    for sample_size in (0..=FrameHeader::MAX_SAMPLE_SIZE).step_by(7) {
+                        let header = FrameHeader::new(
+                            encoding,
+                            sample_size,
+                            sample_rate,
+                            channels,
+                            24,
+                            Endianness::BigEndian,
+                            Some(7),
+                            None,
+                        )
+                        .unwrap();
+                        // Only the documented sample sizes may decode both ways.
+                        if (0xA8..=0xAB).contains(&(sample_size & 0xFF)) {
+                            continue;
+                        }
+                        for order in [WireByteOrder::BigEndian, WireByteOrder::LittleEndian] {
+                            let bytes = encode_v1(&header, order);
+                            assert_eq!(FrameHeader::sniff_byte_order(&bytes), Some(order));
+                        }
+                    }
+                }
+
+                for bits_per_sample in [8, 16, 24, 32, 64] {
+                    for sample_rate in FrameHeaderV2::VALID_SAMPLE_RATES {
+                        let header = FrameHeaderV2::new(
+                            encoding,
+                            300,
+                            960,
+                            sample_rate,
+                            channels,
+                            bits_per_sample,
+                            Endianness::LittleEndian,
+                            Some(u64::MAX),
+                            Some(1),
+                            None,
+                        )
+                        .unwrap();
+                        for header in [header.clone(), header.with_user_data(Some(0x0102_0304))] {
+                            for order in [WireByteOrder::BigEndian, WireByteOrder::LittleEndian] {
+                                let bytes = encode_v2(&header, order);
+                                assert_eq!(FrameHeaderV2::sniff_byte_order(&bytes), Some(order));
+                                assert_eq!(
+                                    FrameHeaderV2::decode_with(&mut &bytes[..], order).unwrap(),
+                                    header
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
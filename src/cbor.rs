@@ -0,0 +1,322 @@
+use crate::{encoding_from_code, Endianness, FrameHeader, FrameHeaderError};
+use ciborium::value::{Integer, Value};
+use std::fmt;
+
+// Map keys, matching the proto3 field numbers.
+const KEY_ENCODING: u64 = 1;
+const KEY_SAMPLES_PER_CHANNEL: u64 = 2;
+const KEY_SAMPLE_RATE: u64 = 3;
+const KEY_CHANNELS: u64 = 4;
+const KEY_BITS_PER_SAMPLE: u64 = 5;
+const KEY_ENDIANNESS: u64 = 6;
+const KEY_ID: u64 = 7;
+const KEY_PTS: u64 = 8;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CborError {
+    /// Not a single well-formed CBOR item.
+    Malformed(String),
+    NotAMap,
+    MissingField(&'static str),
+    /// A known key whose value is not an unsigned integer in range.
+    InvalidField(&'static str),
+    Header(FrameHeaderError),
+}
+
+impl fmt::Display for CborError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CborError::Malformed(message) => write!(f, "Malformed CBOR: {}", message),
+            CborError::NotAMap => write!(f, "CBOR header is not a map"),
+            CborError::MissingField(field) => write!(f, "CBOR header is missing {}", field),
+            CborError::InvalidField(field) => write!(f, "Invalid CBOR header field: {}", field),
+            CborError::Header(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for CborError {}
+
+fn field_name(key: u64) -> Option<&'static str> {
+    Some(match key {
+        KEY_ENCODING => "encoding",
+        KEY_SAMPLES_PER_CHANNEL => "samples_per_channel",
+        KEY_SAMPLE_RATE => "sample_rate",
+        KEY_CHANNELS => "channels",
+        KEY_BITS_PER_SAMPLE => "bits_per_sample",
+        KEY_ENDIANNESS => "endianness",
+        KEY_ID => "id",
+        KEY_PTS => "pts",
+        _ => return None,
+    })
+}
+
+impl FrameHeader {
+    /// Encodes the header as a definite-length CBOR map with unsigned
+    /// integer keys and values, in key order, each in its shortest form:
+    ///
+    /// | Key | Field | Value |
+    /// |-----|-------|-------|
+    /// | 1 | encoding | encoding code |
+    /// | 2 | samples per channel | |
+    /// | 3 | sample rate | Hz |
+    /// | 4 | channels | |
+    /// | 5 | bits per sample | |
+    /// | 6 | endianness | 0 little, 1 big |
+    /// | 7 | ID | u64, omitted when absent |
+    /// | 8 | PTS | u64, omitted when absent |
+    pub fn to_cbor(&self) -> Vec<u8> {
+        let mut fields = vec![
+            (KEY_ENCODING, self.encoding.code() as u64),
+            (KEY_SAMPLES_PER_CHANNEL, self.sample_size as u64),
+            (KEY_SAMPLE_RATE, self.sample_rate as u64),
+            (KEY_CHANNELS, self.channels as u64),
+            (KEY_BITS_PER_SAMPLE, self.bits_per_sample as u64),
+            (KEY_ENDIANNESS, self.endianness as u64),
+        ];
+        fields.extend(self.id.map(|id| (KEY_ID, id)));
+        fields.extend(self.pts.map(|pts| (KEY_PTS, pts)));
+        let map = Value::Map(
+            fields
+                .into_iter()
+                .map(|(key, value)| (Value::Integer(key.into()), Value::Integer(value.into())))
+                .collect(),
+        );
+
+        let mut out = Vec::with_capacity(40);
+        ciborium::into_writer(&map, &mut out).expect("writing to a Vec cannot fail");
+        out
+    }
+
+    /// Decodes a map written by `to_cbor`, in any key order and length
+    /// form. Keys it does not know, of any type, are ignored so newer
+    /// writers can add fields.
+    pub fn from_cbor(bytes: &[u8]) -> Result<FrameHeader, CborError> {
+        let mut rest = bytes;
+        let value: Value = ciborium::from_reader(&mut rest)
+            .map_err(|err| CborError::Malformed(err.to_string()))?;
+        if !rest.is_empty() {
+            return Err(CborError::Malformed(format!(
+                "{} trailing bytes",
+                rest.len()
+            )));
+        }
+        let Value::Map(entries) = value else {
+            return Err(CborError::NotAMap);
+        };
+
+        let mut fields: [Option<u64>; 8] = [None; 8];
+        for (key, value) in entries {
+            let Some(key) = key.as_integer().and_then(|key| u64::try_from(key).ok()) else {
+                continue;
+            };
+            let Some(name) = field_name(key) else {
+                continue;
+            };
+            let value = value
+                .as_integer()
+                .and_then(|value: Integer| u64::try_from(value).ok())
+                .ok_or(CborError::InvalidField(name))?;
+            fields[key as usize - 1] = Some(value);
+        }
+
+        let required = |key: u64| {
+            fields[key as usize - 1].ok_or(CborError::MissingField(field_name(key).unwrap()))
+        };
+        let narrow = |key: u64| {
+            let name = field_name(key).unwrap();
+            u32::try_from(required(key)?).map_err(|_| CborError::InvalidField(name))
+        };
+        let encoding_code = narrow(KEY_ENCODING)?;
+        let encoding = encoding_from_code(encoding_code).ok_or(CborError::Header(
+            FrameHeaderError::InvalidEncoding(encoding_code),
+        ))?;
+        let endianness = match required(KEY_ENDIANNESS)? {
+            0 => Endianness::LittleEndian,
+            1 => Endianness::BigEndian,
+            _ => return Err(CborError::InvalidField("endianness")),
+        };
+        FrameHeader::new(
+            encoding,
+            u16::try_from(narrow(KEY_SAMPLES_PER_CHANNEL)?)
+                .map_err(|_| CborError::InvalidField("samples_per_channel"))?,
+            narrow(KEY_SAMPLE_RATE)?,
+            u8::try_from(narrow(KEY_CHANNELS)?).map_err(|_| CborError::InvalidField("channels"))?,
+            u8::try_from(narrow(KEY_BITS_PER_SAMPLE)?)
+                .map_err(|_| CborError::InvalidField("bits_per_sample"))?,
+            endianness,
+            fields[KEY_ID as usize - 1],
+            fields[KEY_PTS as usize - 1],
+        )
+        .map_err(|err| CborError::Header(FrameHeaderError::Invalid(err)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EncodingFlag;
+
+    fn full() -> FrameHeader {
+        FrameHeader::new(
+            EncodingFlag::Opus,
+            960,
+            48000,
+            2,
+            16,
+            Endianness::LittleEndian,
+            Some(0x0102_0304_0506_0708),
+            Some(48000),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_cbor_golden_bytes() {
+        #[rustfmt::skip]
+        let full_bytes: &[u8] = &[
+            0xA8,
+            0x01, 0x02,
+            0x02, 0x19, 0x03, 0xC0,
+            0x03, 0x19, 0xBB, 0x80,
+            0x04, 0x02,
+            0x05, 0x10,
+            0x06, 0x00,
+            0x07, 0x1B, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08,
+            0x08, 0x19, 0xBB, 0x80,
+        ];
+        assert_eq!(full().to_cbor(), full_bytes);
+        assert_eq!(FrameHeader::from_cbor(full_bytes), Ok(full()));
+
+        let minimal = FrameHeader::new(
+            EncodingFlag::PCMSigned,
+            480,
+            44100,
+            1,
+            24,
+            Endianness::BigEndian,
+            None,
+            None,
+        )
+        .unwrap();
+        #[rustfmt::skip]
+        let minimal_bytes: &[u8] = &[
+            0xA6,
+            0x01, 0x00,
+            0x02, 0x19, 0x01, 0xE0,
+            0x03, 0x19, 0xAC, 0x44,
+            0x04, 0x01,
+            0x05, 0x18, 0x18,
+            0x06, 0x01,
+        ];
+        assert_eq!(minimal.to_cbor(), minimal_bytes);
+        assert_eq!(FrameHeader::from_cbor(minimal_bytes), Ok(minimal));
+
+        // Small IDs and PTS take the shortest integer form.
+        let small = FrameHeader::new(
+            EncodingFlag::FLAC,
+            1152,
+            96000,
+            16,
+            32,
+            Endianness::LittleEndian,
+            Some(23),
+            Some(0),
+        )
+        .unwrap();
+        assert_eq!(
+            small.to_cbor()[small.to_cbor().len() - 4..],
+            [0x07, 0x17, 0x08, 0x00]
+        );
+    }
+
+    #[test]
+    fn test_cbor_roundtrip_and_forward_compatibility() {
+        let header = full();
+        assert_eq!(
+            FrameHeader::from_cbor(&header.to_cbor()),
+            Ok(header.clone())
+        );
+
+        // Unknown integer and text keys, in any position, and known keys out
+        // of order.
+        let mut entries = vec![(Value::Integer(99.into()), Value::Text("future".to_string()))];
+        let Value::Map(known) = ciborium::from_reader(&header.to_cbor()[..]).unwrap() else {
+            unreachable!();
+        };
+        entries.extend(known.into_iter().rev());
+        entries.insert(
+            3,
+            (
+                Value::Text("note".to_string()),
+                Value::Array(vec![Value::Null]),
+            ),
+        );
+        let mut bytes = Vec::new();
+        ciborium::into_writer(&Value::Map(entries), &mut bytes).unwrap();
+        assert_eq!(FrameHeader::from_cbor(&bytes), Ok(header));
+    }
+
+    #[test]
+    fn test_cbor_rejects_bad_input() {
+        let bytes = full().to_cbor();
+        assert!(matches!(
+            FrameHeader::from_cbor(&bytes[..bytes.len() - 1]),
+            Err(CborError::Malformed(_))
+        ));
+        assert!(matches!(
+            FrameHeader::from_cbor(&[bytes.as_slice(), &[0x00]].concat()),
+            Err(CborError::Malformed(_))
+        ));
+        assert_eq!(FrameHeader::from_cbor(&[0x80]), Err(CborError::NotAMap));
+
+        let encode = |entries: Vec<(u64, Value)>| {
+            let map = Value::Map(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (Value::Integer(key.into()), value))
+                    .collect(),
+            );
+            let mut bytes = Vec::new();
+            ciborium::into_writer(&map, &mut bytes).unwrap();
+            FrameHeader::from_cbor(&bytes)
+        };
+        let uint = |value: u64| Value::Integer(value.into());
+        let fields = |channels: Value| {
+            vec![
+                (KEY_ENCODING, uint(2)),
+                (KEY_SAMPLES_PER_CHANNEL, uint(960)),
+                (KEY_SAMPLE_RATE, uint(48000)),
+                (KEY_CHANNELS, channels),
+                (KEY_BITS_PER_SAMPLE, uint(16)),
+                (KEY_ENDIANNESS, uint(0)),
+            ]
+        };
+        assert!(encode(fields(uint(2))).is_ok());
+        assert_eq!(
+            encode(fields(Value::Text("2".to_string()))),
+            Err(CborError::InvalidField("channels"))
+        );
+        assert_eq!(
+            encode(fields(Value::Integer((-2).into()))),
+            Err(CborError::InvalidField("channels"))
+        );
+        assert_eq!(
+            encode(fields(uint(300))),
+            Err(CborError::InvalidField("channels"))
+        );
+        assert!(matches!(
+            encode(fields(uint(17))),
+            Err(CborError::Header(_))
+        ));
+        let mut missing = fields(uint(2));
+        missing.remove(2);
+        assert_eq!(encode(missing), Err(CborError::MissingField("sample_rate")));
+        let mut unknown_encoding = fields(uint(2));
+        unknown_encoding[0].1 = uint(9);
+        assert_eq!(
+            encode(unknown_encoding),
+            Err(CborError::Header(FrameHeaderError::InvalidEncoding(9)))
+        );
+    }
+}
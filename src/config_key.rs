@@ -0,0 +1,191 @@
+use crate::{EncodingFlag, Endianness, FrameHeader};
+
+/// The stream configuration of a header: everything but ID, PTS and sample
+/// size, for use as a map key. Compressed codecs carry their own byte order,
+/// so their endianness is canonicalized to big-endian and does not split keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ConfigKey {
+    encoding: EncodingFlag,
+    sample_rate: u32,
+    channels: u8,
+    bits_per_sample: u8,
+    endianness: Endianness,
+}
+
+impl ConfigKey {
+    pub fn encoding(&self) -> EncodingFlag {
+        self.encoding
+    }
+
+    #[deprecated(
+        since = "0.3.0",
+        note = "use `encoding()`, which returns the flag by value"
+    )]
+    pub fn encoding_ref(&self) -> &EncodingFlag {
+        &self.encoding
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn channels(&self) -> u8 {
+        self.channels
+    }
+
+    pub fn bits_per_sample(&self) -> u8 {
+        self.bits_per_sample
+    }
+
+    pub fn endianness(&self) -> Endianness {
+        self.endianness
+    }
+
+    #[deprecated(since = "0.3.0", note = "use `endianness()`, which returns the value")]
+    pub fn endianness_ref(&self) -> &Endianness {
+        &self.endianness
+    }
+
+    /// 64-bit FNV-1a over the key's fields. Unlike `Hash`, the value is
+    /// stable across processes and releases, so it can be stored.
+    pub fn fingerprint(&self) -> u64 {
+        let mut hash: u64 = 0xCBF2_9CE4_8422_2325;
+        for byte in self.to_bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0000_0100_0000_01B3);
+        }
+        hash
+    }
+
+    // The bytes every fingerprint of the key is taken over.
+    pub(crate) fn to_bytes(self) -> [u8; 8] {
+        let rate = self.sample_rate.to_be_bytes();
+        [
+            self.encoding.code(),
+            rate[0],
+            rate[1],
+            rate[2],
+            rate[3],
+            self.channels,
+            self.bits_per_sample,
+            self.endianness as u8,
+        ]
+    }
+}
+
+impl FrameHeader {
+    pub fn config_key(&self) -> ConfigKey {
+        let endianness = match self.encoding {
+            EncodingFlag::Opus
+            | EncodingFlag::FLAC
+            | EncodingFlag::AAC
+            | EncodingFlag::H264
+            | EncodingFlag::Metadata => Endianness::BigEndian,
+            EncodingFlag::PCMSigned | EncodingFlag::PCMFloat | EncodingFlag::Unknown(_) => {
+                self.endianness
+            }
+        };
+        ConfigKey {
+            encoding: self.encoding,
+            sample_rate: self.sample_rate,
+            channels: self.channels,
+            bits_per_sample: self.bits_per_sample,
+            endianness,
+        }
+    }
+
+    /// `config_key().fingerprint()`.
+    pub fn config_fingerprint(&self) -> u64 {
+        self.config_key().fingerprint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn header(
+        encoding: EncodingFlag,
+        sample_size: u16,
+        endianness: Endianness,
+        id: Option<u64>,
+        pts: Option<u64>,
+    ) -> FrameHeader {
+        FrameHeader::new(encoding, sample_size, 48000, 2, 16, endianness, id, pts).unwrap()
+    }
+
+    #[test]
+    fn test_config_key_ignores_per_frame_fields() {
+        let a = header(
+            EncodingFlag::PCMSigned,
+            960,
+            Endianness::LittleEndian,
+            None,
+            None,
+        );
+        let b = header(
+            EncodingFlag::PCMSigned,
+            480,
+            Endianness::LittleEndian,
+            Some(9),
+            Some(20_000),
+        );
+        assert_eq!(a.config_key(), b.config_key());
+        assert_eq!(a.config_fingerprint(), b.config_fingerprint());
+
+        let mut frames = HashMap::new();
+        *frames.entry(a.config_key()).or_insert(0) += 1;
+        *frames.entry(b.config_key()).or_insert(0) += 1;
+        assert_eq!(frames[&a.config_key()], 2);
+
+        // Endianness only matters for PCM.
+        let opus_le = header(
+            EncodingFlag::Opus,
+            960,
+            Endianness::LittleEndian,
+            None,
+            None,
+        );
+        let opus_be = header(EncodingFlag::Opus, 960, Endianness::BigEndian, None, None);
+        assert_eq!(opus_le.config_key(), opus_be.config_key());
+    }
+
+    #[test]
+    fn test_config_key_distinguishes_config_fields() {
+        let base = header(
+            EncodingFlag::PCMSigned,
+            960,
+            Endianness::LittleEndian,
+            None,
+            None,
+        );
+        let variants = [
+            FrameHeader {
+                encoding: EncodingFlag::PCMFloat,
+                ..base.clone()
+            },
+            FrameHeader {
+                sample_rate: 44100,
+                ..base.clone()
+            },
+            FrameHeader {
+                channels: 1,
+                ..base.clone()
+            },
+            FrameHeader {
+                bits_per_sample: 24,
+                ..base.clone()
+            },
+            FrameHeader {
+                endianness: Endianness::BigEndian,
+                ..base.clone()
+            },
+        ];
+        for variant in &variants {
+            assert_ne!(variant.config_key(), base.config_key(), "{:?}", variant);
+            assert_ne!(variant.config_fingerprint(), base.config_fingerprint());
+        }
+        assert_eq!(base.config_fingerprint(), base.clone().config_fingerprint());
+    }
+}
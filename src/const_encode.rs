@@ -0,0 +1,288 @@
+use crate::{EncodingFlag, Endianness, FrameHeader};
+
+impl FrameHeader {
+    pub(crate) const fn sample_rate_code(sample_rate: u32) -> Option<u32> {
+        match sample_rate {
+            16000 => Some(0),
+            44100 => Some(1),
+            48000 => Some(2),
+            96000 => Some(3),
+            _ => None,
+        }
+    }
+
+    pub(crate) const fn bits_code(bits_per_sample: u8) -> Option<u32> {
+        match bits_per_sample {
+            16 => Some(0),
+            24 => Some(1),
+            32 => Some(2),
+            _ => None,
+        }
+    }
+
+    /// The base header word without the ID and PTS flags, from the raw
+    /// sample-rate and bits codes. Panics on invalid input, which is a
+    /// compile error when evaluated in a const or static.
+    pub const fn const_word(
+        encoding: EncodingFlag,
+        sample_size: u16,
+        sample_rate_code: u32,
+        channels: u8,
+        bits_code: u32,
+        endianness: Endianness,
+    ) -> u32 {
+        let encoding_code = encoding.code() as u32;
+        assert!(
+            encoding_code <= Self::ENCODING_MASK >> Self::ENCODING_SHIFT,
+            "encoding code does not fit the header"
+        );
+        if let EncodingFlag::Unknown(code) = encoding {
+            assert!(
+                code > 5,
+                "known encoding codes must use their named variant"
+            );
+        }
+        assert!(
+            sample_size <= Self::MAX_SAMPLE_SIZE,
+            "sample size exceeds 4095"
+        );
+        assert!(sample_rate_code <= 3, "invalid sample rate code");
+        assert!(channels >= 1 && channels <= 16, "channels must be 1-16");
+        assert!(bits_code <= 2, "invalid bits per sample code");
+
+        Self::MAGIC_WORD << Self::MAGIC_SHIFT
+            | sample_rate_code << Self::SAMPLE_RATE_SHIFT
+            | bits_code << Self::BITS_SHIFT
+            | encoding_code << Self::ENCODING_SHIFT
+            | (endianness as u32) << Self::ENDIAN_SHIFT
+            | ((channels - 1) as u32) << Self::CHANNELS_SHIFT
+            | sample_size as u32
+    }
+
+    /// Encodes a header at compile time, byte-identical to `encode`:
+    ///
+    /// ```
+    /// use frame_header::{EncodingFlag, Endianness, FrameHeader};
+    ///
+    /// static HEADER: [u8; 4] = FrameHeader::encode_const(
+    ///     EncodingFlag::Opus, 960, 48000, 2, 16, Endianness::LittleEndian, None, None,
+    /// );
+    /// ```
+    ///
+    /// `N` must be 4, plus 8 each for an ID and a PTS. Invalid input panics,
+    /// failing compilation in a const or static.
+    #[allow(clippy::too_many_arguments)]
+    pub const fn encode_const<const N: usize>(
+        encoding: EncodingFlag,
+        sample_size: u16,
+        sample_rate: u32,
+        channels: u8,
+        bits_per_sample: u8,
+        endianness: Endianness,
+        id: Option<u64>,
+        pts: Option<u64>,
+    ) -> [u8; N] {
+        let Some(sample_rate_code) = Self::sample_rate_code(sample_rate) else {
+            panic!("invalid sample rate");
+        };
+        let Some(bits_code) = Self::bits_code(bits_per_sample) else {
+            panic!("bits per sample must be 16, 24 or 32");
+        };
+        let mut word = Self::const_word(
+            encoding,
+            sample_size,
+            sample_rate_code,
+            channels,
+            bits_code,
+            endianness,
+        );
+        word |= (pts.is_some() as u32) << Self::PTS_SHIFT;
+        word |= (id.is_some() as u32) << Self::ID_SHIFT;
+        assert!(
+            N == 4 + 8 * (id.is_some() as usize + pts.is_some() as usize),
+            "array length does not match the optional fields"
+        );
+
+        let mut bytes = [0u8; N];
+        let mut offset = write_be(&mut bytes, 0, &word.to_be_bytes());
+        if let Some(id) = id {
+            offset = write_be(&mut bytes, offset, &id.to_be_bytes());
+        }
+        if let Some(pts) = pts {
+            write_be(&mut bytes, offset, &pts.to_be_bytes());
+        }
+        bytes
+    }
+}
+
+// Copies `value` into `bytes` at `offset` and returns the offset after it.
+const fn write_be(bytes: &mut [u8], offset: usize, value: &[u8]) -> usize {
+    let mut i = 0;
+    while i < value.len() {
+        bytes[offset + i] = value[i];
+        i += 1;
+    }
+    offset + value.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static OPUS_HEADER: [u8; 4] = FrameHeader::encode_const(
+        EncodingFlag::Opus,
+        960,
+        48000,
+        2,
+        16,
+        Endianness::LittleEndian,
+        None,
+        None,
+    );
+
+    const FULL_HEADER: [u8; 20] = FrameHeader::encode_const(
+        EncodingFlag::PCMFloat,
+        4095,
+        96000,
+        16,
+        32,
+        Endianness::BigEndian,
+        Some(u64::MAX),
+        Some(0x0102_0304_0506_0708),
+    );
+
+    fn runtime_encode(header: &FrameHeader) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        header.encode(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_const_encoding_matches_runtime() {
+        let opus = FrameHeader::new(
+            EncodingFlag::Opus,
+            960,
+            48000,
+            2,
+            16,
+            Endianness::LittleEndian,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(OPUS_HEADER[..], runtime_encode(&opus));
+
+        let full = FrameHeader::decode(&mut &FULL_HEADER[..]).unwrap();
+        assert_eq!(full.id(), Some(u64::MAX));
+        assert_eq!(runtime_encode(&full), FULL_HEADER);
+
+        for encoding in [
+            EncodingFlag::PCMSigned,
+            EncodingFlag::PCMFloat,
+            EncodingFlag::Opus,
+            EncodingFlag::FLAC,
+            EncodingFlag::AAC,
+            EncodingFlag::H264,
+            EncodingFlag::Unknown(7),
+        ] {
+            for (sample_rate, bits, channels, sample_size) in [
+                (16000, 16, 1, 0),
+                (44100, 24, 2, 441),
+                (48000, 32, 8, 960),
+                (96000, 16, 16, 4095),
+            ] {
+                for endianness in [Endianness::LittleEndian, Endianness::BigEndian] {
+                    let header = |id, pts| {
+                        FrameHeader::new(
+                            encoding,
+                            sample_size,
+                            sample_rate,
+                            channels,
+                            bits,
+                            endianness,
+                            id,
+                            pts,
+                        )
+                        .unwrap()
+                    };
+                    let base: [u8; 4] = FrameHeader::encode_const(
+                        encoding,
+                        sample_size,
+                        sample_rate,
+                        channels,
+                        bits,
+                        endianness,
+                        None,
+                        None,
+                    );
+                    assert_eq!(base[..], runtime_encode(&header(None, None)));
+                    let with_id: [u8; 12] = FrameHeader::encode_const(
+                        encoding,
+                        sample_size,
+                        sample_rate,
+                        channels,
+                        bits,
+                        endianness,
+                        Some(42),
+                        None,
+                    );
+                    assert_eq!(with_id[..], runtime_encode(&header(Some(42), None)));
+                    let with_pts: [u8; 12] = FrameHeader::encode_const(
+                        encoding,
+                        sample_size,
+                        sample_rate,
+                        channels,
+                        bits,
+                        endianness,
+                        None,
+                        Some(7),
+                    );
+                    assert_eq!(with_pts[..], runtime_encode(&header(None, Some(7))));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_const_word() {
+        const WORD: u32 =
+            FrameHeader::const_word(EncodingFlag::Opus, 960, 2, 2, 0, Endianness::LittleEndian);
+        assert_eq!(WORD.to_be_bytes(), OPUS_HEADER);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid sample rate")]
+    fn test_encode_const_rejects_sample_rate() {
+        let _: [u8; 4] = FrameHeader::encode_const(
+            EncodingFlag::Opus,
+            960,
+            22050,
+            2,
+            16,
+            Endianness::LittleEndian,
+            None,
+            None,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "array length does not match the optional fields")]
+    fn test_encode_const_rejects_wrong_length() {
+        let _: [u8; 4] = FrameHeader::encode_const(
+            EncodingFlag::Opus,
+            960,
+            48000,
+            2,
+            16,
+            Endianness::LittleEndian,
+            Some(1),
+            None,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "channels must be 1-16")]
+    fn test_const_word_rejects_channels() {
+        FrameHeader::const_word(EncodingFlag::Opus, 960, 2, 17, 0, Endianness::LittleEndian);
+    }
+}
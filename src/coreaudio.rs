@@ -0,0 +1,240 @@
+use crate::{ChannelCount, EncodingFlag, Endianness, FrameHeader, FrameHeaderError, SampleRate};
+
+/// `kAudioFormatLinearPCM`, `'lpcm'`.
+pub const K_AUDIO_FORMAT_LINEAR_PCM: u32 = u32::from_be_bytes(*b"lpcm");
+pub const K_AUDIO_FORMAT_FLAG_IS_FLOAT: u32 = 1 << 0;
+pub const K_AUDIO_FORMAT_FLAG_IS_BIG_ENDIAN: u32 = 1 << 1;
+pub const K_AUDIO_FORMAT_FLAG_IS_SIGNED_INTEGER: u32 = 1 << 2;
+pub const K_AUDIO_FORMAT_FLAG_IS_PACKED: u32 = 1 << 3;
+pub const K_AUDIO_FORMAT_FLAG_IS_ALIGNED_HIGH: u32 = 1 << 4;
+pub const K_AUDIO_FORMAT_FLAG_IS_NON_INTERLEAVED: u32 = 1 << 5;
+
+/// Mirror of Core Audio's `AudioStreamBasicDescription`, so it can be passed
+/// to and from the C API without depending on a bindings crate.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct AudioStreamBasicDescription {
+    pub sample_rate: f64,
+    pub format_id: u32,
+    pub format_flags: u32,
+    pub bytes_per_packet: u32,
+    pub frames_per_packet: u32,
+    pub bytes_per_frame: u32,
+    pub channels_per_frame: u32,
+    pub bits_per_channel: u32,
+    pub reserved: u32,
+}
+
+impl FrameHeader {
+    /// Packed, interleaved linear PCM.
+    pub fn to_asbd(&self) -> Result<AudioStreamBasicDescription, FrameHeaderError> {
+        let mut format_flags = match (self.encoding, self.bits_per_sample) {
+            (EncodingFlag::PCMSigned, _) => K_AUDIO_FORMAT_FLAG_IS_SIGNED_INTEGER,
+            (EncodingFlag::PCMFloat, 32) => K_AUDIO_FORMAT_FLAG_IS_FLOAT,
+            (EncodingFlag::PCMFloat, _) => {
+                return Err(FrameHeaderError::FieldOutOfRange("bits_per_sample"))
+            }
+            (encoding, _) => return Err(FrameHeaderError::InvalidEncoding(encoding.code() as u32)),
+        } | K_AUDIO_FORMAT_FLAG_IS_PACKED;
+        if self.endianness == Endianness::BigEndian {
+            format_flags |= K_AUDIO_FORMAT_FLAG_IS_BIG_ENDIAN;
+        }
+        let bytes_per_frame = self.channels as u32 * self.bits_per_sample as u32 / 8;
+        Ok(AudioStreamBasicDescription {
+            sample_rate: self.sample_rate as f64,
+            format_id: K_AUDIO_FORMAT_LINEAR_PCM,
+            format_flags,
+            bytes_per_packet: bytes_per_frame,
+            frames_per_packet: 1,
+            bytes_per_frame,
+            channels_per_frame: self.channels as u32,
+            bits_per_channel: self.bits_per_sample as u32,
+            reserved: 0,
+        })
+    }
+
+    /// Rejects what a header cannot describe: formats other than linear PCM,
+    /// unsigned integers, non-interleaved buffers, and samples that do not
+    /// fill their container (such as 24 bits in 4 bytes).
+    pub fn from_asbd(
+        asbd: &AudioStreamBasicDescription,
+        sample_size: u16,
+    ) -> Result<FrameHeader, FrameHeaderError> {
+        if asbd.format_id != K_AUDIO_FORMAT_LINEAR_PCM {
+            return Err(FrameHeaderError::InvalidEncoding(asbd.format_id));
+        }
+        let flags = asbd.format_flags;
+        if flags & K_AUDIO_FORMAT_FLAG_IS_NON_INTERLEAVED != 0 {
+            return Err(FrameHeaderError::FieldOutOfRange("format_flags"));
+        }
+        let encoding = if flags & K_AUDIO_FORMAT_FLAG_IS_FLOAT != 0 {
+            EncodingFlag::PCMFloat
+        } else if flags & K_AUDIO_FORMAT_FLAG_IS_SIGNED_INTEGER != 0 {
+            EncodingFlag::PCMSigned
+        } else {
+            return Err(FrameHeaderError::FieldOutOfRange("format_flags"));
+        };
+        let endianness = if flags & K_AUDIO_FORMAT_FLAG_IS_BIG_ENDIAN != 0 {
+            Endianness::BigEndian
+        } else {
+            Endianness::LittleEndian
+        };
+
+        let channels = u8::try_from(asbd.channels_per_frame)
+            .map_err(|_| FrameHeaderError::FieldOutOfRange("channels_per_frame"))?;
+        let bits_per_sample = u8::try_from(asbd.bits_per_channel)
+            .map_err(|_| FrameHeaderError::FieldOutOfRange("bits_per_channel"))?;
+        // Packed or not, the samples must exactly fill each frame.
+        if asbd.bytes_per_frame != channels as u32 * bits_per_sample as u32 / 8 {
+            return Err(FrameHeaderError::FieldOutOfRange("bytes_per_frame"));
+        }
+        if asbd.frames_per_packet != 1 || asbd.bytes_per_packet != asbd.bytes_per_frame {
+            return Err(FrameHeaderError::FieldOutOfRange("frames_per_packet"));
+        }
+        if asbd.sample_rate.fract() != 0.0 || !(0.0..=u32::MAX as f64).contains(&asbd.sample_rate) {
+            return Err(FrameHeaderError::FieldOutOfRange("sample_rate"));
+        }
+        if encoding == EncodingFlag::PCMFloat && bits_per_sample != 32 {
+            return Err(FrameHeaderError::FieldOutOfRange("bits_per_channel"));
+        }
+
+        Ok(FrameHeader::new_typed(
+            encoding,
+            sample_size,
+            SampleRate::try_from(asbd.sample_rate as u32)?,
+            ChannelCount::try_from(channels)?,
+            bits_per_sample,
+            endianness,
+            None,
+            None,
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use EncodingFlag::{PCMFloat, PCMSigned};
+    use Endianness::{BigEndian, LittleEndian};
+
+    const SIGNED: u32 = K_AUDIO_FORMAT_FLAG_IS_SIGNED_INTEGER;
+    const FLOAT: u32 = K_AUDIO_FORMAT_FLAG_IS_FLOAT;
+    const BIG: u32 = K_AUDIO_FORMAT_FLAG_IS_BIG_ENDIAN;
+    const PACKED: u32 = K_AUDIO_FORMAT_FLAG_IS_PACKED;
+
+    fn asbd(flags: u32, bits: u32, channels: u32) -> AudioStreamBasicDescription {
+        let bytes_per_frame = channels * bits / 8;
+        AudioStreamBasicDescription {
+            sample_rate: 48000.0,
+            format_id: K_AUDIO_FORMAT_LINEAR_PCM,
+            format_flags: flags,
+            bytes_per_packet: bytes_per_frame,
+            frames_per_packet: 1,
+            bytes_per_frame,
+            channels_per_frame: channels,
+            bits_per_channel: bits,
+            reserved: 0,
+        }
+    }
+
+    #[test]
+    fn test_asbd_flag_combinations() {
+        let cases = [
+            (SIGNED | PACKED, 16, PCMSigned, LittleEndian),
+            (SIGNED | PACKED | BIG, 16, PCMSigned, BigEndian),
+            (SIGNED | PACKED, 24, PCMSigned, LittleEndian),
+            (SIGNED | PACKED | BIG, 32, PCMSigned, BigEndian),
+            (FLOAT | PACKED, 32, PCMFloat, LittleEndian),
+            (FLOAT | PACKED | BIG, 32, PCMFloat, BigEndian),
+        ];
+        for (flags, bits, encoding, endianness) in cases {
+            for channels in [1, 2, 8] {
+                let asbd = asbd(flags, bits, channels);
+                let header = FrameHeader::from_asbd(&asbd, 512).unwrap();
+                assert_eq!(header.encoding(), encoding);
+                assert_eq!(header.endianness(), endianness);
+                assert_eq!(header.bits_per_sample() as u32, bits);
+                assert_eq!(header.channels() as u32, channels);
+                assert_eq!(header.samples_per_channel(), 512);
+                assert_eq!(header.to_asbd(), Ok(asbd));
+            }
+        }
+
+        // Without the packed flag the sizes alone show the samples are packed.
+        let header = FrameHeader::from_asbd(&asbd(FLOAT, 32, 2), 512).unwrap();
+        assert_eq!(header.to_asbd(), Ok(asbd(FLOAT | PACKED, 32, 2)));
+    }
+
+    #[test]
+    fn test_asbd_rejects_what_headers_cannot_express() {
+        let from = |asbd: AudioStreamBasicDescription| FrameHeader::from_asbd(&asbd, 512);
+
+        let non_interleaved = asbd(
+            FLOAT | PACKED | K_AUDIO_FORMAT_FLAG_IS_NON_INTERLEAVED,
+            32,
+            2,
+        );
+        assert_eq!(
+            from(non_interleaved),
+            Err(FrameHeaderError::FieldOutOfRange("format_flags"))
+        );
+        assert_eq!(
+            from(asbd(PACKED, 16, 2)),
+            Err(FrameHeaderError::FieldOutOfRange("format_flags"))
+        );
+        assert_eq!(
+            from(asbd(FLOAT | PACKED, 64, 2)),
+            Err(FrameHeaderError::FieldOutOfRange("bits_per_channel"))
+        );
+        // 24-bit samples high-aligned in 4-byte containers.
+        let unpacked = AudioStreamBasicDescription {
+            bytes_per_frame: 8,
+            bytes_per_packet: 8,
+            ..asbd(SIGNED | K_AUDIO_FORMAT_FLAG_IS_ALIGNED_HIGH, 24, 2)
+        };
+        assert_eq!(
+            from(unpacked),
+            Err(FrameHeaderError::FieldOutOfRange("bytes_per_frame"))
+        );
+        assert_eq!(
+            from(AudioStreamBasicDescription {
+                frames_per_packet: 2,
+                ..asbd(SIGNED | PACKED, 16, 2)
+            }),
+            Err(FrameHeaderError::FieldOutOfRange("frames_per_packet"))
+        );
+        assert_eq!(
+            from(AudioStreamBasicDescription {
+                sample_rate: 44100.5,
+                ..asbd(SIGNED | PACKED, 16, 2)
+            }),
+            Err(FrameHeaderError::FieldOutOfRange("sample_rate"))
+        );
+        assert!(from(AudioStreamBasicDescription {
+            sample_rate: 22050.0,
+            ..asbd(SIGNED | PACKED, 16, 2)
+        })
+        .is_err());
+        let aac = u32::from_be_bytes(*b"aac ");
+        assert_eq!(
+            from(AudioStreamBasicDescription {
+                format_id: aac,
+                ..Default::default()
+            }),
+            Err(FrameHeaderError::InvalidEncoding(aac))
+        );
+
+        let opus = FrameHeader::new(
+            EncodingFlag::Opus,
+            960,
+            48000,
+            2,
+            16,
+            LittleEndian,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(opus.to_asbd(), Err(FrameHeaderError::InvalidEncoding(2)));
+    }
+}
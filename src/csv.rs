@@ -0,0 +1,217 @@
+use crate::{EncodingFlag, Endianness, FrameHeader, FrameHeaderError};
+
+impl FrameHeader {
+    pub const CSV_HEADER: &'static str =
+        "encoding,sample_size,sample_rate,channels,bits_per_sample,endianness,id,pts";
+
+    /// One CSV row in `CSV_HEADER` column order; a missing ID or PTS is an
+    /// empty field.
+    pub fn to_csv_row(&self) -> String {
+        let optional = |value: Option<u64>| value.map(|v| v.to_string()).unwrap_or_default();
+        format!(
+            "{},{},{},{},{},{},{},{}",
+            encoding_name(&self.encoding),
+            self.sample_size,
+            self.sample_rate,
+            self.channels,
+            self.bits_per_sample,
+            match self.endianness {
+                Endianness::LittleEndian => "LE",
+                Endianness::BigEndian => "BE",
+            },
+            optional(self.id),
+            optional(self.pts),
+        )
+    }
+
+    pub fn from_csv_row(row: &str) -> Result<FrameHeader, FrameHeaderError> {
+        let fields: Vec<&str> = row.trim_end_matches(['\r', '\n']).split(',').collect();
+        let [encoding, sample_size, sample_rate, channels, bits, endianness, id, pts] = fields[..]
+        else {
+            return Err(FrameHeaderError::Invalid(format!(
+                "Expected 8 CSV fields, found {}",
+                fields.len()
+            )));
+        };
+
+        let encoding = encoding_from_name(encoding)?;
+        let endianness = match endianness {
+            "LE" => Endianness::LittleEndian,
+            "BE" => Endianness::BigEndian,
+            other => {
+                return Err(FrameHeaderError::Invalid(format!(
+                    "Unknown endianness: {}",
+                    other
+                )))
+            }
+        };
+
+        Ok(FrameHeader::new(
+            encoding,
+            number(sample_size, "sample_size")?,
+            number(sample_rate, "sample_rate")?,
+            number(channels, "channels")?,
+            number(bits, "bits_per_sample")?,
+            endianness,
+            optional_number(id, "id")?,
+            optional_number(pts, "pts")?,
+        )?)
+    }
+
+    /// `CSV_HEADER` followed by one row per header, each line ending in `\n`.
+    pub fn headers_to_csv(headers: &[FrameHeader]) -> String {
+        let mut csv = String::from(Self::CSV_HEADER);
+        csv.push('\n');
+        for header in headers {
+            csv.push_str(&header.to_csv_row());
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+fn encoding_name(encoding: &EncodingFlag) -> String {
+    match encoding {
+        EncodingFlag::PCMSigned => "pcm_signed".to_string(),
+        EncodingFlag::PCMFloat => "pcm_float".to_string(),
+        EncodingFlag::Opus => "opus".to_string(),
+        EncodingFlag::FLAC => "flac".to_string(),
+        EncodingFlag::AAC => "aac".to_string(),
+        EncodingFlag::H264 => "h264".to_string(),
+        EncodingFlag::Metadata => "metadata".to_string(),
+        EncodingFlag::Unknown(code) => code.to_string(),
+    }
+}
+
+fn encoding_from_name(name: &str) -> Result<EncodingFlag, FrameHeaderError> {
+    match name {
+        "pcm_signed" => Ok(EncodingFlag::PCMSigned),
+        "pcm_float" => Ok(EncodingFlag::PCMFloat),
+        "opus" => Ok(EncodingFlag::Opus),
+        "flac" => Ok(EncodingFlag::FLAC),
+        "aac" => Ok(EncodingFlag::AAC),
+        "h264" => Ok(EncodingFlag::H264),
+        "metadata" => Ok(EncodingFlag::Metadata),
+        other => other
+            .parse()
+            .map(EncodingFlag::Unknown)
+            .map_err(|_| FrameHeaderError::Invalid(format!("Unknown encoding: {}", other))),
+    }
+}
+
+fn number<T: std::str::FromStr>(field: &str, name: &'static str) -> Result<T, FrameHeaderError> {
+    field
+        .parse()
+        .map_err(|_| FrameHeaderError::FieldOutOfRange(name))
+}
+
+fn optional_number(field: &str, name: &'static str) -> Result<Option<u64>, FrameHeaderError> {
+    if field.is_empty() {
+        Ok(None)
+    } else {
+        number(field, name).map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_row_format() {
+        let header = FrameHeader::new(
+            EncodingFlag::Opus,
+            1024,
+            48000,
+            2,
+            16,
+            Endianness::LittleEndian,
+            Some(12345678),
+            Some(987654321),
+        )
+        .unwrap();
+        assert_eq!(
+            header.to_csv_row(),
+            "opus,1024,48000,2,16,LE,12345678,987654321"
+        );
+
+        let header = FrameHeader::new(
+            EncodingFlag::PCMFloat,
+            4000,
+            96000,
+            16,
+            32,
+            Endianness::BigEndian,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(header.to_csv_row(), "pcm_float,4000,96000,16,32,BE,,");
+        assert_eq!(
+            FrameHeader::from_csv_row("pcm_float,4000,96000,16,32,BE,,\r\n"),
+            Ok(header)
+        );
+
+        for row in [
+            "opus,1024,48000,2,16,LE,1",
+            "vorbis,1024,48000,2,16,LE,,",
+            "opus,1024,48000,2,16,XE,,",
+            "opus,-1,48000,2,16,LE,,",
+            "opus,1024,22050,2,16,LE,,",
+        ] {
+            assert!(FrameHeader::from_csv_row(row).is_err(), "{}", row);
+        }
+    }
+
+    #[test]
+    fn test_csv_roundtrip_randomized() {
+        let encodings = [
+            EncodingFlag::PCMSigned,
+            EncodingFlag::PCMFloat,
+            EncodingFlag::Opus,
+            EncodingFlag::FLAC,
+            EncodingFlag::AAC,
+            EncodingFlag::H264,
+            EncodingFlag::Metadata,
+        ];
+        let mut state = 0x2545_F491_4F6C_DD1Du64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        let mut headers = Vec::new();
+        for _ in 0..100 {
+            let header = FrameHeader::new(
+                encodings[next() as usize % encodings.len()],
+                (next() % 4096) as u16,
+                [16000, 44100, 48000, 96000][next() as usize % 4],
+                (next() % 16 + 1) as u8,
+                [16, 24, 32][next() as usize % 3],
+                if next() % 2 == 0 {
+                    Endianness::LittleEndian
+                } else {
+                    Endianness::BigEndian
+                },
+                Some(next()).filter(|_| next() % 2 == 0),
+                Some(next()).filter(|_| next() % 2 == 0),
+            )
+            .unwrap();
+            assert_eq!(
+                FrameHeader::from_csv_row(&header.to_csv_row()),
+                Ok(header.clone())
+            );
+            headers.push(header);
+        }
+
+        let csv = FrameHeader::headers_to_csv(&headers);
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some(FrameHeader::CSV_HEADER));
+        let parsed: Vec<_> = lines
+            .map(|row| FrameHeader::from_csv_row(row).unwrap())
+            .collect();
+        assert_eq!(parsed, headers);
+    }
+}
@@ -0,0 +1,574 @@
+use crate::proto3::{read_varint, write_varint};
+use crate::{
+    crc32_ieee, FrameHeaderError, FrameHeaderV2, ResyncEvent, ResyncObserver, ResyncStats, Timecode,
+};
+use std::io;
+
+// Every record is either a full v2 header or a delta against the previous
+// header, followed by a one-byte check (low byte of the record's CRC32).
+//
+// Delta records start with a tag byte 0b010xxxxx whose low bits say which
+// fields follow. Timecodes, user data, gapless trim, sample offsets, auth
+// tags and packet CRCs are sent whole after them. A full v2 header always starts with
+// 0xAC..=0xAF, so the two can never be confused.
+const DELTA_TAG: u8 = 0x40;
+const DELTA_TAG_MASK: u8 = 0xE0;
+
+const CHANGED_PAYLOAD_SIZE: u8 = 1 << 0;
+const CHANGED_FRAME_COUNT: u8 = 1 << 1;
+const CHANGED_ID: u8 = 1 << 2;
+const CHANGED_PTS_STRIDE: u8 = 1 << 3;
+const CHANGED_PACKET_FLAGS: u8 = 1 << 4;
+
+pub struct DeltaEncoder {
+    full_every: u32,
+    since_full: u32,
+    reference: Option<FrameHeaderV2>,
+    pts_stride: i64,
+}
+
+impl DeltaEncoder {
+    /// Writes a full header at least every `full_every` frames, or only when
+    /// required if `full_every` is 0.
+    pub fn new(full_every: u32) -> Self {
+        DeltaEncoder {
+            full_every,
+            since_full: 0,
+            reference: None,
+            pts_stride: 0,
+        }
+    }
+
+    /// Appends one record to `out`. Returns true if a full header was written.
+    pub fn encode(&mut self, header: &FrameHeaderV2, out: &mut Vec<u8>) -> io::Result<bool> {
+        let start = out.len();
+        let full = match &self.reference {
+            Some(reference) => {
+                !same_layout(reference, header)
+                    || (self.full_every > 0 && self.since_full + 1 >= self.full_every)
+            }
+            None => true,
+        };
+
+        if full {
+            header.encode(out)?;
+            self.since_full = 0;
+            self.pts_stride = 0;
+        } else {
+            let reference = self.reference.as_ref().unwrap();
+            out.push(0);
+            let mut tag = DELTA_TAG;
+
+            if header.payload_size != reference.payload_size {
+                tag |= CHANGED_PAYLOAD_SIZE;
+                write_varint(out, header.payload_size as u64);
+            }
+            if header.frame_count != reference.frame_count {
+                tag |= CHANGED_FRAME_COUNT;
+                write_varint(out, header.frame_count as u64);
+            }
+            if let (Some(id), Some(previous)) = (header.id, reference.id) {
+                let diff = id.wrapping_sub(previous.wrapping_add(1)) as i64;
+                if diff != 0 {
+                    tag |= CHANGED_ID;
+                    write_varint(out, zigzag(diff));
+                }
+            }
+            if let (Some(pts), Some(previous)) = (header.pts, reference.pts) {
+                let stride = pts.wrapping_sub(previous) as i64;
+                if stride != self.pts_stride {
+                    tag |= CHANGED_PTS_STRIDE;
+                    write_varint(out, zigzag(stride));
+                    self.pts_stride = stride;
+                }
+            }
+            if header.packet_flags != reference.packet_flags {
+                tag |= CHANGED_PACKET_FLAGS;
+                out.push(header.packet_flags);
+            }
+            if let Some(timecode) = header.timecode {
+                out.extend_from_slice(&timecode.to_packed().to_be_bytes());
+            }
+            if let Some(user_data) = header.user_data {
+                out.extend_from_slice(&user_data.to_be_bytes());
+            }
+            for trim in [header.encoder_delay, header.end_padding]
+                .into_iter()
+                .flatten()
+            {
+                out.extend_from_slice(&trim.to_be_bytes());
+            }
+            if let Some(sample_offset) = header.sample_offset {
+                out.extend_from_slice(&sample_offset.to_be_bytes());
+            }
+            if let Some(auth_tag) = header.auth_tag {
+                out.extend_from_slice(&auth_tag);
+            }
+            if let Some(crc) = header.packet_crc32 {
+                out.extend_from_slice(&crc.to_be_bytes());
+            }
+
+            out[start] = tag;
+            self.since_full += 1;
+        }
+
+        out.push(crc32_ieee(&out[start..]) as u8);
+        self.reference = Some(header.clone());
+        Ok(full)
+    }
+
+    /// Forces the next header to be written in full, e.g. after the caller
+    /// learns that earlier records were lost.
+    pub fn reset(&mut self) {
+        self.reference = None;
+    }
+}
+
+#[derive(Default)]
+pub struct DeltaDecoder {
+    reference: Option<FrameHeaderV2>,
+    pts_stride: i64,
+    resync_stats: ResyncStats,
+    observer: Option<Box<dyn ResyncObserver>>,
+}
+
+impl DeltaDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reports each skip `decode_stream` makes past a corrupt record.
+    pub fn with_resync_observer(mut self, observer: impl ResyncObserver + 'static) -> Self {
+        self.observer = Some(Box::new(observer));
+        self
+    }
+
+    /// Decodes the record at the start of `input`, returning the header and
+    /// the number of bytes consumed. Any error other than `Truncated` drops
+    /// the reference header, so deltas are rejected until the next full one.
+    pub fn decode(&mut self, input: &[u8]) -> Result<(FrameHeaderV2, usize), FrameHeaderError> {
+        let result = match input.first() {
+            None => Err(FrameHeaderError::Truncated),
+            Some(&tag) if tag & DELTA_TAG_MASK == DELTA_TAG => self.decode_delta(input),
+            Some(_) => decode_full(input).map(|(header, len)| {
+                self.pts_stride = 0;
+                (header, len)
+            }),
+        };
+
+        match result {
+            Ok((header, len)) => {
+                self.reference = Some(header.clone());
+                Ok((header, len))
+            }
+            Err(FrameHeaderError::Truncated) => Err(FrameHeaderError::Truncated),
+            Err(err) => {
+                self.reference = None;
+                Err(err)
+            }
+        }
+    }
+
+    /// Decodes every record in `input`. Corrupt records and any deltas that
+    /// follow them are skipped up to the next full header; a truncated
+    /// trailing record is ignored.
+    pub fn decode_stream(&mut self, input: &[u8]) -> Vec<FrameHeaderV2> {
+        let mut headers = Vec::new();
+        let mut pos = 0;
+        while pos < input.len() {
+            match self.decode(&input[pos..]) {
+                Ok((header, len)) => {
+                    headers.push(header);
+                    pos += len;
+                }
+                Err(FrameHeaderError::Truncated) => break,
+                Err(_) => {
+                    let (next, next_valid_header) = (pos + 1..input.len())
+                        .find_map(|offset| {
+                            decode_full(&input[offset..])
+                                .ok()
+                                .map(|(header, _)| (offset, Some(header)))
+                        })
+                        .unwrap_or((input.len(), None));
+                    let event = ResyncEvent {
+                        start_offset: pos as u64,
+                        skipped_bytes: (next - pos) as u64,
+                        next_valid_header,
+                    };
+                    self.resync_stats.record(&event);
+                    if let Some(observer) = self.observer.as_mut() {
+                        observer.on_resync(&event);
+                    }
+                    pos = next;
+                }
+            }
+        }
+        headers
+    }
+
+    pub fn skipped_bytes(&self) -> u64 {
+        self.resync_stats.skipped_bytes
+    }
+
+    pub fn resync_stats(&self) -> ResyncStats {
+        self.resync_stats
+    }
+
+    fn decode_delta(&mut self, input: &[u8]) -> Result<(FrameHeaderV2, usize), FrameHeaderError> {
+        let reference = self.reference.as_ref().ok_or_else(|| {
+            FrameHeaderError::Invalid("Delta record without a reference header".to_string())
+        })?;
+        let tag = input[0];
+        let mut pos = 1;
+        let mut header = reference.clone();
+        let mut pts_stride = self.pts_stride;
+
+        if tag & CHANGED_PAYLOAD_SIZE != 0 {
+            header.payload_size = read_u32_varint(input, &mut pos, "payload_size")?;
+        }
+        if tag & CHANGED_FRAME_COUNT != 0 {
+            header.frame_count = read_u32_varint(input, &mut pos, "frame_count")?;
+        }
+        if let Some(previous) = reference.id {
+            let diff = if tag & CHANGED_ID != 0 {
+                unzigzag(read_varint(input, &mut pos)?)
+            } else {
+                0
+            };
+            header.id = Some(previous.wrapping_add(1).wrapping_add(diff as u64));
+        } else if tag & CHANGED_ID != 0 {
+            return Err(FrameHeaderError::Invalid(
+                "Delta changes an ID the reference does not have".to_string(),
+            ));
+        }
+        if let Some(previous) = reference.pts {
+            if tag & CHANGED_PTS_STRIDE != 0 {
+                pts_stride = unzigzag(read_varint(input, &mut pos)?);
+            }
+            header.pts = Some(previous.wrapping_add(pts_stride as u64));
+        } else if tag & CHANGED_PTS_STRIDE != 0 {
+            return Err(FrameHeaderError::Invalid(
+                "Delta changes a PTS the reference does not have".to_string(),
+            ));
+        }
+        if tag & CHANGED_PACKET_FLAGS != 0 {
+            header.packet_flags = *input.get(pos).ok_or(FrameHeaderError::Truncated)?;
+            pos += 1;
+        }
+        if header.timecode.is_some() {
+            let timecode = input.get(pos..pos + 4).ok_or(FrameHeaderError::Truncated)?;
+            header.timecode = Some(Timecode::from_packed(u32::from_be_bytes(
+                timecode.try_into().unwrap(),
+            ))?);
+            pos += 4;
+        }
+        if header.user_data.is_some() {
+            let user_data = input.get(pos..pos + 4).ok_or(FrameHeaderError::Truncated)?;
+            header.user_data = Some(u32::from_be_bytes(user_data.try_into().unwrap()));
+            pos += 4;
+        }
+        for trim in [&mut header.encoder_delay, &mut header.end_padding] {
+            if trim.is_some() {
+                let bytes = input.get(pos..pos + 2).ok_or(FrameHeaderError::Truncated)?;
+                *trim = Some(u16::from_be_bytes(bytes.try_into().unwrap()));
+                pos += 2;
+            }
+        }
+        if header.sample_offset.is_some() {
+            let bytes = input.get(pos..pos + 8).ok_or(FrameHeaderError::Truncated)?;
+            header.sample_offset = Some(u64::from_be_bytes(bytes.try_into().unwrap()));
+            pos += 8;
+        }
+        if header.auth_tag.is_some() {
+            let bytes = input
+                .get(pos..pos + FrameHeaderV2::AUTH_TAG_BYTES)
+                .ok_or(FrameHeaderError::Truncated)?;
+            header.auth_tag = Some(bytes.try_into().unwrap());
+            pos += FrameHeaderV2::AUTH_TAG_BYTES;
+        }
+        if header.packet_crc32.is_some() {
+            let crc = input.get(pos..pos + 4).ok_or(FrameHeaderError::Truncated)?;
+            header.packet_crc32 = Some(u32::from_be_bytes(crc.try_into().unwrap()));
+            pos += 4;
+        }
+
+        check_record(input, pos)?;
+        header.validate_fields()?;
+        self.pts_stride = pts_stride;
+        Ok((header, pos + 1))
+    }
+}
+
+fn decode_full(input: &[u8]) -> Result<(FrameHeaderV2, usize), FrameHeaderError> {
+    let header_size = FrameHeaderV2::validate_header_detailed(input)?;
+    check_record(input, header_size)?;
+    let header = FrameHeaderV2::decode(&mut &input[..header_size])?;
+    Ok((header, header_size + 1))
+}
+
+fn check_record(input: &[u8], len: usize) -> Result<(), FrameHeaderError> {
+    let check = *input.get(len).ok_or(FrameHeaderError::Truncated)?;
+    if crc32_ieee(&input[..len]) as u8 != check {
+        return Err(FrameHeaderError::Invalid(
+            "Delta record check byte mismatch".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+// Only these fields may change in a delta; anything else forces a full header.
+fn same_layout(reference: &FrameHeaderV2, header: &FrameHeaderV2) -> bool {
+    reference.encoding == header.encoding
+        && reference.sample_rate == header.sample_rate
+        && reference.channels == header.channels
+        && reference.bits_per_sample == header.bits_per_sample
+        && reference.endianness == header.endianness
+        && reference.id.is_some() == header.id.is_some()
+        && reference.id_is_u64 == header.id_is_u64
+        && reference.pts.is_some() == header.pts.is_some()
+        && reference.packet_crc32.is_some() == header.packet_crc32.is_some()
+        && reference.user_data.is_some() == header.user_data.is_some()
+        && reference.timecode.is_some() == header.timecode.is_some()
+        && reference.encoder_delay.is_some() == header.encoder_delay.is_some()
+        && reference.end_padding.is_some() == header.end_padding.is_some()
+        && reference.sample_offset.is_some() == header.sample_offset.is_some()
+        && reference.auth_tag.is_some() == header.auth_tag.is_some()
+        // Corrupt payloads are rare; marking one costs a full header.
+        && reference.payload_corrupt == header.payload_corrupt
+        && reference.packed == header.packed
+        && reference.redundant == header.redundant
+}
+
+fn read_u32_varint(
+    input: &[u8],
+    pos: &mut usize,
+    field: &'static str,
+) -> Result<u32, FrameHeaderError> {
+    u32::try_from(read_varint(input, pos)?).map_err(|_| FrameHeaderError::FieldOutOfRange(field))
+}
+
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+fn unzigzag(value: u64) -> i64 {
+    (value >> 1) as i64 ^ -((value & 1) as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EncodingFlag, Endianness};
+
+    fn opus_session(frames: u64) -> Vec<FrameHeaderV2> {
+        let mut state = 0x2545_F491u32;
+        (0..frames)
+            .map(|index| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                // A stereo/mono switch mid-session must fall back to full headers.
+                let channels = if (90_000..90_500).contains(&index) {
+                    1
+                } else {
+                    2
+                };
+                let header = FrameHeaderV2::new(
+                    EncodingFlag::Opus,
+                    60 + state % 140,
+                    960,
+                    48000,
+                    channels,
+                    0,
+                    Endianness::LittleEndian,
+                    Some(index + 1),
+                    Some(index * 960),
+                    None,
+                )
+                .unwrap();
+                if index % 10_000 == 5_000 {
+                    header
+                        .with_packet_flags(FrameHeaderV2::FLAG_DISCONTINUITY)
+                        .unwrap()
+                } else {
+                    header
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_delta_one_hour_opus_session_is_lossless_and_smaller() {
+        // 20 ms frames for one hour.
+        let headers = opus_session(180_000);
+        let mut encoder = DeltaEncoder::new(250);
+        let mut encoded = Vec::new();
+        let mut full_headers = 0;
+        for header in &headers {
+            full_headers += encoder.encode(header, &mut encoded).unwrap() as usize;
+        }
+
+        let plain: usize = headers.iter().map(FrameHeaderV2::size).sum();
+        assert!(
+            encoded.len() * 4 < plain,
+            "delta stream {} bytes vs {} plain",
+            encoded.len(),
+            plain
+        );
+        assert!(full_headers >= 180_000 / 250);
+
+        let mut decoder = DeltaDecoder::new();
+        assert_eq!(decoder.decode_stream(&encoded), headers);
+        assert_eq!(decoder.skipped_bytes(), 0);
+    }
+
+    #[test]
+    fn test_delta_resyncs_after_corruption() {
+        let headers: Vec<_> = opus_session(2_000)
+            .into_iter()
+            .enumerate()
+            .map(|(index, header)| header.with_packet_crc32(&[index as u8; 8]).unwrap())
+            .collect();
+        let mut encoder = DeltaEncoder::new(100);
+        let mut encoded = Vec::new();
+        let mut offsets = Vec::new();
+        for header in &headers {
+            offsets.push(encoded.len());
+            encoder.encode(header, &mut encoded).unwrap();
+        }
+
+        encoded[offsets[550] + 1] ^= 0x55;
+        let mut decoder = DeltaDecoder::new();
+        let decoded = decoder.decode_stream(&encoded);
+        assert!(decoder.skipped_bytes() > 0);
+        assert_eq!(decoded.len(), 2_000 - 50);
+        assert_eq!(decoded[..550], headers[..550]);
+        assert_eq!(decoded[550..], headers[600..]);
+
+        // A truncated trailing record is dropped without disturbing the rest.
+        let decoded = DeltaDecoder::new().decode_stream(&encoded[..encoded.len() - 1]);
+        assert_eq!(decoded.last(), headers.get(1_998));
+    }
+
+    #[test]
+    fn test_delta_preserves_header_extensions() {
+        let headers: Vec<_> = opus_session(1_000)
+            .into_iter()
+            .enumerate()
+            .map(|(index, header)| match index {
+                // Presence changes force a full header; values ride in deltas.
+                300..=399 => header,
+                _ => header
+                    .with_user_data(Some((index as u32 / 7) ^ 0xA5A5_0000))
+                    .with_timecode(Some(
+                        Timecode::from_frame_count(index as u64, 30, true).unwrap(),
+                    ))
+                    .with_encoder_delay(Some(index as u16 % 3 * 312))
+                    .with_end_padding(Some(index as u16 % 5))
+                    .with_auth_tag(Some([index as u8; 16]))
+                    .with_payload_corrupt(index == 500),
+            })
+            .collect();
+        let mut encoder = DeltaEncoder::new(0);
+        let mut encoded = Vec::new();
+        let mut full_headers = 0;
+        for header in &headers {
+            full_headers += encoder.encode(header, &mut encoded).unwrap() as usize;
+        }
+        assert_eq!(full_headers, 5);
+
+        let mut decoder = DeltaDecoder::new();
+        assert_eq!(decoder.decode_stream(&encoded), headers);
+        assert_eq!(decoder.skipped_bytes(), 0);
+    }
+
+    #[test]
+    fn test_delta_resync_events() {
+        let headers = opus_session(2_000);
+        let mut encoder = DeltaEncoder::new(100);
+        let mut encoded = Vec::new();
+        let mut offsets = Vec::new();
+        for header in &headers {
+            offsets.push(encoded.len());
+            encoder.encode(header, &mut encoded).unwrap();
+        }
+        offsets.push(encoded.len());
+        let clean = encoded.clone();
+
+        // One flipped byte, 20 zeroed bytes and 200 bytes of 0xFF, each
+        // ending before the next full header.
+        encoded[offsets[150] + 1] ^= 0x55;
+        encoded[offsets[420]..offsets[420] + 20].fill(0);
+        assert!(offsets[1210] + 200 < offsets[1300]);
+        encoded[offsets[1210]..offsets[1210] + 200].fill(0xFF);
+
+        let events = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let recorded = events.clone();
+        let mut decoder = DeltaDecoder::new().with_resync_observer(move |event: &ResyncEvent| {
+            recorded.borrow_mut().push(event.clone())
+        });
+        let decoded = decoder.decode_stream(&encoded);
+        assert_eq!(decoded.len(), 2_000 - 50 - 80 - 90);
+
+        let expected: Vec<_> = [(150, 200), (420, 500), (1210, 1300)]
+            .into_iter()
+            .map(|(corrupt, next_full)| ResyncEvent {
+                start_offset: offsets[corrupt] as u64,
+                skipped_bytes: (offsets[next_full] - offsets[corrupt]) as u64,
+                next_valid_header: Some(headers[next_full].clone()),
+            })
+            .collect();
+        assert_eq!(*events.borrow(), expected);
+        assert_eq!(
+            decoder.resync_stats(),
+            ResyncStats {
+                skipped_bytes: expected.iter().map(|event| event.skipped_bytes).sum(),
+                resyncs: 3,
+                largest_gap: expected
+                    .iter()
+                    .map(|event| event.skipped_bytes)
+                    .max()
+                    .unwrap(),
+            }
+        );
+        assert_eq!(
+            decoder.skipped_bytes(),
+            decoder.resync_stats().skipped_bytes
+        );
+
+        // Garbage running to the end has no header to resume at.
+        let mut tail = clean[..offsets[1900]].to_vec();
+        tail.extend_from_slice(&[0; 40]);
+        let events = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let recorded = events.clone();
+        DeltaDecoder::new()
+            .with_resync_observer(move |event: &ResyncEvent| {
+                recorded.borrow_mut().push(event.clone())
+            })
+            .decode_stream(&tail);
+        assert_eq!(
+            *events.borrow(),
+            [ResyncEvent {
+                start_offset: offsets[1900] as u64,
+                skipped_bytes: 40,
+                next_valid_header: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_delta_encoder_reset_forces_full_header() {
+        let headers = opus_session(3);
+        let mut encoder = DeltaEncoder::new(0);
+        let mut encoded = Vec::new();
+        assert!(encoder.encode(&headers[0], &mut encoded).unwrap());
+        assert!(!encoder.encode(&headers[1], &mut encoded).unwrap());
+        encoder.reset();
+        assert!(encoder.encode(&headers[2], &mut encoded).unwrap());
+
+        let mut decoder = DeltaDecoder::new();
+        let (_, full_len) = decode_full(&encoded).unwrap();
+        assert!(decoder.decode(&encoded[full_len..]).is_err());
+        assert_eq!(decoder.decode_stream(&encoded), headers);
+    }
+}
@@ -0,0 +1,154 @@
+use crate::FrameHeader;
+
+const C: f32 = std::f32::consts::FRAC_1_SQRT_2;
+
+// ITU-R BS.775 weights per channel as [left, right], for the layouts this
+// crate treats as standard. Channel order is L, R, C, Ls, Rs, (Lb, Rb,) LFE,
+// and the LFE is dropped.
+fn layout(channels: u8) -> Option<&'static [[f32; 2]]> {
+    const MONO: [[f32; 2]; 1] = [[C, C]];
+    const STEREO: [[f32; 2]; 2] = [[1.0, 0.0], [0.0, 1.0]];
+    const LCR: [[f32; 2]; 3] = [[1.0, 0.0], [0.0, 1.0], [C, C]];
+    const QUAD: [[f32; 2]; 4] = [[1.0, 0.0], [0.0, 1.0], [C, 0.0], [0.0, C]];
+    const FIVE: [[f32; 2]; 5] = [[1.0, 0.0], [0.0, 1.0], [C, C], [C, 0.0], [0.0, C]];
+    const FIVE_ONE: [[f32; 2]; 6] = [
+        [1.0, 0.0],
+        [0.0, 1.0],
+        [C, C],
+        [C, 0.0],
+        [0.0, C],
+        [0.0, 0.0],
+    ];
+    const SEVEN_ONE: [[f32; 2]; 8] = [
+        [1.0, 0.0],
+        [0.0, 1.0],
+        [C, C],
+        [C, 0.0],
+        [0.0, C],
+        [C, 0.0],
+        [0.0, C],
+        [0.0, 0.0],
+    ];
+    match channels {
+        1 => Some(&MONO),
+        2 => Some(&STEREO),
+        3 => Some(&LCR),
+        4 => Some(&QUAD),
+        5 => Some(&FIVE),
+        6 => Some(&FIVE_ONE),
+        8 => Some(&SEVEN_ONE),
+        _ => None,
+    }
+}
+
+impl FrameHeader {
+    /// Per-channel gains for a mono downmix. Standard layouts (1, 2, 3, 4,
+    /// 5, 5.1 and 7.1 channels) use the BS.775 weights, scaled to sum to 1.0
+    /// so the mix cannot clip; other channel counts get an equal-power mix.
+    pub fn mono_downmix_coefficients(&self) -> Vec<f32> {
+        let Some(layout) = layout(self.channels) else {
+            return vec![1.0 / (self.channels as f32).sqrt(); self.channels as usize];
+        };
+        let weights: Vec<f32> = layout.iter().map(|[l, r]| l.max(*r)).collect();
+        let total: f32 = weights.iter().sum();
+        weights.iter().map(|weight| weight / total).collect()
+    }
+
+    /// Per-channel `[left, right]` gains for a stereo downmix, scaled like
+    /// `mono_downmix_coefficients` so each side sums to 1.0. Mono feeds both
+    /// sides fully.
+    pub fn stereo_downmix_coefficients(&self) -> Vec<[f32; 2]> {
+        if self.channels == 1 {
+            return vec![[1.0, 1.0]];
+        }
+        let Some(layout) = layout(self.channels) else {
+            // Alternate unknown channels between the sides, equal-power.
+            let per_side = ((self.channels as f32) / 2.0).sqrt();
+            return (0..self.channels)
+                .map(|channel| {
+                    if channel % 2 == 0 {
+                        [1.0 / per_side, 0.0]
+                    } else {
+                        [0.0, 1.0 / per_side]
+                    }
+                })
+                .collect();
+        };
+        let left: f32 = layout.iter().map(|[l, _]| l).sum();
+        let right: f32 = layout.iter().map(|[_, r]| r).sum();
+        layout.iter().map(|[l, r]| [l / left, r / right]).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EncodingFlag, Endianness};
+
+    fn header(channels: u8) -> FrameHeader {
+        FrameHeader::new(
+            EncodingFlag::PCMFloat,
+            1024,
+            48000,
+            channels,
+            32,
+            Endianness::LittleEndian,
+            None,
+            None,
+        )
+        .unwrap()
+    }
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-5
+    }
+
+    #[test]
+    fn test_standard_layouts_sum_to_unity() {
+        for channels in [1, 2, 3, 4, 5, 6, 8] {
+            let header = header(channels);
+            let mono = header.mono_downmix_coefficients();
+            assert_eq!(mono.len(), channels as usize);
+            assert!(close(mono.iter().sum(), 1.0), "{} channels", channels);
+
+            let stereo = header.stereo_downmix_coefficients();
+            assert_eq!(stereo.len(), channels as usize);
+            if channels > 1 {
+                assert!(close(stereo.iter().map(|[l, _]| l).sum(), 1.0));
+                assert!(close(stereo.iter().map(|[_, r]| r).sum(), 1.0));
+            }
+        }
+
+        assert_eq!(header(1).mono_downmix_coefficients(), vec![1.0]);
+        assert_eq!(header(1).stereo_downmix_coefficients(), vec![[1.0, 1.0]]);
+        assert_eq!(header(2).mono_downmix_coefficients(), vec![0.5, 0.5]);
+        assert_eq!(
+            header(2).stereo_downmix_coefficients(),
+            vec![[1.0, 0.0], [0.0, 1.0]]
+        );
+
+        // 5.1 keeps the BS.775 proportions and drops the LFE.
+        let five_one = header(6).mono_downmix_coefficients();
+        assert!(close(five_one[2] / five_one[0], C));
+        assert!(close(five_one[3], five_one[2]));
+        assert_eq!(five_one[5], 0.0);
+        assert_eq!(header(8).mono_downmix_coefficients()[7], 0.0);
+    }
+
+    #[test]
+    fn test_other_channel_counts_are_equal_power() {
+        for channels in [7, 9, 16] {
+            let header = header(channels);
+            let mono = header.mono_downmix_coefficients();
+            assert!(close(mono.iter().map(|c| c * c).sum(), 1.0));
+
+            let stereo = header.stereo_downmix_coefficients();
+            let left_power: f32 = stereo.iter().map(|[l, _]| l * l).sum();
+            assert!(close(
+                left_power,
+                (channels as f32 / 2.0).ceil() * 2.0 / channels as f32
+            ));
+            assert!(stereo.iter().all(|[l, r]| *l == 0.0 || *r == 0.0));
+        }
+    }
+}
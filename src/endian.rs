@@ -0,0 +1,177 @@
+use crate::{EncodingFlag, Endianness, FrameHeader};
+
+const ENDIANNESS_PROBE_SAMPLES: usize = 16;
+// Real audio read with the wrong byte order puts its noisy low byte in the
+// high byte, so it comes out far louder than when read correctly.
+const ENDIANNESS_MISMATCH_RATIO: f64 = 4.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EndiannessMismatchWarning {
+    pub declared: Endianness,
+    pub declared_mean_abs: f64,
+    pub swapped_mean_abs: f64,
+}
+
+impl EndiannessMismatchWarning {
+    pub fn likely(&self) -> Endianness {
+        match self.declared {
+            Endianness::LittleEndian => Endianness::BigEndian,
+            Endianness::BigEndian => Endianness::LittleEndian,
+        }
+    }
+}
+
+impl FrameHeader {
+    pub fn to_canonical_endian(&self, canonical: Endianness) -> FrameHeader {
+        FrameHeader {
+            endianness: canonical,
+            ..self.clone()
+        }
+    }
+
+    /// True if `a` and `b` differ at most in payload endianness, i.e. they
+    /// describe the same audio once one side's samples are byte-swapped.
+    pub fn is_byte_equivalent_assuming_swapped_endian(a: &FrameHeader, b: &FrameHeader) -> bool {
+        a.to_canonical_endian(Endianness::BigEndian) == b.to_canonical_endian(Endianness::BigEndian)
+    }
+
+    /// Diagnostic for 16-bit signed PCM: warns when the first 16 samples are
+    /// much louder under the declared byte order than under the swapped one.
+    /// Returns `None` for other formats and for payloads that look consistent
+    /// or give no signal either way (silence, full-scale noise).
+    pub fn verify_payload_endianness_heuristic(
+        &self,
+        payload: &[u8],
+    ) -> Option<EndiannessMismatchWarning> {
+        if self.encoding != EncodingFlag::PCMSigned || self.bits_per_sample != 16 {
+            return None;
+        }
+
+        let mut count = 0;
+        let mut little_sum = 0.0;
+        let mut big_sum = 0.0;
+        for sample in payload.chunks_exact(2).take(ENDIANNESS_PROBE_SAMPLES) {
+            let bytes = [sample[0], sample[1]];
+            little_sum += (i16::from_le_bytes(bytes) as f64).abs();
+            big_sum += (i16::from_be_bytes(bytes) as f64).abs();
+            count += 1;
+        }
+        if count == 0 {
+            return None;
+        }
+
+        let (declared_sum, swapped_sum) = match self.endianness {
+            Endianness::LittleEndian => (little_sum, big_sum),
+            Endianness::BigEndian => (big_sum, little_sum),
+        };
+        if declared_sum <= swapped_sum * ENDIANNESS_MISMATCH_RATIO {
+            return None;
+        }
+
+        Some(EndiannessMismatchWarning {
+            declared: self.endianness,
+            declared_mean_abs: declared_sum / count as f64,
+            swapped_mean_abs: swapped_sum / count as f64,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pcm_header(endianness: Endianness, channels: u8) -> FrameHeader {
+        FrameHeader::new(
+            EncodingFlag::PCMSigned,
+            1024,
+            48000,
+            channels,
+            24,
+            endianness,
+            Some(5),
+            Some(1_000),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_canonical_endian_equivalence() {
+        let little = pcm_header(Endianness::LittleEndian, 2);
+        let big = pcm_header(Endianness::BigEndian, 2);
+        assert_ne!(little, big);
+
+        let canonical = little.to_canonical_endian(Endianness::BigEndian);
+        assert_eq!(canonical, big);
+        assert_eq!(canonical.id(), little.id());
+        assert_eq!(canonical.pts(), little.pts());
+
+        assert!(FrameHeader::is_byte_equivalent_assuming_swapped_endian(
+            &little, &big
+        ));
+        assert!(FrameHeader::is_byte_equivalent_assuming_swapped_endian(
+            &little, &little
+        ));
+        assert!(!FrameHeader::is_byte_equivalent_assuming_swapped_endian(
+            &little,
+            &pcm_header(Endianness::BigEndian, 1)
+        ));
+    }
+
+    #[test]
+    fn test_payload_endianness_heuristic() {
+        let tone: Vec<i16> = (0..32)
+            .map(|i| ((i as f64 * 0.3).sin() * 1_200.0) as i16)
+            .collect();
+        let little: Vec<u8> = tone.iter().flat_map(|s| s.to_le_bytes()).collect();
+        let big: Vec<u8> = tone.iter().flat_map(|s| s.to_be_bytes()).collect();
+
+        let header = |endianness| {
+            FrameHeader::new(
+                EncodingFlag::PCMSigned,
+                32,
+                48000,
+                1,
+                16,
+                endianness,
+                None,
+                None,
+            )
+            .unwrap()
+        };
+        let declared_little = header(Endianness::LittleEndian);
+        let declared_big = header(Endianness::BigEndian);
+
+        assert_eq!(
+            declared_little.verify_payload_endianness_heuristic(&little),
+            None
+        );
+        assert_eq!(declared_big.verify_payload_endianness_heuristic(&big), None);
+
+        let warning = declared_little
+            .verify_payload_endianness_heuristic(&big)
+            .unwrap();
+        assert_eq!(warning.declared, Endianness::LittleEndian);
+        assert_eq!(warning.likely(), Endianness::BigEndian);
+        assert!(warning.declared_mean_abs > warning.swapped_mean_abs);
+        assert_eq!(
+            declared_big
+                .verify_payload_endianness_heuristic(&little)
+                .unwrap()
+                .likely(),
+            Endianness::LittleEndian
+        );
+
+        assert_eq!(
+            declared_little.verify_payload_endianness_heuristic(&[0; 64]),
+            None
+        );
+        assert_eq!(
+            declared_little.verify_payload_endianness_heuristic(&[]),
+            None
+        );
+        assert_eq!(
+            pcm_header(Endianness::LittleEndian, 1).verify_payload_endianness_heuristic(&big),
+            None
+        );
+    }
+}
@@ -0,0 +1,183 @@
+use std::fmt;
+use std::io;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FrameHeaderError {
+    Truncated,
+    VarintOverflow,
+    UnsupportedWireType(u8),
+    FieldOutOfRange(&'static str),
+    InvalidEncoding(u32),
+    InvalidMagic(u32),
+    InvalidSampleRateCode(u32),
+    InvalidBitsCode(u32),
+    InvalidChannels(u8),
+    MissingPts,
+    PtsOutOfRange {
+        pts: u64,
+        offset: i64,
+    },
+    LimitExceeded {
+        limit: &'static str,
+        value: u64,
+        max: u64,
+    },
+    Invalid(String),
+}
+
+impl fmt::Display for FrameHeaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FrameHeaderError::Truncated => write!(f, "Input truncated"),
+            FrameHeaderError::VarintOverflow => write!(f, "Varint exceeds 64 bits"),
+            FrameHeaderError::UnsupportedWireType(wire_type) => {
+                write!(f, "Unsupported wire type: {}", wire_type)
+            }
+            FrameHeaderError::FieldOutOfRange(field) => write!(f, "Field out of range: {}", field),
+            FrameHeaderError::InvalidEncoding(code) => write!(f, "Invalid encoding flag: {}", code),
+            FrameHeaderError::InvalidMagic(magic) => {
+                write!(f, "Invalid header magic word: {:#04x}", magic)
+            }
+            FrameHeaderError::InvalidSampleRateCode(code) => {
+                write!(f, "Invalid sample rate code: {}", code)
+            }
+            FrameHeaderError::InvalidBitsCode(code) => {
+                write!(f, "Invalid bits per sample code: {}", code)
+            }
+            FrameHeaderError::InvalidChannels(channels) => {
+                write!(f, "Invalid channel count: {}", channels)
+            }
+            FrameHeaderError::MissingPts => write!(f, "Header has no PTS"),
+            FrameHeaderError::PtsOutOfRange { pts, offset } => {
+                write!(f, "PTS {} shifted by {} is out of range", pts, offset)
+            }
+            FrameHeaderError::LimitExceeded { limit, value, max } => {
+                write!(f, "{} of {} exceeds the limit of {}", limit, value, max)
+            }
+            FrameHeaderError::Invalid(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for FrameHeaderError {}
+
+impl From<String> for FrameHeaderError {
+    fn from(message: String) -> Self {
+        FrameHeaderError::Invalid(message)
+    }
+}
+
+impl From<io::Error> for FrameHeaderError {
+    fn from(err: io::Error) -> Self {
+        let kind = err.kind();
+        if kind == io::ErrorKind::UnexpectedEof {
+            return FrameHeaderError::Truncated;
+        }
+        match err.into_inner() {
+            Some(inner) => match inner.downcast::<FrameHeaderError>() {
+                Ok(err) => *err,
+                // The position is dropped; downcast to `DecodeError` to keep it.
+                Err(inner) => match inner.downcast::<DecodeError>() {
+                    Ok(err) => FrameHeaderError::from(err.source),
+                    Err(inner) => FrameHeaderError::Invalid(inner.to_string()),
+                },
+            },
+            None => FrameHeaderError::Invalid(kind.to_string()),
+        }
+    }
+}
+
+impl From<FrameHeaderError> for io::Error {
+    fn from(err: FrameHeaderError) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, err)
+    }
+}
+
+/// An error from reading a stream, with the frame it happened in. Readers
+/// return it inside an `io::Error` of the same kind; get it back with
+/// `DecodeError::find`.
+#[derive(Debug)]
+pub struct DecodeError {
+    /// Index of the frame being read, counting from the reader's first.
+    pub frame: u64,
+    /// Absolute byte offset where that frame starts.
+    pub offset: u64,
+    /// The field being read, when the error names one.
+    pub field: Option<&'static str>,
+    source: io::Error,
+}
+
+impl DecodeError {
+    /// Wraps `err` unless it already carries a position. Without `field`,
+    /// the field is taken from the `FrameHeaderError` inside `err`, if any.
+    pub(crate) fn wrap(
+        err: io::Error,
+        frame: u64,
+        offset: u64,
+        field: Option<&'static str>,
+    ) -> io::Error {
+        if DecodeError::find(&err).is_some() {
+            return err;
+        }
+        let field = field.or_else(|| {
+            err.get_ref()
+                .and_then(|inner| inner.downcast_ref::<FrameHeaderError>())
+                .and_then(FrameHeaderError::field)
+        });
+        io::Error::new(
+            err.kind(),
+            DecodeError {
+                frame,
+                offset,
+                field,
+                source: err,
+            },
+        )
+    }
+
+    /// The position carried by an error from a reader, if it has one.
+    pub fn find(err: &io::Error) -> Option<&DecodeError> {
+        err.get_ref()?.downcast_ref()
+    }
+
+    pub fn kind(&self) -> io::ErrorKind {
+        self.source.kind()
+    }
+
+    pub fn into_inner(self) -> io::Error {
+        self.source
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "frame {} at offset {:#X}: ", self.frame, self.offset)?;
+        if let Some(field) = self.field {
+            write!(f, "{}: ", field)?;
+        }
+        write!(f, "{}", self.source)
+    }
+}
+
+impl std::error::Error for DecodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+impl FrameHeaderError {
+    // The header field an error is about, for `DecodeError`.
+    fn field(&self) -> Option<&'static str> {
+        match self {
+            FrameHeaderError::FieldOutOfRange(field) => Some(field),
+            FrameHeaderError::InvalidEncoding(_) => Some("encoding"),
+            FrameHeaderError::InvalidMagic(_) => Some("magic"),
+            FrameHeaderError::InvalidSampleRateCode(_) => Some("sample_rate"),
+            FrameHeaderError::InvalidBitsCode(_) => Some("bits_per_sample"),
+            FrameHeaderError::InvalidChannels(_) => Some("channels"),
+            FrameHeaderError::MissingPts => Some("pts"),
+            FrameHeaderError::LimitExceeded { limit, .. } => Some(limit),
+            _ => None,
+        }
+    }
+}
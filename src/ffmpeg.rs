@@ -0,0 +1,307 @@
+use crate::{ChannelCount, EncodingFlag, Endianness, FrameHeader, FrameHeaderError, SampleRate};
+use Endianness::{BigEndian, LittleEndian};
+
+// `AVMediaType` values.
+pub const AVMEDIA_TYPE_UNKNOWN: i32 = -1;
+pub const AVMEDIA_TYPE_VIDEO: i32 = 0;
+pub const AVMEDIA_TYPE_AUDIO: i32 = 1;
+
+// `AVCodecID` values from libavcodec/codec_id.h.
+pub const AV_CODEC_ID_NONE: u32 = 0;
+pub const AV_CODEC_ID_H264: u32 = 27;
+pub const AV_CODEC_ID_PCM_S16LE: u32 = 0x10000;
+pub const AV_CODEC_ID_PCM_S16BE: u32 = 0x10001;
+pub const AV_CODEC_ID_PCM_S32LE: u32 = 0x10008;
+pub const AV_CODEC_ID_PCM_S32BE: u32 = 0x10009;
+pub const AV_CODEC_ID_PCM_S24LE: u32 = 0x1000C;
+pub const AV_CODEC_ID_PCM_S24BE: u32 = 0x1000D;
+pub const AV_CODEC_ID_PCM_F32BE: u32 = 0x10014;
+pub const AV_CODEC_ID_PCM_F32LE: u32 = 0x10015;
+pub const AV_CODEC_ID_AAC: u32 = 0x15002;
+pub const AV_CODEC_ID_FLAC: u32 = 0x1500C;
+pub const AV_CODEC_ID_OPUS: u32 = 0x1503C;
+
+// `AVSampleFormat` values.
+pub const AV_SAMPLE_FMT_NONE: i32 = -1;
+pub const AV_SAMPLE_FMT_S16: i32 = 1;
+pub const AV_SAMPLE_FMT_S32: i32 = 2;
+pub const AV_SAMPLE_FMT_FLT: i32 = 3;
+
+// PCM codec IDs by (encoding, bits, endianness).
+const PCM_CODEC_IDS: [(EncodingFlag, u8, Endianness, u32); 8] = [
+    (
+        EncodingFlag::PCMSigned,
+        16,
+        LittleEndian,
+        AV_CODEC_ID_PCM_S16LE,
+    ),
+    (
+        EncodingFlag::PCMSigned,
+        16,
+        BigEndian,
+        AV_CODEC_ID_PCM_S16BE,
+    ),
+    (
+        EncodingFlag::PCMSigned,
+        24,
+        LittleEndian,
+        AV_CODEC_ID_PCM_S24LE,
+    ),
+    (
+        EncodingFlag::PCMSigned,
+        24,
+        BigEndian,
+        AV_CODEC_ID_PCM_S24BE,
+    ),
+    (
+        EncodingFlag::PCMSigned,
+        32,
+        LittleEndian,
+        AV_CODEC_ID_PCM_S32LE,
+    ),
+    (
+        EncodingFlag::PCMSigned,
+        32,
+        BigEndian,
+        AV_CODEC_ID_PCM_S32BE,
+    ),
+    (
+        EncodingFlag::PCMFloat,
+        32,
+        LittleEndian,
+        AV_CODEC_ID_PCM_F32LE,
+    ),
+    (EncodingFlag::PCMFloat, 32, BigEndian, AV_CODEC_ID_PCM_F32BE),
+];
+
+/// The `AVCodecParameters` fields a header determines, with FFmpeg's integer
+/// values (the `AVMEDIA_TYPE_*`, `AV_CODEC_ID_*` and `AV_SAMPLE_FMT_*`
+/// constants in this module).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AvCodecParamsHint {
+    pub codec_type: i32,
+    pub codec_id: u32,
+    pub sample_rate: i32,
+    pub ch_layout_nb_channels: i32,
+    /// Decoded sample format; 24-bit PCM decodes to `AV_SAMPLE_FMT_S32`.
+    pub format: i32,
+    pub bits_per_coded_sample: i32,
+    /// Zero when the bit rate is not fixed by the header.
+    pub bit_rate: i64,
+    /// Zero for compressed codecs.
+    pub block_align: i32,
+}
+
+impl FrameHeader {
+    /// PCM headers without an FFmpeg codec ID, such as 16-bit float, and
+    /// unknown encodings get `AV_CODEC_ID_NONE` and `AVMEDIA_TYPE_UNKNOWN`.
+    pub fn to_avcodec_params_hint(&self) -> AvCodecParamsHint {
+        let pcm_codec_id = PCM_CODEC_IDS
+            .iter()
+            .find(|(encoding, bits, endianness, _)| {
+                (*encoding, *bits, *endianness)
+                    == (self.encoding, self.bits_per_sample, self.endianness)
+            })
+            .map(|(.., codec_id)| *codec_id);
+        let (codec_type, codec_id) = match self.encoding {
+            EncodingFlag::PCMSigned | EncodingFlag::PCMFloat => match pcm_codec_id {
+                Some(codec_id) => (AVMEDIA_TYPE_AUDIO, codec_id),
+                None => (AVMEDIA_TYPE_UNKNOWN, AV_CODEC_ID_NONE),
+            },
+            EncodingFlag::Opus => (AVMEDIA_TYPE_AUDIO, AV_CODEC_ID_OPUS),
+            EncodingFlag::FLAC => (AVMEDIA_TYPE_AUDIO, AV_CODEC_ID_FLAC),
+            EncodingFlag::AAC => (AVMEDIA_TYPE_AUDIO, AV_CODEC_ID_AAC),
+            EncodingFlag::H264 => (AVMEDIA_TYPE_VIDEO, AV_CODEC_ID_H264),
+            EncodingFlag::Metadata | EncodingFlag::Unknown(_) => {
+                (AVMEDIA_TYPE_UNKNOWN, AV_CODEC_ID_NONE)
+            }
+        };
+
+        let (format, bit_rate, block_align) = if pcm_codec_id == Some(codec_id) {
+            let format = match (self.encoding, self.bits_per_sample) {
+                (EncodingFlag::PCMFloat, _) => AV_SAMPLE_FMT_FLT,
+                (_, 16) => AV_SAMPLE_FMT_S16,
+                _ => AV_SAMPLE_FMT_S32,
+            };
+            let block_align = self.channels as i32 * self.bits_per_sample as i32 / 8;
+            (
+                format,
+                self.sample_rate as i64 * block_align as i64 * 8,
+                block_align,
+            )
+        } else {
+            (AV_SAMPLE_FMT_NONE, 0, 0)
+        };
+
+        AvCodecParamsHint {
+            codec_type,
+            codec_id,
+            sample_rate: self.sample_rate as i32,
+            ch_layout_nb_channels: self.channels as i32,
+            format,
+            bits_per_coded_sample: self.bits_per_sample as i32,
+            bit_rate,
+            block_align,
+        }
+    }
+
+    /// PCM byte order and sample width come from the codec ID. Compressed
+    /// codecs are little-endian, and a `bits_per_coded_sample` of zero (as
+    /// FFmpeg often reports for them) is read as 16.
+    pub fn from_avcodec_params_hint(
+        hint: &AvCodecParamsHint,
+        sample_size: u16,
+    ) -> Result<FrameHeader, FrameHeaderError> {
+        let (encoding, bits_per_sample, endianness, codec_type) = match hint.codec_id {
+            AV_CODEC_ID_OPUS => (EncodingFlag::Opus, None, LittleEndian, AVMEDIA_TYPE_AUDIO),
+            AV_CODEC_ID_FLAC => (EncodingFlag::FLAC, None, LittleEndian, AVMEDIA_TYPE_AUDIO),
+            AV_CODEC_ID_AAC => (EncodingFlag::AAC, None, LittleEndian, AVMEDIA_TYPE_AUDIO),
+            AV_CODEC_ID_H264 => (EncodingFlag::H264, None, LittleEndian, AVMEDIA_TYPE_VIDEO),
+            codec_id => {
+                let (encoding, bits, endianness, _) = PCM_CODEC_IDS
+                    .iter()
+                    .find(|(.., pcm_codec_id)| *pcm_codec_id == codec_id)
+                    .ok_or(FrameHeaderError::InvalidEncoding(codec_id))?;
+                (*encoding, Some(*bits), *endianness, AVMEDIA_TYPE_AUDIO)
+            }
+        };
+        if hint.codec_type != codec_type {
+            return Err(FrameHeaderError::FieldOutOfRange("codec_type"));
+        }
+        let bits_per_sample = match (bits_per_sample, hint.bits_per_coded_sample) {
+            (Some(bits), _) => bits,
+            (None, 0) => 16,
+            (None, bits) => u8::try_from(bits)
+                .map_err(|_| FrameHeaderError::FieldOutOfRange("bits_per_coded_sample"))?,
+        };
+
+        Ok(FrameHeader::new_typed(
+            encoding,
+            sample_size,
+            SampleRate::try_from(
+                u32::try_from(hint.sample_rate)
+                    .map_err(|_| FrameHeaderError::FieldOutOfRange("sample_rate"))?,
+            )?,
+            ChannelCount::try_from(
+                u8::try_from(hint.ch_layout_nb_channels)
+                    .map_err(|_| FrameHeaderError::FieldOutOfRange("ch_layout_nb_channels"))?,
+            )?,
+            bits_per_sample,
+            endianness,
+            None,
+            None,
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(encoding: EncodingFlag, bits: u8, endianness: Endianness) -> FrameHeader {
+        FrameHeader::new(encoding, 960, 48000, 2, bits, endianness, None, None).unwrap()
+    }
+
+    #[test]
+    fn test_pcm_params_hint() {
+        for (encoding, bits, endianness, codec_id, format) in [
+            (EncodingFlag::PCMSigned, 16, LittleEndian, 65536, 1),
+            (EncodingFlag::PCMSigned, 16, BigEndian, 65537, 1),
+            (EncodingFlag::PCMSigned, 24, LittleEndian, 65548, 2),
+            (EncodingFlag::PCMSigned, 24, BigEndian, 65549, 2),
+            (EncodingFlag::PCMSigned, 32, LittleEndian, 65544, 2),
+            (EncodingFlag::PCMSigned, 32, BigEndian, 65545, 2),
+            (EncodingFlag::PCMFloat, 32, LittleEndian, 65557, 3),
+            (EncodingFlag::PCMFloat, 32, BigEndian, 65556, 3),
+        ] {
+            let header = header(encoding, bits, endianness);
+            let hint = header.to_avcodec_params_hint();
+            assert_eq!(
+                hint,
+                AvCodecParamsHint {
+                    codec_type: 1,
+                    codec_id,
+                    sample_rate: 48000,
+                    ch_layout_nb_channels: 2,
+                    format,
+                    bits_per_coded_sample: bits as i32,
+                    bit_rate: 48000 * 2 * bits as i64,
+                    block_align: 2 * bits as i32 / 8,
+                }
+            );
+            assert_eq!(
+                FrameHeader::from_avcodec_params_hint(&hint, 960),
+                Ok(header)
+            );
+        }
+    }
+
+    #[test]
+    fn test_compressed_params_hint() {
+        for (encoding, codec_type, codec_id) in [
+            (EncodingFlag::Opus, 1, 86076),
+            (EncodingFlag::FLAC, 1, 86028),
+            (EncodingFlag::AAC, 1, 86018),
+            (EncodingFlag::H264, 0, 27),
+        ] {
+            let header = header(encoding, 24, LittleEndian);
+            let hint = header.to_avcodec_params_hint();
+            assert_eq!((hint.codec_type, hint.codec_id), (codec_type, codec_id));
+            assert_eq!((hint.format, hint.bit_rate, hint.block_align), (-1, 0, 0));
+            assert_eq!(
+                FrameHeader::from_avcodec_params_hint(&hint, 960),
+                Ok(header)
+            );
+
+            let unset = AvCodecParamsHint {
+                bits_per_coded_sample: 0,
+                ..hint
+            };
+            let decoded = FrameHeader::from_avcodec_params_hint(&unset, 960).unwrap();
+            assert_eq!(decoded.bits_per_sample(), 16);
+        }
+    }
+
+    #[test]
+    fn test_params_hint_errors() {
+        let hint = header(EncodingFlag::PCMFloat, 16, LittleEndian).to_avcodec_params_hint();
+        assert_eq!((hint.codec_type, hint.codec_id), (-1, 0));
+        assert_eq!(
+            FrameHeader::from_avcodec_params_hint(&hint, 960),
+            Err(FrameHeaderError::InvalidEncoding(0))
+        );
+
+        let opus = header(EncodingFlag::Opus, 16, LittleEndian).to_avcodec_params_hint();
+        assert_eq!(
+            FrameHeader::from_avcodec_params_hint(
+                &AvCodecParamsHint {
+                    codec_type: AVMEDIA_TYPE_VIDEO,
+                    ..opus
+                },
+                960
+            ),
+            Err(FrameHeaderError::FieldOutOfRange("codec_type"))
+        );
+        assert_eq!(
+            FrameHeader::from_avcodec_params_hint(
+                &AvCodecParamsHint {
+                    sample_rate: -1,
+                    ..opus
+                },
+                960
+            ),
+            Err(FrameHeaderError::FieldOutOfRange("sample_rate"))
+        );
+        // MP3 has no encoding here.
+        assert_eq!(
+            FrameHeader::from_avcodec_params_hint(
+                &AvCodecParamsHint {
+                    codec_id: 0x15001,
+                    ..opus
+                },
+                960
+            ),
+            Err(FrameHeaderError::InvalidEncoding(0x15001))
+        );
+    }
+}
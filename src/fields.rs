@@ -0,0 +1,136 @@
+use crate::FrameHeader;
+
+/// A header field value, for tooling that handles fields generically.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldValue {
+    U64(u64),
+    U32(u32),
+    U8(u8),
+    Str(String),
+    Bool(bool),
+    /// An optional field that is not set.
+    None,
+}
+
+impl FrameHeader {
+    /// Field names in `fields()` order, matching the `CSV_HEADER` columns.
+    pub const FIELD_NAMES: [&'static str; 8] = [
+        "encoding",
+        "sample_size",
+        "sample_rate",
+        "channels",
+        "bits_per_sample",
+        "endianness",
+        "id",
+        "pts",
+    ];
+
+    // Values in `FIELD_NAMES` order. Encoding and endianness use their serde
+    // names.
+    fn field_values(&self) -> [FieldValue; 8] {
+        let optional = |value: Option<u64>| value.map_or(FieldValue::None, FieldValue::U64);
+        [
+            FieldValue::Str(match self.encoding.serde_name() {
+                Some(name) => name.to_string(),
+                None => format!("unknown:{}", self.encoding.code()),
+            }),
+            FieldValue::U32(self.sample_size as u32),
+            FieldValue::U32(self.sample_rate),
+            FieldValue::U8(self.channels),
+            FieldValue::U8(self.bits_per_sample),
+            FieldValue::Str(self.endianness.serde_name().to_string()),
+            optional(self.id),
+            optional(self.pts),
+        ]
+    }
+
+    /// Every field with its name, including unset optional fields as
+    /// `FieldValue::None`.
+    pub fn fields(&self) -> impl Iterator<Item = (&'static str, FieldValue)> {
+        Self::FIELD_NAMES.into_iter().zip(self.field_values())
+    }
+
+    pub fn get_field(&self, name: &str) -> Option<FieldValue> {
+        self.fields()
+            .find(|(field, _)| *field == name)
+            .map(|(_, value)| value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EncodingFlag, Endianness};
+
+    #[test]
+    fn test_fields() {
+        let header = FrameHeader::new(
+            EncodingFlag::Opus,
+            960,
+            48000,
+            2,
+            16,
+            Endianness::BigEndian,
+            None,
+            Some(20_000),
+        )
+        .unwrap();
+        assert_eq!(
+            header.fields().collect::<Vec<_>>(),
+            [
+                ("encoding", FieldValue::Str("opus".to_string())),
+                ("sample_size", FieldValue::U32(960)),
+                ("sample_rate", FieldValue::U32(48000)),
+                ("channels", FieldValue::U8(2)),
+                ("bits_per_sample", FieldValue::U8(16)),
+                ("endianness", FieldValue::Str("be".to_string())),
+                ("id", FieldValue::None),
+                ("pts", FieldValue::U64(20_000)),
+            ]
+        );
+        assert_eq!(header.get_field("channels"), Some(FieldValue::U8(2)));
+        assert_eq!(header.get_field("id"), Some(FieldValue::None));
+        assert_eq!(header.get_field("Channels"), None);
+        assert_eq!(header.get_field("frame_count"), None);
+
+        let unknown = FrameHeader::new(
+            EncodingFlag::Unknown(7),
+            960,
+            48000,
+            2,
+            16,
+            Endianness::LittleEndian,
+            Some(3),
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            unknown.get_field("encoding"),
+            Some(FieldValue::Str("unknown:7".to_string()))
+        );
+        assert_eq!(unknown.get_field("id"), Some(FieldValue::U64(3)));
+    }
+
+    #[test]
+    fn test_field_names_match_csv_and_serde() {
+        assert_eq!(FrameHeader::FIELD_NAMES.join(","), FrameHeader::CSV_HEADER);
+
+        let header = FrameHeader::new(
+            EncodingFlag::PCMSigned,
+            960,
+            48000,
+            2,
+            16,
+            Endianness::LittleEndian,
+            None,
+            None,
+        )
+        .unwrap();
+        let json = serde_json::to_value(&header).unwrap();
+        let mut serde_names: Vec<_> = json.as_object().unwrap().keys().cloned().collect();
+        let mut names = FrameHeader::FIELD_NAMES.map(str::to_string).to_vec();
+        serde_names.sort();
+        names.sort();
+        assert_eq!(serde_names, names);
+    }
+}
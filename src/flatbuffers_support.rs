@@ -0,0 +1,184 @@
+use crate::{encoding_from_code, Endianness, FrameHeader, FrameHeaderError};
+use flatbuffers::FlatBufferBuilder;
+
+#[allow(unused_imports, mismatched_lifetime_syntaxes, clippy::all)]
+#[rustfmt::skip]
+#[path = "generated/frame_header_generated.rs"]
+mod frame_header_generated;
+
+use frame_header_generated::frame_header as fb;
+
+impl FrameHeader {
+    /// Serializes to a FlatBuffer with the schema in `proto/frame_header.fbs`.
+    /// ID and PTS are optional scalars, left out of the buffer when unset.
+    pub fn to_flatbuffer_bytes(&self) -> Vec<u8> {
+        let mut builder = FlatBufferBuilder::with_capacity(64);
+        let header = fb::FrameHeader::create(
+            &mut builder,
+            &fb::FrameHeaderArgs {
+                encoding: fb::Encoding(self.encoding.code()),
+                sample_size: self.sample_size,
+                sample_rate: self.sample_rate,
+                channels: self.channels,
+                bits_per_sample: self.bits_per_sample,
+                endianness: fb::Endianness(self.endianness as u8),
+                id: self.id,
+                pts: self.pts,
+            },
+        );
+        fb::finish_frame_header_buffer(&mut builder, header);
+        builder.finished_data().to_vec()
+    }
+
+    /// Verifies the buffer before reading it, then applies the same checks as
+    /// `FrameHeader::new`.
+    pub fn from_flatbuffer_bytes(bytes: &[u8]) -> Result<FrameHeader, FrameHeaderError> {
+        let header = fb::root_as_frame_header(bytes)
+            .map_err(|err| FrameHeaderError::Invalid(format!("Invalid FlatBuffer: {}", err)))?;
+
+        let encoding_code = header.encoding().0 as u32;
+        let encoding = encoding_from_code(encoding_code)
+            .ok_or(FrameHeaderError::InvalidEncoding(encoding_code))?;
+        let endianness = match header.endianness() {
+            fb::Endianness::LittleEndian => Endianness::LittleEndian,
+            fb::Endianness::BigEndian => Endianness::BigEndian,
+            _ => return Err(FrameHeaderError::FieldOutOfRange("endianness")),
+        };
+
+        Ok(FrameHeader::new(
+            encoding,
+            header.sample_size(),
+            header.sample_rate(),
+            header.channels(),
+            header.bits_per_sample(),
+            endianness,
+            header.id(),
+            header.pts(),
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EncodingFlag;
+
+    fn header(id: Option<u64>, pts: Option<u64>) -> FrameHeader {
+        FrameHeader::new(
+            EncodingFlag::Opus,
+            960,
+            48000,
+            2,
+            16,
+            Endianness::BigEndian,
+            id,
+            pts,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_flatbuffer_roundtrip() {
+        for encoding in [
+            EncodingFlag::PCMSigned,
+            EncodingFlag::PCMFloat,
+            EncodingFlag::Opus,
+            EncodingFlag::FLAC,
+            EncodingFlag::AAC,
+            EncodingFlag::H264,
+            EncodingFlag::Metadata,
+        ] {
+            for (id, pts) in [
+                (None, None),
+                (Some(0), None),
+                (None, Some(u64::MAX)),
+                (Some(42), Some(1_000_000)),
+            ] {
+                let header = FrameHeader::new(
+                    encoding,
+                    4095,
+                    96000,
+                    16,
+                    32,
+                    Endianness::LittleEndian,
+                    id,
+                    pts,
+                )
+                .unwrap();
+                let bytes = header.to_flatbuffer_bytes();
+                assert_eq!(FrameHeader::from_flatbuffer_bytes(&bytes), Ok(header));
+            }
+        }
+        // An ID of zero is still present, unlike a defaulted scalar.
+        let bytes = header(Some(0), None).to_flatbuffer_bytes();
+        assert_eq!(fb::root_as_frame_header(&bytes).unwrap().id(), Some(0));
+        assert_eq!(fb::root_as_frame_header(&bytes).unwrap().pts(), None);
+    }
+
+    #[test]
+    fn test_flatbuffer_size_vs_native() {
+        for (id, pts, native, flat) in [
+            (None, None, 4, 36),
+            (Some(7), None, 12, 48),
+            (Some(7), Some(9), 20, 56),
+        ] {
+            let header = header(id, pts);
+            let mut encoded = Vec::new();
+            header.encode(&mut encoded).unwrap();
+            assert_eq!(encoded.len(), native);
+            assert_eq!(header.to_flatbuffer_bytes().len(), flat);
+        }
+    }
+
+    #[test]
+    fn test_flatbuffer_rejects_invalid_input() {
+        assert!(matches!(
+            FrameHeader::from_flatbuffer_bytes(&[1, 2, 3]),
+            Err(FrameHeaderError::Invalid(_))
+        ));
+
+        let mut builder = FlatBufferBuilder::new();
+        let args = fb::FrameHeaderArgs {
+            encoding: fb::Encoding(9),
+            sample_size: 960,
+            sample_rate: 48000,
+            channels: 2,
+            bits_per_sample: 16,
+            ..Default::default()
+        };
+        let root = fb::FrameHeader::create(&mut builder, &args);
+        fb::finish_frame_header_buffer(&mut builder, root);
+        assert_eq!(
+            FrameHeader::from_flatbuffer_bytes(builder.finished_data()),
+            Err(FrameHeaderError::InvalidEncoding(9))
+        );
+
+        let mut builder = FlatBufferBuilder::new();
+        let root = fb::FrameHeader::create(
+            &mut builder,
+            &fb::FrameHeaderArgs {
+                encoding: fb::Encoding::Opus,
+                endianness: fb::Endianness(2),
+                ..args
+            },
+        );
+        fb::finish_frame_header_buffer(&mut builder, root);
+        assert_eq!(
+            FrameHeader::from_flatbuffer_bytes(builder.finished_data()),
+            Err(FrameHeaderError::FieldOutOfRange("endianness"))
+        );
+
+        // A well-formed buffer with fields `FrameHeader::new` rejects.
+        let mut builder = FlatBufferBuilder::new();
+        let root = fb::FrameHeader::create(
+            &mut builder,
+            &fb::FrameHeaderArgs {
+                encoding: fb::Encoding::Opus,
+                sample_rate: 22050,
+                ..args
+            },
+        );
+        fb::finish_frame_header_buffer(&mut builder, root);
+        assert!(FrameHeader::from_flatbuffer_bytes(builder.finished_data()).is_err());
+    }
+}
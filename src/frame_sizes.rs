@@ -0,0 +1,202 @@
+use crate::{EncodingFlag, FrameHeader, FrameHeaderError};
+
+// Opus frames of 2.5, 5, 10, 20, 40 and 60 ms at each supported rate.
+const OPUS_8K: [u16; 6] = [20, 40, 80, 160, 320, 480];
+const OPUS_12K: [u16; 6] = [30, 60, 120, 240, 480, 720];
+const OPUS_16K: [u16; 6] = [40, 80, 160, 320, 640, 960];
+const OPUS_24K: [u16; 6] = [60, 120, 240, 480, 960, 1440];
+const OPUS_48K: [u16; 6] = [120, 240, 480, 960, 1920, 2880];
+// Opus packets hold a whole number of 2.5 ms steps, up to 120 ms.
+const OPUS_STEPS_PER_SECOND: u32 = 400;
+const OPUS_MAX_STEPS: u32 = 48;
+const AAC: [u16; 1] = [1024];
+const FLAC: [u16; 10] = [192, 256, 512, 576, 1024, 1152, 2048, 2304, 4096, 4608];
+
+impl EncodingFlag {
+    /// Standard frame sizes in samples, smallest first. Empty for encodings
+    /// without fixed frame sizes or rates the codec does not support.
+    pub fn standard_frame_sizes(&self, sample_rate: u32) -> &'static [u16] {
+        match (self, sample_rate) {
+            (EncodingFlag::Opus, 8000) => &OPUS_8K,
+            (EncodingFlag::Opus, 12000) => &OPUS_12K,
+            (EncodingFlag::Opus, 16000) => &OPUS_16K,
+            (EncodingFlag::Opus, 24000) => &OPUS_24K,
+            (EncodingFlag::Opus, 48000) => &OPUS_48K,
+            (EncodingFlag::AAC, _) => &AAC,
+            (EncodingFlag::FLAC, _) => &FLAC,
+            _ => &[],
+        }
+    }
+
+    /// Smallest standard frame size of at least `min_sample_size` samples
+    /// whose duration exceeds `max_jitter_us`.
+    pub fn jitter_safe_sample_size(
+        &self,
+        sample_rate: u32,
+        max_jitter_us: u64,
+        min_sample_size: u16,
+    ) -> Result<u16, FrameHeaderError> {
+        if sample_rate == 0 {
+            return Err(FrameHeaderError::FieldOutOfRange("sample_rate"));
+        }
+        self.standard_frame_sizes(sample_rate)
+            .iter()
+            .copied()
+            .find(|&size| {
+                size >= min_sample_size
+                    && size as u128 * 1_000_000 > max_jitter_us as u128 * sample_rate as u128
+            })
+            .ok_or(FrameHeaderError::FieldOutOfRange("max_jitter_us"))
+    }
+}
+
+impl FrameHeader {
+    /// `jitter_safe_sample_size` for Opus.
+    pub fn jitter_safe_sample_size(
+        sample_rate: u32,
+        max_jitter_us: u64,
+        min_sample_size: u16,
+    ) -> Result<u16, FrameHeaderError> {
+        EncodingFlag::Opus.jitter_safe_sample_size(sample_rate, max_jitter_us, min_sample_size)
+    }
+
+    /// Cross-field checks a well-formed header can still fail: float PCM must
+    /// be 32-bit, and Opus must run at a rate it supports with a whole number
+    /// of 2.5 ms steps up to 120 ms. FLAC and AAC sizes are not checked: a
+    /// final FLAC block can be short, and HE-AAC frames hold 2048 samples.
+    pub fn validate_encoding_constraints(&self) -> Result<(), FrameHeaderError> {
+        if self.encoding == EncodingFlag::PCMFloat && self.bits_per_sample != 32 {
+            return Err(FrameHeaderError::FieldOutOfRange("bits_per_sample"));
+        }
+        if self.encoding == EncodingFlag::Opus {
+            // Opus has standard sizes at every rate it supports.
+            if EncodingFlag::Opus
+                .standard_frame_sizes(self.sample_rate)
+                .is_empty()
+            {
+                return Err(FrameHeaderError::FieldOutOfRange("sample_rate"));
+            }
+            let step = self.sample_rate / OPUS_STEPS_PER_SECOND;
+            let sample_size = self.sample_size as u32;
+            if sample_size == 0
+                || !sample_size.is_multiple_of(step)
+                || sample_size / step > OPUS_MAX_STEPS
+            {
+                return Err(FrameHeaderError::FieldOutOfRange("sample_size"));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Endianness;
+
+    #[test]
+    fn test_jitter_safe_sample_size() {
+        assert_eq!(
+            FrameHeader::jitter_safe_sample_size(48000, 40_000, 0),
+            Ok(2880)
+        );
+        assert_eq!(
+            FrameHeader::jitter_safe_sample_size(48000, 39_999, 0),
+            Ok(1920)
+        );
+        assert_eq!(FrameHeader::jitter_safe_sample_size(48000, 0, 0), Ok(120));
+        assert_eq!(
+            FrameHeader::jitter_safe_sample_size(48000, 5_000, 960),
+            Ok(960)
+        );
+        assert_eq!(
+            FrameHeader::jitter_safe_sample_size(16000, 15_000, 0),
+            Ok(320)
+        );
+        assert_eq!(
+            FrameHeader::jitter_safe_sample_size(48000, 60_000, 0),
+            Err(FrameHeaderError::FieldOutOfRange("max_jitter_us"))
+        );
+        assert!(FrameHeader::jitter_safe_sample_size(44100, 0, 0).is_err());
+        assert!(FrameHeader::jitter_safe_sample_size(0, 0, 0).is_err());
+
+        assert_eq!(
+            EncodingFlag::FLAC.jitter_safe_sample_size(44100, 20_000, 0),
+            Ok(1024)
+        );
+        assert!(EncodingFlag::PCMSigned
+            .standard_frame_sizes(48000)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_decode_validated() {
+        let encode = |encoding, sample_size, sample_rate, bits| {
+            let header = FrameHeader::new(
+                encoding,
+                sample_size,
+                sample_rate,
+                2,
+                bits,
+                Endianness::LittleEndian,
+                None,
+                Some(960),
+            )
+            .unwrap();
+            let mut bytes = Vec::new();
+            header.encode(&mut bytes).unwrap();
+            (header, bytes)
+        };
+
+        for (encoding, sample_size, sample_rate, bits) in [
+            (EncodingFlag::Opus, 960, 48000, 16),
+            (EncodingFlag::Opus, 320, 16000, 16),
+            (EncodingFlag::AAC, 1024, 44100, 16),
+            (EncodingFlag::FLAC, 2048, 96000, 24),
+            // A short final FLAC block, and an HE-AAC frame.
+            (EncodingFlag::FLAC, 1000, 48000, 16),
+            (EncodingFlag::AAC, 2048, 48000, 16),
+            // Multi-frame Opus packets, up to 120 ms.
+            (EncodingFlag::Opus, 2400, 48000, 16),
+            (EncodingFlag::Opus, 1920, 16000, 16),
+            (EncodingFlag::PCMFloat, 441, 44100, 32),
+            (EncodingFlag::PCMSigned, 441, 44100, 24),
+        ] {
+            let (header, bytes) = encode(encoding, sample_size, sample_rate, bits);
+            let mut reader = &bytes[..];
+            assert_eq!(FrameHeader::decode_validated(&mut reader).unwrap(), header);
+            assert!(reader.is_empty());
+        }
+
+        // Each of these decodes, but fails the cross-field checks.
+        for (encoding, sample_size, sample_rate, bits, field) in [
+            (EncodingFlag::Opus, 960, 44100, 16, "sample_rate"),
+            (EncodingFlag::Opus, 1000, 48000, 16, "sample_size"),
+            (EncodingFlag::Opus, 1960, 16000, 16, "sample_size"),
+            (EncodingFlag::PCMFloat, 441, 44100, 24, "bits_per_sample"),
+        ] {
+            let (header, bytes) = encode(encoding, sample_size, sample_rate, bits);
+            assert_eq!(FrameHeader::decode(&mut &bytes[..]).unwrap(), header);
+            assert_eq!(
+                header.validate_encoding_constraints(),
+                Err(FrameHeaderError::FieldOutOfRange(field))
+            );
+            let err = FrameHeader::decode_validated(&mut &bytes[..]).unwrap_err();
+            assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        }
+
+        // Decode errors come through unchanged.
+        assert_eq!(
+            FrameHeader::decode_validated(&mut &[0u8; 4][..])
+                .unwrap_err()
+                .kind(),
+            std::io::ErrorKind::InvalidData
+        );
+        assert_eq!(
+            FrameHeader::decode_validated(&mut &[0x2Au8][..])
+                .unwrap_err()
+                .kind(),
+            std::io::ErrorKind::UnexpectedEof
+        );
+    }
+}
@@ -0,0 +1,139 @@
+use crate::{EncodingFlag, Endianness, FrameHeader, FrameHeaderV2};
+
+pub fn fuzz_decode(data: &[u8]) {
+    if let Ok(header) = FrameHeader::decode(&mut &data[..]) {
+        // Every v1 bit is meaningful, so a successful decode must re-encode
+        // to exactly the bytes it consumed.
+        let mut encoded = Vec::with_capacity(header.size());
+        header.encode(&mut encoded).unwrap();
+        assert_eq!(encoded, data[..header.size()]);
+    }
+
+    let _ = FrameHeaderV2::decode(&mut &data[..]);
+}
+
+pub fn fuzz_validate(data: &[u8]) {
+    let _ = FrameHeader::validate_header(data);
+    let _ = FrameHeaderV2::validate_header(data);
+    let _ = FrameHeaderV2::header_size(data);
+}
+
+pub fn fuzz_extract_all(data: &[u8]) {
+    let _ = FrameHeader::extract_sample_count(data);
+    let _ = FrameHeader::extract_encoding(data);
+    let _ = FrameHeader::extract_id(data);
+    let _ = FrameHeader::extract_pts(data);
+    let _ = FrameHeaderV2::extract_payload_size(data);
+    let _ = FrameHeaderV2::extract_frame_count(data);
+    let _ = FrameHeaderV2::extract_pts(data);
+}
+
+pub fn fuzz_decode_batch(data: &[u8]) {
+    if let Ok(headers) = FrameHeader::decode_size_prefixed_batch(&mut &data[..]) {
+        let mut encoded = Vec::new();
+        FrameHeader::encode_size_prefixed_batch(&headers, &mut encoded).unwrap();
+        assert_eq!(encoded, data[..encoded.len()]);
+    }
+}
+
+pub fn fuzz_encode_decode_roundtrip(data: &[u8]) {
+    let mut fields = [0u8; 24];
+    let len = data.len().min(fields.len());
+    fields[..len].copy_from_slice(&data[..len]);
+
+    let encoding = match fields[0] % 6 {
+        0 => EncodingFlag::PCMSigned,
+        1 => EncodingFlag::PCMFloat,
+        2 => EncodingFlag::Opus,
+        3 => EncodingFlag::FLAC,
+        4 => EncodingFlag::AAC,
+        _ => EncodingFlag::H264,
+    };
+    let sample_size =
+        u16::from_be_bytes([fields[1], fields[2]]) % (FrameHeader::MAX_SAMPLE_SIZE + 1);
+    let sample_rate = FrameHeader::VALID_SAMPLE_RATES[fields[3] as usize % 4];
+    let channels = fields[4] % 16 + 1;
+    let bits_per_sample = [16, 24, 32][fields[5] as usize % 3];
+    let endianness = if fields[6] & 1 == 0 {
+        Endianness::LittleEndian
+    } else {
+        Endianness::BigEndian
+    };
+    let id = (fields[7] & 1 != 0).then(|| u64::from_be_bytes(fields[8..16].try_into().unwrap()));
+    let pts = (fields[7] & 2 != 0).then(|| u64::from_be_bytes(fields[16..24].try_into().unwrap()));
+
+    let header = FrameHeader::new(
+        encoding,
+        sample_size,
+        sample_rate,
+        channels,
+        bits_per_sample,
+        endianness,
+        id,
+        pts,
+    )
+    .unwrap();
+
+    let mut encoded = Vec::with_capacity(header.size());
+    header.encode(&mut encoded).unwrap();
+    assert_eq!(encoded.len(), header.size());
+    assert!(FrameHeader::validate_header(&encoded).unwrap());
+    assert_eq!(FrameHeader::decode(&mut &encoded[..]).unwrap(), header);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzz_targets_accept_arbitrary_input() {
+        let mut inputs: Vec<Vec<u8>> = vec![vec![], vec![0xA8], vec![0xFF; 40]];
+        for seed in 0u32..512 {
+            let mut input = Vec::with_capacity(40);
+            let mut state = seed.wrapping_mul(0x9E37_79B9) | 1;
+            for _ in 0..(seed % 40) {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                input.push(state as u8);
+            }
+            // Half of the inputs carry a valid v1 or v2 magic word.
+            if seed % 4 == 1 && !input.is_empty() {
+                input[0] = (input[0] & 0x03) | 0xA8;
+            } else if seed % 4 == 2 && !input.is_empty() {
+                input[0] = 0xAE;
+            }
+            inputs.push(input);
+        }
+
+        for input in &inputs {
+            fuzz_decode(input);
+            fuzz_validate(input);
+            fuzz_extract_all(input);
+            fuzz_encode_decode_roundtrip(input);
+            fuzz_decode_batch(input);
+        }
+    }
+
+    #[test]
+    fn test_fuzz_targets_accept_seed_corpus() {
+        let corpus = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("fuzz/corpus");
+        let targets = [
+            ("decode", fuzz_decode as fn(&[u8])),
+            ("validate", fuzz_validate),
+            ("extract_all", fuzz_extract_all),
+            ("encode_decode_roundtrip", fuzz_encode_decode_roundtrip),
+            ("decode_batch", fuzz_decode_batch),
+        ];
+
+        for (name, target) in targets {
+            let seeds = std::fs::read_dir(corpus.join(name)).unwrap();
+            let mut count = 0;
+            for seed in seeds {
+                target(&std::fs::read(seed.unwrap().path()).unwrap());
+                count += 1;
+            }
+            assert!(count >= 5, "{} corpus has only {} seeds", name, count);
+        }
+    }
+}
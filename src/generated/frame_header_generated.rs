@@ -0,0 +1,448 @@
+// automatically generated by the FlatBuffers compiler, do not modify
+// source: proto/frame_header.fbs
+// regenerate with: flatc --rust -o src/generated proto/frame_header.fbs
+
+// @generated
+
+use core::cmp::Ordering;
+use core::mem;
+
+extern crate flatbuffers;
+use self::flatbuffers::{EndianScalar, Follow};
+
+#[allow(unused_imports, dead_code)]
+pub mod frame_header {
+
+  use core::cmp::Ordering;
+  use core::mem;
+
+  extern crate flatbuffers;
+  use self::flatbuffers::{EndianScalar, Follow};
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[repr(transparent)]
+pub struct Encoding(pub u8);
+#[allow(non_upper_case_globals)]
+impl Encoding {
+  pub const PCMSigned: Self = Self(0);
+  pub const PCMFloat: Self = Self(1);
+  pub const Opus: Self = Self(2);
+  pub const FLAC: Self = Self(3);
+  pub const AAC: Self = Self(4);
+  pub const H264: Self = Self(5);
+  pub const Metadata: Self = Self(6);
+
+  pub const ENUM_MIN: u8 = 0;
+  pub const ENUM_MAX: u8 = 6;
+  pub const ENUM_VALUES: &'static [Self] = &[
+    Self::PCMSigned,
+    Self::PCMFloat,
+    Self::Opus,
+    Self::FLAC,
+    Self::AAC,
+    Self::H264,
+    Self::Metadata,
+  ];
+  /// Returns the variant's name or "" if unknown.
+  pub fn variant_name(self) -> Option<&'static str> {
+    match self {
+      Self::PCMSigned => Some("PCMSigned"),
+      Self::PCMFloat => Some("PCMFloat"),
+      Self::Opus => Some("Opus"),
+      Self::FLAC => Some("FLAC"),
+      Self::AAC => Some("AAC"),
+      Self::H264 => Some("H264"),
+      Self::Metadata => Some("Metadata"),
+      _ => None,
+    }
+  }
+}
+impl core::fmt::Debug for Encoding {
+  fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+    if let Some(name) = self.variant_name() {
+      f.write_str(name)
+    } else {
+      f.write_fmt(format_args!("<UNKNOWN {:?}>", self.0))
+    }
+  }
+}
+impl<'a> flatbuffers::Follow<'a> for Encoding {
+  type Inner = Self;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    let b = flatbuffers::read_scalar_at::<u8>(buf, loc);
+    Self(b)
+  }
+}
+
+impl flatbuffers::Push for Encoding {
+    type Output = Encoding;
+    #[inline]
+    unsafe fn push(&self, dst: &mut [u8], _written_len: usize) {
+        flatbuffers::emplace_scalar::<u8>(dst, self.0);
+    }
+}
+
+impl flatbuffers::EndianScalar for Encoding {
+  type Scalar = u8;
+  #[inline]
+  fn to_little_endian(self) -> u8 {
+    self.0.to_le()
+  }
+  #[inline]
+  #[allow(clippy::wrong_self_convention)]
+  fn from_little_endian(v: u8) -> Self {
+    let b = u8::from_le(v);
+    Self(b)
+  }
+}
+
+impl<'a> flatbuffers::Verifiable for Encoding {
+  #[inline]
+  fn run_verifier(
+    v: &mut flatbuffers::Verifier, pos: usize
+  ) -> Result<(), flatbuffers::InvalidFlatbuffer> {
+    use self::flatbuffers::Verifiable;
+    u8::run_verifier(v, pos)
+  }
+}
+
+impl flatbuffers::SimpleToVerifyInSlice for Encoding {}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[repr(transparent)]
+pub struct Endianness(pub u8);
+#[allow(non_upper_case_globals)]
+impl Endianness {
+  pub const LittleEndian: Self = Self(0);
+  pub const BigEndian: Self = Self(1);
+
+  pub const ENUM_MIN: u8 = 0;
+  pub const ENUM_MAX: u8 = 1;
+  pub const ENUM_VALUES: &'static [Self] = &[
+    Self::LittleEndian,
+    Self::BigEndian,
+  ];
+  /// Returns the variant's name or "" if unknown.
+  pub fn variant_name(self) -> Option<&'static str> {
+    match self {
+      Self::LittleEndian => Some("LittleEndian"),
+      Self::BigEndian => Some("BigEndian"),
+      _ => None,
+    }
+  }
+}
+impl core::fmt::Debug for Endianness {
+  fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+    if let Some(name) = self.variant_name() {
+      f.write_str(name)
+    } else {
+      f.write_fmt(format_args!("<UNKNOWN {:?}>", self.0))
+    }
+  }
+}
+impl<'a> flatbuffers::Follow<'a> for Endianness {
+  type Inner = Self;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    let b = flatbuffers::read_scalar_at::<u8>(buf, loc);
+    Self(b)
+  }
+}
+
+impl flatbuffers::Push for Endianness {
+    type Output = Endianness;
+    #[inline]
+    unsafe fn push(&self, dst: &mut [u8], _written_len: usize) {
+        flatbuffers::emplace_scalar::<u8>(dst, self.0);
+    }
+}
+
+impl flatbuffers::EndianScalar for Endianness {
+  type Scalar = u8;
+  #[inline]
+  fn to_little_endian(self) -> u8 {
+    self.0.to_le()
+  }
+  #[inline]
+  #[allow(clippy::wrong_self_convention)]
+  fn from_little_endian(v: u8) -> Self {
+    let b = u8::from_le(v);
+    Self(b)
+  }
+}
+
+impl<'a> flatbuffers::Verifiable for Endianness {
+  #[inline]
+  fn run_verifier(
+    v: &mut flatbuffers::Verifier, pos: usize
+  ) -> Result<(), flatbuffers::InvalidFlatbuffer> {
+    use self::flatbuffers::Verifiable;
+    u8::run_verifier(v, pos)
+  }
+}
+
+impl flatbuffers::SimpleToVerifyInSlice for Endianness {}
+
+pub enum FrameHeaderOffset {}
+#[derive(Copy, Clone, PartialEq)]
+
+pub struct FrameHeader<'a> {
+  pub _tab: flatbuffers::Table<'a>,
+}
+
+impl<'a> flatbuffers::Follow<'a> for FrameHeader<'a> {
+  type Inner = FrameHeader<'a>;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    Self { _tab: flatbuffers::Table::new(buf, loc) }
+  }
+}
+
+impl<'a> FrameHeader<'a> {
+  pub const VT_ENCODING: flatbuffers::VOffsetT = 4;
+  pub const VT_SAMPLE_SIZE: flatbuffers::VOffsetT = 6;
+  pub const VT_SAMPLE_RATE: flatbuffers::VOffsetT = 8;
+  pub const VT_CHANNELS: flatbuffers::VOffsetT = 10;
+  pub const VT_BITS_PER_SAMPLE: flatbuffers::VOffsetT = 12;
+  pub const VT_ENDIANNESS: flatbuffers::VOffsetT = 14;
+  pub const VT_ID: flatbuffers::VOffsetT = 16;
+  pub const VT_PTS: flatbuffers::VOffsetT = 18;
+
+  #[inline]
+  pub unsafe fn init_from_table(table: flatbuffers::Table<'a>) -> Self {
+    FrameHeader { _tab: table }
+  }
+  #[allow(unused_mut)]
+  pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr, A: flatbuffers::Allocator + 'bldr>(
+    _fbb: &'mut_bldr mut flatbuffers::FlatBufferBuilder<'bldr, A>,
+    args: &'args FrameHeaderArgs
+  ) -> flatbuffers::WIPOffset<FrameHeader<'bldr>> {
+    let mut builder = FrameHeaderBuilder::new(_fbb);
+    if let Some(x) = args.pts { builder.add_pts(x); }
+    if let Some(x) = args.id { builder.add_id(x); }
+    builder.add_sample_rate(args.sample_rate);
+    builder.add_sample_size(args.sample_size);
+    builder.add_endianness(args.endianness);
+    builder.add_bits_per_sample(args.bits_per_sample);
+    builder.add_channels(args.channels);
+    builder.add_encoding(args.encoding);
+    builder.finish()
+  }
+
+
+  #[inline]
+  pub fn encoding(&self) -> Encoding {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<Encoding>(FrameHeader::VT_ENCODING, Some(Encoding::PCMSigned)).unwrap()}
+  }
+  #[inline]
+  pub fn sample_size(&self) -> u16 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<u16>(FrameHeader::VT_SAMPLE_SIZE, Some(0)).unwrap()}
+  }
+  #[inline]
+  pub fn sample_rate(&self) -> u32 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<u32>(FrameHeader::VT_SAMPLE_RATE, Some(0)).unwrap()}
+  }
+  #[inline]
+  pub fn channels(&self) -> u8 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<u8>(FrameHeader::VT_CHANNELS, Some(0)).unwrap()}
+  }
+  #[inline]
+  pub fn bits_per_sample(&self) -> u8 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<u8>(FrameHeader::VT_BITS_PER_SAMPLE, Some(0)).unwrap()}
+  }
+  #[inline]
+  pub fn endianness(&self) -> Endianness {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<Endianness>(FrameHeader::VT_ENDIANNESS, Some(Endianness::LittleEndian)).unwrap()}
+  }
+  #[inline]
+  pub fn id(&self) -> Option<u64> {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<u64>(FrameHeader::VT_ID, None)}
+  }
+  #[inline]
+  pub fn pts(&self) -> Option<u64> {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<u64>(FrameHeader::VT_PTS, None)}
+  }
+}
+
+impl flatbuffers::Verifiable for FrameHeader<'_> {
+  #[inline]
+  fn run_verifier(
+    v: &mut flatbuffers::Verifier, pos: usize
+  ) -> Result<(), flatbuffers::InvalidFlatbuffer> {
+    use self::flatbuffers::Verifiable;
+    v.visit_table(pos)?
+     .visit_field::<Encoding>("encoding", Self::VT_ENCODING, false)?
+     .visit_field::<u16>("sample_size", Self::VT_SAMPLE_SIZE, false)?
+     .visit_field::<u32>("sample_rate", Self::VT_SAMPLE_RATE, false)?
+     .visit_field::<u8>("channels", Self::VT_CHANNELS, false)?
+     .visit_field::<u8>("bits_per_sample", Self::VT_BITS_PER_SAMPLE, false)?
+     .visit_field::<Endianness>("endianness", Self::VT_ENDIANNESS, false)?
+     .visit_field::<u64>("id", Self::VT_ID, false)?
+     .visit_field::<u64>("pts", Self::VT_PTS, false)?
+     .finish();
+    Ok(())
+  }
+}
+pub struct FrameHeaderArgs {
+    pub encoding: Encoding,
+    pub sample_size: u16,
+    pub sample_rate: u32,
+    pub channels: u8,
+    pub bits_per_sample: u8,
+    pub endianness: Endianness,
+    pub id: Option<u64>,
+    pub pts: Option<u64>,
+}
+impl<'a> Default for FrameHeaderArgs {
+  #[inline]
+  fn default() -> Self {
+    FrameHeaderArgs {
+      encoding: Encoding::PCMSigned,
+      sample_size: 0,
+      sample_rate: 0,
+      channels: 0,
+      bits_per_sample: 0,
+      endianness: Endianness::LittleEndian,
+      id: None,
+      pts: None,
+    }
+  }
+}
+
+pub struct FrameHeaderBuilder<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> {
+  fbb_: &'b mut flatbuffers::FlatBufferBuilder<'a, A>,
+  start_: flatbuffers::WIPOffset<flatbuffers::TableUnfinishedWIPOffset>,
+}
+impl<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> FrameHeaderBuilder<'a, 'b, A> {
+  #[inline]
+  pub fn add_encoding(&mut self, encoding: Encoding) {
+    self.fbb_.push_slot::<Encoding>(FrameHeader::VT_ENCODING, encoding, Encoding::PCMSigned);
+  }
+  #[inline]
+  pub fn add_sample_size(&mut self, sample_size: u16) {
+    self.fbb_.push_slot::<u16>(FrameHeader::VT_SAMPLE_SIZE, sample_size, 0);
+  }
+  #[inline]
+  pub fn add_sample_rate(&mut self, sample_rate: u32) {
+    self.fbb_.push_slot::<u32>(FrameHeader::VT_SAMPLE_RATE, sample_rate, 0);
+  }
+  #[inline]
+  pub fn add_channels(&mut self, channels: u8) {
+    self.fbb_.push_slot::<u8>(FrameHeader::VT_CHANNELS, channels, 0);
+  }
+  #[inline]
+  pub fn add_bits_per_sample(&mut self, bits_per_sample: u8) {
+    self.fbb_.push_slot::<u8>(FrameHeader::VT_BITS_PER_SAMPLE, bits_per_sample, 0);
+  }
+  #[inline]
+  pub fn add_endianness(&mut self, endianness: Endianness) {
+    self.fbb_.push_slot::<Endianness>(FrameHeader::VT_ENDIANNESS, endianness, Endianness::LittleEndian);
+  }
+  #[inline]
+  pub fn add_id(&mut self, id: u64) {
+    self.fbb_.push_slot_always::<u64>(FrameHeader::VT_ID, id);
+  }
+  #[inline]
+  pub fn add_pts(&mut self, pts: u64) {
+    self.fbb_.push_slot_always::<u64>(FrameHeader::VT_PTS, pts);
+  }
+  #[inline]
+  pub fn new(_fbb: &'b mut flatbuffers::FlatBufferBuilder<'a, A>) -> FrameHeaderBuilder<'a, 'b, A> {
+    let start = _fbb.start_table();
+    FrameHeaderBuilder {
+      fbb_: _fbb,
+      start_: start,
+    }
+  }
+  #[inline]
+  pub fn finish(self) -> flatbuffers::WIPOffset<FrameHeader<'a>> {
+    let o = self.fbb_.end_table(self.start_);
+    flatbuffers::WIPOffset::new(o.value())
+  }
+}
+
+impl core::fmt::Debug for FrameHeader<'_> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    let mut ds = f.debug_struct("FrameHeader");
+      ds.field("encoding", &self.encoding());
+      ds.field("sample_size", &self.sample_size());
+      ds.field("sample_rate", &self.sample_rate());
+      ds.field("channels", &self.channels());
+      ds.field("bits_per_sample", &self.bits_per_sample());
+      ds.field("endianness", &self.endianness());
+      ds.field("id", &self.id());
+      ds.field("pts", &self.pts());
+      ds.finish()
+  }
+}
+#[inline]
+/// Verifies that a buffer of bytes contains a `FrameHeader`
+/// and returns it.
+/// Note that verification is still experimental and may not
+/// catch every error, or be maximally performant. For the
+/// previous, unchecked, behavior use
+/// `root_as_frame_header_unchecked`.
+pub fn root_as_frame_header(buf: &[u8]) -> Result<FrameHeader, flatbuffers::InvalidFlatbuffer> {
+  flatbuffers::root::<FrameHeader>(buf)
+}
+#[inline]
+/// Verifies that a buffer of bytes contains a size prefixed
+/// `FrameHeader` and returns it.
+/// Note that verification is still experimental and may not
+/// catch every error, or be maximally performant. For the
+/// previous, unchecked, behavior use
+/// `size_prefixed_root_as_frame_header_unchecked`.
+pub fn size_prefixed_root_as_frame_header(buf: &[u8]) -> Result<FrameHeader, flatbuffers::InvalidFlatbuffer> {
+  flatbuffers::size_prefixed_root::<FrameHeader>(buf)
+}
+#[inline]
+/// Assumes, without verification, that a buffer of bytes contains a FrameHeader and returns it.
+/// # Safety
+/// Callers must trust the given bytes do indeed contain a valid `FrameHeader`.
+pub unsafe fn root_as_frame_header_unchecked(buf: &[u8]) -> FrameHeader {
+  flatbuffers::root_unchecked::<FrameHeader>(buf)
+}
+#[inline]
+/// Assumes, without verification, that a buffer of bytes contains a size prefixed FrameHeader and returns it.
+/// # Safety
+/// Callers must trust the given bytes do indeed contain a valid size prefixed `FrameHeader`.
+pub unsafe fn size_prefixed_root_as_frame_header_unchecked(buf: &[u8]) -> FrameHeader {
+  flatbuffers::size_prefixed_root_unchecked::<FrameHeader>(buf)
+}
+#[inline]
+pub fn finish_frame_header_buffer<'a, 'b, A: flatbuffers::Allocator + 'a>(
+    fbb: &'b mut flatbuffers::FlatBufferBuilder<'a, A>,
+    root: flatbuffers::WIPOffset<FrameHeader<'a>>) {
+  fbb.finish(root, None);
+}
+
+#[inline]
+pub fn finish_size_prefixed_frame_header_buffer<'a, 'b, A: flatbuffers::Allocator + 'a>(fbb: &'b mut flatbuffers::FlatBufferBuilder<'a, A>, root: flatbuffers::WIPOffset<FrameHeader<'a>>) {
+  fbb.finish_size_prefixed(root, None);
+}
+}  // pub mod frame_header
@@ -0,0 +1,243 @@
+use crate::{EncodingFlag, Endianness, FrameHeader, FrameHeaderBuilder, FrameHeaderError};
+use Endianness::{BigEndian, LittleEndian};
+
+// `audio/x-raw` formats by (encoding, bits, endianness). 24-bit samples are
+// packed, as in `S24LE`, not the `S24_32LE` container format.
+const PCM_FORMATS: [(EncodingFlag, u8, Endianness, &str); 8] = [
+    (EncodingFlag::PCMSigned, 16, LittleEndian, "S16LE"),
+    (EncodingFlag::PCMSigned, 16, BigEndian, "S16BE"),
+    (EncodingFlag::PCMSigned, 24, LittleEndian, "S24LE"),
+    (EncodingFlag::PCMSigned, 24, BigEndian, "S24BE"),
+    (EncodingFlag::PCMSigned, 32, LittleEndian, "S32LE"),
+    (EncodingFlag::PCMSigned, 32, BigEndian, "S32BE"),
+    (EncodingFlag::PCMFloat, 32, LittleEndian, "F32LE"),
+    (EncodingFlag::PCMFloat, 32, BigEndian, "F32BE"),
+];
+
+impl FrameHeader {
+    /// Caps for an appsrc, e.g.
+    /// `audio/x-raw,format=S24LE,layout=interleaved,rate=48000,channels=2`.
+    /// PCM payloads are always interleaved.
+    pub fn to_gst_caps(&self) -> Result<String, FrameHeaderError> {
+        let media = match self.encoding {
+            EncodingFlag::PCMSigned | EncodingFlag::PCMFloat => {
+                let format = PCM_FORMATS
+                    .iter()
+                    .find(|(encoding, bits, endianness, _)| {
+                        (*encoding, *bits, *endianness)
+                            == (self.encoding, self.bits_per_sample, self.endianness)
+                    })
+                    .map(|(.., format)| format)
+                    .ok_or(FrameHeaderError::FieldOutOfRange("bits_per_sample"))?;
+                format!("audio/x-raw,format={},layout=interleaved", format)
+            }
+            EncodingFlag::Opus => "audio/x-opus".to_string(),
+            EncodingFlag::FLAC => "audio/x-flac,framed=true".to_string(),
+            EncodingFlag::AAC => "audio/mpeg,mpegversion=4".to_string(),
+            EncodingFlag::H264 => return Ok("video/x-h264".to_string()),
+            EncodingFlag::Metadata | EncodingFlag::Unknown(_) => {
+                return Err(FrameHeaderError::InvalidEncoding(
+                    self.encoding.code() as u32
+                ))
+            }
+        };
+        Ok(format!(
+            "{},rate={},channels={}",
+            media, self.sample_rate, self.channels
+        ))
+    }
+
+    /// Parses caps in the form `to_gst_caps` writes. Field values may carry a
+    /// GStreamer type prefix such as `(int)48000`, and fields other than
+    /// format, layout, rate, channels and mpegversion are ignored. Absent
+    /// fields keep the builder defaults.
+    pub fn from_gst_caps(caps: &str) -> Result<FrameHeaderBuilder, FrameHeaderError> {
+        let mut fields = caps.split(',');
+        let media = fields.next().unwrap_or("").trim();
+
+        let mut format = None;
+        let mut layout = None;
+        let mut rate = None;
+        let mut channels = None;
+        let mut mpegversion = None;
+        for field in fields {
+            let Some((name, value)) = field.split_once('=') else {
+                continue;
+            };
+            let value = value.trim();
+            let value = match value.strip_prefix('(') {
+                Some(typed) => typed.split_once(')').map_or(value, |(_, value)| value),
+                None => value,
+            };
+            match name.trim() {
+                "format" => format = Some(value),
+                "layout" => layout = Some(value),
+                "rate" => rate = Some(value),
+                "channels" => channels = Some(value),
+                "mpegversion" => mpegversion = Some(value),
+                _ => {}
+            }
+        }
+
+        let mut builder = FrameHeader::builder();
+        builder = match media {
+            "audio/x-raw" => {
+                if layout.is_some_and(|layout| layout != "interleaved") {
+                    return Err(FrameHeaderError::FieldOutOfRange("layout"));
+                }
+                let format = format.ok_or(FrameHeaderError::FieldOutOfRange("format"))?;
+                let (encoding, bits_per_sample, endianness, _) = PCM_FORMATS
+                    .iter()
+                    .find(|(.., name)| *name == format)
+                    .ok_or(FrameHeaderError::FieldOutOfRange("format"))?;
+                builder
+                    .encoding(*encoding)
+                    .bits_per_sample(*bits_per_sample)
+                    .endianness(*endianness)
+            }
+            "audio/x-opus" => builder.encoding(EncodingFlag::Opus).sample_rate(48000),
+            "audio/x-flac" => builder.encoding(EncodingFlag::FLAC),
+            // mpegversion 1 is MP3.
+            "audio/mpeg" if matches!(mpegversion, Some("2" | "4")) => {
+                builder.encoding(EncodingFlag::AAC)
+            }
+            "video/x-h264" => builder.encoding(EncodingFlag::H264),
+            _ => {
+                return Err(FrameHeaderError::Invalid(format!(
+                    "Unsupported caps: {}",
+                    caps
+                )))
+            }
+        };
+
+        if let Some(rate) = rate {
+            builder = builder.sample_rate(
+                rate.parse()
+                    .map_err(|_| FrameHeaderError::FieldOutOfRange("rate"))?,
+            );
+        }
+        if let Some(channels) = channels {
+            builder = builder.channels(
+                channels
+                    .parse()
+                    .map_err(|_| FrameHeaderError::FieldOutOfRange("channels"))?,
+            );
+        }
+        Ok(builder)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(encoding: EncodingFlag, bits: u8, endianness: Endianness) -> FrameHeader {
+        FrameHeader::new(encoding, 960, 48000, 2, bits, endianness, None, None).unwrap()
+    }
+
+    fn parse(caps: &str) -> FrameHeader {
+        FrameHeader::from_gst_caps(caps)
+            .unwrap()
+            .sample_size(960)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_gst_caps_pcm_matrix() {
+        let cases = [
+            (EncodingFlag::PCMSigned, 16, LittleEndian, Some("S16LE")),
+            (EncodingFlag::PCMSigned, 16, BigEndian, Some("S16BE")),
+            (EncodingFlag::PCMSigned, 24, LittleEndian, Some("S24LE")),
+            (EncodingFlag::PCMSigned, 24, BigEndian, Some("S24BE")),
+            (EncodingFlag::PCMSigned, 32, LittleEndian, Some("S32LE")),
+            (EncodingFlag::PCMSigned, 32, BigEndian, Some("S32BE")),
+            (EncodingFlag::PCMFloat, 16, LittleEndian, None),
+            (EncodingFlag::PCMFloat, 24, BigEndian, None),
+            (EncodingFlag::PCMFloat, 32, LittleEndian, Some("F32LE")),
+            (EncodingFlag::PCMFloat, 32, BigEndian, Some("F32BE")),
+        ];
+        for (encoding, bits, endianness, format) in cases {
+            let header = header(encoding, bits, endianness);
+            let Some(format) = format else {
+                assert_eq!(
+                    header.to_gst_caps(),
+                    Err(FrameHeaderError::FieldOutOfRange("bits_per_sample"))
+                );
+                continue;
+            };
+            let caps = header.to_gst_caps().unwrap();
+            assert_eq!(
+                caps,
+                format!(
+                    "audio/x-raw,format={},layout=interleaved,rate=48000,channels=2",
+                    format
+                )
+            );
+            assert_eq!(parse(&caps), header);
+        }
+
+        // gst_caps_to_string output, with spaces and type prefixes.
+        let typed = parse(
+            "audio/x-raw, format=(string)S24LE, layout=(string)interleaved, \
+             rate=(int)44100, channels=(int)6, channel-mask=(bitmask)0x3f",
+        );
+        assert_eq!(typed.bits_per_sample(), 24);
+        assert_eq!((typed.sample_rate(), typed.channels()), (44100, 6));
+    }
+
+    #[test]
+    fn test_gst_caps_compressed() {
+        let cases = [
+            (EncodingFlag::Opus, "audio/x-opus,rate=48000,channels=2"),
+            (
+                EncodingFlag::FLAC,
+                "audio/x-flac,framed=true,rate=48000,channels=2",
+            ),
+            (
+                EncodingFlag::AAC,
+                "audio/mpeg,mpegversion=4,rate=48000,channels=2",
+            ),
+        ];
+        for (encoding, caps) in cases {
+            let header = header(encoding, 16, LittleEndian);
+            assert_eq!(header.to_gst_caps().unwrap(), caps);
+            assert_eq!(parse(caps), header);
+        }
+
+        let h264 = header(EncodingFlag::H264, 16, LittleEndian);
+        assert_eq!(h264.to_gst_caps().unwrap(), "video/x-h264");
+        assert_eq!(parse("video/x-h264").encoding(), EncodingFlag::H264);
+        assert_eq!(
+            header(EncodingFlag::Unknown(7), 16, LittleEndian).to_gst_caps(),
+            Err(FrameHeaderError::InvalidEncoding(7))
+        );
+    }
+
+    #[test]
+    fn test_gst_caps_rejects_unsupported() {
+        assert_eq!(
+            FrameHeader::from_gst_caps("audio/x-raw,format=S16LE,layout=non-interleaved"),
+            Err(FrameHeaderError::FieldOutOfRange("layout"))
+        );
+        assert_eq!(
+            FrameHeader::from_gst_caps("audio/x-raw,format=U8"),
+            Err(FrameHeaderError::FieldOutOfRange("format"))
+        );
+        assert_eq!(
+            FrameHeader::from_gst_caps("audio/x-raw,rate=48000"),
+            Err(FrameHeaderError::FieldOutOfRange("format"))
+        );
+        assert_eq!(
+            FrameHeader::from_gst_caps("audio/x-opus,channels=two"),
+            Err(FrameHeaderError::FieldOutOfRange("channels"))
+        );
+        assert!(FrameHeader::from_gst_caps("audio/mpeg,mpegversion=1").is_err());
+        assert!(FrameHeader::from_gst_caps("video/x-vp9").is_err());
+        // Parses, but 22.05 kHz is not a v1 sample rate.
+        assert!(FrameHeader::from_gst_caps("audio/x-flac,rate=22050")
+            .unwrap()
+            .build()
+            .is_err());
+    }
+}
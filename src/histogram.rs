@@ -0,0 +1,156 @@
+use crate::FrameHeader;
+
+const BASE_SIZE: usize = 4;
+const ONE_FIELD_SIZE: usize = 12;
+const BOTH_FIELDS_SIZE: usize = 20;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct HeaderSizeHistogram {
+    pub base_only: u64,
+    pub with_id_only: u64,
+    pub with_pts_only: u64,
+    pub with_id_and_pts: u64,
+}
+
+impl HeaderSizeHistogram {
+    pub fn add(&mut self, header: &FrameHeader) {
+        match (header.id().is_some(), header.pts().is_some()) {
+            (false, false) => self.base_only += 1,
+            (true, false) => self.with_id_only += 1,
+            (false, true) => self.with_pts_only += 1,
+            (true, true) => self.with_id_and_pts += 1,
+        }
+    }
+
+    /// ID-only and PTS-only headers are both 12 bytes and count together.
+    /// Ties go to the smaller size; an empty histogram reports the base size.
+    pub fn most_common_size(&self) -> usize {
+        self.sizes()
+            .into_iter()
+            .rev()
+            .max_by_key(|&(_, count)| count)
+            .map_or(BASE_SIZE, |(size, _)| size)
+    }
+
+    pub fn total_header_bytes(&self) -> u64 {
+        self.sizes()
+            .iter()
+            .map(|&(size, count)| size as u64 * count)
+            .sum()
+    }
+
+    pub fn total_frames(&self) -> u64 {
+        self.base_only + self.with_id_only + self.with_pts_only + self.with_id_and_pts
+    }
+
+    pub fn encode_distribution_csv(&self) -> String {
+        let mut csv = String::from("kind,header_size,frames,header_bytes\n");
+        for (kind, size, count) in [
+            ("base_only", BASE_SIZE, self.base_only),
+            ("with_id_only", ONE_FIELD_SIZE, self.with_id_only),
+            ("with_pts_only", ONE_FIELD_SIZE, self.with_pts_only),
+            ("with_id_and_pts", BOTH_FIELDS_SIZE, self.with_id_and_pts),
+        ] {
+            csv.push_str(&format!(
+                "{},{},{},{}\n",
+                kind,
+                size,
+                count,
+                size as u64 * count
+            ));
+        }
+        csv
+    }
+
+    fn sizes(&self) -> [(usize, u64); 3] {
+        [
+            (BASE_SIZE, self.base_only),
+            (ONE_FIELD_SIZE, self.with_id_only + self.with_pts_only),
+            (BOTH_FIELDS_SIZE, self.with_id_and_pts),
+        ]
+    }
+}
+
+impl<'a> FromIterator<&'a FrameHeader> for HeaderSizeHistogram {
+    fn from_iter<I: IntoIterator<Item = &'a FrameHeader>>(headers: I) -> Self {
+        let mut histogram = HeaderSizeHistogram::default();
+        for header in headers {
+            histogram.add(header);
+        }
+        histogram
+    }
+}
+
+impl FrameHeader {
+    pub fn size_histogram<'a, I: IntoIterator<Item = &'a FrameHeader>>(
+        headers: I,
+    ) -> HeaderSizeHistogram {
+        headers.into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EncodingFlag, Endianness};
+
+    fn header(id: Option<u64>, pts: Option<u64>) -> FrameHeader {
+        FrameHeader::new(
+            EncodingFlag::Opus,
+            960,
+            48000,
+            2,
+            16,
+            Endianness::LittleEndian,
+            id,
+            pts,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_size_histogram_counts_mixed_headers() {
+        let mut headers = Vec::new();
+        headers.extend((0..5).map(|_| header(None, None)));
+        headers.extend((0..3).map(|i| header(Some(i), None)));
+        headers.extend((0..4).map(|i| header(None, Some(i * 960))));
+        headers.extend((0..6).map(|i| header(Some(i), Some(i * 960))));
+
+        let histogram = FrameHeader::size_histogram(&headers);
+        assert_eq!(
+            histogram,
+            HeaderSizeHistogram {
+                base_only: 5,
+                with_id_only: 3,
+                with_pts_only: 4,
+                with_id_and_pts: 6,
+            }
+        );
+        assert_eq!(histogram.total_frames(), 18);
+        assert_eq!(
+            histogram.total_header_bytes(),
+            headers.iter().map(|h| h.size() as u64).sum::<u64>()
+        );
+        // Seven 12-byte headers outnumber six 20-byte ones.
+        assert_eq!(histogram.most_common_size(), 12);
+        assert_eq!(
+            histogram.encode_distribution_csv(),
+            "kind,header_size,frames,header_bytes\n\
+             base_only,4,5,20\n\
+             with_id_only,12,3,36\n\
+             with_pts_only,12,4,48\n\
+             with_id_and_pts,20,6,120\n"
+        );
+    }
+
+    #[test]
+    fn test_size_histogram_most_common_size_ties_and_empty() {
+        assert_eq!(HeaderSizeHistogram::default().most_common_size(), 4);
+        assert_eq!(HeaderSizeHistogram::default().total_header_bytes(), 0);
+
+        let tied: HeaderSizeHistogram = [header(Some(1), Some(2)), header(None, None)]
+            .iter()
+            .collect();
+        assert_eq!(tied.most_common_size(), 4);
+    }
+}
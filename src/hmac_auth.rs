@@ -0,0 +1,153 @@
+use crate::FrameHeader;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::io::{self, Read, Write};
+
+pub(crate) type HmacSha256 = Hmac<Sha256>;
+
+pub const HMAC_TAG_SIZE: usize = 32;
+
+// An HMAC-SHA256 keyed with `key`, shared with the `auth` feature's v2 tags.
+pub(crate) fn keyed_mac(key: &[u8]) -> HmacSha256 {
+    // HMAC takes keys of any length.
+    HmacSha256::new_from_slice(key).expect("HMAC accepts any key length")
+}
+
+fn mac(key: &[u8], header_bytes: &[u8]) -> HmacSha256 {
+    let mut mac = keyed_mac(key);
+    mac.update(header_bytes);
+    mac
+}
+
+// Keeps a copy of everything read, so the tag covers the exact wire bytes.
+struct Recording<'a, R> {
+    inner: &'a mut R,
+    bytes: Vec<u8>,
+}
+
+impl<R: Read> Read for Recording<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.bytes.extend_from_slice(&buf[..read]);
+        Ok(read)
+    }
+}
+
+impl FrameHeader {
+    /// Encodes the header followed by an HMAC-SHA256 tag over its bytes.
+    ///
+    /// The tag proves the header came from a key holder unmodified, but not
+    /// that it is fresh: a recorded header and tag verify again when
+    /// replayed. Reject replays by checking the ID or PTS, for example with
+    /// `ensure_monotonic`.
+    pub fn encode_with_hmac<W: Write>(&self, key: &[u8], writer: &mut W) -> io::Result<()> {
+        let mut bytes = Vec::with_capacity(self.size_with_hmac());
+        self.encode(&mut bytes)?;
+        let tag = mac(key, &bytes).finalize().into_bytes();
+        bytes.extend_from_slice(&tag);
+        writer.write_all(&bytes)
+    }
+
+    /// Decodes a header written by `encode_with_hmac`, comparing tags in
+    /// constant time. A wrong tag is `InvalidData`.
+    pub fn decode_with_hmac_verify<R: Read>(key: &[u8], reader: &mut R) -> io::Result<FrameHeader> {
+        let mut recording = Recording {
+            inner: reader,
+            bytes: Vec::with_capacity(20),
+        };
+        let header = FrameHeader::decode(&mut recording)?;
+        let mut tag = [0u8; HMAC_TAG_SIZE];
+        recording.inner.read_exact(&mut tag)?;
+
+        mac(key, &recording.bytes)
+            .verify_slice(&tag)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "HMAC verification failed"))?;
+        Ok(header)
+    }
+
+    pub fn size_with_hmac(&self) -> usize {
+        self.size() + HMAC_TAG_SIZE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ensure_monotonic, EncodingFlag, Endianness, MonotonicPolicy};
+
+    const KEY: &[u8] = b"shared secret";
+
+    fn header(pts: u64) -> FrameHeader {
+        FrameHeader::new(
+            EncodingFlag::Opus,
+            960,
+            48000,
+            2,
+            16,
+            Endianness::LittleEndian,
+            Some(7),
+            Some(pts),
+        )
+        .unwrap()
+    }
+
+    fn signed(header: &FrameHeader) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        header.encode_with_hmac(KEY, &mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_hmac_roundtrip() {
+        let header = header(20_000);
+        let bytes = signed(&header);
+        assert_eq!(bytes.len(), header.size_with_hmac());
+        assert_eq!(bytes.len(), 20 + 32);
+
+        let mut reader = &bytes[..];
+        assert_eq!(
+            FrameHeader::decode_with_hmac_verify(KEY, &mut reader).unwrap(),
+            header
+        );
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn test_hmac_rejects_modified_headers() {
+        let bytes = signed(&header(20_000));
+        // Flip one bit anywhere in the header or the tag.
+        for index in 0..bytes.len() {
+            let mut modified = bytes.clone();
+            modified[index] ^= 0x01;
+            let result = FrameHeader::decode_with_hmac_verify(KEY, &mut &modified[..]);
+            assert!(result.is_err(), "byte {}", index);
+        }
+
+        let err = FrameHeader::decode_with_hmac_verify(b"wrong key", &mut &bytes[..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        let err =
+            FrameHeader::decode_with_hmac_verify(KEY, &mut &bytes[..bytes.len() - 1]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn test_hmac_replayed_headers() {
+        // A replayed header carries a valid tag, so the MAC alone accepts it...
+        let first = signed(&header(20_000));
+        let second = signed(&header(40_000));
+        let stream = [first.clone(), second, first].concat();
+        let mut reader = &stream[..];
+        let decoded: Vec<FrameHeader> = (0..3)
+            .map(|_| FrameHeader::decode_with_hmac_verify(KEY, &mut reader).unwrap())
+            .collect();
+
+        // ...and the PTS check catches it.
+        let checked: Vec<_> = ensure_monotonic(
+            decoded.into_iter().map(|header| (header, ())),
+            MonotonicPolicy::Error,
+        )
+        .collect();
+        assert!(checked[0].is_ok() && checked[1].is_ok());
+        assert!(checked[2].is_err());
+    }
+}
@@ -0,0 +1,355 @@
+use crate::{FrameHeaderV2, FrameReader, HeadersOnly};
+use std::io::{self, Read, Seek, Write};
+
+// Index file layout (big-endian):
+//   magic "FHIX" | version u16 | reserved u16
+//   then one 28-byte record per frame: offset u64 | pts u64 | id u64 | flags u32
+const INDEX_MAGIC: [u8; 4] = *b"FHIX";
+const INDEX_VERSION: u16 = 1;
+const INDEX_PREAMBLE_SIZE: usize = 8;
+const RECORD_SIZE: usize = 28;
+
+const RECORD_PTS_PRESENT: u32 = 1 << 0;
+const RECORD_ID_PRESENT: u32 = 1 << 1;
+const RECORD_KEYFRAME: u32 = 1 << 2;
+const RECORD_DISCONTINUITY: u32 = 1 << 3;
+const RECORD_KNOWN_FLAGS: u32 =
+    RECORD_PTS_PRESENT | RECORD_ID_PRESENT | RECORD_KEYFRAME | RECORD_DISCONTINUITY;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct IndexEntry {
+    pub offset: u64,
+    pub pts: Option<u64>,
+    pub id: Option<u64>,
+    pub keyframe: bool,
+    pub discontinuity: bool,
+}
+
+impl IndexEntry {
+    pub fn from_header(offset: u64, header: &FrameHeaderV2, keyframe: bool) -> Self {
+        IndexEntry {
+            offset,
+            pts: header.pts(),
+            id: header.id(),
+            keyframe,
+            discontinuity: header.packet_flags() & FrameHeaderV2::FLAG_DISCONTINUITY != 0,
+        }
+    }
+
+    fn encode(&self) -> [u8; RECORD_SIZE] {
+        let mut flags = 0;
+        if self.pts.is_some() {
+            flags |= RECORD_PTS_PRESENT;
+        }
+        if self.id.is_some() {
+            flags |= RECORD_ID_PRESENT;
+        }
+        if self.keyframe {
+            flags |= RECORD_KEYFRAME;
+        }
+        if self.discontinuity {
+            flags |= RECORD_DISCONTINUITY;
+        }
+
+        let mut record = [0u8; RECORD_SIZE];
+        record[..8].copy_from_slice(&self.offset.to_be_bytes());
+        record[8..16].copy_from_slice(&self.pts.unwrap_or(0).to_be_bytes());
+        record[16..24].copy_from_slice(&self.id.unwrap_or(0).to_be_bytes());
+        record[24..].copy_from_slice(&flags.to_be_bytes());
+        record
+    }
+
+    fn decode(record: &[u8; RECORD_SIZE]) -> io::Result<Self> {
+        let flags = u32::from_be_bytes(record[24..].try_into().unwrap());
+        if flags & !RECORD_KNOWN_FLAGS != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Unknown index record flags",
+            ));
+        }
+
+        Ok(IndexEntry {
+            offset: u64::from_be_bytes(record[..8].try_into().unwrap()),
+            pts: (flags & RECORD_PTS_PRESENT != 0)
+                .then(|| u64::from_be_bytes(record[8..16].try_into().unwrap())),
+            id: (flags & RECORD_ID_PRESENT != 0)
+                .then(|| u64::from_be_bytes(record[16..24].try_into().unwrap())),
+            keyframe: flags & RECORD_KEYFRAME != 0,
+            discontinuity: flags & RECORD_DISCONTINUITY != 0,
+        })
+    }
+}
+
+pub struct IndexWriter<W: Write> {
+    writer: W,
+    entries: u64,
+}
+
+impl<W: Write> IndexWriter<W> {
+    pub fn new(mut writer: W) -> io::Result<Self> {
+        writer.write_all(&INDEX_MAGIC)?;
+        writer.write_all(&INDEX_VERSION.to_be_bytes())?;
+        writer.write_all(&[0, 0])?;
+        Ok(IndexWriter { writer, entries: 0 })
+    }
+
+    /// Records a frame using the offset returned by `FrameWriter::write_frame`.
+    pub fn record(
+        &mut self,
+        offset: u64,
+        header: &FrameHeaderV2,
+        keyframe: bool,
+    ) -> io::Result<()> {
+        self.push(&IndexEntry::from_header(offset, header, keyframe))
+    }
+
+    pub fn push(&mut self, entry: &IndexEntry) -> io::Result<()> {
+        self.writer.write_all(&entry.encode())?;
+        self.entries += 1;
+        Ok(())
+    }
+
+    pub fn entries(&self) -> u64 {
+        self.entries
+    }
+
+    pub fn finish(mut self) -> io::Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct IndexFile {
+    entries: Vec<IndexEntry>,
+    // Indices into `entries` of frames carrying a PTS, ordered by PTS.
+    by_pts: Vec<usize>,
+    has_keyframes: bool,
+}
+
+impl IndexFile {
+    pub fn from_entries(entries: Vec<IndexEntry>) -> Self {
+        let mut by_pts: Vec<usize> = (0..entries.len())
+            .filter(|&index| entries[index].pts.is_some())
+            .collect();
+        by_pts.sort_by_key(|&index| (entries[index].pts, entries[index].offset));
+        let has_keyframes = entries.iter().any(|entry| entry.keyframe);
+
+        IndexFile {
+            entries,
+            by_pts,
+            has_keyframes,
+        }
+    }
+
+    pub fn load<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut preamble = [0u8; INDEX_PREAMBLE_SIZE];
+        reader.read_exact(&mut preamble)?;
+        if preamble[..4] != INDEX_MAGIC {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Invalid index magic",
+            ));
+        }
+        if u16::from_be_bytes([preamble[4], preamble[5]]) != INDEX_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Unsupported index version",
+            ));
+        }
+
+        let mut records = Vec::new();
+        reader.read_to_end(&mut records)?;
+        if records.len() % RECORD_SIZE != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "Index ends inside a record",
+            ));
+        }
+
+        let entries = records
+            .chunks_exact(RECORD_SIZE)
+            .map(|record| IndexEntry::decode(record.try_into().unwrap()))
+            .collect::<io::Result<Vec<_>>>()?;
+        Ok(Self::from_entries(entries))
+    }
+
+    /// Indexes a v2 frame stream that has no sidecar. Frames are not marked
+    /// as keyframes, so `find` returns the nearest frame at or before the PTS.
+    pub fn build_from_stream<R: Read>(reader: R) -> io::Result<Self> {
+        let entries = FrameReader::new(reader)
+            .headers_with_offsets()
+            .map(|frame| {
+                frame.map(|(offset, header)| IndexEntry::from_header(offset, &header, false))
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+        Ok(Self::from_entries(entries))
+    }
+
+    /// `build_from_stream`, seeking past payloads so only headers are read.
+    pub fn build_from_seekable_stream<R: Read + Seek>(reader: R) -> io::Result<Self> {
+        let entries = HeadersOnly::new(reader)
+            .map(|frame| {
+                frame.map(|(offset, header)| IndexEntry::from_header(offset, &header, false))
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+        Ok(Self::from_entries(entries))
+    }
+
+    pub fn write_to<W: Write>(&self, writer: W) -> io::Result<W> {
+        let mut index_writer = IndexWriter::new(writer)?;
+        for entry in &self.entries {
+            index_writer.push(entry)?;
+        }
+        index_writer.finish()
+    }
+
+    /// Returns the last entry with a PTS at or before `pts`. When the index
+    /// marks keyframes, this is the nearest keyframe at or before `pts`.
+    pub fn find(&self, pts: u64) -> Option<IndexEntry> {
+        let end = self
+            .by_pts
+            .partition_point(|&index| self.entries[index].pts <= Some(pts));
+        let candidates = &self.by_pts[..end];
+
+        let index = if self.has_keyframes {
+            *candidates
+                .iter()
+                .rev()
+                .find(|&&index| self.entries[index].keyframe)?
+        } else {
+            *candidates.last()?
+        };
+        Some(self.entries[index])
+    }
+
+    pub fn entries(&self) -> &[IndexEntry] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EncodingFlag, Endianness, FrameWriter};
+    use std::io::{Cursor, Seek, SeekFrom};
+
+    const FRAME_SAMPLES: u64 = 960;
+
+    // Three hours of 20 ms Opus frames at 48 kHz would be 540k frames; an
+    // hour's worth keeps the test fast while still exercising deep searches.
+    fn generate_stream(frames: u64, keyframe_every: Option<u64>) -> (Vec<u8>, Vec<u8>) {
+        let mut frame_writer = FrameWriter::new(Vec::new());
+        let mut index_writer = IndexWriter::new(Vec::new()).unwrap();
+
+        for index in 0..frames {
+            let payload = vec![index as u8; 20 + (index % 7) as usize];
+            let header = FrameHeaderV2::new(
+                EncodingFlag::Opus,
+                payload.len() as u32,
+                FRAME_SAMPLES as u32,
+                48000,
+                2,
+                0,
+                Endianness::LittleEndian,
+                (index % 3 != 0).then_some(index),
+                Some(index * FRAME_SAMPLES),
+                None,
+            )
+            .unwrap();
+            let offset = frame_writer.write_frame(&header, &payload).unwrap();
+            let keyframe = keyframe_every.is_some_and(|every| index % every == 0);
+            index_writer.record(offset, &header, keyframe).unwrap();
+        }
+
+        (frame_writer.into_inner(), index_writer.finish().unwrap())
+    }
+
+    #[test]
+    fn test_index_roundtrip() {
+        let (stream, index_bytes) = generate_stream(50, Some(10));
+        assert_eq!(index_bytes.len(), INDEX_PREAMBLE_SIZE + 50 * RECORD_SIZE);
+
+        let index = IndexFile::load(&index_bytes[..]).unwrap();
+        assert_eq!(index.len(), 50);
+        assert_eq!(index.write_to(Vec::new()).unwrap(), index_bytes);
+
+        let built = IndexFile::build_from_stream(&stream[..]).unwrap();
+        assert_eq!(built.len(), 50);
+        let seeked = IndexFile::build_from_seekable_stream(io::Cursor::new(&stream)).unwrap();
+        assert_eq!(seeked.entries(), built.entries());
+        for (loaded, built) in index.entries().iter().zip(built.entries()) {
+            assert_eq!(loaded.offset, built.offset);
+            assert_eq!(loaded.pts, built.pts);
+            assert_eq!(loaded.id, built.id);
+        }
+
+        assert!(IndexFile::load(&index_bytes[..index_bytes.len() - 1]).is_err());
+        let mut bad_magic = index_bytes.clone();
+        bad_magic[0] = b'X';
+        assert!(IndexFile::load(&bad_magic[..]).is_err());
+    }
+
+    #[test]
+    fn test_index_seek_by_pts_on_long_stream() {
+        let frames = 180_000;
+        let (stream, index_bytes) = generate_stream(frames, None);
+        let index = IndexFile::load(&index_bytes[..]).unwrap();
+        let mut cursor = Cursor::new(stream);
+
+        // Minute 42 at 48 kHz, plus a point between two frames.
+        for target in [
+            42 * 60 * 48_000,
+            42 * 60 * 48_000 + 500,
+            0,
+            (frames - 1) * FRAME_SAMPLES,
+        ] {
+            let entry = index.find(target).unwrap();
+            let expected = target / FRAME_SAMPLES * FRAME_SAMPLES;
+            assert_eq!(entry.pts, Some(expected));
+
+            cursor.seek(SeekFrom::Start(entry.offset)).unwrap();
+            let header = FrameHeaderV2::decode(&mut cursor).unwrap();
+            assert_eq!(header.pts(), Some(expected));
+        }
+
+        assert_eq!(
+            index.find(u64::MAX).unwrap().pts,
+            Some((frames - 1) * FRAME_SAMPLES)
+        );
+    }
+
+    #[test]
+    fn test_index_find_nearest_keyframe_before() {
+        let (_, index_bytes) = generate_stream(100, Some(25));
+        let index = IndexFile::load(&index_bytes[..]).unwrap();
+
+        assert_eq!(index.find(0).unwrap().pts, Some(0));
+        assert_eq!(index.find(24 * FRAME_SAMPLES).unwrap().pts, Some(0));
+        assert_eq!(
+            index.find(60 * FRAME_SAMPLES + 1).unwrap().pts,
+            Some(50 * FRAME_SAMPLES)
+        );
+        assert!(index.find(60 * FRAME_SAMPLES).unwrap().keyframe);
+
+        let late_start = IndexFile::from_entries(
+            index
+                .entries()
+                .iter()
+                .map(|entry| IndexEntry {
+                    pts: entry.pts.map(|pts| pts + 1_000),
+                    ..*entry
+                })
+                .collect(),
+        );
+        assert_eq!(late_start.find(999), None);
+    }
+}
@@ -0,0 +1,134 @@
+use crate::{ChannelCount, EncodingFlag, Endianness, FrameHeader, FrameHeaderError, SampleRate};
+
+/// What a JACK port needs to know about a header. JACK samples are always
+/// 32-bit float.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JackPortInfo {
+    pub sample_rate: u32,
+    /// `jack_nframes_t` per process cycle.
+    pub buffer_frames: u32,
+    pub is_mono: bool,
+}
+
+impl FrameHeader {
+    pub fn to_jack_port_info(&self) -> Result<JackPortInfo, FrameHeaderError> {
+        if self.encoding != EncodingFlag::PCMFloat {
+            return Err(FrameHeaderError::InvalidEncoding(
+                self.encoding.code() as u32
+            ));
+        }
+        if self.bits_per_sample != 32 {
+            return Err(FrameHeaderError::FieldOutOfRange("bits_per_sample"));
+        }
+        Ok(JackPortInfo {
+            sample_rate: self.sample_rate,
+            buffer_frames: self.sample_size as u32,
+            is_mono: self.channels == 1,
+        })
+    }
+
+    /// `channels` must be 1 when `info.is_mono` is set.
+    pub fn from_jack_port_info(
+        info: &JackPortInfo,
+        channels: u8,
+        endianness: Endianness,
+    ) -> Result<FrameHeader, FrameHeaderError> {
+        if info.is_mono != (channels == 1) {
+            return Err(FrameHeaderError::InvalidChannels(channels));
+        }
+        let sample_size = u16::try_from(info.buffer_frames)
+            .map_err(|_| FrameHeaderError::FieldOutOfRange("buffer_frames"))?;
+        Ok(FrameHeader::new_typed(
+            EncodingFlag::PCMFloat,
+            sample_size,
+            SampleRate::try_from(info.sample_rate)?,
+            ChannelCount::try_from(channels)?,
+            32,
+            endianness,
+            None,
+            None,
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(encoding: EncodingFlag, bits: u8, channels: u8) -> FrameHeader {
+        FrameHeader::new(
+            encoding,
+            256,
+            48000,
+            channels,
+            bits,
+            Endianness::LittleEndian,
+            None,
+            None,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_jack_port_info_roundtrip() {
+        for channels in [1, 2] {
+            let header = header(EncodingFlag::PCMFloat, 32, channels);
+            let info = header.to_jack_port_info().unwrap();
+            assert_eq!(
+                info,
+                JackPortInfo {
+                    sample_rate: 48000,
+                    buffer_frames: 256,
+                    is_mono: channels == 1,
+                }
+            );
+            assert_eq!(
+                FrameHeader::from_jack_port_info(&info, channels, Endianness::LittleEndian),
+                Ok(header)
+            );
+        }
+    }
+
+    #[test]
+    fn test_jack_port_info_rejects_other_formats() {
+        assert_eq!(
+            header(EncodingFlag::Opus, 16, 2).to_jack_port_info(),
+            Err(FrameHeaderError::InvalidEncoding(2))
+        );
+        assert_eq!(
+            header(EncodingFlag::PCMSigned, 32, 2).to_jack_port_info(),
+            Err(FrameHeaderError::InvalidEncoding(0))
+        );
+        assert_eq!(
+            header(EncodingFlag::PCMFloat, 24, 2).to_jack_port_info(),
+            Err(FrameHeaderError::FieldOutOfRange("bits_per_sample"))
+        );
+
+        let info = JackPortInfo {
+            sample_rate: 48000,
+            buffer_frames: 1024,
+            is_mono: true,
+        };
+        let from = |info: JackPortInfo, channels| {
+            FrameHeader::from_jack_port_info(&info, channels, Endianness::BigEndian)
+        };
+        assert!(from(info, 1).is_ok());
+        assert_eq!(from(info, 2), Err(FrameHeaderError::InvalidChannels(2)));
+        assert!(from(
+            JackPortInfo {
+                buffer_frames: 8192,
+                ..info
+            },
+            1
+        )
+        .is_err());
+        assert!(from(
+            JackPortInfo {
+                sample_rate: 22050,
+                ..info
+            },
+            1
+        )
+        .is_err());
+    }
+}
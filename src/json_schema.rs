@@ -0,0 +1,205 @@
+use crate::FrameHeader;
+#[cfg(feature = "jsonschema")]
+use std::sync::OnceLock;
+
+impl FrameHeader {
+    /// JSON Schema (draft 2020-12) for the serde JSON form of a valid v1
+    /// header. Encoding and endianness names are listed in lowercase, though
+    /// deserializing ignores case. wasm32 builds write the ID as a decimal
+    /// string, which the schema does not cover.
+    pub const JSON_SCHEMA: &'static str = r#"{
+  "$schema": "https://json-schema.org/draft/2020-12/schema",
+  "title": "FrameHeader",
+  "type": "object",
+  "required": [
+    "encoding",
+    "sample_size",
+    "sample_rate",
+    "channels",
+    "bits_per_sample",
+    "endianness"
+  ],
+  "properties": {
+    "encoding": {
+      "anyOf": [
+        {
+          "enum": [
+            "pcm_signed", "pcmsigned", "pcm", "pcm_s16", "pcm_s24", "pcm_s32", "s16", "s24", "s32",
+            "pcm_float", "pcmfloat", "pcm_f32", "f32", "float",
+            "opus",
+            "flac",
+            "aac", "mp4a",
+            "h264", "h.264", "avc",
+            "metadata"
+          ]
+        },
+        { "type": "string", "pattern": "^unknown:[0-7]$" }
+      ]
+    },
+    "sample_size": { "type": "integer", "minimum": 0, "maximum": 4095 },
+    "sample_rate": { "enum": [16000, 44100, 48000, 96000] },
+    "channels": { "type": "integer", "minimum": 1, "maximum": 16 },
+    "bits_per_sample": { "enum": [16, 24, 32] },
+    "endianness": {
+      "enum": ["le", "little", "little_endian", "littleendian", "be", "big", "big_endian", "bigendian"]
+    },
+    "id": { "type": ["integer", "null"], "minimum": 0, "maximum": 18446744073709551615 },
+    "pts": { "type": ["integer", "null"], "minimum": 0, "maximum": 18446744073709551615 }
+  }
+}"#;
+
+    pub fn json_schema_string() -> &'static str {
+        Self::JSON_SCHEMA
+    }
+
+    /// Checks `json` against `JSON_SCHEMA`, returning one message per
+    /// violation, prefixed with the JSON pointer of the value at fault unless
+    /// that is the whole document.
+    #[cfg(feature = "jsonschema")]
+    pub fn validate_json(json: &str) -> Result<(), Vec<String>> {
+        static VALIDATOR: OnceLock<jsonschema::Validator> = OnceLock::new();
+        let validator = VALIDATOR.get_or_init(|| {
+            let schema =
+                serde_json::from_str(FrameHeader::JSON_SCHEMA).expect("JSON_SCHEMA is valid JSON");
+            jsonschema::validator_for(&schema).expect("JSON_SCHEMA is a valid schema")
+        });
+
+        let instance: serde_json::Value =
+            serde_json::from_str(json).map_err(|err| vec![format!("Invalid JSON: {}", err)])?;
+        let errors: Vec<String> = validator
+            .iter_errors(&instance)
+            .map(|err| match err.instance_path.as_str() {
+                "" => err.to_string(),
+                path => format!("{}: {}", path, err),
+            })
+            .collect();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EncodingFlag, Endianness};
+    use serde_json::Value;
+
+    fn schema() -> Value {
+        serde_json::from_str(FrameHeader::json_schema_string()).unwrap()
+    }
+
+    fn names(enumeration: &Value) -> Vec<&str> {
+        enumeration
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|name| name.as_str().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_schema_matches_header_rules() {
+        let schema = schema();
+        let properties = &schema["properties"];
+
+        let encodings = names(&properties["encoding"]["anyOf"][0]["enum"]);
+        assert!(encodings
+            .iter()
+            .all(|name| EncodingFlag::from_serde_name(name).is_some()));
+        for code in 0..=7 {
+            let encoding = EncodingFlag::from_serde_name(&format!("unknown:{}", code)).unwrap();
+            if let Some(name) = encoding.serde_name() {
+                assert!(encodings.contains(&name), "{}", name);
+            }
+        }
+        let endianness = names(&properties["endianness"]["enum"]);
+        assert!(endianness
+            .iter()
+            .all(|name| Endianness::from_serde_name(name).is_some()));
+
+        let rates: Vec<u64> = properties["sample_rate"]["enum"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|rate| rate.as_u64().unwrap())
+            .collect();
+        let valid: Vec<u64> = FrameHeader::VALID_SAMPLE_RATES
+            .iter()
+            .map(|&rate| rate as u64)
+            .collect();
+        assert_eq!(rates, valid);
+        assert_eq!(
+            properties["sample_size"]["maximum"],
+            FrameHeader::MAX_SAMPLE_SIZE
+        );
+        assert_eq!(properties["id"]["maximum"], u64::MAX);
+    }
+
+    #[cfg(feature = "jsonschema")]
+    #[test]
+    fn test_validate_json() {
+        for header in [
+            FrameHeader::new(
+                EncodingFlag::Opus,
+                960,
+                48000,
+                2,
+                16,
+                Endianness::LittleEndian,
+                None,
+                None,
+            )
+            .unwrap(),
+            FrameHeader::new(
+                EncodingFlag::Unknown(7),
+                4095,
+                96000,
+                16,
+                32,
+                Endianness::BigEndian,
+                Some(u64::MAX),
+                Some(0),
+            )
+            .unwrap(),
+        ] {
+            let json = serde_json::to_string(&header).unwrap();
+            assert_eq!(FrameHeader::validate_json(&json), Ok(()), "{}", json);
+        }
+
+        let valid = r#"{"encoding": "pcm_s24", "sample_size": 480, "sample_rate": 44100,
+            "channels": 1, "bits_per_sample": 24, "endianness": "big"}"#;
+        assert_eq!(FrameHeader::validate_json(valid), Ok(()));
+
+        let errors = FrameHeader::validate_json(
+            r#"{"encoding": "mp3", "sample_size": 5000, "sample_rate": 22050,
+                "channels": 0, "bits_per_sample": 8, "endianness": "le", "pts": -1}"#,
+        )
+        .unwrap_err();
+        let paths: Vec<&str> = errors
+            .iter()
+            .map(|error| error.split(':').next().unwrap())
+            .collect();
+        for path in [
+            "/encoding",
+            "/sample_size",
+            "/sample_rate",
+            "/channels",
+            "/bits_per_sample",
+            "/pts",
+        ] {
+            assert!(paths.contains(&path), "{} not in {:?}", path, errors);
+        }
+
+        // One message per missing field.
+        let errors = FrameHeader::validate_json(r#"{"encoding": "opus"}"#).unwrap_err();
+        assert_eq!(errors.len(), 5);
+        assert!(errors[0].contains("sample_size"), "{:?}", errors);
+
+        let errors = FrameHeader::validate_json("{").unwrap_err();
+        assert!(errors[0].starts_with("Invalid JSON"), "{:?}", errors);
+        assert!(FrameHeader::validate_json("[]").is_err());
+    }
+}
@@ -0,0 +1,320 @@
+use crate::{FrameHeader, FrameHeaderV2};
+use std::fmt::Write;
+
+/// How the bits of a header field are read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FieldKind {
+    /// Always holds the given value.
+    Constant(u32),
+    /// An index into a table, such as the sample-rate code.
+    Code,
+    /// A single presence or selector bit.
+    Flag,
+    /// A set of single-bit flags.
+    Flags,
+    /// The value minus one, so zero is not wasted.
+    MinusOne,
+    /// The value itself.
+    Value,
+}
+
+/// One field of a 32-bit header word, counting bits from the least
+/// significant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FieldLayout {
+    pub name: &'static str,
+    pub bit_offset: u32,
+    pub bit_width: u32,
+    pub kind: FieldKind,
+}
+
+impl FieldLayout {
+    // Built from the mask the encoder uses, so the layout cannot drift.
+    const fn from_mask(name: &'static str, mask: u32, kind: FieldKind) -> FieldLayout {
+        FieldLayout {
+            name,
+            bit_offset: mask.trailing_zeros(),
+            bit_width: mask.count_ones(),
+            kind,
+        }
+    }
+
+    pub fn mask(&self) -> u32 {
+        (u32::MAX >> (32 - self.bit_width)) << self.bit_offset
+    }
+}
+
+const V1_LAYOUT: [FieldLayout; 9] = [
+    FieldLayout::from_mask(
+        "magic",
+        FrameHeader::MAGIC_MASK,
+        FieldKind::Constant(FrameHeader::MAGIC_WORD),
+    ),
+    FieldLayout::from_mask(
+        "sample_rate",
+        FrameHeader::SAMPLE_RATE_MASK,
+        FieldKind::Code,
+    ),
+    FieldLayout::from_mask("bits_per_sample", FrameHeader::BITS_MASK, FieldKind::Code),
+    FieldLayout::from_mask("pts_present", FrameHeader::PTS_MASK, FieldKind::Flag),
+    FieldLayout::from_mask("id_present", FrameHeader::ID_MASK, FieldKind::Flag),
+    FieldLayout::from_mask("encoding", FrameHeader::ENCODING_MASK, FieldKind::Code),
+    FieldLayout::from_mask("endianness", FrameHeader::ENDIAN_MASK, FieldKind::Flag),
+    FieldLayout::from_mask("channels", FrameHeader::CHANNELS_MASK, FieldKind::MinusOne),
+    FieldLayout::from_mask(
+        "sample_size",
+        FrameHeader::SAMPLE_SIZE_MASK,
+        FieldKind::Value,
+    ),
+];
+
+const V2_LAYOUT: [FieldLayout; 7] = [
+    FieldLayout::from_mask(
+        "magic",
+        FrameHeaderV2::MAGIC_MASK,
+        FieldKind::Constant(FrameHeaderV2::MAGIC_WORD),
+    ),
+    FieldLayout::from_mask("version", FrameHeaderV2::VERSION_MASK, FieldKind::Code),
+    FieldLayout::from_mask("flags", FrameHeaderV2::FLAGS_MASK, FieldKind::Flags),
+    FieldLayout::from_mask("encoding", FrameHeaderV2::ENCODING_MASK, FieldKind::Code),
+    FieldLayout::from_mask(
+        "sample_rate",
+        FrameHeaderV2::SAMPLE_RATE_MASK,
+        FieldKind::Code,
+    ),
+    FieldLayout::from_mask(
+        "channels",
+        FrameHeaderV2::CHANNELS_MASK,
+        FieldKind::MinusOne,
+    ),
+    FieldLayout::from_mask("bits_per_sample", FrameHeaderV2::BITS_MASK, FieldKind::Code),
+];
+
+impl FrameHeader {
+    /// The fields of the 32-bit base header, most significant first.
+    pub fn layout() -> &'static [FieldLayout] {
+        &V1_LAYOUT
+    }
+}
+
+impl FrameHeaderV2 {
+    /// The fields of the 32-bit control word, most significant first. The
+    /// size word that follows holds the payload size in its upper 16 bits and
+    /// the frame count in its lower 16.
+    pub fn layout() -> &'static [FieldLayout] {
+        &V2_LAYOUT
+    }
+}
+
+/// Renders a layout as a bit ruler over one letter per bit, with a legend
+/// mapping each letter to its field.
+pub fn render_layout_diagram(layout: &[FieldLayout]) -> String {
+    let mut tens = String::new();
+    let mut units = String::new();
+    let mut cells = String::new();
+    for bit in (0..32).rev() {
+        tens.push(char::from_digit(bit / 10, 10).unwrap());
+        units.push(char::from_digit(bit % 10, 10).unwrap());
+        cells.push(
+            layout
+                .iter()
+                .position(|field| field.mask() & (1 << bit) != 0)
+                .map_or('.', |index| (b'a' + index as u8) as char),
+        );
+    }
+
+    let mut diagram = format!("{}\n{}\n{}\n\n", tens, units, cells);
+    for (index, field) in layout.iter().enumerate() {
+        let high = field.bit_offset + field.bit_width - 1;
+        let bits = if field.bit_width == 1 {
+            format!("[{}]", high)
+        } else {
+            format!("[{}-{}]", high, field.bit_offset)
+        };
+        let _ = writeln!(
+            diagram,
+            "{} {:<8}{} ({} bit{})",
+            (b'a' + index as u8) as char,
+            bits,
+            field.name,
+            field.bit_width,
+            if field.bit_width == 1 { "" } else { "s" },
+        );
+    }
+    diagram
+}
+
+/// `#define`s for each field's shift and mask, plus the value of constant
+/// fields, named `<prefix>_<FIELD>_SHIFT` and so on.
+pub fn layout_c_defines(layout: &[FieldLayout], prefix: &str) -> String {
+    let mut defines = String::new();
+    for field in layout {
+        let name = format!("{}_{}", prefix, field.name.to_ascii_uppercase());
+        let _ = writeln!(defines, "#define {}_SHIFT {}", name, field.bit_offset);
+        let _ = writeln!(defines, "#define {}_MASK 0x{:08X}u", name, field.mask());
+        if let FieldKind::Constant(value) = field.kind {
+            let _ = writeln!(defines, "#define {}_VALUE 0x{:X}u", name, value);
+        }
+    }
+    defines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_covers_word(layout: &[FieldLayout]) {
+        let mut covered = 0u32;
+        let mut previous_offset = 32;
+        for field in layout {
+            assert_eq!(covered & field.mask(), 0, "{} overlaps", field.name);
+            assert!(
+                field.bit_offset < previous_offset,
+                "{} out of order",
+                field.name
+            );
+            covered |= field.mask();
+            previous_offset = field.bit_offset;
+        }
+        assert_eq!(covered, u32::MAX);
+    }
+
+    fn field(layout: &[FieldLayout], name: &str) -> FieldLayout {
+        *layout.iter().find(|field| field.name == name).unwrap()
+    }
+
+    #[test]
+    fn test_v1_layout_matches_constants() {
+        let layout = FrameHeader::layout();
+        assert_covers_word(layout);
+        for (name, mask, shift) in [
+            ("magic", FrameHeader::MAGIC_MASK, FrameHeader::MAGIC_SHIFT),
+            (
+                "sample_rate",
+                FrameHeader::SAMPLE_RATE_MASK,
+                FrameHeader::SAMPLE_RATE_SHIFT,
+            ),
+            (
+                "bits_per_sample",
+                FrameHeader::BITS_MASK,
+                FrameHeader::BITS_SHIFT,
+            ),
+            ("pts_present", FrameHeader::PTS_MASK, FrameHeader::PTS_SHIFT),
+            ("id_present", FrameHeader::ID_MASK, FrameHeader::ID_SHIFT),
+            (
+                "encoding",
+                FrameHeader::ENCODING_MASK,
+                FrameHeader::ENCODING_SHIFT,
+            ),
+            (
+                "endianness",
+                FrameHeader::ENDIAN_MASK,
+                FrameHeader::ENDIAN_SHIFT,
+            ),
+            (
+                "channels",
+                FrameHeader::CHANNELS_MASK,
+                FrameHeader::CHANNELS_SHIFT,
+            ),
+            ("sample_size", FrameHeader::SAMPLE_SIZE_MASK, 0),
+        ] {
+            let field = field(layout, name);
+            assert_eq!((field.mask(), field.bit_offset), (mask, shift), "{}", name);
+        }
+        assert_eq!(field(layout, "magic").kind, FieldKind::Constant(0x2A));
+    }
+
+    #[test]
+    fn test_v2_layout_matches_constants() {
+        let layout = FrameHeaderV2::layout();
+        assert_covers_word(layout);
+        for (name, mask, shift) in [
+            (
+                "magic",
+                FrameHeaderV2::MAGIC_MASK,
+                FrameHeaderV2::MAGIC_SHIFT,
+            ),
+            (
+                "version",
+                FrameHeaderV2::VERSION_MASK,
+                FrameHeaderV2::VERSION_SHIFT,
+            ),
+            (
+                "flags",
+                FrameHeaderV2::FLAGS_MASK,
+                FrameHeaderV2::FLAGS_SHIFT,
+            ),
+            (
+                "encoding",
+                FrameHeaderV2::ENCODING_MASK,
+                FrameHeaderV2::ENCODING_SHIFT,
+            ),
+            (
+                "sample_rate",
+                FrameHeaderV2::SAMPLE_RATE_MASK,
+                FrameHeaderV2::SAMPLE_RATE_SHIFT,
+            ),
+            (
+                "channels",
+                FrameHeaderV2::CHANNELS_MASK,
+                FrameHeaderV2::CHANNELS_SHIFT,
+            ),
+            ("bits_per_sample", FrameHeaderV2::BITS_MASK, 0),
+        ] {
+            let field = field(layout, name);
+            assert_eq!((field.mask(), field.bit_offset), (mask, shift), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_layout_matches_encoded_header() {
+        // Decoding each field of a real header through the layout.
+        let header = FrameHeader::new(
+            crate::EncodingFlag::FLAC,
+            1234,
+            96000,
+            5,
+            24,
+            crate::Endianness::BigEndian,
+            None,
+            Some(1),
+        )
+        .unwrap();
+        let mut bytes = Vec::new();
+        header.encode(&mut bytes).unwrap();
+        let word = u32::from_be_bytes(bytes[..4].try_into().unwrap());
+        let value = |name| {
+            let field = field(FrameHeader::layout(), name);
+            (word & field.mask()) >> field.bit_offset
+        };
+        assert_eq!(value("magic"), 0x2A);
+        assert_eq!(value("sample_rate"), 3);
+        assert_eq!(value("bits_per_sample"), 1);
+        assert_eq!((value("pts_present"), value("id_present")), (1, 0));
+        assert_eq!(value("encoding"), 3);
+        assert_eq!(value("endianness"), 1);
+        assert_eq!(value("channels"), 4);
+        assert_eq!(value("sample_size"), 1234);
+    }
+
+    #[test]
+    fn test_render_layout() {
+        let diagram = render_layout_diagram(FrameHeader::layout());
+        let lines: Vec<&str> = diagram.lines().collect();
+        assert_eq!(lines[0], "33222222222211111111110000000000");
+        assert_eq!(lines[1], "10987654321098765432109876543210");
+        assert_eq!(lines[2], "aaaaaabbccdefffghhhhiiiiiiiiiiii");
+        assert_eq!(lines[4], "a [31-26] magic (6 bits)");
+        assert_eq!(lines[7], "d [21]    pts_present (1 bit)");
+        assert_eq!(lines.len(), 4 + 9);
+
+        let defines = layout_c_defines(FrameHeader::layout(), "FRAME_HEADER_V1");
+        assert!(defines.starts_with(
+            "#define FRAME_HEADER_V1_MAGIC_SHIFT 26\n\
+             #define FRAME_HEADER_V1_MAGIC_MASK 0xFC000000u\n\
+             #define FRAME_HEADER_V1_MAGIC_VALUE 0x2Au\n"
+        ));
+        assert!(defines.ends_with("#define FRAME_HEADER_V1_SAMPLE_SIZE_MASK 0x00000FFFu\n"));
+        assert_eq!(defines.lines().count(), 9 * 2 + 1);
+    }
+}
@@ -0,0 +1,305 @@
+use crate::{EncodingFlag, Endianness, FrameHeader, FrameHeaderError};
+
+// Samples of `payload` as fractions of full scale.
+fn samples<'a>(
+    header: &FrameHeader,
+    payload: &'a [u8],
+) -> Result<impl Iterator<Item = f64> + 'a, FrameHeaderError> {
+    match header.encoding {
+        EncodingFlag::PCMSigned => {}
+        EncodingFlag::PCMFloat if header.bits_per_sample == 32 => {}
+        EncodingFlag::PCMFloat => return Err(FrameHeaderError::FieldOutOfRange("bits_per_sample")),
+        encoding => return Err(FrameHeaderError::InvalidEncoding(encoding.code() as u32)),
+    }
+    if payload.len() != header.total_pcm_frame_bytes() as usize {
+        return Err(FrameHeaderError::Invalid(format!(
+            "Payload is {} bytes, header describes {}",
+            payload.len(),
+            header.total_pcm_frame_bytes()
+        )));
+    }
+
+    let float = header.encoding == EncodingFlag::PCMFloat;
+    let big_endian = header.endianness == Endianness::BigEndian;
+    let width = header.bytes_per_sample() as usize;
+    let full_scale = (1u64 << (header.bits_per_sample - 1)) as f64;
+    Ok(payload.chunks_exact(width).map(move |sample| {
+        // Left-align the sample in a big-endian word to sign-extend it.
+        let mut word = [0u8; 4];
+        if big_endian {
+            word[..width].copy_from_slice(sample);
+        } else {
+            for (i, byte) in sample.iter().enumerate() {
+                word[width - 1 - i] = *byte;
+            }
+        }
+        if float {
+            f32::from_be_bytes(word) as f64
+        } else {
+            (i32::from_be_bytes(word) >> (32 - 8 * width)) as f64 / full_scale
+        }
+    }))
+}
+
+// Writes `sample`, a fraction of full scale, over `bytes` in the header's
+// sample format. Integer samples are rounded and clipped to their range.
+fn write_sample(header: &FrameHeader, sample: f64, bytes: &mut [u8]) {
+    let width = bytes.len();
+    let word = if header.encoding == EncodingFlag::PCMFloat {
+        (sample as f32).to_be_bytes()
+    } else {
+        let full_scale = (1u64 << (header.bits_per_sample - 1)) as f64;
+        let value = (sample * full_scale)
+            .round()
+            .clamp(-full_scale, full_scale - 1.0) as i32;
+        (value << (32 - 8 * width)).to_be_bytes()
+    };
+    bytes.copy_from_slice(&word[..width]);
+    if header.endianness == Endianness::LittleEndian {
+        bytes.reverse();
+    }
+}
+
+impl FrameHeader {
+    /// Scales `payload` in place so its peak sample reaches `target_peak`, a
+    /// fraction of full scale from 0 to 1, and returns the gain applied in
+    /// dB. A silent frame is left as it is, with a gain of 0 dB.
+    pub fn pcm_normalize_payload(
+        header: &FrameHeader,
+        payload: &mut [u8],
+        target_peak: f32,
+    ) -> Result<f32, FrameHeaderError> {
+        if !(target_peak > 0.0 && target_peak <= 1.0) {
+            return Err(FrameHeaderError::FieldOutOfRange("target_peak"));
+        }
+        let levels: Vec<f64> = samples(header, payload)?.collect();
+        if levels.is_empty() {
+            return Err(FrameHeaderError::Invalid(
+                "Cannot normalize an empty frame".to_string(),
+            ));
+        }
+        let peak = levels
+            .iter()
+            .fold(0.0f64, |peak, sample| peak.max(sample.abs()));
+        if peak == 0.0 {
+            return Ok(0.0);
+        }
+
+        let gain = target_peak as f64 / peak;
+        let width = header.bytes_per_sample() as usize;
+        for (sample, bytes) in levels.iter().zip(payload.chunks_exact_mut(width)) {
+            write_sample(header, sample * gain, bytes);
+        }
+        Ok((20.0 * gain.log10()) as f32)
+    }
+
+    /// RMS level of all samples in dBFS, `-inf` for silence. A full-scale
+    /// sine measures -3 dBFS.
+    pub fn compute_rms_level_db(
+        header: &FrameHeader,
+        payload: &[u8],
+    ) -> Result<f32, FrameHeaderError> {
+        let (sum, count) = samples(header, payload)?.fold((0.0, 0u64), |(sum, count), sample| {
+            (sum + sample * sample, count + 1)
+        });
+        if count == 0 {
+            return Ok(f32::NEG_INFINITY);
+        }
+        Ok((20.0 * (sum / count as f64).sqrt().log10()) as f32)
+    }
+
+    /// Peak sample level in dBFS, `-inf` for silence.
+    pub fn compute_peak_level_db(
+        header: &FrameHeader,
+        payload: &[u8],
+    ) -> Result<f32, FrameHeaderError> {
+        let peak = samples(header, payload)?.fold(0.0f64, |peak, sample| peak.max(sample.abs()));
+        Ok((20.0 * peak.log10()) as f32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 20 cycles of a 1kHz sine at -6 dBFS, 48kHz stereo.
+    fn sine(encoding: EncodingFlag, bits: u8, endianness: Endianness) -> (FrameHeader, Vec<u8>) {
+        let header =
+            FrameHeader::new(encoding, 960, 48000, 2, bits, endianness, None, None).unwrap();
+        let amplitude = 10f64.powf(-6.0 / 20.0);
+        let mut payload = Vec::new();
+        for n in 0..960 {
+            let value =
+                amplitude * (2.0 * std::f64::consts::PI * 1000.0 * n as f64 / 48000.0).sin();
+            for _ in 0..2 {
+                let bytes = if encoding == EncodingFlag::PCMFloat {
+                    (value as f32).to_be_bytes()
+                } else {
+                    let scaled = (value * (1u64 << (bits - 1)) as f64).round() as i32;
+                    (scaled << (32 - bits)).to_be_bytes()
+                };
+                let mut sample = bytes[..bits as usize / 8].to_vec();
+                if endianness == Endianness::LittleEndian {
+                    sample.reverse();
+                }
+                payload.extend_from_slice(&sample);
+            }
+        }
+        (header, payload)
+    }
+
+    #[test]
+    fn test_sine_levels() {
+        // A sine's RMS sits 3.01 dB below its peak.
+        let expected_rms = -6.0 - 20.0 * 2f32.sqrt().log10();
+        for (encoding, bits) in [
+            (EncodingFlag::PCMSigned, 16),
+            (EncodingFlag::PCMSigned, 24),
+            (EncodingFlag::PCMSigned, 32),
+            (EncodingFlag::PCMFloat, 32),
+        ] {
+            for endianness in [Endianness::LittleEndian, Endianness::BigEndian] {
+                let (header, payload) = sine(encoding, bits, endianness);
+                let rms = FrameHeader::compute_rms_level_db(&header, &payload).unwrap();
+                let peak = FrameHeader::compute_peak_level_db(&header, &payload).unwrap();
+                assert!((rms - expected_rms).abs() < 0.01, "{} bits: {}", bits, rms);
+                assert!((peak + 6.0).abs() < 0.01, "{} bits: {}", bits, peak);
+            }
+        }
+    }
+
+    #[test]
+    fn test_silence_and_full_scale() {
+        let (header, payload) = sine(EncodingFlag::PCMSigned, 24, Endianness::LittleEndian);
+        let silence = vec![0u8; payload.len()];
+        assert_eq!(
+            FrameHeader::compute_rms_level_db(&header, &silence),
+            Ok(f32::NEG_INFINITY)
+        );
+        assert_eq!(
+            FrameHeader::compute_peak_level_db(&header, &silence),
+            Ok(f32::NEG_INFINITY)
+        );
+
+        // Negative full scale is exactly 0 dBFS.
+        let full = [0x00, 0x00, 0x80].repeat(960 * 2);
+        assert_eq!(FrameHeader::compute_rms_level_db(&header, &full), Ok(0.0));
+        assert_eq!(FrameHeader::compute_peak_level_db(&header, &full), Ok(0.0));
+    }
+
+    #[test]
+    fn test_normalize_to_target_peak() {
+        for (encoding, bits) in [
+            (EncodingFlag::PCMSigned, 16),
+            (EncodingFlag::PCMSigned, 24),
+            (EncodingFlag::PCMSigned, 32),
+            (EncodingFlag::PCMFloat, 32),
+        ] {
+            for endianness in [Endianness::LittleEndian, Endianness::BigEndian] {
+                for target_db in [-1.0f32, -12.0] {
+                    let (header, mut payload) = sine(encoding, bits, endianness);
+                    let target = 10f32.powf(target_db / 20.0);
+                    let gain =
+                        FrameHeader::pcm_normalize_payload(&header, &mut payload, target).unwrap();
+                    assert!(
+                        (gain - (target_db + 6.0)).abs() < 0.01,
+                        "{} bits: {}",
+                        bits,
+                        gain
+                    );
+                    let peak = FrameHeader::compute_peak_level_db(&header, &payload).unwrap();
+                    assert!((peak - target_db).abs() < 0.01, "{} bits: {}", bits, peak);
+                    // The waveform keeps its shape, so the RMS moves with the peak.
+                    let rms = FrameHeader::compute_rms_level_db(&header, &payload).unwrap();
+                    assert!((peak - rms - 3.01).abs() < 0.01, "{} bits: {}", bits, rms);
+                }
+            }
+        }
+
+        // Integer samples clip at the top of their range.
+        let (header, mut payload) = sine(EncodingFlag::PCMSigned, 16, Endianness::LittleEndian);
+        FrameHeader::pcm_normalize_payload(&header, &mut payload, 1.0).unwrap();
+        let max = payload
+            .chunks_exact(2)
+            .map(|sample| i16::from_le_bytes([sample[0], sample[1]]))
+            .max();
+        assert_eq!(max, Some(i16::MAX));
+
+        let mut silence = vec![0u8; payload.len()];
+        assert_eq!(
+            FrameHeader::pcm_normalize_payload(&header, &mut silence, 0.5),
+            Ok(0.0)
+        );
+        assert!(silence.iter().all(|&byte| byte == 0));
+    }
+
+    #[test]
+    fn test_normalize_errors() {
+        let (header, mut payload) = sine(EncodingFlag::PCMSigned, 16, Endianness::LittleEndian);
+        for target in [0.0, -0.5, 1.5, f32::NAN] {
+            assert_eq!(
+                FrameHeader::pcm_normalize_payload(&header, &mut payload, target),
+                Err(FrameHeaderError::FieldOutOfRange("target_peak"))
+            );
+        }
+
+        let empty = FrameHeader::new(
+            EncodingFlag::PCMSigned,
+            0,
+            48000,
+            2,
+            16,
+            Endianness::LittleEndian,
+            None,
+            None,
+        )
+        .unwrap();
+        assert!(matches!(
+            FrameHeader::pcm_normalize_payload(&empty, &mut Vec::new(), 0.5),
+            Err(FrameHeaderError::Invalid(_))
+        ));
+
+        let opus = FrameHeader::new(
+            EncodingFlag::Opus,
+            960,
+            48000,
+            2,
+            16,
+            Endianness::LittleEndian,
+            None,
+            None,
+        )
+        .unwrap();
+        let original = payload.clone();
+        assert_eq!(
+            FrameHeader::pcm_normalize_payload(&opus, &mut payload, 0.5),
+            Err(FrameHeaderError::InvalidEncoding(2))
+        );
+        assert_eq!(payload, original);
+    }
+
+    #[test]
+    fn test_level_errors() {
+        let (header, payload) = sine(EncodingFlag::PCMSigned, 16, Endianness::LittleEndian);
+        assert!(matches!(
+            FrameHeader::compute_rms_level_db(&header, &payload[1..]),
+            Err(FrameHeaderError::Invalid(_))
+        ));
+
+        let opus = FrameHeader::new(
+            EncodingFlag::Opus,
+            960,
+            48000,
+            2,
+            16,
+            Endianness::LittleEndian,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            FrameHeader::compute_peak_level_db(&opus, &payload),
+            Err(FrameHeaderError::InvalidEncoding(2))
+        );
+    }
+}
@@ -1,20 +1,266 @@
 use serde::{Deserialize, Serialize};
 use std::io::{self, Read, Write};
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+mod aiff;
+mod alsa;
+#[cfg(feature = "tokio")]
+mod async_decode;
+#[cfg(feature = "auth")]
+mod auth;
+mod batch;
+#[cfg(feature = "binrw")]
+mod binrw_support;
+mod builder;
+mod byte_order;
+#[cfg(feature = "cbor")]
+mod cbor;
+mod config_key;
+mod const_encode;
+mod coreaudio;
+mod csv;
+mod delta;
+mod downmix;
+mod endian;
+mod error;
+mod ffmpeg;
+mod fields;
+#[cfg(feature = "flatbuffers")]
+mod flatbuffers_support;
+mod frame_sizes;
+#[cfg(feature = "fuzz")]
+pub mod fuzz_targets;
+mod gstreamer;
+mod histogram;
+#[cfg(feature = "hmac")]
+mod hmac_auth;
+mod index;
+mod jack;
+mod json_schema;
+mod layout;
+mod level;
+mod limits;
+mod log;
+mod lv2;
+mod matroska;
+#[cfg(feature = "metadata")]
+mod metadata;
+mod mime;
+mod monotonic;
+mod mpeg4;
+mod mtc;
+mod mxf;
+mod ndi;
+mod ogg;
+mod opus;
+#[cfg(feature = "rayon")]
+mod parallel_index;
+mod parse;
+mod parts;
+mod pipeline;
+mod plc;
+mod proto3;
+mod pts33;
+mod pts_stats;
+mod pulseaudio;
+mod push_parser;
+#[cfg(feature = "rand")]
+mod random;
+mod raw_word;
+mod redis;
+mod redundant;
+mod restamp;
+mod resync;
+mod retime;
+mod rtp;
+mod sample_offset;
+mod sequence;
+mod serde_repr;
+mod signed_pts;
+mod sniff;
+mod stream;
+mod subframes;
+mod template;
+#[cfg(feature = "test-vectors")]
+pub mod test_vectors;
+mod timecode;
+mod timing;
+mod tone;
+mod typed;
+mod upgrade;
+mod wav;
+mod web_audio;
+mod webrtc;
+mod webvtt;
+#[cfg(feature = "xxhash")]
+mod xxhash;
+
+pub use aiff::{extended_to_f64, f64_to_extended};
+#[cfg(feature = "tokio")]
+pub use async_decode::AsyncHeaderDecoder;
+#[cfg(feature = "auth")]
+pub use auth::{AuthError, AuthPolicy};
+pub use builder::FrameHeaderBuilder;
+pub use byte_order::WireByteOrder;
+#[cfg(feature = "cbor")]
+pub use cbor::CborError;
+pub use config_key::ConfigKey;
+pub use coreaudio::{
+    AudioStreamBasicDescription, K_AUDIO_FORMAT_FLAG_IS_ALIGNED_HIGH,
+    K_AUDIO_FORMAT_FLAG_IS_BIG_ENDIAN, K_AUDIO_FORMAT_FLAG_IS_FLOAT,
+    K_AUDIO_FORMAT_FLAG_IS_NON_INTERLEAVED, K_AUDIO_FORMAT_FLAG_IS_PACKED,
+    K_AUDIO_FORMAT_FLAG_IS_SIGNED_INTEGER, K_AUDIO_FORMAT_LINEAR_PCM,
+};
+pub use delta::{DeltaDecoder, DeltaEncoder};
+pub use endian::EndiannessMismatchWarning;
+pub use error::{DecodeError, FrameHeaderError};
+pub use ffmpeg::{
+    AvCodecParamsHint, AVMEDIA_TYPE_AUDIO, AVMEDIA_TYPE_UNKNOWN, AVMEDIA_TYPE_VIDEO,
+    AV_CODEC_ID_AAC, AV_CODEC_ID_FLAC, AV_CODEC_ID_H264, AV_CODEC_ID_NONE, AV_CODEC_ID_OPUS,
+    AV_CODEC_ID_PCM_F32BE, AV_CODEC_ID_PCM_F32LE, AV_CODEC_ID_PCM_S16BE, AV_CODEC_ID_PCM_S16LE,
+    AV_CODEC_ID_PCM_S24BE, AV_CODEC_ID_PCM_S24LE, AV_CODEC_ID_PCM_S32BE, AV_CODEC_ID_PCM_S32LE,
+    AV_SAMPLE_FMT_FLT, AV_SAMPLE_FMT_NONE, AV_SAMPLE_FMT_S16, AV_SAMPLE_FMT_S32,
+};
+pub use fields::FieldValue;
+pub use histogram::HeaderSizeHistogram;
+#[cfg(feature = "hmac")]
+pub use hmac_auth::HMAC_TAG_SIZE;
+pub use index::{IndexEntry, IndexFile, IndexWriter};
+pub use jack::JackPortInfo;
+pub use layout::{layout_c_defines, render_layout_diagram, FieldKind, FieldLayout};
+pub use limits::Limits;
+pub use log::{LogReader, LogRecovery, LogWriter, SyncPolicy};
+pub use lv2::{Lv2AtomSoundHeader, LV2_ATOM_SOUND_URI};
+pub use matroska::EbmlElement;
+#[cfg(feature = "metadata")]
+pub use metadata::MetadataFormat;
+pub use monotonic::{
+    ensure_monotonic, EnsureMonotonic, MonotonicCounters, MonotonicHeader, MonotonicPolicy,
+    PtsViolation,
+};
+pub use mtc::MtcFrameRate;
+pub use mxf::MxfWaveAudioDescriptor;
+pub use ndi::{NdiAudioFrameDescriptor, NDI_TIMECODE_SYNTHESIZE};
+pub use ogg::OggPacket;
+pub use opus::OpusBandwidth;
+#[cfg(feature = "winnow")]
+pub use parse::frame_header_parser;
+pub use parts::FrameHeaderParts;
+pub use pipeline::{PipelineConfig, PipelineConfigBuilder};
+pub use pts33::Pts33;
+pub use pts_stats::PtsStatistics;
+pub use pulseaudio::PaSampleFormat;
+pub use push_parser::{FrameEvent, FrameParser};
+#[cfg(feature = "rand")]
+pub use random::RandomConstraints;
+pub use raw_word::{RawHeaderFields, RawHeaderWord};
+pub use redundant::HeaderCopy;
+pub use resync::{ResyncEvent, ResyncObserver, ResyncStats};
+pub use retime::{retime_slice, retime_stream, PtsRange, RetimePolicy, RetimeSummary};
+pub use rtp::RtpHeaderFields;
+pub use sample_offset::{validate_stream, SampleOffsetMismatch};
+pub use sequence::SequencedFrame;
+#[cfg(feature = "serde_with")]
+pub use serde_repr::{EncodingCode, EndiannessCode};
+pub use stream::{FrameReader, FrameWriter, HeadersOnly};
+pub use template::{FieldOverride, HeaderTemplate, Overrides};
+pub use timecode::Timecode;
+pub use timing::PtsUnit;
+pub use typed::{ChannelCount, SampleRate};
+pub use wav::{
+    read_wav, write_wav, ExportError, ImportError, WavFrames, WavImportOptions, WavSummary,
+};
+pub use web_audio::AudioBufferConfig;
+#[cfg(feature = "wasm")]
+pub use web_audio::{audio_buffer_config_from_header, header_from_audio_buffer_config};
+pub use webrtc::ChromeRtpCodecParams;
+#[cfg(feature = "wasm")]
+pub use webrtc::{chrome_rtp_codec_params_from_header, header_from_chrome_rtp_codec_params};
+pub use webvtt::TimedTextCue;
+
+// Serializes as "le"/"be" in human-readable formats and as 0/1 in compact
+// ones; see `serde_repr`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub enum Endianness {
     LittleEndian,
     BigEndian,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+// Serializes as a lowercase name such as "pcm_signed" in human-readable
+// formats and as its code in compact ones; see `serde_repr`.
+// Non-exhaustive so that new codecs are not breaking changes.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[non_exhaustive]
 pub enum EncodingFlag {
-    PCMSigned = 0,
-    PCMFloat = 1,
-    Opus = 2,
-    FLAC = 3,
-    AAC = 4,
-    H264 = 5,
+    PCMSigned,
+    PCMFloat,
+    Opus,
+    FLAC,
+    AAC,
+    H264,
+    /// Structured side data rather than media; see `is_metadata`.
+    Metadata,
+    // A code this version does not know, kept so it re-encodes unchanged.
+    // Only produced when decoding with `DecodeOptions::allow_unknown_encoding`.
+    Unknown(u8),
+}
+
+impl EncodingFlag {
+    pub const fn code(&self) -> u8 {
+        match self {
+            EncodingFlag::PCMSigned => 0,
+            EncodingFlag::PCMFloat => 1,
+            EncodingFlag::Opus => 2,
+            EncodingFlag::FLAC => 3,
+            EncodingFlag::AAC => 4,
+            EncodingFlag::H264 => 5,
+            EncodingFlag::Metadata => 6,
+            EncodingFlag::Unknown(code) => *code,
+        }
+    }
+
+    pub fn is_known(&self) -> bool {
+        !matches!(self, EncodingFlag::Unknown(_))
+    }
+
+    pub fn is_metadata(&self) -> bool {
+        *self == EncodingFlag::Metadata
+    }
+
+    /// Anything but metadata, including unknown encodings.
+    pub fn is_media(&self) -> bool {
+        !self.is_metadata()
+    }
+
+    fn validate_code(&self, max_code: u8) -> Result<(), String> {
+        match self {
+            EncodingFlag::Unknown(code) if encoding_from_code(*code as u32).is_some() => {
+                Err(format!(
+                    "Encoding code {} is known and must use its named variant",
+                    code
+                ))
+            }
+            EncodingFlag::Unknown(code) if *code > max_code => {
+                Err(format!("Encoding code {} does not fit the header", code))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct DecodeOptions {
+    // Decode unrecognised encoding codes as `EncodingFlag::Unknown` instead
+    // of rejecting the header.
+    pub allow_unknown_encoding: bool,
+}
+
+impl DecodeOptions {
+    fn encoding(&self, code: u32) -> Option<EncodingFlag> {
+        encoding_from_code(code).or_else(|| {
+            self.allow_unknown_encoding
+                .then_some(EncodingFlag::Unknown(code as u8))
+        })
+    }
 }
 
 fn encoding_from_code(code: u32) -> Option<EncodingFlag> {
@@ -25,11 +271,12 @@ fn encoding_from_code(code: u32) -> Option<EncodingFlag> {
         3 => Some(EncodingFlag::FLAC),
         4 => Some(EncodingFlag::AAC),
         5 => Some(EncodingFlag::H264),
+        6 => Some(EncodingFlag::Metadata),
         _ => None,
     }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct FrameHeader {
     encoding: EncodingFlag,
     sample_size: u16,
@@ -131,11 +378,163 @@ pub struct FrameHeaderV2 {
     pts: Option<u64>,
     packet_crc32: Option<u32>,
     packet_flags: u8,
+    #[serde(default)]
+    redundant: bool,
+    #[serde(default)]
+    user_data: Option<u32>,
+    #[serde(default)]
+    timecode: Option<Timecode>,
+    #[serde(default)]
+    encoder_delay: Option<u16>,
+    #[serde(default)]
+    end_padding: Option<u16>,
+    #[serde(default)]
+    sample_offset: Option<u64>,
+    #[serde(default)]
+    payload_corrupt: bool,
+    #[serde(default)]
+    packed: bool,
+    #[serde(default)]
+    auth_tag: Option<[u8; 16]>,
+}
+
+// What follows the size word of a v2 header copy.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct SizeWordLayout {
+    pub(crate) extension_word: bool,
+    pub(crate) extended_sizes: bool,
+    pub(crate) redundant: bool,
+    pub(crate) user_data: bool,
+    pub(crate) timecode: bool,
+    pub(crate) encoder_delay: bool,
+    pub(crate) end_padding: bool,
+    pub(crate) sample_offset: bool,
+    pub(crate) payload_corrupt: bool,
+    pub(crate) packed: bool,
+    pub(crate) auth_tag: bool,
+}
+
+impl SizeWordLayout {
+    // Present fields after the PTS, in wire order, with their widths.
+    fn extensions(self) -> [(Extension, bool, usize); 6] {
+        [
+            (
+                Extension::Timecode,
+                self.timecode,
+                FrameHeaderV2::TIMECODE_BYTES,
+            ),
+            (
+                Extension::UserData,
+                self.user_data,
+                FrameHeaderV2::USER_DATA_BYTES,
+            ),
+            (Extension::EncoderDelay, self.encoder_delay, 2),
+            (Extension::EndPadding, self.end_padding, 2),
+            (
+                Extension::SampleOffset,
+                self.sample_offset,
+                FrameHeaderV2::SAMPLE_OFFSET_BYTES,
+            ),
+            (
+                Extension::AuthTag,
+                self.auth_tag,
+                FrameHeaderV2::AUTH_TAG_BYTES,
+            ),
+        ]
+    }
+
+    pub(crate) fn extension_widths(self) -> impl Iterator<Item = usize> {
+        self.extensions()
+            .into_iter()
+            .filter(|&(_, present, _)| present)
+            .map(|(_, _, width)| width)
+    }
+
+    fn extension_bits(self) -> [(u32, bool); 9] {
+        [
+            (FrameHeaderV2::EXTENSION_REDUNDANT, self.redundant),
+            (FrameHeaderV2::EXTENSION_USER_DATA, self.user_data),
+            (FrameHeaderV2::EXTENSION_TIMECODE, self.timecode),
+            (FrameHeaderV2::EXTENSION_ENCODER_DELAY, self.encoder_delay),
+            (FrameHeaderV2::EXTENSION_END_PADDING, self.end_padding),
+            (FrameHeaderV2::EXTENSION_SAMPLE_OFFSET, self.sample_offset),
+            (
+                FrameHeaderV2::EXTENSION_PAYLOAD_CORRUPT,
+                self.payload_corrupt,
+            ),
+            (FrameHeaderV2::EXTENSION_PACKED, self.packed),
+            (FrameHeaderV2::EXTENSION_AUTH_TAG, self.auth_tag),
+        ]
+    }
+
+    fn encoded_extension_word(self) -> u32 {
+        self.extension_bits()
+            .into_iter()
+            .filter(|&(_, present)| present)
+            .fold(0, |word, (bit, _)| word | bit)
+    }
+
+    // Fills in what the extension word announces. A word announcing nothing,
+    // or with bits this version does not know, is rejected.
+    fn with_extension_word(self, word: u32) -> Result<Self, &'static str> {
+        if word == 0 {
+            return Err("v2 extension word announces no extensions");
+        }
+        if word & !FrameHeaderV2::KNOWN_EXTENSIONS != 0 {
+            return Err("v2 extension word has unknown bits set");
+        }
+        let has = |bit: u32| word & bit != 0;
+        Ok(SizeWordLayout {
+            redundant: has(FrameHeaderV2::EXTENSION_REDUNDANT),
+            user_data: has(FrameHeaderV2::EXTENSION_USER_DATA),
+            timecode: has(FrameHeaderV2::EXTENSION_TIMECODE),
+            encoder_delay: has(FrameHeaderV2::EXTENSION_ENCODER_DELAY),
+            end_padding: has(FrameHeaderV2::EXTENSION_END_PADDING),
+            sample_offset: has(FrameHeaderV2::EXTENSION_SAMPLE_OFFSET),
+            payload_corrupt: has(FrameHeaderV2::EXTENSION_PAYLOAD_CORRUPT),
+            packed: has(FrameHeaderV2::EXTENSION_PACKED),
+            auth_tag: has(FrameHeaderV2::EXTENSION_AUTH_TAG),
+            ..self
+        })
+    }
+
+    // A redundant header says so in both its version and its extension
+    // word, so one damaged bit cannot make the first copy look like a
+    // whole header.
+    fn check_redundancy(self, version: u32) -> Result<Self, &'static str> {
+        if self.redundant != (version == FrameHeaderV2::VERSION_REDUNDANT) {
+            return Err("v2 header version and redundancy bit disagree");
+        }
+        Ok(self)
+    }
+
+    fn sizes_offset(self) -> usize {
+        FrameHeaderV2::BASE_SIZE
+            + (self.extension_word as usize) * FrameHeaderV2::EXTENSION_WORD_BYTES
+    }
+
+    // Where the ID, or whatever comes first after it, starts.
+    fn id_offset(self) -> usize {
+        self.sizes_offset() + (self.extended_sizes as usize) * FrameHeaderV2::EXTENDED_SIZE_BYTES
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Extension {
+    Timecode,
+    UserData,
+    EncoderDelay,
+    EndPadding,
+    SampleOffset,
+    AuthTag,
 }
 
 impl FrameHeaderV2 {
     const MAGIC_WORD: u32 = 0x2B;
     const VERSION: u32 = 2;
+    // Same layout as VERSION, followed by an identical second copy. Each
+    // copy also sets EXTENSION_REDUNDANT.
+    const VERSION_REDUNDANT: u32 = 3;
 
     const MAGIC_SHIFT: u32 = 26;
     const MAGIC_MASK: u32 = 0x3F << 26;
@@ -168,13 +567,50 @@ impl FrameHeaderV2 {
     const PUBLIC_PACKET_FLAGS: u8 = Self::FLAG_DISCONTINUITY | Self::FLAG_ENCRYPTED;
 
     pub const BASE_SIZE: usize = 8;
+    pub const EXTENSION_WORD_BYTES: usize = 4;
     pub const EXTENDED_SIZE_BYTES: usize = 8;
+    pub const TIMECODE_BYTES: usize = 4;
+    pub const USER_DATA_BYTES: usize = 4;
+    pub const SAMPLE_OFFSET_BYTES: usize = 8;
+    pub const AUTH_TAG_BYTES: usize = 16;
+    pub const MAX_COPY_SIZE: usize = Self::BASE_SIZE
+        + Self::EXTENSION_WORD_BYTES
+        + Self::EXTENDED_SIZE_BYTES
+        + 8
+        + 8
+        + Self::TIMECODE_BYTES
+        + Self::USER_DATA_BYTES
+        + 2
+        + 2
+        + Self::SAMPLE_OFFSET_BYTES
+        + Self::AUTH_TAG_BYTES
+        + 4;
+    pub const MAX_SIZE: usize = 2 * Self::MAX_COPY_SIZE;
     pub const SHORT_SIZE_MAX: u32 = 0xFFFE;
     const SHORT_SIZE_SENTINEL: u32 = 0xFFFF;
+    // With FLAG_EXTENDED_SIZES set, the size word is EXTENDED_SIZES_WORD for
+    // extended sizes alone, EXTENDED_SIZES_WITH_EXTENSIONS for an extension
+    // word followed by extended sizes, or short sizes followed by an
+    // extension word.
+    const EXTENDED_SIZES_WORD: u32 = 0xFFFF_FFFF;
+    const EXTENDED_SIZES_WITH_EXTENSIONS: u32 = 0xFFFF_FFFE;
+    // Extension word bits. The fields they announce follow the PTS in the
+    // order `SizeWordLayout::extensions` lists them.
+    const EXTENSION_REDUNDANT: u32 = 1 << 0;
+    const EXTENSION_USER_DATA: u32 = 1 << 1;
+    const EXTENSION_TIMECODE: u32 = 1 << 2;
+    const EXTENSION_ENCODER_DELAY: u32 = 1 << 3;
+    const EXTENSION_END_PADDING: u32 = 1 << 4;
+    const EXTENSION_SAMPLE_OFFSET: u32 = 1 << 5;
+    const EXTENSION_PAYLOAD_CORRUPT: u32 = 1 << 6;
+    const EXTENSION_PACKED: u32 = 1 << 7;
+    const EXTENSION_AUTH_TAG: u32 = 1 << 8;
+    const KNOWN_EXTENSIONS: u32 = (1 << 9) - 1;
     pub const VALID_SAMPLE_RATES: [u32; 11] = [
         8000, 12000, 16000, 24000, 32000, 44100, 48000, 88200, 96000, 176400, 192000,
     ];
 
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         encoding: EncodingFlag,
         payload_size: u32,
@@ -200,6 +636,15 @@ impl FrameHeaderV2 {
             pts,
             packet_crc32,
             packet_flags: 0,
+            redundant: false,
+            user_data: None,
+            timecode: None,
+            encoder_delay: None,
+            end_padding: None,
+            sample_offset: None,
+            payload_corrupt: false,
+            packed: false,
+            auth_tag: None,
         };
         header.validate_fields()?;
         Ok(header)
@@ -221,9 +666,9 @@ impl FrameHeaderV2 {
         let mut header_with_crc_flag = self.clone();
         header_with_crc_flag.packet_crc32 = Some(0);
 
-        let mut bytes = Vec::with_capacity(header_with_crc_flag.size());
+        let mut bytes = Vec::with_capacity(header_with_crc_flag.copy_size());
         header_with_crc_flag
-            .encode(&mut bytes)
+            .encode_copy(&mut bytes)
             .map_err(|err| err.to_string())?;
         Ok(packet_crc32(&bytes[..bytes.len() - 4], payload))
     }
@@ -237,15 +682,25 @@ impl FrameHeaderV2 {
             Some(value) => value,
             None => return Ok(false),
         };
-        let header_size = self.size();
-        if encoded_header.len() < header_size {
+        let copy_size = self.copy_size();
+        if encoded_header.len() < copy_size {
             return Err("Encoded header too small".to_string());
         }
 
-        Ok(packet_crc32(&encoded_header[..header_size - 4], payload) == expected)
+        Ok(packet_crc32(&encoded_header[..copy_size - 4], payload) == expected)
     }
 
     pub fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        if !self.redundant {
+            return self.encode_copy(writer);
+        }
+        let mut copy = Vec::with_capacity(self.copy_size());
+        self.encode_copy(&mut copy)?;
+        writer.write_all(&copy)?;
+        writer.write_all(&copy)
+    }
+
+    fn encode_copy<W: Write>(&self, writer: &mut W) -> io::Result<()> {
         self.validate_fields()
             .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
 
@@ -255,25 +710,28 @@ impl FrameHeaderV2 {
             io::Error::new(io::ErrorKind::InvalidInput, "Invalid bits per sample")
         })?;
         let flags = self.encoded_flags();
-        let extended_sizes = self.needs_extended_sizes();
+        let layout = self.size_word_layout();
 
         let mut word: u32 = Self::MAGIC_WORD << Self::MAGIC_SHIFT;
-        word |= Self::VERSION << Self::VERSION_SHIFT;
+        word |= self.version() << Self::VERSION_SHIFT;
         word |= (flags as u32) << Self::FLAGS_SHIFT;
-        word |= (self.encoding as u32) << Self::ENCODING_SHIFT;
+        word |= (self.encoding.code() as u32) << Self::ENCODING_SHIFT;
         word |= sample_rate_code << Self::SAMPLE_RATE_SHIFT;
         word |= ((self.channels - 1) as u32) << Self::CHANNELS_SHIFT;
         word |= bits_code;
 
         writer.write_all(&word.to_be_bytes())?;
-        let size_word = if extended_sizes {
-            (Self::SHORT_SIZE_SENTINEL << 16) | Self::SHORT_SIZE_SENTINEL
-        } else {
-            (self.payload_size << 16) | self.frame_count
+        let size_word = match (layout.extended_sizes, layout.extension_word) {
+            (false, _) => (self.payload_size << 16) | self.frame_count,
+            (true, false) => Self::EXTENDED_SIZES_WORD,
+            (true, true) => Self::EXTENDED_SIZES_WITH_EXTENSIONS,
         };
         writer.write_all(&size_word.to_be_bytes())?;
+        if layout.extension_word {
+            writer.write_all(&layout.encoded_extension_word().to_be_bytes())?;
+        }
 
-        if extended_sizes {
+        if layout.extended_sizes {
             writer.write_all(&self.payload_size.to_be_bytes())?;
             writer.write_all(&self.frame_count.to_be_bytes())?;
         }
@@ -288,6 +746,24 @@ impl FrameHeaderV2 {
         if let Some(pts) = self.pts {
             writer.write_all(&pts.to_be_bytes())?;
         }
+        if let Some(timecode) = self.timecode {
+            writer.write_all(&timecode.to_packed().to_be_bytes())?;
+        }
+        if let Some(user_data) = self.user_data {
+            writer.write_all(&user_data.to_be_bytes())?;
+        }
+        if let Some(encoder_delay) = self.encoder_delay {
+            writer.write_all(&encoder_delay.to_be_bytes())?;
+        }
+        if let Some(end_padding) = self.end_padding {
+            writer.write_all(&end_padding.to_be_bytes())?;
+        }
+        if let Some(sample_offset) = self.sample_offset {
+            writer.write_all(&sample_offset.to_be_bytes())?;
+        }
+        if let Some(auth_tag) = self.auth_tag {
+            writer.write_all(&auth_tag)?;
+        }
         if let Some(crc) = self.packet_crc32 {
             writer.write_all(&crc.to_be_bytes())?;
         }
@@ -296,6 +772,29 @@ impl FrameHeaderV2 {
     }
 
     pub fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+        Self::decode_with_options(reader, DecodeOptions::default())
+    }
+
+    /// A redundant header is decoded from its first copy; the second is
+    /// skipped. Use `decode_redundant` to fall back to it.
+    pub fn decode_with_options<R: Read>(
+        reader: &mut R,
+        options: DecodeOptions,
+    ) -> io::Result<Self> {
+        let header = Self::decode_copy(reader, options)?;
+        if header.redundant {
+            let copy_size = header.copy_size() as u64;
+            if io::copy(&mut reader.take(copy_size), &mut io::sink())? < copy_size {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "Stream ended inside the redundant header copy",
+                ));
+            }
+        }
+        Ok(header)
+    }
+
+    pub(crate) fn decode_copy<R: Read>(reader: &mut R, options: DecodeOptions) -> io::Result<Self> {
         let mut base = [0u8; Self::BASE_SIZE];
         reader.read_exact(&mut base)?;
 
@@ -306,7 +805,8 @@ impl FrameHeaderV2 {
                 "Invalid v2 header magic word",
             ));
         }
-        if (word & Self::VERSION_MASK) >> Self::VERSION_SHIFT != Self::VERSION {
+        let version = (word & Self::VERSION_MASK) >> Self::VERSION_SHIFT;
+        if version != Self::VERSION && version != Self::VERSION_REDUNDANT {
             return Err(io::Error::new(
                 io::ErrorKind::InvalidData,
                 "Invalid v2 header version",
@@ -322,7 +822,7 @@ impl FrameHeaderV2 {
         }
 
         let encoding_code = (word & Self::ENCODING_MASK) >> Self::ENCODING_SHIFT;
-        let encoding = encoding_from_code(encoding_code).ok_or_else(|| {
+        let encoding = options.encoding(encoding_code).ok_or_else(|| {
             io::Error::new(io::ErrorKind::InvalidData, "Invalid v2 encoding flag")
         })?;
 
@@ -341,23 +841,19 @@ impl FrameHeaderV2 {
 
         let channels = (((word & Self::CHANNELS_MASK) >> Self::CHANNELS_SHIFT) + 1) as u8;
         let size_word = u32::from_be_bytes(base[4..8].try_into().unwrap());
-        let extended_sizes = flags & Self::FLAG_EXTENDED_SIZES != 0;
-        if extended_sizes && size_word != 0xFFFF_FFFF {
-            return Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                "Extended v2 sizes must use short-size sentinels",
-            ));
-        }
-        if !extended_sizes
-            && (((size_word >> 16) & 0xFFFF) == Self::SHORT_SIZE_SENTINEL
-                || (size_word & 0xFFFF) == Self::SHORT_SIZE_SENTINEL)
-        {
-            return Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                "v2 short-size sentinel requires extended sizes",
-            ));
+        let mut layout = Self::size_layout(flags, size_word)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        if layout.extension_word {
+            let mut extension_word = [0u8; Self::EXTENSION_WORD_BYTES];
+            reader.read_exact(&mut extension_word)?;
+            layout = layout
+                .with_extension_word(u32::from_be_bytes(extension_word))
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
         }
-        let (payload_size, frame_count) = if extended_sizes {
+        layout = layout
+            .check_redundancy(version)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        let (payload_size, frame_count) = if layout.extended_sizes {
             let mut sizes = [0u8; Self::EXTENDED_SIZE_BYTES];
             reader.read_exact(&mut sizes)?;
             (
@@ -391,6 +887,49 @@ impl FrameHeaderV2 {
             None
         };
 
+        let timecode = if layout.timecode {
+            let mut timecode_bytes = [0u8; Self::TIMECODE_BYTES];
+            reader.read_exact(&mut timecode_bytes)?;
+            let timecode = Timecode::from_packed(u32::from_be_bytes(timecode_bytes))
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))?;
+            Some(timecode)
+        } else {
+            None
+        };
+
+        let user_data = if layout.user_data {
+            let mut user_data_bytes = [0u8; Self::USER_DATA_BYTES];
+            reader.read_exact(&mut user_data_bytes)?;
+            Some(u32::from_be_bytes(user_data_bytes))
+        } else {
+            None
+        };
+
+        let mut read_u16 = |present: bool| -> io::Result<Option<u16>> {
+            if !present {
+                return Ok(None);
+            }
+            let mut bytes = [0u8; 2];
+            reader.read_exact(&mut bytes)?;
+            Ok(Some(u16::from_be_bytes(bytes)))
+        };
+        let encoder_delay = read_u16(layout.encoder_delay)?;
+        let end_padding = read_u16(layout.end_padding)?;
+        let sample_offset = if layout.sample_offset {
+            let mut bytes = [0u8; Self::SAMPLE_OFFSET_BYTES];
+            reader.read_exact(&mut bytes)?;
+            Some(u64::from_be_bytes(bytes))
+        } else {
+            None
+        };
+        let auth_tag = if layout.auth_tag {
+            let mut bytes = [0u8; Self::AUTH_TAG_BYTES];
+            reader.read_exact(&mut bytes)?;
+            Some(bytes)
+        } else {
+            None
+        };
+
         let packet_crc32 = if flags & Self::FLAG_PACKET_CRC32_PRESENT != 0 {
             let mut crc_bytes = [0u8; 4];
             reader.read_exact(&mut crc_bytes)?;
@@ -416,6 +955,15 @@ impl FrameHeaderV2 {
             pts,
             packet_crc32,
             packet_flags: flags & Self::PUBLIC_PACKET_FLAGS,
+            redundant: version == Self::VERSION_REDUNDANT,
+            user_data,
+            timecode,
+            encoder_delay,
+            end_padding,
+            sample_offset,
+            payload_corrupt: layout.payload_corrupt,
+            packed: layout.packed,
+            auth_tag,
         };
         header
             .validate_fields()
@@ -430,19 +978,44 @@ impl FrameHeaderV2 {
         Ok(header_bytes.len() >= expected_size)
     }
 
+    /// Like `validate_header`, but distinguishes a header that is cut short
+    /// (`Truncated`) from one that is corrupt. Returns the header size.
+    pub fn validate_header_detailed(header_bytes: &[u8]) -> Result<usize, FrameHeaderError> {
+        if header_bytes.len() < Self::BASE_SIZE
+            || header_bytes.len() < Self::header_prefix_size(header_bytes)?
+        {
+            return Err(FrameHeaderError::Truncated);
+        }
+        let header_size = Self::header_size(header_bytes)?;
+        if header_bytes.len() < header_size {
+            return Err(FrameHeaderError::Truncated);
+        }
+        Self::decode(&mut &header_bytes[..header_size])?;
+        Ok(header_size)
+    }
+
+    /// Includes the second copy of a redundant header.
     pub fn size(&self) -> usize {
-        Self::BASE_SIZE
-            + if self.needs_extended_sizes() {
-                Self::EXTENDED_SIZE_BYTES
-            } else {
-                0
-            }
+        self.copy_size() * self.copies()
+    }
+
+    fn copy_size(&self) -> usize {
+        self.size_word_layout().id_offset()
             + self.encoded_id_bytes()
             + (self.pts.is_some() as usize) * 8
+            + self.size_word_layout().extension_widths().sum::<usize>()
             + (self.packet_crc32.is_some() as usize) * 4
     }
 
-    pub fn encoding(&self) -> &EncodingFlag {
+    pub fn encoding(&self) -> EncodingFlag {
+        self.encoding
+    }
+
+    #[deprecated(
+        since = "0.3.0",
+        note = "use `encoding()`, which returns the flag by value"
+    )]
+    pub fn encoding_ref(&self) -> &EncodingFlag {
         &self.encoding
     }
 
@@ -466,7 +1039,12 @@ impl FrameHeaderV2 {
         self.bits_per_sample
     }
 
-    pub fn endianness(&self) -> &Endianness {
+    pub fn endianness(&self) -> Endianness {
+        self.endianness
+    }
+
+    #[deprecated(since = "0.3.0", note = "use `endianness()`, which returns the value")]
+    pub fn endianness_ref(&self) -> &Endianness {
         &self.endianness
     }
 
@@ -490,20 +1068,185 @@ impl FrameHeaderV2 {
         self.packet_flags
     }
 
+    /// Four bytes for the application's own use, such as sensor flags or a
+    /// scene ID. The crate never interprets them, and carries them through
+    /// re-encoding, delta coding and stream rewriting unchanged.
+    pub fn user_data(&self) -> Option<u32> {
+        self.user_data
+    }
+
+    pub fn with_user_data(mut self, user_data: Option<u32>) -> Self {
+        self.user_data = user_data;
+        self
+    }
+
+    /// The SMPTE timecode of the frame, carried alongside the PTS because
+    /// drop-frame labels cannot be derived from it. Like the other optional
+    /// fields after the PTS, it is announced in the extension word, so the
+    /// first such field costs 4 more bytes per header copy.
+    pub fn timecode(&self) -> Option<Timecode> {
+        self.timecode
+    }
+
+    pub fn with_timecode(mut self, timecode: Option<Timecode>) -> Self {
+        self.timecode = timecode;
+        self
+    }
+
+    /// Priming samples per channel at the start of this frame that players
+    /// drop for gapless playback. A delay longer than one frame is split
+    /// over the leading frames.
+    pub fn encoder_delay(&self) -> Option<u16> {
+        self.encoder_delay
+    }
+
+    pub fn with_encoder_delay(mut self, encoder_delay: Option<u16>) -> Self {
+        self.encoder_delay = encoder_delay;
+        self
+    }
+
+    /// Padding samples per channel at the end of this frame that players
+    /// drop for gapless playback, usually set on a stream's last frame.
+    pub fn end_padding(&self) -> Option<u16> {
+        self.end_padding
+    }
+
+    pub fn with_end_padding(mut self, end_padding: Option<u16>) -> Self {
+        self.end_padding = end_padding;
+        self
+    }
+
+    /// Sample frames per channel in the stream before this frame, so a late
+    /// joiner can place it without trusting the PTS. `FrameWriter` can fill
+    /// it in; see `with_sample_offsets`.
+    pub fn sample_offset(&self) -> Option<u64> {
+        self.sample_offset
+    }
+
+    pub fn with_sample_offset(mut self, sample_offset: Option<u64>) -> Self {
+        self.sample_offset = sample_offset;
+        self
+    }
+
+    /// Decoded frames per channel left to play once the encoder delay and
+    /// end padding are trimmed.
+    pub fn trimmed_frame_count(&self) -> u32 {
+        self.frame_count
+            .saturating_sub(self.encoder_delay.unwrap_or(0) as u32)
+            .saturating_sub(self.end_padding.unwrap_or(0) as u32)
+    }
+
     pub fn is_encrypted(&self) -> bool {
         self.packet_flags & Self::FLAG_ENCRYPTED != 0
     }
 
+    /// Whether the payload is known to be damaged, for example by a capture
+    /// error found after the fact. The frame still carries its timing, and
+    /// decoders should conceal it rather than decode it.
+    pub fn is_payload_corrupt(&self) -> bool {
+        self.payload_corrupt
+    }
+
+    pub fn with_payload_corrupt(mut self, corrupt: bool) -> Self {
+        self.payload_corrupt = corrupt;
+        self
+    }
+
+    pub fn auth_tag(&self) -> Option<[u8; 16]> {
+        self.auth_tag
+    }
+
+    /// Sets the authentication tag field. A tag is normally added to an
+    /// encoded frame by `sign`, behind the `auth` feature.
+    pub fn with_auth_tag(mut self, auth_tag: Option<[u8; 16]>) -> Self {
+        self.auth_tag = auth_tag;
+        self
+    }
+
+    /// Checks that `payload` is `payload_size` bytes and, for PCM, holds
+    /// `frame_count` samples per channel. Frames marked corrupt skip the
+    /// PCM check, and packed frames check their sub-frame table instead.
+    pub fn validate_payload(&self, payload: &[u8]) -> Result<(), FrameHeaderError> {
+        if payload.len() != self.payload_size as usize {
+            return Err(FrameHeaderError::Invalid(format!(
+                "Payload is {} bytes, header says {}",
+                payload.len(),
+                self.payload_size
+            )));
+        }
+        if self.packed {
+            return subframes::subframe_lengths(payload).map(|_| ());
+        }
+        if self.payload_corrupt
+            || !matches!(
+                self.encoding,
+                EncodingFlag::PCMSigned | EncodingFlag::PCMFloat
+            )
+        {
+            return Ok(());
+        }
+        let expected = self.frame_count as u64
+            * self.channels as u64
+            * (self.bits_per_sample as u64).div_ceil(8);
+        if payload.len() as u64 != expected {
+            return Err(FrameHeaderError::Invalid(format!(
+                "PCM payload is {} bytes, {} frames need {}",
+                payload.len(),
+                self.frame_count,
+                expected
+            )));
+        }
+        Ok(())
+    }
+
+    /// Whether the payload is side data rather than media, so readers that
+    /// only want media can skip the frame.
+    pub fn is_metadata(&self) -> bool {
+        self.encoding.is_metadata()
+    }
+
+    pub fn is_media(&self) -> bool {
+        self.encoding.is_media()
+    }
+
+    /// Whether `encode` writes the header twice, for recovery with
+    /// `decode_redundant` when one copy is damaged in transit.
+    pub fn is_redundant(&self) -> bool {
+        self.redundant
+    }
+
+    pub fn with_redundant_copy(mut self, redundant: bool) -> Self {
+        self.redundant = redundant;
+        self
+    }
+
+    fn version(&self) -> u32 {
+        if self.redundant {
+            Self::VERSION_REDUNDANT
+        } else {
+            Self::VERSION
+        }
+    }
+
+    pub(crate) fn copies(&self) -> usize {
+        if self.redundant {
+            2
+        } else {
+            1
+        }
+    }
+
     pub fn header_size(header_bytes: &[u8]) -> Result<usize, String> {
         if header_bytes.len() < Self::BASE_SIZE {
-            return Err("Header too small to inspect v2 header size".to_string());
+            return Err(Self::need_more_bytes(Self::BASE_SIZE, header_bytes.len()));
         }
 
         let word = u32::from_be_bytes(header_bytes[..4].try_into().unwrap());
         if (word & Self::MAGIC_MASK) >> Self::MAGIC_SHIFT != Self::MAGIC_WORD {
             return Err("Invalid v2 header magic".to_string());
         }
-        if (word & Self::VERSION_MASK) >> Self::VERSION_SHIFT != Self::VERSION {
+        let version = (word & Self::VERSION_MASK) >> Self::VERSION_SHIFT;
+        if version != Self::VERSION && version != Self::VERSION_REDUNDANT {
             return Err("Invalid v2 header version".to_string());
         }
 
@@ -523,24 +1266,14 @@ impl FrameHeaderV2 {
             return Err("Invalid v2 bits-per-sample code".to_string());
         }
 
-        let size_word = u32::from_be_bytes(header_bytes[4..8].try_into().unwrap());
-        let extended_sizes = flags & Self::FLAG_EXTENDED_SIZES != 0;
-        if extended_sizes && size_word != 0xFFFF_FFFF {
-            return Err("Extended v2 sizes must use short-size sentinels".to_string());
-        }
-        if !extended_sizes
-            && (((size_word >> 16) & 0xFFFF) == Self::SHORT_SIZE_SENTINEL
-                || (size_word & 0xFFFF) == Self::SHORT_SIZE_SENTINEL)
-        {
-            return Err("Short-size sentinel requires extended v2 sizes".to_string());
-        }
+        let layout = Self::copy_layout(header_bytes)?;
 
-        Ok(Self::BASE_SIZE
-            + if extended_sizes {
-                Self::EXTENDED_SIZE_BYTES
-            } else {
-                0
-            }
+        let copies = if version == Self::VERSION_REDUNDANT {
+            2
+        } else {
+            1
+        };
+        let copy_size = layout.id_offset()
             + if flags & Self::FLAG_ID_PRESENT != 0 {
                 if flags & Self::FLAG_ID_U64 != 0 {
                     8
@@ -555,21 +1288,22 @@ impl FrameHeaderV2 {
             } else {
                 0
             }
+            + layout.extension_widths().sum::<usize>()
             + if flags & Self::FLAG_PACKET_CRC32_PRESENT != 0 {
                 4
             } else {
                 0
-            })
+            };
+        Ok(copy_size * copies)
     }
 
     pub fn extract_payload_size(header_bytes: &[u8]) -> Result<u32, String> {
-        if !Self::validate_header(header_bytes)? {
-            return Err("Invalid v2 header".to_string());
-        }
-        let word = u32::from_be_bytes(header_bytes[..4].try_into().unwrap());
-        let flags = ((word & Self::FLAGS_MASK) >> Self::FLAGS_SHIFT) as u8;
-        if flags & Self::FLAG_EXTENDED_SIZES != 0 {
-            Ok(u32::from_be_bytes(header_bytes[8..12].try_into().unwrap()))
+        let (_, layout) = Self::wire_layout(header_bytes)?;
+        if layout.extended_sizes {
+            let offset = layout.sizes_offset();
+            Ok(u32::from_be_bytes(
+                header_bytes[offset..offset + 4].try_into().unwrap(),
+            ))
         } else {
             let sizes = u32::from_be_bytes(header_bytes[4..8].try_into().unwrap());
             Ok((sizes >> 16) & 0xFFFF)
@@ -577,80 +1311,438 @@ impl FrameHeaderV2 {
     }
 
     pub fn extract_frame_count(header_bytes: &[u8]) -> Result<u32, String> {
-        if !Self::validate_header(header_bytes)? {
-            return Err("Invalid v2 header".to_string());
-        }
-        let word = u32::from_be_bytes(header_bytes[..4].try_into().unwrap());
-        let flags = ((word & Self::FLAGS_MASK) >> Self::FLAGS_SHIFT) as u8;
-        if flags & Self::FLAG_EXTENDED_SIZES != 0 {
-            Ok(u32::from_be_bytes(header_bytes[12..16].try_into().unwrap()))
+        let (_, layout) = Self::wire_layout(header_bytes)?;
+        if layout.extended_sizes {
+            let offset = layout.sizes_offset() + 4;
+            Ok(u32::from_be_bytes(
+                header_bytes[offset..offset + 4].try_into().unwrap(),
+            ))
         } else {
             let sizes = u32::from_be_bytes(header_bytes[4..8].try_into().unwrap());
             Ok(sizes & 0xFFFF)
         }
     }
 
-    fn validate_fields(&self) -> Result<(), String> {
-        if self.channels == 0 || self.channels > 32 {
-            return Err("Channel count must be between 1 and 32".to_string());
-        }
-        if Self::sample_rate_code(self.sample_rate).is_none() {
-            return Err(format!(
-                "Invalid sample rate: {}. Must be one of: {:?}",
-                self.sample_rate,
-                Self::VALID_SAMPLE_RATES
-            ));
-        }
-        if Self::bits_code(self.bits_per_sample).is_none() {
-            return Err("Bits per sample must be 0, 8, 16, 24, 32, or 64".to_string());
-        }
-        if matches!(
-            self.encoding,
-            EncodingFlag::PCMSigned | EncodingFlag::PCMFloat
-        ) && self.bits_per_sample == 0
-        {
-            return Err("PCM headers must set bits per sample".to_string());
-        }
-        if self.packet_flags & !Self::PUBLIC_PACKET_FLAGS != 0 {
-            return Err("Unsupported v2 packet flags set".to_string());
-        }
-        if self.id.is_none() && self.id_is_u64 {
-            return Err("64-bit ID flag requires an ID".to_string());
-        }
-        if !self.needs_extended_sizes()
-            && (self.payload_size == Self::SHORT_SIZE_SENTINEL
-                || self.frame_count == Self::SHORT_SIZE_SENTINEL)
-        {
-            return Err("Short v2 size fields reserve 65535 as the extension sentinel".to_string());
-        }
-        Ok(())
+    pub fn extract_pts(header_bytes: &[u8]) -> Result<Option<u64>, String> {
+        let Some(pts_offset) = Self::pts_offset(header_bytes)? else {
+            return Ok(None);
+        };
+        Ok(Some(u64::from_be_bytes(
+            header_bytes[pts_offset..pts_offset + 8].try_into().unwrap(),
+        )))
     }
 
-    fn encoded_flags(&self) -> u8 {
-        let mut flags = self.packet_flags & Self::PUBLIC_PACKET_FLAGS;
-        if self.id.is_some() {
-            flags |= Self::FLAG_ID_PRESENT;
-        }
-        if self.encoded_id_is_u64() {
-            flags |= Self::FLAG_ID_U64;
-        }
-        if self.pts.is_some() {
-            flags |= Self::FLAG_PTS_PRESENT;
-        }
-        if self.packet_crc32.is_some() {
-            flags |= Self::FLAG_PACKET_CRC32_PRESENT;
-        }
-        if self.endianness == Endianness::BigEndian {
-            flags |= Self::FLAG_BIG_ENDIAN;
-        }
-        if self.needs_extended_sizes() {
-            flags |= Self::FLAG_EXTENDED_SIZES;
+    pub(crate) fn pts_offset(header_bytes: &[u8]) -> Result<Option<usize>, String> {
+        let (flags, layout) = Self::wire_layout(header_bytes)?;
+        if flags & Self::FLAG_PTS_PRESENT == 0 {
+            return Ok(None);
         }
-        flags
-    }
 
-    fn needs_extended_sizes(&self) -> bool {
-        self.payload_size > Self::SHORT_SIZE_MAX || self.frame_count > Self::SHORT_SIZE_MAX
+        let mut offset = layout.id_offset();
+        if flags & Self::FLAG_ID_PRESENT != 0 {
+            offset += if flags & Self::FLAG_ID_U64 != 0 { 8 } else { 4 };
+        }
+        Ok(Some(offset))
+    }
+
+    pub fn extract_timecode(header_bytes: &[u8]) -> Result<Option<Timecode>, String> {
+        let Some(offset) = Self::extension_offset(header_bytes, Extension::Timecode)? else {
+            return Ok(None);
+        };
+        let packed = u32::from_be_bytes(
+            header_bytes[offset..offset + Self::TIMECODE_BYTES]
+                .try_into()
+                .unwrap(),
+        );
+        Timecode::from_packed(packed)
+            .map(Some)
+            .map_err(|err| err.to_string())
+    }
+
+    /// Overwrites the timecode of an encoded header in place, under the same
+    /// conditions as `patch_user_data`.
+    pub fn patch_timecode(header_bytes: &mut [u8], timecode: Timecode) -> Result<(), String> {
+        Self::patch_extension(
+            header_bytes,
+            Extension::Timecode,
+            &timecode.to_packed().to_be_bytes(),
+        )
+    }
+
+    pub fn extract_user_data(header_bytes: &[u8]) -> Result<Option<u32>, String> {
+        let Some(offset) = Self::extension_offset(header_bytes, Extension::UserData)? else {
+            return Ok(None);
+        };
+        Ok(Some(u32::from_be_bytes(
+            header_bytes[offset..offset + Self::USER_DATA_BYTES]
+                .try_into()
+                .unwrap(),
+        )))
+    }
+
+    /// Overwrites the user data of an encoded header in place, in both
+    /// copies of a redundant one. The header must already carry user data,
+    /// and must not carry a packet CRC or auth tag, which cover it: re-encode
+    /// or re-sign with the payload to change it.
+    pub fn patch_user_data(header_bytes: &mut [u8], user_data: u32) -> Result<(), String> {
+        Self::patch_extension(header_bytes, Extension::UserData, &user_data.to_be_bytes())
+    }
+
+    pub fn extract_encoder_delay(header_bytes: &[u8]) -> Result<Option<u16>, String> {
+        Self::extract_u16_extension(header_bytes, Extension::EncoderDelay)
+    }
+
+    /// Overwrites the encoder delay of an encoded header in place, under the
+    /// same conditions as `patch_user_data`.
+    pub fn patch_encoder_delay(header_bytes: &mut [u8], encoder_delay: u16) -> Result<(), String> {
+        Self::patch_extension(
+            header_bytes,
+            Extension::EncoderDelay,
+            &encoder_delay.to_be_bytes(),
+        )
+    }
+
+    pub fn extract_end_padding(header_bytes: &[u8]) -> Result<Option<u16>, String> {
+        Self::extract_u16_extension(header_bytes, Extension::EndPadding)
+    }
+
+    /// Overwrites the end padding of an encoded header in place, under the
+    /// same conditions as `patch_user_data`.
+    pub fn patch_end_padding(header_bytes: &mut [u8], end_padding: u16) -> Result<(), String> {
+        Self::patch_extension(
+            header_bytes,
+            Extension::EndPadding,
+            &end_padding.to_be_bytes(),
+        )
+    }
+
+    pub fn extract_payload_corrupt(header_bytes: &[u8]) -> Result<bool, String> {
+        Ok(Self::wire_layout(header_bytes)?.1.payload_corrupt)
+    }
+
+    /// Sets or clears the corrupt-payload mark of the header at the start of
+    /// `frame_bytes`, re-encoding it in place; a payload after it moves with
+    /// the header if its size changes. A packet CRC or auth tag covers the
+    /// mark, so a header with either must be re-encoded or re-signed with its
+    /// payload instead.
+    pub fn patch_payload_corrupt(frame_bytes: &mut Vec<u8>, corrupt: bool) -> Result<(), String> {
+        if Self::has_packet_crc32(frame_bytes)? {
+            return Err(
+                "v2 packet CRC covers the corrupt-payload mark; re-encode the frame".to_string(),
+            );
+        }
+        if Self::extract_auth_tag(frame_bytes)?.is_some() {
+            return Err(
+                "v2 auth tag covers the corrupt-payload mark; re-sign the frame".to_string(),
+            );
+        }
+        let (header, _, size) =
+            Self::decode_redundant(frame_bytes).map_err(|err| err.to_string())?;
+        let mut encoded = Vec::with_capacity(Self::MAX_SIZE);
+        header
+            .with_payload_corrupt(corrupt)
+            .encode(&mut encoded)
+            .map_err(|err| err.to_string())?;
+        frame_bytes.splice(..size, encoded);
+        Ok(())
+    }
+
+    pub fn extract_sample_offset(header_bytes: &[u8]) -> Result<Option<u64>, String> {
+        let Some(offset) = Self::extension_offset(header_bytes, Extension::SampleOffset)? else {
+            return Ok(None);
+        };
+        Ok(Some(u64::from_be_bytes(
+            header_bytes[offset..offset + Self::SAMPLE_OFFSET_BYTES]
+                .try_into()
+                .unwrap(),
+        )))
+    }
+
+    /// Overwrites the sample offset of an encoded header in place, under the
+    /// same conditions as `patch_user_data`.
+    pub fn patch_sample_offset(header_bytes: &mut [u8], sample_offset: u64) -> Result<(), String> {
+        Self::patch_extension(
+            header_bytes,
+            Extension::SampleOffset,
+            &sample_offset.to_be_bytes(),
+        )
+    }
+
+    pub fn extract_auth_tag(header_bytes: &[u8]) -> Result<Option<[u8; 16]>, String> {
+        let Some(offset) = Self::extension_offset(header_bytes, Extension::AuthTag)? else {
+            return Ok(None);
+        };
+        Ok(Some(
+            header_bytes[offset..offset + Self::AUTH_TAG_BYTES]
+                .try_into()
+                .unwrap(),
+        ))
+    }
+
+    fn extract_u16_extension(
+        header_bytes: &[u8],
+        extension: Extension,
+    ) -> Result<Option<u16>, String> {
+        let Some(offset) = Self::extension_offset(header_bytes, extension)? else {
+            return Ok(None);
+        };
+        Ok(Some(u16::from_be_bytes(
+            header_bytes[offset..offset + 2].try_into().unwrap(),
+        )))
+    }
+
+    fn patch_extension(
+        header_bytes: &mut [u8],
+        extension: Extension,
+        value: &[u8],
+    ) -> Result<(), String> {
+        let name = match extension {
+            Extension::Timecode => "timecode",
+            Extension::UserData => "user data",
+            Extension::EncoderDelay => "encoder delay",
+            Extension::EndPadding => "end padding",
+            Extension::SampleOffset => "sample offset",
+            Extension::AuthTag => "auth tag",
+        };
+        let Some(offset) = Self::extension_offset(header_bytes, extension)? else {
+            return Err(format!("v2 header has no {} field", name));
+        };
+        if Self::has_packet_crc32(header_bytes)? {
+            return Err(format!(
+                "v2 packet CRC covers the {}; re-encode the frame",
+                name
+            ));
+        }
+        if Self::extract_auth_tag(header_bytes)?.is_some() {
+            return Err(format!(
+                "v2 auth tag covers the {}; re-sign the frame",
+                name
+            ));
+        }
+        let copy_size = Self::header_copy_size(header_bytes)?;
+        let header_size = Self::header_size(header_bytes)?;
+        for copy_start in (0..header_size).step_by(copy_size) {
+            let start = copy_start + offset;
+            header_bytes[start..start + value.len()].copy_from_slice(value);
+        }
+        Ok(())
+    }
+
+    // Where an extension field of a valid header copy starts, if present.
+    // The fields follow the PTS in `SizeWordLayout::extensions` order.
+    fn extension_offset(
+        header_bytes: &[u8],
+        extension: Extension,
+    ) -> Result<Option<usize>, String> {
+        let (flags, layout) = Self::wire_layout(header_bytes)?;
+        let mut offset = layout.id_offset();
+        if flags & Self::FLAG_ID_PRESENT != 0 {
+            offset += if flags & Self::FLAG_ID_U64 != 0 { 8 } else { 4 };
+        }
+        if flags & Self::FLAG_PTS_PRESENT != 0 {
+            offset += 8;
+        }
+        for (field, present, width) in layout.extensions() {
+            if field == extension {
+                return Ok(present.then_some(offset));
+            }
+            if present {
+                offset += width;
+            }
+        }
+        Ok(None)
+    }
+
+    // The flags and size word layout of a valid header.
+    fn wire_layout(header_bytes: &[u8]) -> Result<(u8, SizeWordLayout), String> {
+        if !Self::validate_header(header_bytes)? {
+            return Err("Invalid v2 header".to_string());
+        }
+        let word = u32::from_be_bytes(header_bytes[..4].try_into().unwrap());
+        let flags = ((word & Self::FLAGS_MASK) >> Self::FLAGS_SHIFT) as u8;
+        Ok((flags, Self::copy_layout(header_bytes)?))
+    }
+
+    /// The bytes `header_size` needs to see: the base header, plus the
+    /// extension word when the base header announces one. Needs the base
+    /// header.
+    pub fn header_prefix_size(header_bytes: &[u8]) -> Result<usize, String> {
+        if header_bytes.len() < Self::BASE_SIZE {
+            return Err(Self::need_more_bytes(Self::BASE_SIZE, header_bytes.len()));
+        }
+        let word = u32::from_be_bytes(header_bytes[..4].try_into().unwrap());
+        let flags = ((word & Self::FLAGS_MASK) >> Self::FLAGS_SHIFT) as u8;
+        let size_word = u32::from_be_bytes(header_bytes[4..8].try_into().unwrap());
+        Ok(Self::size_layout(flags, size_word)?.sizes_offset())
+    }
+
+    // The size word layout of the copy at the start of `header_bytes`,
+    // including what its extension word announces.
+    pub(crate) fn copy_layout(header_bytes: &[u8]) -> Result<SizeWordLayout, String> {
+        let prefix_size = Self::header_prefix_size(header_bytes)?;
+        if header_bytes.len() < prefix_size {
+            return Err(Self::need_more_bytes(prefix_size, header_bytes.len()));
+        }
+        let word = u32::from_be_bytes(header_bytes[..4].try_into().unwrap());
+        let flags = ((word & Self::FLAGS_MASK) >> Self::FLAGS_SHIFT) as u8;
+        let size_word = u32::from_be_bytes(header_bytes[4..8].try_into().unwrap());
+        let mut layout = Self::size_layout(flags, size_word)?;
+        if layout.extension_word {
+            let extension_word = u32::from_be_bytes(
+                header_bytes[Self::BASE_SIZE..prefix_size]
+                    .try_into()
+                    .unwrap(),
+            );
+            layout = layout.with_extension_word(extension_word)?;
+        }
+        let version = (word & Self::VERSION_MASK) >> Self::VERSION_SHIFT;
+        Ok(layout.check_redundancy(version)?)
+    }
+
+    // The error for a buffer that ends before the bytes `header_size` needs,
+    // kept apart from a corrupt header so stream readers know to read more.
+    fn need_more_bytes(needed: usize, got: usize) -> String {
+        format!(
+            "Need {} bytes to read the v2 header size, got {}",
+            needed, got
+        )
+    }
+
+    // What the flags and size word announce: extended sizes, an extension
+    // word, or both. The extension word itself is read separately.
+    fn size_layout(flags: u8, size_word: u32) -> Result<SizeWordLayout, &'static str> {
+        let payload_sentinel = size_word >> 16 == Self::SHORT_SIZE_SENTINEL;
+        let frame_count_sentinel = size_word & 0xFFFF == Self::SHORT_SIZE_SENTINEL;
+        if flags & Self::FLAG_EXTENDED_SIZES == 0 {
+            if payload_sentinel || frame_count_sentinel {
+                return Err("Short-size sentinel requires extended v2 sizes");
+            }
+            return Ok(SizeWordLayout::default());
+        }
+        match size_word {
+            Self::EXTENDED_SIZES_WORD => Ok(SizeWordLayout {
+                extended_sizes: true,
+                ..SizeWordLayout::default()
+            }),
+            Self::EXTENDED_SIZES_WITH_EXTENSIONS => Ok(SizeWordLayout {
+                extension_word: true,
+                extended_sizes: true,
+                ..SizeWordLayout::default()
+            }),
+            _ if payload_sentinel || frame_count_sentinel => {
+                Err("Extended v2 sizes must use short-size sentinels")
+            }
+            _ => Ok(SizeWordLayout {
+                extension_word: true,
+                ..SizeWordLayout::default()
+            }),
+        }
+    }
+
+    fn size_word_layout(&self) -> SizeWordLayout {
+        let layout = SizeWordLayout {
+            extension_word: false,
+            extended_sizes: self.needs_extended_sizes(),
+            redundant: self.redundant,
+            user_data: self.user_data.is_some(),
+            timecode: self.timecode.is_some(),
+            encoder_delay: self.encoder_delay.is_some(),
+            end_padding: self.end_padding.is_some(),
+            sample_offset: self.sample_offset.is_some(),
+            payload_corrupt: self.payload_corrupt,
+            packed: self.packed,
+            auth_tag: self.auth_tag.is_some(),
+        };
+        SizeWordLayout {
+            extension_word: layout.encoded_extension_word() != 0,
+            ..layout
+        }
+    }
+
+    // Size of one copy of a possibly redundant header.
+    pub(crate) fn header_copy_size(header_bytes: &[u8]) -> Result<usize, String> {
+        let header_size = Self::header_size(header_bytes)?;
+        let word = u32::from_be_bytes(header_bytes[..4].try_into().unwrap());
+        if (word & Self::VERSION_MASK) >> Self::VERSION_SHIFT == Self::VERSION_REDUNDANT {
+            Ok(header_size / 2)
+        } else {
+            Ok(header_size)
+        }
+    }
+
+    pub(crate) fn has_packet_crc32(header_bytes: &[u8]) -> Result<bool, String> {
+        if !Self::validate_header(header_bytes)? {
+            return Err("Invalid v2 header".to_string());
+        }
+        let word = u32::from_be_bytes(header_bytes[..4].try_into().unwrap());
+        let flags = ((word & Self::FLAGS_MASK) >> Self::FLAGS_SHIFT) as u8;
+        Ok(flags & Self::FLAG_PACKET_CRC32_PRESENT != 0)
+    }
+
+    fn validate_fields(&self) -> Result<(), String> {
+        self.encoding
+            .validate_code((Self::ENCODING_MASK >> Self::ENCODING_SHIFT) as u8)?;
+        if self.channels == 0 || self.channels > 32 {
+            return Err("Channel count must be between 1 and 32".to_string());
+        }
+        if Self::sample_rate_code(self.sample_rate).is_none() {
+            return Err(format!(
+                "Invalid sample rate: {}. Must be one of: {:?}",
+                self.sample_rate,
+                Self::VALID_SAMPLE_RATES
+            ));
+        }
+        if Self::bits_code(self.bits_per_sample).is_none() {
+            return Err("Bits per sample must be 0, 8, 16, 24, 32, or 64".to_string());
+        }
+        if matches!(
+            self.encoding,
+            EncodingFlag::PCMSigned | EncodingFlag::PCMFloat
+        ) && self.bits_per_sample == 0
+        {
+            return Err("PCM headers must set bits per sample".to_string());
+        }
+        if self.packet_flags & !Self::PUBLIC_PACKET_FLAGS != 0 {
+            return Err("Unsupported v2 packet flags set".to_string());
+        }
+        if self.id.is_none() && self.id_is_u64 {
+            return Err("64-bit ID flag requires an ID".to_string());
+        }
+        if !self.needs_extended_sizes()
+            && (self.payload_size == Self::SHORT_SIZE_SENTINEL
+                || self.frame_count == Self::SHORT_SIZE_SENTINEL)
+        {
+            return Err("Short v2 size fields reserve 65535 as the extension sentinel".to_string());
+        }
+        Ok(())
+    }
+
+    fn encoded_flags(&self) -> u8 {
+        let mut flags = self.packet_flags & Self::PUBLIC_PACKET_FLAGS;
+        if self.id.is_some() {
+            flags |= Self::FLAG_ID_PRESENT;
+        }
+        if self.encoded_id_is_u64() {
+            flags |= Self::FLAG_ID_U64;
+        }
+        if self.pts.is_some() {
+            flags |= Self::FLAG_PTS_PRESENT;
+        }
+        if self.packet_crc32.is_some() {
+            flags |= Self::FLAG_PACKET_CRC32_PRESENT;
+        }
+        if self.endianness == Endianness::BigEndian {
+            flags |= Self::FLAG_BIG_ENDIAN;
+        }
+        let layout = self.size_word_layout();
+        if layout.extended_sizes || layout.extension_word {
+            flags |= Self::FLAG_EXTENDED_SIZES;
+        }
+        flags
+    }
+
+    fn needs_extended_sizes(&self) -> bool {
+        self.payload_size > Self::SHORT_SIZE_MAX || self.frame_count > Self::SHORT_SIZE_MAX
     }
 
     fn encoded_id_is_u64(&self) -> bool {
@@ -733,9 +1825,10 @@ impl FrameHeader {
 
     const SAMPLE_SIZE_MASK: u32 = 0xFFF;
 
-    const VALID_SAMPLE_RATES: [u32; 4] = [16000, 44100, 48000, 96000];
+    pub(crate) const VALID_SAMPLE_RATES: [u32; 4] = [16000, 44100, 48000, 96000];
     const MAX_SAMPLE_SIZE: u16 = 0xFFF;
 
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         encoding: EncodingFlag,
         sample_size: u16,
@@ -746,6 +1839,7 @@ impl FrameHeader {
         id: Option<u64>,
         pts: Option<u64>,
     ) -> Result<Self, String> {
+        encoding.validate_code((Self::ENCODING_MASK >> Self::ENCODING_SHIFT) as u8)?;
         if channels == 0 || channels > 16 {
             return Err("Channel count must be between 1 and 16".to_string());
         }
@@ -783,38 +1877,24 @@ impl FrameHeader {
     }
 
     pub fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.encoding
+            .validate_code((Self::ENCODING_MASK >> Self::ENCODING_SHIFT) as u8)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+
         let mut header: u32 = Self::MAGIC_WORD << Self::MAGIC_SHIFT;
 
-        let sample_rate_code = match self.sample_rate {
-            16000 => 0,
-            44100 => 1,
-            48000 => 2,
-            96000 => 3,
-            _ => {
-                return Err(io::Error::new(
-                    io::ErrorKind::InvalidInput,
-                    "Invalid sample rate",
-                ))
-            }
-        };
+        let sample_rate_code = Self::sample_rate_code(self.sample_rate)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Invalid sample rate"))?;
         header |= sample_rate_code << Self::SAMPLE_RATE_SHIFT;
 
-        let bits_code = match self.bits_per_sample {
-            16 => 0,
-            24 => 1,
-            32 => 2,
-            _ => {
-                return Err(io::Error::new(
-                    io::ErrorKind::InvalidInput,
-                    "Invalid bits per sample",
-                ))
-            }
-        };
+        let bits_code = Self::bits_code(self.bits_per_sample).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "Invalid bits per sample")
+        })?;
         header |= bits_code << Self::BITS_SHIFT;
 
         header |= (self.pts.is_some() as u32) << Self::PTS_SHIFT;
         header |= (self.id.is_some() as u32) << Self::ID_SHIFT;
-        header |= (self.encoding as u32) << Self::ENCODING_SHIFT;
+        header |= (self.encoding.code() as u32) << Self::ENCODING_SHIFT;
         header |= (self.endianness as u32) << Self::ENDIAN_SHIFT;
         header |= ((self.channels - 1) as u32) << Self::CHANNELS_SHIFT;
         header |= self.sample_size as u32;
@@ -833,6 +1913,21 @@ impl FrameHeader {
     }
 
     pub fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+        Self::decode_with_options(reader, DecodeOptions::default())
+    }
+
+    /// `decode` followed by `validate_encoding_constraints`, so a header that
+    /// is well-formed but inconsistent fails with `InvalidData`.
+    pub fn decode_validated<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let header = Self::decode(reader)?;
+        header.validate_encoding_constraints()?;
+        Ok(header)
+    }
+
+    pub fn decode_with_options<R: Read>(
+        reader: &mut R,
+        options: DecodeOptions,
+    ) -> io::Result<Self> {
         let mut header_bytes = [0u8; 4];
         reader.read_exact(&mut header_bytes)?;
         let header = u32::from_be_bytes(header_bytes);
@@ -873,7 +1968,7 @@ impl FrameHeader {
         let has_id = (header & Self::ID_MASK) >> Self::ID_SHIFT == 1;
 
         let encoding =
-            match encoding_from_code((header & Self::ENCODING_MASK) >> Self::ENCODING_SHIFT) {
+            match options.encoding((header & Self::ENCODING_MASK) >> Self::ENCODING_SHIFT) {
                 Some(encoding) => encoding,
                 None => {
                     return Err(io::Error::new(
@@ -921,37 +2016,50 @@ impl FrameHeader {
     }
 
     pub fn validate_header(header_bytes: &[u8]) -> Result<bool, String> {
+        match Self::validate_header_with_reason(header_bytes) {
+            Ok(()) => Ok(true),
+            Err(FrameHeaderError::Truncated) => Err("Header too small".to_string()),
+            Err(_) => Ok(false),
+        }
+    }
+
+    /// Like `validate_header`, but names the field that made the header invalid.
+    pub fn validate_header_with_reason(header_bytes: &[u8]) -> Result<(), FrameHeaderError> {
         if header_bytes.len() < 4 {
-            return Err("Header too small".to_string());
+            return Err(FrameHeaderError::Truncated);
         }
 
         let header = u32::from_be_bytes(header_bytes[..4].try_into().unwrap());
+        if Self::validate_word(header) {
+            return Ok(());
+        }
 
-        if (header & Self::MAGIC_MASK) >> Self::MAGIC_SHIFT != Self::MAGIC_WORD {
-            return Ok(false);
+        let magic = (header & Self::MAGIC_MASK) >> Self::MAGIC_SHIFT;
+        if magic != Self::MAGIC_WORD {
+            return Err(FrameHeaderError::InvalidMagic(magic));
         }
 
         let encoding = (header & Self::ENCODING_MASK) >> Self::ENCODING_SHIFT;
-        if encoding > 5 {
-            return Ok(false);
+        if encoding_from_code(encoding).is_none() {
+            return Err(FrameHeaderError::InvalidEncoding(encoding));
         }
 
         let sample_rate_code = (header & Self::SAMPLE_RATE_MASK) >> Self::SAMPLE_RATE_SHIFT;
         if sample_rate_code > 3 {
-            return Ok(false);
+            return Err(FrameHeaderError::InvalidSampleRateCode(sample_rate_code));
         }
 
         let channels = (((header & Self::CHANNELS_MASK) >> Self::CHANNELS_SHIFT) + 1) as u8;
         if channels == 0 || channels > 16 {
-            return Ok(false);
+            return Err(FrameHeaderError::InvalidChannels(channels));
         }
 
         let bits_code = (header & Self::BITS_MASK) >> Self::BITS_SHIFT;
         if bits_code > 2 {
-            return Ok(false);
+            return Err(FrameHeaderError::InvalidBitsCode(bits_code));
         }
 
-        Ok(true)
+        Ok(())
     }
 
     pub fn size(&self) -> usize {
@@ -960,15 +2068,68 @@ impl FrameHeader {
         (self.pts.is_some() as usize) * 8 // Optional PTS
     }
 
+    /// The largest `size()` of any header: base word, ID and PTS.
+    pub const fn max_encoded_header_size() -> usize {
+        4 + 8 + 8
+    }
+
+    /// The largest PCM payload any header can describe: 4095 samples of
+    /// 4 bytes on 16 channels.
+    pub const fn max_pcm_payload_bytes() -> usize {
+        Self::MAX_SAMPLE_SIZE as usize * 16 * 4
+    }
+
+    /// Worst-case bytes for a header and PCM payload, whatever this
+    /// header's own fields are, so one buffer fits any frame of a stream.
+    pub fn max_frame_size_pcm(&self) -> usize {
+        Self::max_encoded_header_size() + Self::max_pcm_payload_bytes()
+    }
+
+    /// A zeroed buffer that any header can be encoded into.
+    pub fn safe_encode_buffer() -> Box<[u8; 20]> {
+        Box::new([0; Self::max_encoded_header_size()])
+    }
+
+    /// A zeroed buffer of `max_frame_size_pcm()` bytes.
+    pub fn safe_frame_buffer(&self) -> Vec<u8> {
+        vec![0; self.max_frame_size_pcm()]
+    }
+
     // Getter methods
-    pub fn encoding(&self) -> &EncodingFlag {
+    pub fn encoding(&self) -> EncodingFlag {
+        self.encoding
+    }
+
+    #[deprecated(
+        since = "0.3.0",
+        note = "use `encoding()`, which returns the flag by value"
+    )]
+    pub fn encoding_ref(&self) -> &EncodingFlag {
         &self.encoding
     }
 
+    /// Samples per channel, not bytes. Use `samples_per_channel()`, or
+    /// `total_samples()` and `frame_payload_bytes()` for the other counts.
+    #[deprecated(
+        since = "0.3.0",
+        note = "use `samples_per_channel()`; `sample_size` is a sample count, not a byte size"
+    )]
     pub fn sample_size(&self) -> u16 {
+        self.samples_per_channel()
+    }
+
+    /// Samples in this frame for each channel.
+    #[inline]
+    pub fn samples_per_channel(&self) -> u16 {
         self.sample_size
     }
 
+    /// Samples in this frame across all channels.
+    #[inline]
+    pub fn total_samples(&self) -> u32 {
+        self.samples_per_channel() as u32 * self.channels as u32
+    }
+
     pub fn sample_rate(&self) -> u32 {
         self.sample_rate
     }
@@ -981,7 +2142,12 @@ impl FrameHeader {
         self.bits_per_sample
     }
 
-    pub fn endianness(&self) -> &Endianness {
+    pub fn endianness(&self) -> Endianness {
+        self.endianness
+    }
+
+    #[deprecated(since = "0.3.0", note = "use `endianness()`, which returns the value")]
+    pub fn endianness_ref(&self) -> &Endianness {
         &self.endianness
     }
 
@@ -993,7 +2159,41 @@ impl FrameHeader {
         self.pts
     }
 
+    /// 2, 3 or 4.
+    #[inline]
+    pub fn bytes_per_sample(&self) -> u8 {
+        self.bits_per_sample / 8
+    }
+
+    /// Payload bytes of one channel in this frame.
+    #[inline]
+    pub fn bytes_per_frame_channel(&self) -> u16 {
+        self.samples_per_channel() * self.bytes_per_sample() as u16
+    }
+
+    /// Payload bytes of this frame across all channels, assuming PCM.
+    #[inline]
+    pub fn total_pcm_frame_bytes(&self) -> u32 {
+        self.checked_pcm_payload_len()
+            .expect("4095 samples × 16 channels × 4 bytes fits a u32")
+    }
+
+    /// Payload bytes of this frame for PCM, `None` for compressed encodings
+    /// whose packet sizes vary.
+    pub fn frame_payload_bytes(&self) -> Option<u32> {
+        matches!(
+            self.encoding,
+            EncodingFlag::PCMSigned | EncodingFlag::PCMFloat
+        )
+        .then(|| self.total_pcm_frame_bytes())
+    }
+
     // Extract methods
+    /// Same as `extract_sample_count`: samples per channel, not bytes.
+    pub fn extract_samples_per_channel(header_bytes: &[u8]) -> Result<u16, String> {
+        Self::extract_sample_count(header_bytes)
+    }
+
     pub fn extract_sample_count(header_bytes: &[u8]) -> Result<u16, String> {
         if header_bytes.len() < 4 {
             return Err("Header too small".to_string());
@@ -1118,10 +2318,11 @@ impl FrameHeader {
         if !Self::validate_header(header_bytes)? {
             return Err("Invalid header".to_string());
         }
+        encoding.validate_code((Self::ENCODING_MASK >> Self::ENCODING_SHIFT) as u8)?;
 
         let mut header = u32::from_be_bytes(header_bytes[..4].try_into().unwrap());
         header &= !Self::ENCODING_MASK;
-        header |= ((encoding as u32) << Self::ENCODING_SHIFT) & Self::ENCODING_MASK;
+        header |= ((encoding.code() as u32) << Self::ENCODING_SHIFT) & Self::ENCODING_MASK;
         header_bytes[..4].copy_from_slice(&header.to_be_bytes());
         Ok(())
     }
@@ -1210,6 +2411,17 @@ impl FrameHeader {
         header_bytes[..4].copy_from_slice(&header.to_be_bytes());
         Ok(())
     }
+
+    /// Shifts the PTS by `delta` in place and returns the new PTS. On error
+    /// the buffer is left untouched.
+    pub fn patch_pts_add(header_bytes: &mut [u8], delta: i64) -> Result<u64, FrameHeaderError> {
+        let pts = Self::extract_pts(header_bytes)?.ok_or(FrameHeaderError::MissingPts)?;
+        let shifted = pts
+            .checked_add_signed(delta)
+            .ok_or(FrameHeaderError::PtsOutOfRange { pts, offset: delta })?;
+        Self::patch_pts(header_bytes, Some(shifted))?;
+        Ok(shifted)
+    }
 }
 
 #[cfg(test)]
@@ -1352,12 +2564,12 @@ mod tests {
         // Test sample size patching
         assert!(FrameHeader::patch_sample_size(&mut header_bytes, 2048).is_ok());
         let updated = FrameHeader::decode(&mut &header_bytes[..]).unwrap();
-        assert_eq!(updated.sample_size(), 2048);
+        assert_eq!(updated.samples_per_channel(), 2048);
 
         // Test encoding patching
         assert!(FrameHeader::patch_encoding(&mut header_bytes, EncodingFlag::FLAC).is_ok());
         let updated = FrameHeader::decode(&mut &header_bytes[..]).unwrap();
-        assert_eq!(*updated.encoding(), EncodingFlag::FLAC);
+        assert_eq!(updated.encoding(), EncodingFlag::FLAC);
 
         // Test sample rate patching
         assert!(FrameHeader::patch_sample_rate(&mut header_bytes, 96000).is_ok());
@@ -1452,8 +2664,56 @@ mod tests {
 
         // Now test with a decoded header to verify consistency
         let decoded = FrameHeader::decode(&mut &buffer[..]).unwrap();
-        assert_eq!(decoded.sample_size(), 1024, "Sample size decode failed");
-    }
+        assert_eq!(
+            decoded.samples_per_channel(),
+            1024,
+            "Sample size decode failed"
+        );
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_samples_per_channel_accessors() {
+        for (encoding, bits, channels) in [
+            (EncodingFlag::PCMSigned, 16, 2),
+            (EncodingFlag::PCMSigned, 24, 6),
+            (EncodingFlag::PCMFloat, 32, 16),
+            (EncodingFlag::Opus, 16, 1),
+        ] {
+            let header = FrameHeader::new(
+                encoding,
+                960,
+                48000,
+                channels,
+                bits,
+                Endianness::LittleEndian,
+                None,
+                None,
+            )
+            .unwrap();
+            let mut buffer = Vec::new();
+            header.encode(&mut buffer).unwrap();
+
+            assert_eq!(header.samples_per_channel(), header.sample_size());
+            assert_eq!(
+                FrameHeader::extract_samples_per_channel(&buffer),
+                FrameHeader::extract_sample_count(&buffer)
+            );
+            assert_eq!(header.total_samples(), 960 * channels as u32);
+            assert_eq!(
+                header.total_pcm_frame_bytes(),
+                header.total_samples() * bits as u32 / 8
+            );
+            if encoding == EncodingFlag::Opus {
+                assert_eq!(header.frame_payload_bytes(), None);
+            } else {
+                assert_eq!(
+                    header.frame_payload_bytes(),
+                    Some(header.total_pcm_frame_bytes())
+                );
+            }
+        }
+    }
 
     //This test ensures field boundaries by setting each field to its maximum value and verifying no corruption.
     #[test]
@@ -1475,10 +2735,10 @@ mod tests {
         let decoded = FrameHeader::decode(&mut &buffer[..]).unwrap();
 
         // Verify max values are preserved
-        assert_eq!(decoded.sample_size(), 0xFFF);
+        assert_eq!(decoded.samples_per_channel(), 0xFFF);
         assert_eq!(decoded.channels(), 16);
         assert_eq!(decoded.bits_per_sample(), 32);
-        assert_eq!(decoded.endianness(), &Endianness::BigEndian);
+        assert_eq!(decoded.endianness(), Endianness::BigEndian);
         assert!(decoded.id().is_some());
         assert!(decoded.pts().is_some());
     }
@@ -1554,12 +2814,12 @@ mod tests {
                                     );
 
                                         let decoded = decoded.unwrap();
-                                        assert_eq!(*decoded.encoding(), encoding);
-                                        assert_eq!(decoded.sample_size(), sample_size);
+                                        assert_eq!(decoded.encoding(), encoding);
+                                        assert_eq!(decoded.samples_per_channel(), sample_size);
                                         assert_eq!(decoded.sample_rate(), sample_rate);
                                         assert_eq!(decoded.channels(), channels);
                                         assert_eq!(decoded.bits_per_sample(), bits);
-                                        assert_eq!(*decoded.endianness(), endianness);
+                                        assert_eq!(decoded.endianness(), endianness);
                                         assert_eq!(decoded.id(), Some(id_val));
                                         assert_eq!(decoded.pts(), Some(pts_val));
                                     }
@@ -1594,12 +2854,12 @@ mod tests {
         let mut test_buffer = buffer.clone();
         FrameHeader::patch_sample_size(&mut test_buffer, 2048).unwrap();
         let updated = FrameHeader::decode(&mut &test_buffer[..]).unwrap();
-        assert_eq!(updated.sample_size(), 2048); // Changed field
-        assert_eq!(*updated.encoding(), *original.encoding());
+        assert_eq!(updated.samples_per_channel(), 2048); // Changed field
+        assert_eq!(updated.encoding(), original.encoding());
         assert_eq!(updated.sample_rate(), original.sample_rate());
         assert_eq!(updated.channels(), original.channels());
         assert_eq!(updated.bits_per_sample(), original.bits_per_sample());
-        assert_eq!(*updated.endianness(), *original.endianness());
+        assert_eq!(updated.endianness(), original.endianness());
         assert_eq!(updated.id(), original.id());
         assert_eq!(updated.pts(), original.pts());
 
@@ -1607,12 +2867,15 @@ mod tests {
         let mut test_buffer = buffer.clone();
         FrameHeader::patch_encoding(&mut test_buffer, EncodingFlag::FLAC).unwrap();
         let updated = FrameHeader::decode(&mut &test_buffer[..]).unwrap();
-        assert_eq!(*updated.encoding(), EncodingFlag::FLAC); // Changed field
-        assert_eq!(updated.sample_size(), original.sample_size());
+        assert_eq!(updated.encoding(), EncodingFlag::FLAC); // Changed field
+        assert_eq!(
+            updated.samples_per_channel(),
+            original.samples_per_channel()
+        );
         assert_eq!(updated.sample_rate(), original.sample_rate());
         assert_eq!(updated.channels(), original.channels());
         assert_eq!(updated.bits_per_sample(), original.bits_per_sample());
-        assert_eq!(*updated.endianness(), *original.endianness());
+        assert_eq!(updated.endianness(), original.endianness());
         assert_eq!(updated.id(), original.id());
         assert_eq!(updated.pts(), original.pts());
 
@@ -1621,11 +2884,14 @@ mod tests {
         FrameHeader::patch_sample_rate(&mut test_buffer, 96000).unwrap();
         let updated = FrameHeader::decode(&mut &test_buffer[..]).unwrap();
         assert_eq!(updated.sample_rate(), 96000); // Changed field
-        assert_eq!(*updated.encoding(), *original.encoding());
-        assert_eq!(updated.sample_size(), original.sample_size());
+        assert_eq!(updated.encoding(), original.encoding());
+        assert_eq!(
+            updated.samples_per_channel(),
+            original.samples_per_channel()
+        );
         assert_eq!(updated.channels(), original.channels());
         assert_eq!(updated.bits_per_sample(), original.bits_per_sample());
-        assert_eq!(*updated.endianness(), *original.endianness());
+        assert_eq!(updated.endianness(), original.endianness());
         assert_eq!(updated.id(), original.id());
         assert_eq!(updated.pts(), original.pts());
 
@@ -1634,11 +2900,14 @@ mod tests {
         FrameHeader::patch_bits_per_sample(&mut test_buffer, 32).unwrap();
         let updated = FrameHeader::decode(&mut &test_buffer[..]).unwrap();
         assert_eq!(updated.bits_per_sample(), 32); // Changed field
-        assert_eq!(*updated.encoding(), *original.encoding());
-        assert_eq!(updated.sample_size(), original.sample_size());
+        assert_eq!(updated.encoding(), original.encoding());
+        assert_eq!(
+            updated.samples_per_channel(),
+            original.samples_per_channel()
+        );
         assert_eq!(updated.sample_rate(), original.sample_rate());
         assert_eq!(updated.channels(), original.channels());
-        assert_eq!(*updated.endianness(), *original.endianness());
+        assert_eq!(updated.endianness(), original.endianness());
         assert_eq!(updated.id(), original.id());
         assert_eq!(updated.pts(), original.pts());
 
@@ -1647,11 +2916,14 @@ mod tests {
         FrameHeader::patch_channels(&mut test_buffer, 8).unwrap();
         let updated = FrameHeader::decode(&mut &test_buffer[..]).unwrap();
         assert_eq!(updated.channels(), 8); // Changed field
-        assert_eq!(*updated.encoding(), *original.encoding());
-        assert_eq!(updated.sample_size(), original.sample_size());
+        assert_eq!(updated.encoding(), original.encoding());
+        assert_eq!(
+            updated.samples_per_channel(),
+            original.samples_per_channel()
+        );
         assert_eq!(updated.sample_rate(), original.sample_rate());
         assert_eq!(updated.bits_per_sample(), original.bits_per_sample());
-        assert_eq!(*updated.endianness(), *original.endianness());
+        assert_eq!(updated.endianness(), original.endianness());
         assert_eq!(updated.id(), original.id());
         assert_eq!(updated.pts(), original.pts());
 
@@ -1660,12 +2932,15 @@ mod tests {
         FrameHeader::patch_id(&mut test_buffer, Some(0xFEEDFACE)).unwrap();
         let updated = FrameHeader::decode(&mut &test_buffer[..]).unwrap();
         assert_eq!(updated.id(), Some(0xFEEDFACE)); // Changed field
-        assert_eq!(*updated.encoding(), *original.encoding());
-        assert_eq!(updated.sample_size(), original.sample_size());
+        assert_eq!(updated.encoding(), original.encoding());
+        assert_eq!(
+            updated.samples_per_channel(),
+            original.samples_per_channel()
+        );
         assert_eq!(updated.sample_rate(), original.sample_rate());
         assert_eq!(updated.channels(), original.channels());
         assert_eq!(updated.bits_per_sample(), original.bits_per_sample());
-        assert_eq!(*updated.endianness(), *original.endianness());
+        assert_eq!(updated.endianness(), original.endianness());
         assert_eq!(updated.pts(), original.pts());
 
         // Test PTS patching
@@ -1673,12 +2948,15 @@ mod tests {
         FrameHeader::patch_pts(&mut test_buffer, Some(0xF00DFACE)).unwrap();
         let updated = FrameHeader::decode(&mut &test_buffer[..]).unwrap();
         assert_eq!(updated.pts(), Some(0xF00DFACE)); // Changed field
-        assert_eq!(*updated.encoding(), *original.encoding());
-        assert_eq!(updated.sample_size(), original.sample_size());
+        assert_eq!(updated.encoding(), original.encoding());
+        assert_eq!(
+            updated.samples_per_channel(),
+            original.samples_per_channel()
+        );
         assert_eq!(updated.sample_rate(), original.sample_rate());
         assert_eq!(updated.channels(), original.channels());
         assert_eq!(updated.bits_per_sample(), original.bits_per_sample());
-        assert_eq!(*updated.endianness(), *original.endianness());
+        assert_eq!(updated.endianness(), original.endianness());
         assert_eq!(updated.id(), original.id());
     }
 
@@ -1805,13 +3083,13 @@ mod tests {
         assert_eq!(FrameHeaderV2::extract_frame_count(&buffer).unwrap(), 960);
 
         let decoded = FrameHeaderV2::decode(&mut &buffer[..]).unwrap();
-        assert_eq!(*decoded.encoding(), EncodingFlag::Opus);
+        assert_eq!(decoded.encoding(), EncodingFlag::Opus);
         assert_eq!(decoded.payload_size(), 127);
         assert_eq!(decoded.frame_count(), 960);
         assert_eq!(decoded.sample_rate(), 48000);
         assert_eq!(decoded.channels(), 2);
         assert_eq!(decoded.bits_per_sample(), 0);
-        assert_eq!(*decoded.endianness(), Endianness::LittleEndian);
+        assert_eq!(decoded.endianness(), Endianness::LittleEndian);
         assert_eq!(decoded.id(), Some(7));
         assert!(!decoded.id_is_u64());
         assert_eq!(decoded.pts(), Some(48_000));
@@ -1914,7 +3192,7 @@ mod tests {
         let decoded = FrameHeaderV2::decode(&mut &buffer[..]).unwrap();
         assert_eq!(decoded.payload_size(), 70_000);
         assert_eq!(decoded.frame_count(), 70_001);
-        assert_eq!(*decoded.endianness(), Endianness::BigEndian);
+        assert_eq!(decoded.endianness(), Endianness::BigEndian);
     }
 
     #[test]
@@ -1966,4 +3244,888 @@ mod tests {
         assert!(!FrameHeaderV2::validate_header(&buffer).unwrap());
         assert!(FrameHeaderV2::decode(&mut &buffer[..]).is_err());
     }
+
+    fn user_data_header(payload_size: u32, id: Option<u64>, pts: Option<u64>) -> FrameHeaderV2 {
+        FrameHeaderV2::new(
+            EncodingFlag::Opus,
+            payload_size,
+            960,
+            48000,
+            2,
+            0,
+            Endianness::LittleEndian,
+            id,
+            pts,
+            None,
+        )
+        .unwrap()
+        .with_user_data(Some(0xDEAD_BEEF))
+    }
+
+    #[test]
+    fn test_v2_user_data_roundtrip() {
+        for payload_size in [0, 127, 0xFFFE, 0xFFFF, 1 << 20] {
+            for id in [None, Some(7), Some(u32::MAX as u64 + 1)] {
+                for pts in [None, Some(48_000)] {
+                    for (crc, redundant) in [(false, false), (true, false), (true, true)] {
+                        let mut header =
+                            user_data_header(payload_size, id, pts).with_redundant_copy(redundant);
+                        if crc {
+                            header = header.with_packet_crc32(b"payload").unwrap();
+                        }
+
+                        let mut buffer = Vec::new();
+                        header.encode(&mut buffer).unwrap();
+                        assert_eq!(buffer.len(), header.size());
+                        assert_eq!(FrameHeaderV2::header_size(&buffer), Ok(buffer.len()));
+                        assert_eq!(FrameHeaderV2::decode(&mut &buffer[..]).unwrap(), header);
+                        assert_eq!(
+                            FrameHeaderV2::extract_user_data(&buffer),
+                            Ok(Some(0xDEAD_BEEF))
+                        );
+                        assert_eq!(
+                            FrameHeaderV2::extract_payload_size(&buffer),
+                            Ok(payload_size)
+                        );
+                        assert_eq!(FrameHeaderV2::extract_frame_count(&buffer), Ok(960));
+                        assert_eq!(FrameHeaderV2::extract_pts(&buffer), Ok(pts));
+
+                        let json = serde_json::to_string(&header).unwrap();
+                        assert_eq!(
+                            serde_json::from_str::<FrameHeaderV2>(&json).unwrap(),
+                            header
+                        );
+                    }
+                }
+            }
+        }
+
+        // Without user data the wire format is unchanged.
+        let plain = user_data_header(127, Some(7), None).with_user_data(None);
+        let mut buffer = Vec::new();
+        plain.encode(&mut buffer).unwrap();
+        assert_eq!(buffer.len(), FrameHeaderV2::BASE_SIZE + 4);
+        assert_eq!(FrameHeaderV2::extract_user_data(&buffer), Ok(None));
+    }
+
+    #[test]
+    fn test_v2_patch_user_data_changes_only_user_data() {
+        for payload_size in [127, 1 << 20] {
+            let header = user_data_header(payload_size, Some(7), Some(48_000))
+                .with_packet_flags(FrameHeaderV2::FLAG_DISCONTINUITY)
+                .unwrap()
+                .with_redundant_copy(true);
+            let mut buffer = Vec::new();
+            header.encode(&mut buffer).unwrap();
+            let original = buffer.clone();
+
+            FrameHeaderV2::patch_user_data(&mut buffer, 42).unwrap();
+            assert_eq!(FrameHeaderV2::extract_user_data(&buffer), Ok(Some(42)));
+            assert_eq!(
+                FrameHeaderV2::decode(&mut &buffer[..]).unwrap(),
+                header.clone().with_user_data(Some(42))
+            );
+            assert_eq!(
+                FrameHeaderV2::decode_redundant(&buffer).unwrap().0,
+                header.clone().with_user_data(Some(42))
+            );
+            let changed: Vec<usize> = (0..buffer.len())
+                .filter(|&index| buffer[index] != original[index])
+                .collect();
+            let copy_size = buffer.len() / 2;
+            let offset = copy_size - FrameHeaderV2::USER_DATA_BYTES;
+            assert!(
+                changed
+                    .iter()
+                    .all(|&index| (offset..copy_size).contains(&(index % copy_size))),
+                "{:?}",
+                changed
+            );
+        }
+
+        let mut plain = Vec::new();
+        user_data_header(127, None, None)
+            .with_user_data(None)
+            .encode(&mut plain)
+            .unwrap();
+        assert!(FrameHeaderV2::patch_user_data(&mut plain, 1).is_err());
+
+        // The packet CRC covers the user data, so it cannot change alone.
+        let header = user_data_header(7, None, None)
+            .with_packet_crc32(b"payload")
+            .unwrap();
+        let mut buffer = Vec::new();
+        header.encode(&mut buffer).unwrap();
+        assert!(FrameHeaderV2::patch_user_data(&mut buffer, 1).is_err());
+        assert!(header.verify_packet_crc32(&buffer, b"payload").unwrap());
+        buffer[FrameHeaderV2::BASE_SIZE + FrameHeaderV2::EXTENSION_WORD_BYTES] ^= 1;
+        assert!(!header.verify_packet_crc32(&buffer, b"payload").unwrap());
+    }
+
+    #[test]
+    fn test_v2_user_data_survives_stream_rewrites() {
+        let mut writer = FrameWriter::new(Vec::new());
+        for index in 0..4u64 {
+            let header = user_data_header(3, Some(index), Some(index * 960))
+                .with_user_data(Some(index as u32 * 0x0101_0101))
+                .with_timecode(Some(Timecode::from_frame_count(index, 25, false).unwrap()));
+            let header = header.with_packet_crc32(&[index as u8; 3]).unwrap();
+            writer.write_frame(&header, &[index as u8; 3]).unwrap();
+        }
+        let mut bytes = writer.into_inner();
+        retime_slice(&mut bytes, 960, RetimePolicy::Error).unwrap();
+
+        for (index, frame) in FrameReader::new(&bytes[..]).enumerate() {
+            let (header, payload) = frame.unwrap();
+            assert_eq!(header.user_data(), Some(index as u32 * 0x0101_0101));
+            assert_eq!(
+                header.timecode().map(|timecode| timecode.frames()),
+                Some(index as u8)
+            );
+            assert_eq!(header.pts(), Some((index as u64 + 1) * 960));
+            assert_eq!(
+                header.packet_crc32_value(),
+                Some(header.compute_packet_crc32(&payload).unwrap())
+            );
+        }
+    }
+
+    #[test]
+    fn test_v2_extension_word_wire_forms() {
+        let encode = |header: &FrameHeaderV2| {
+            let mut buffer = Vec::new();
+            header.encode(&mut buffer).unwrap();
+            buffer
+        };
+        let word = |buffer: &[u8], offset: usize| {
+            u32::from_be_bytes(buffer[offset..offset + 4].try_into().unwrap())
+        };
+
+        // Short sizes, then the extension word, then the user data.
+        let short = encode(&user_data_header(127, None, None));
+        assert_eq!(word(&short, 4), 127 << 16 | 960);
+        assert_eq!(word(&short, 8), FrameHeaderV2::EXTENSION_USER_DATA);
+        assert_eq!(word(&short, 12), 0xDEAD_BEEF);
+        assert_eq!(short.len(), 16);
+        assert_eq!(FrameHeaderV2::header_prefix_size(&short), Ok(12));
+
+        // The extension word comes before the extended sizes.
+        let extended = encode(&user_data_header(1 << 20, None, None));
+        assert_eq!(word(&extended, 4), 0xFFFF_FFFE);
+        assert_eq!(word(&extended, 8), FrameHeaderV2::EXTENSION_USER_DATA);
+        assert_eq!(word(&extended, 12), 1 << 20);
+        assert_eq!(word(&extended, 16), 960);
+        assert_eq!(word(&extended, 20), 0xDEAD_BEEF);
+        assert_eq!(FrameHeaderV2::header_prefix_size(&extended), Ok(12));
+
+        // Extended sizes alone have no extension word.
+        let sizes_only = encode(&user_data_header(1 << 20, None, None).with_user_data(None));
+        assert_eq!(word(&sizes_only, 4), 0xFFFF_FFFF);
+        assert_eq!(word(&sizes_only, 8), 1 << 20);
+        assert_eq!(sizes_only.len(), 16);
+        assert_eq!(FrameHeaderV2::header_prefix_size(&sizes_only), Ok(8));
+
+        // Neither does a header without optional fields or large sizes.
+        let plain = encode(&user_data_header(127, None, None).with_user_data(None));
+        assert_eq!(plain.len(), FrameHeaderV2::BASE_SIZE);
+        assert_eq!(FrameHeaderV2::header_prefix_size(&plain), Ok(8));
+
+        let rejected = |buffer: &[u8]| {
+            assert!(!FrameHeaderV2::validate_header(buffer).unwrap());
+            assert!(FrameHeaderV2::decode(&mut &buffer[..]).is_err());
+        };
+        // Half sentinels and unassigned sentinel words.
+        for size_word in [0xFFFF_03C0u32, 0x007F_FFFF, 0xFFFF_FFFB] {
+            let mut bad = short.clone();
+            bad[4..8].copy_from_slice(&size_word.to_be_bytes());
+            rejected(&bad);
+        }
+        // An empty extension word, or one with unknown bits.
+        for extension_word in [0u32, 1 << 31] {
+            for buffer in [&short, &extended] {
+                let mut bad = buffer.clone();
+                bad[8..12].copy_from_slice(&extension_word.to_be_bytes());
+                rejected(&bad);
+            }
+        }
+    }
+
+    #[test]
+    fn test_v2_timecode_roundtrip() {
+        let timecode = Timecode::new(1, 23, 45, 12, true).unwrap();
+        for user_data in [None, Some(0xDEAD_BEEF)] {
+            for payload_size in [127, 1 << 20] {
+                for crc in [false, true] {
+                    let mut header = user_data_header(payload_size, Some(7), Some(48_000))
+                        .with_user_data(user_data)
+                        .with_timecode(Some(timecode))
+                        .with_redundant_copy(crc);
+                    if crc {
+                        header = header.with_packet_crc32(b"payload").unwrap();
+                    }
+
+                    let mut buffer = Vec::new();
+                    header.encode(&mut buffer).unwrap();
+                    assert_eq!(FrameHeaderV2::header_size(&buffer), Ok(header.size()));
+                    assert_eq!(FrameHeaderV2::decode(&mut &buffer[..]).unwrap(), header);
+                    assert_eq!(FrameHeaderV2::extract_timecode(&buffer), Ok(Some(timecode)));
+                    assert_eq!(FrameHeaderV2::extract_user_data(&buffer), Ok(user_data));
+                    assert_eq!(
+                        FrameHeaderV2::extract_payload_size(&buffer),
+                        Ok(payload_size)
+                    );
+                    assert_eq!(FrameHeaderV2::extract_pts(&buffer), Ok(Some(48_000)));
+                    let extended = if payload_size > 0xFFFE { 8 } else { 0 };
+                    let offset = FrameHeaderV2::BASE_SIZE
+                        + FrameHeaderV2::EXTENSION_WORD_BYTES
+                        + extended
+                        + 4
+                        + 8;
+                    assert_eq!(buffer[offset..][..4], 0x0123_4552u32.to_be_bytes());
+
+                    let json = serde_json::to_string(&header).unwrap();
+                    assert_eq!(
+                        serde_json::from_str::<FrameHeaderV2>(&json).unwrap(),
+                        header
+                    );
+                }
+            }
+        }
+
+        let mut buffer = Vec::new();
+        user_data_header(127, None, None)
+            .encode(&mut buffer)
+            .unwrap();
+        assert_eq!(FrameHeaderV2::extract_timecode(&buffer), Ok(None));
+        assert!(FrameHeaderV2::patch_timecode(&mut buffer, timecode).is_err());
+
+        // A packed value that is not BCD is rejected on decode.
+        let header = user_data_header(127, None, None).with_timecode(Some(timecode));
+        let mut buffer = Vec::new();
+        header.encode(&mut buffer).unwrap();
+        buffer[FrameHeaderV2::BASE_SIZE + FrameHeaderV2::EXTENSION_WORD_BYTES] = 0x2A;
+        assert!(FrameHeaderV2::decode(&mut &buffer[..]).is_err());
+        assert!(FrameHeaderV2::extract_timecode(&buffer).is_err());
+    }
+
+    #[test]
+    fn test_v2_timecode_and_user_data_patches_are_isolated() {
+        let timecode = Timecode::new(0, 9, 59, 29, true).unwrap();
+        let next =
+            Timecode::from_frame_count(timecode.to_frame_count(30).unwrap() + 1, 30, true).unwrap();
+        assert_eq!(next.to_string(), "00:10:00;00");
+
+        let header = user_data_header(127, Some(7), Some(48_000))
+            .with_timecode(Some(timecode))
+            .with_redundant_copy(true);
+        let mut buffer = Vec::new();
+        header.encode(&mut buffer).unwrap();
+
+        FrameHeaderV2::patch_timecode(&mut buffer, next).unwrap();
+        assert_eq!(
+            FrameHeaderV2::decode_redundant(&buffer).unwrap().0,
+            header.clone().with_timecode(Some(next))
+        );
+        FrameHeaderV2::patch_user_data(&mut buffer, 1).unwrap();
+        assert_eq!(
+            FrameHeaderV2::decode_redundant(&buffer).unwrap().0,
+            header
+                .clone()
+                .with_timecode(Some(next))
+                .with_user_data(Some(1))
+        );
+
+        let crc = header.with_packet_crc32(b"payload").unwrap();
+        let mut buffer = Vec::new();
+        crc.encode(&mut buffer).unwrap();
+        assert!(FrameHeaderV2::patch_timecode(&mut buffer, next).is_err());
+    }
+
+    fn gapless_stream() -> Vec<FrameHeaderV2> {
+        (0..10u64)
+            .map(|index| {
+                let header = FrameHeaderV2::new(
+                    EncodingFlag::AAC,
+                    4,
+                    1024,
+                    48000,
+                    2,
+                    16,
+                    Endianness::LittleEndian,
+                    None,
+                    Some(index * 1024),
+                    None,
+                )
+                .unwrap();
+                match index {
+                    0 => header.with_encoder_delay(Some(576)),
+                    9 => header.with_end_padding(Some(600)),
+                    _ => header,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_v2_gapless_fields_roundtrip_and_patch() {
+        let header = user_data_header(127, Some(7), Some(48_000))
+            .with_timecode(Some(Timecode::new(1, 2, 3, 4, false).unwrap()))
+            .with_encoder_delay(Some(312))
+            .with_end_padding(Some(100))
+            .with_redundant_copy(true);
+        assert_eq!(header.trimmed_frame_count(), 960 - 312 - 100);
+        let mut buffer = Vec::new();
+        header.encode(&mut buffer).unwrap();
+        assert_eq!(buffer.len(), header.size());
+        assert_eq!(FrameHeaderV2::extract_encoder_delay(&buffer), Ok(Some(312)));
+        assert_eq!(FrameHeaderV2::extract_end_padding(&buffer), Ok(Some(100)));
+        assert_eq!(FrameHeaderV2::decode_redundant(&buffer).unwrap().0, header);
+
+        FrameHeaderV2::patch_encoder_delay(&mut buffer, 0).unwrap();
+        FrameHeaderV2::patch_end_padding(&mut buffer, 960).unwrap();
+        let patched = FrameHeaderV2::decode_redundant(&buffer).unwrap().0;
+        assert_eq!(
+            patched,
+            header
+                .clone()
+                .with_encoder_delay(Some(0))
+                .with_end_padding(Some(960))
+        );
+        assert_eq!(patched.trimmed_frame_count(), 0);
+        assert_eq!(
+            FrameHeaderV2::extract_user_data(&buffer),
+            Ok(Some(0xDEAD_BEEF))
+        );
+
+        // Either field alone, without the other extensions.
+        for header in [
+            user_data_header(0, None, None)
+                .with_user_data(None)
+                .with_end_padding(Some(7)),
+            user_data_header(0, None, None)
+                .with_user_data(None)
+                .with_encoder_delay(Some(7)),
+        ] {
+            let mut buffer = Vec::new();
+            header.encode(&mut buffer).unwrap();
+            assert_eq!(FrameHeaderV2::decode(&mut &buffer[..]).unwrap(), header);
+            assert_eq!(FrameHeaderV2::extract_user_data(&buffer), Ok(None));
+            assert!(FrameHeaderV2::patch_timecode(
+                &mut buffer,
+                Timecode::new(0, 0, 0, 0, false).unwrap()
+            )
+            .is_err());
+        }
+
+        let mut buffer = Vec::new();
+        user_data_header(0, None, None).encode(&mut buffer).unwrap();
+        assert_eq!(FrameHeaderV2::extract_encoder_delay(&buffer), Ok(None));
+        assert!(FrameHeaderV2::patch_end_padding(&mut buffer, 1).is_err());
+
+        let crc = header.with_packet_crc32(b"payload").unwrap();
+        let mut buffer = Vec::new();
+        crc.encode(&mut buffer).unwrap();
+        assert!(FrameHeaderV2::patch_encoder_delay(&mut buffer, 1).is_err());
+    }
+
+    #[test]
+    fn test_v2_sample_offset_roundtrip_and_patch() {
+        for header in [
+            user_data_header(127, None, None).with_user_data(None),
+            user_data_header(1 << 20, Some(u32::MAX as u64 + 1), Some(48_000))
+                .with_timecode(Some(Timecode::new(0, 0, 1, 0, false).unwrap()))
+                .with_end_padding(Some(3))
+                .with_redundant_copy(true),
+        ] {
+            let header = header.with_sample_offset(Some(u64::MAX - 959));
+            let mut buffer = Vec::new();
+            header.encode(&mut buffer).unwrap();
+            assert_eq!(buffer.len(), header.size());
+            assert_eq!(
+                FrameHeaderV2::extract_sample_offset(&buffer),
+                Ok(Some(u64::MAX - 959))
+            );
+            assert_eq!(FrameHeaderV2::decode_redundant(&buffer).unwrap().0, header);
+
+            FrameHeaderV2::patch_sample_offset(&mut buffer, 960).unwrap();
+            let patched = FrameHeaderV2::decode_redundant(&buffer).unwrap().0;
+            assert_eq!(patched, header.clone().with_sample_offset(Some(960)));
+            assert_eq!(
+                FrameHeaderV2::extract_end_padding(&buffer),
+                Ok(header.end_padding())
+            );
+        }
+
+        let mut buffer = Vec::new();
+        user_data_header(0, None, None).encode(&mut buffer).unwrap();
+        assert_eq!(FrameHeaderV2::extract_sample_offset(&buffer), Ok(None));
+        assert!(FrameHeaderV2::patch_sample_offset(&mut buffer, 1).is_err());
+    }
+
+    #[test]
+    fn test_v2_payload_corrupt_roundtrip_and_patch() {
+        let payload = [0xAB; 127];
+        for header in [
+            user_data_header(127, Some(7), Some(48_000)).with_user_data(None),
+            user_data_header(127, None, Some(48_000)).with_redundant_copy(true),
+            user_data_header(127, None, None).with_sample_offset(Some(960)),
+        ] {
+            let mut frame = Vec::new();
+            header.encode(&mut frame).unwrap();
+            frame.extend_from_slice(&payload);
+            let original = frame.clone();
+            assert_eq!(FrameHeaderV2::extract_payload_corrupt(&frame), Ok(false));
+
+            FrameHeaderV2::patch_payload_corrupt(&mut frame, true).unwrap();
+            let corrupt = header.clone().with_payload_corrupt(true);
+            let (decoded, _, size) = FrameHeaderV2::decode_redundant(&frame).unwrap();
+            assert_eq!(decoded, corrupt);
+            assert!(decoded.is_payload_corrupt());
+            assert_eq!(size, corrupt.size());
+            assert_eq!(frame[size..], payload);
+            assert_eq!(FrameHeaderV2::extract_payload_corrupt(&frame), Ok(true));
+
+            let mut encoded = Vec::new();
+            corrupt.encode(&mut encoded).unwrap();
+            assert_eq!(FrameHeaderV2::decode(&mut &encoded[..]).unwrap(), corrupt);
+
+            FrameHeaderV2::patch_payload_corrupt(&mut frame, false).unwrap();
+            assert_eq!(frame, original);
+        }
+
+        let crc = user_data_header(3, None, None)
+            .with_payload_corrupt(true)
+            .with_packet_crc32(&[1, 2, 3])
+            .unwrap();
+        let mut frame = Vec::new();
+        crc.encode(&mut frame).unwrap();
+        assert!(FrameHeaderV2::patch_payload_corrupt(&mut frame, false).is_err());
+        assert_eq!(FrameHeaderV2::extract_payload_corrupt(&frame), Ok(true));
+    }
+
+    #[test]
+    fn test_v2_auth_tag_blocks_patches() {
+        let header = user_data_header(3, None, Some(48_000))
+            .with_sample_offset(Some(960))
+            .with_auth_tag(Some([0x5A; 16]))
+            .with_redundant_copy(true);
+        let mut frame = Vec::new();
+        header.encode(&mut frame).unwrap();
+        frame.extend_from_slice(&[1, 2, 3]);
+        let original = frame.clone();
+
+        // The tag covers every other field, so none can change alone.
+        assert_eq!(
+            FrameHeaderV2::patch_user_data(&mut frame, 1),
+            Err("v2 auth tag covers the user data; re-sign the frame".to_string())
+        );
+        assert!(FrameHeaderV2::patch_sample_offset(&mut frame, 0).is_err());
+        assert_eq!(
+            FrameHeaderV2::patch_payload_corrupt(&mut frame, true),
+            Err("v2 auth tag covers the corrupt-payload mark; re-sign the frame".to_string())
+        );
+        assert_eq!(frame, original);
+
+        // Without the tag the same patches go through.
+        let mut frame = Vec::new();
+        header.with_auth_tag(None).encode(&mut frame).unwrap();
+        frame.extend_from_slice(&[1, 2, 3]);
+        FrameHeaderV2::patch_user_data(&mut frame, 1).unwrap();
+        FrameHeaderV2::patch_payload_corrupt(&mut frame, true).unwrap();
+    }
+
+    #[test]
+    fn test_v2_validate_payload() {
+        let pcm = FrameHeaderV2::new(
+            EncodingFlag::PCMSigned,
+            12,
+            3,
+            48000,
+            2,
+            16,
+            Endianness::LittleEndian,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(pcm.validate_payload(&[0; 12]), Ok(()));
+        assert!(pcm.validate_payload(&[0; 11]).is_err());
+
+        // A payload size that does not fit the frame count.
+        let short = FrameHeaderV2::new(
+            EncodingFlag::PCMSigned,
+            10,
+            3,
+            48000,
+            2,
+            16,
+            Endianness::LittleEndian,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        assert!(short.validate_payload(&[0; 10]).is_err());
+        let short = short.with_payload_corrupt(true);
+        assert_eq!(short.validate_payload(&[0; 10]), Ok(()));
+        assert!(short.validate_payload(&[0; 12]).is_err());
+
+        let opus = user_data_header(5, None, None);
+        assert_eq!(opus.validate_payload(&[0; 5]), Ok(()));
+    }
+
+    #[test]
+    fn test_v2_gapless_trim_survives_remux() {
+        let headers = gapless_stream();
+        let trimmed: u64 = headers
+            .iter()
+            .map(|header| header.trimmed_frame_count() as u64)
+            .sum();
+        assert_eq!(trimmed, 10 * 1024 - 576 - 600);
+
+        let mut writer = FrameWriter::new(Vec::new());
+        for header in &headers {
+            writer.write_frame(header, &[0; 4]).unwrap();
+        }
+        // Copy every frame into a second stream, as a remuxer would.
+        let mut remuxed = FrameWriter::new(Vec::new());
+        for frame in FrameReader::new(&writer.into_inner()[..]) {
+            let (header, payload) = frame.unwrap();
+            remuxed.write_frame(&header, &payload).unwrap();
+        }
+        let decoded: Vec<FrameHeaderV2> = FrameReader::new(&remuxed.into_inner()[..])
+            .map(|frame| frame.unwrap().0)
+            .collect();
+        assert_eq!(decoded, headers);
+        assert_eq!(
+            decoded
+                .iter()
+                .map(|header| header.trimmed_frame_count() as u64)
+                .sum::<u64>(),
+            trimmed
+        );
+    }
+
+    #[test]
+    fn test_unknown_encoding_passthrough() {
+        let passthrough = DecodeOptions {
+            allow_unknown_encoding: true,
+        };
+
+        // v1 with encoding code 7, which this version does not define.
+        let mut v1 = Vec::new();
+        FrameHeader::new(
+            EncodingFlag::Opus,
+            960,
+            48000,
+            2,
+            16,
+            Endianness::LittleEndian,
+            Some(3),
+            Some(4),
+        )
+        .unwrap()
+        .encode(&mut v1)
+        .unwrap();
+        v1[1] |= 0x0E;
+
+        assert!(FrameHeader::decode(&mut &v1[..]).is_err());
+        let decoded = FrameHeader::decode_with_options(&mut &v1[..], passthrough).unwrap();
+        assert_eq!(decoded.encoding(), EncodingFlag::Unknown(7));
+        assert!(!decoded.encoding().is_known());
+        let mut reencoded = Vec::new();
+        decoded.encode(&mut reencoded).unwrap();
+        assert_eq!(reencoded, v1);
+
+        // v2 with encoding code 12 skips the PCM bits-per-sample constraint.
+        let mut v2 = Vec::new();
+        FrameHeaderV2::new(
+            EncodingFlag::Opus,
+            100,
+            960,
+            48000,
+            2,
+            0,
+            Endianness::LittleEndian,
+            Some(3),
+            None,
+            None,
+        )
+        .unwrap()
+        .encode(&mut v2)
+        .unwrap();
+        v2[2] = (v2[2] & 0x0F) | 0xC0;
+
+        assert!(FrameHeaderV2::decode(&mut &v2[..]).is_err());
+        let decoded = FrameHeaderV2::decode_with_options(&mut &v2[..], passthrough).unwrap();
+        assert_eq!(decoded.encoding(), EncodingFlag::Unknown(12));
+        let mut reencoded = Vec::new();
+        decoded.encode(&mut reencoded).unwrap();
+        assert_eq!(reencoded, v2);
+
+        assert!(EncodingFlag::H264.is_known());
+    }
+
+    #[test]
+    fn test_unknown_encoding_code_must_be_unknown_and_fit() {
+        let v1 = |encoding| {
+            FrameHeader::new(
+                encoding,
+                960,
+                48000,
+                2,
+                16,
+                Endianness::LittleEndian,
+                None,
+                None,
+            )
+        };
+        assert!(v1(EncodingFlag::Unknown(7)).is_ok());
+        assert!(v1(EncodingFlag::Unknown(6)).is_err());
+        assert!(v1(EncodingFlag::Unknown(2)).is_err());
+        assert!(v1(EncodingFlag::Unknown(8)).is_err());
+
+        let v2 = |encoding| {
+            FrameHeaderV2::new(
+                encoding,
+                1,
+                1,
+                48000,
+                2,
+                0,
+                Endianness::LittleEndian,
+                None,
+                None,
+                None,
+            )
+        };
+        assert!(v2(EncodingFlag::Unknown(15)).is_ok());
+        assert!(v2(EncodingFlag::Unknown(16)).is_err());
+
+        let mut bytes = create_test_header();
+        assert!(FrameHeader::patch_encoding(&mut bytes, EncodingFlag::Unknown(9)).is_err());
+        assert!(FrameHeader::patch_encoding(&mut bytes, EncodingFlag::Unknown(7)).is_ok());
+        assert!(FrameHeader::decode(&mut &bytes[..]).is_err());
+    }
+
+    #[test]
+    fn test_v1_every_bit_survives_roundtrip_and_identity_patches() {
+        // v1 has no reserved bits, so any decodable word must re-encode
+        // exactly and patching a field to its current value must be a no-op.
+        let passthrough = DecodeOptions {
+            allow_unknown_encoding: true,
+        };
+        let mut state = 0x9E37_79B9u32;
+        let mut checked = 0;
+        for _ in 0..20_000 {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            let word = (state & !FrameHeader::MAGIC_MASK)
+                | (FrameHeader::MAGIC_WORD << FrameHeader::MAGIC_SHIFT);
+            let mut bytes = word.to_be_bytes().to_vec();
+            bytes.extend_from_slice(
+                &(state as u64)
+                    .wrapping_mul(0x0123_4567_89AB_CDEF)
+                    .to_be_bytes(),
+            );
+            bytes.extend_from_slice(&(!state as u64).to_be_bytes());
+
+            let Ok(header) = FrameHeader::decode_with_options(&mut &bytes[..], passthrough) else {
+                continue;
+            };
+            bytes.truncate(header.size());
+            let mut encoded = Vec::new();
+            header.encode(&mut encoded).unwrap();
+            assert_eq!(encoded, bytes);
+
+            if !header.encoding().is_known() {
+                continue;
+            }
+            let mut patched = bytes.clone();
+            FrameHeader::patch_encoding(&mut patched, header.encoding()).unwrap();
+            FrameHeader::patch_sample_size(&mut patched, header.samples_per_channel()).unwrap();
+            FrameHeader::patch_sample_rate(&mut patched, header.sample_rate()).unwrap();
+            FrameHeader::patch_channels(&mut patched, header.channels()).unwrap();
+            FrameHeader::patch_bits_per_sample(&mut patched, header.bits_per_sample()).unwrap();
+            FrameHeader::patch_id(&mut patched, header.id()).unwrap();
+            FrameHeader::patch_pts(&mut patched, header.pts()).unwrap();
+            assert_eq!(patched, bytes);
+            checked += 1;
+        }
+        assert!(checked > 1_000);
+    }
+
+    #[test]
+    fn test_validate_header_with_reason() {
+        let header = FrameHeader::new(
+            EncodingFlag::FLAC,
+            1024,
+            44100,
+            2,
+            24,
+            Endianness::LittleEndian,
+            None,
+            None,
+        )
+        .unwrap();
+        let mut buffer = Vec::new();
+        header.encode(&mut buffer).unwrap();
+        assert_eq!(FrameHeader::validate_header_with_reason(&buffer), Ok(()));
+
+        let with_word = |mask: u32, value: u32| {
+            let word = u32::from_be_bytes(buffer[..4].try_into().unwrap());
+            ((word & !mask) | value).to_be_bytes()
+        };
+
+        assert_eq!(
+            FrameHeader::validate_header_with_reason(&with_word(
+                FrameHeader::MAGIC_MASK,
+                0x2B << FrameHeader::MAGIC_SHIFT
+            )),
+            Err(FrameHeaderError::InvalidMagic(0x2B))
+        );
+        assert_eq!(
+            FrameHeader::validate_header_with_reason(&with_word(
+                FrameHeader::ENCODING_MASK,
+                7 << FrameHeader::ENCODING_SHIFT
+            )),
+            Err(FrameHeaderError::InvalidEncoding(7))
+        );
+        assert_eq!(
+            FrameHeader::validate_header_with_reason(&with_word(
+                FrameHeader::BITS_MASK,
+                3 << FrameHeader::BITS_SHIFT
+            )),
+            Err(FrameHeaderError::InvalidBitsCode(3))
+        );
+        assert_eq!(
+            FrameHeader::validate_header_with_reason(&buffer[..3]),
+            Err(FrameHeaderError::Truncated)
+        );
+
+        // validate_header keeps its existing contract.
+        assert!(FrameHeader::validate_header(&buffer).unwrap());
+        assert!(FrameHeader::validate_header(&buffer[..3]).is_err());
+        assert!(!FrameHeader::validate_header(&with_word(
+            FrameHeader::BITS_MASK,
+            3 << FrameHeader::BITS_SHIFT
+        ))
+        .unwrap());
+    }
+
+    #[test]
+    fn test_patch_pts_add() {
+        for id in [None, Some(0xABCD)] {
+            let header = FrameHeader::new(
+                EncodingFlag::Opus,
+                960,
+                48000,
+                2,
+                16,
+                Endianness::LittleEndian,
+                id,
+                Some(1_000),
+            )
+            .unwrap();
+            let mut buffer = Vec::new();
+            header.encode(&mut buffer).unwrap();
+
+            assert_eq!(FrameHeader::patch_pts_add(&mut buffer, 20_000), Ok(21_000));
+            assert_eq!(FrameHeader::patch_pts_add(&mut buffer, -21_000), Ok(0));
+            assert_eq!(FrameHeader::extract_pts(&buffer), Ok(Some(0)));
+            assert_eq!(FrameHeader::extract_id(&buffer), Ok(id));
+
+            let before = buffer.clone();
+            assert_eq!(
+                FrameHeader::patch_pts_add(&mut buffer, -1),
+                Err(FrameHeaderError::PtsOutOfRange { pts: 0, offset: -1 })
+            );
+            FrameHeader::patch_pts(&mut buffer, Some(u64::MAX)).unwrap();
+            let at_max = buffer.clone();
+            assert!(FrameHeader::patch_pts_add(&mut buffer, 1).is_err());
+            assert_eq!(buffer, at_max);
+            FrameHeader::patch_pts(&mut buffer, Some(0)).unwrap();
+            assert_eq!(buffer, before);
+        }
+
+        let mut buffer = create_test_header();
+        let before = buffer.clone();
+        assert_eq!(
+            FrameHeader::patch_pts_add(&mut buffer, 5),
+            Err(FrameHeaderError::MissingPts)
+        );
+        assert_eq!(buffer, before);
+    }
+
+    #[test]
+    fn test_pcm_byte_sizes() {
+        for (bits, sample_size, channels) in [(16, 441, 2), (24, 1024, 6), (32, 4095, 16)] {
+            let header = FrameHeader::new(
+                EncodingFlag::PCMSigned,
+                sample_size,
+                48000,
+                channels,
+                bits,
+                Endianness::LittleEndian,
+                None,
+                None,
+            )
+            .unwrap();
+            let bytes = bits as u32 / 8;
+            assert_eq!(header.bytes_per_sample() as u32, bytes);
+            assert_eq!(
+                header.bytes_per_frame_channel() as u32,
+                sample_size as u32 * bytes
+            );
+            assert_eq!(
+                header.total_pcm_frame_bytes(),
+                sample_size as u32 * bytes * channels as u32
+            );
+        }
+    }
+
+    #[test]
+    fn test_safe_buffers_fit_the_largest_frame() {
+        let largest = FrameHeader::new(
+            EncodingFlag::PCMFloat,
+            4095,
+            96000,
+            16,
+            32,
+            Endianness::LittleEndian,
+            Some(u64::MAX),
+            Some(u64::MAX),
+        )
+        .unwrap();
+        let mut encoded = Vec::new();
+        largest.encode(&mut encoded).unwrap();
+        assert_eq!(encoded.len(), FrameHeader::max_encoded_header_size());
+        assert_eq!(
+            largest.total_pcm_frame_bytes() as usize,
+            FrameHeader::max_pcm_payload_bytes()
+        );
+
+        let mut buffer = FrameHeader::safe_encode_buffer();
+        assert_eq!(*buffer, [0; 20]);
+        largest.encode(&mut &mut buffer[..]).unwrap();
+        assert_eq!(&buffer[..], &encoded[..]);
+
+        let small = FrameHeader::new(
+            EncodingFlag::PCMSigned,
+            64,
+            16000,
+            1,
+            16,
+            Endianness::LittleEndian,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(small.max_frame_size_pcm(), 20 + 4095 * 16 * 4);
+        let frame = small.safe_frame_buffer();
+        assert_eq!(frame.len(), largest.max_frame_size_pcm());
+        assert!(frame.iter().all(|&byte| byte == 0));
+    }
 }
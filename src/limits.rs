@@ -0,0 +1,193 @@
+use crate::FrameHeaderError;
+
+/// Bounds on lengths taken from the wire, so a corrupt or hostile length
+/// is rejected before it is allocated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Limits {
+    pub max_payload_len: u32,
+    /// Encoded size of a metadata document, subtype byte included.
+    pub max_metadata_len: u32,
+}
+
+impl Limits {
+    pub const DEFAULT_MAX_PAYLOAD_LEN: u32 = 16 * 1024 * 1024;
+    pub const DEFAULT_MAX_METADATA_LEN: u32 = 64 * 1024;
+
+    pub fn with_max_payload_len(mut self, max_payload_len: u32) -> Self {
+        self.max_payload_len = max_payload_len;
+        self
+    }
+
+    pub fn with_max_metadata_len(mut self, max_metadata_len: u32) -> Self {
+        self.max_metadata_len = max_metadata_len;
+        self
+    }
+
+    pub(crate) fn check_payload_len(&self, len: u32) -> Result<(), FrameHeaderError> {
+        if len > self.max_payload_len {
+            return Err(FrameHeaderError::LimitExceeded {
+                limit: "payload_len",
+                value: len as u64,
+                max: self.max_payload_len as u64,
+            });
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "metadata")]
+    pub(crate) fn check_metadata_len(&self, len: usize) -> Result<(), FrameHeaderError> {
+        if len > self.max_metadata_len as usize {
+            return Err(FrameHeaderError::LimitExceeded {
+                limit: "metadata_len",
+                value: len as u64,
+                max: self.max_metadata_len as u64,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            max_payload_len: Self::DEFAULT_MAX_PAYLOAD_LEN,
+            max_metadata_len: Self::DEFAULT_MAX_METADATA_LEN,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EncodingFlag, Endianness, FrameHeaderV2, FrameReader, LogReader};
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::Cell;
+    use std::io;
+
+    // Records the largest single allocation made on each thread.
+    struct CountingAllocator;
+
+    thread_local! {
+        static LARGEST_ALLOCATION: Cell<usize> = const { Cell::new(0) };
+    }
+
+    fn record(size: usize) {
+        let _ = LARGEST_ALLOCATION.try_with(|largest| largest.set(largest.get().max(size)));
+    }
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            record(layout.size());
+            System.alloc(layout)
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            record(layout.size());
+            System.alloc_zeroed(layout)
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            record(new_size);
+            System.realloc(ptr, layout, new_size)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout)
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    // Runs `f` and returns the largest allocation it made.
+    fn largest_allocation<T>(f: impl FnOnce() -> T) -> (T, usize) {
+        LARGEST_ALLOCATION.with(|largest| largest.set(0));
+        let result = f();
+        (result, LARGEST_ALLOCATION.with(Cell::get))
+    }
+
+    fn frame(payload_size: u32, packet_crc32: Option<u32>, redundant: bool) -> Vec<u8> {
+        let header = FrameHeaderV2::new(
+            EncodingFlag::Opus,
+            payload_size,
+            960,
+            48000,
+            2,
+            0,
+            Endianness::LittleEndian,
+            Some(1),
+            Some(0),
+            packet_crc32,
+        )
+        .unwrap()
+        .with_redundant_copy(redundant);
+        let mut bytes = Vec::new();
+        header.encode(&mut bytes).unwrap();
+        bytes.extend_from_slice(&[0xAB; 64]);
+        bytes
+    }
+
+    fn assert_limit_exceeded(err: io::Error, value: u32, max: u32) {
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            FrameHeaderError::from(err),
+            FrameHeaderError::LimitExceeded {
+                limit: "payload_len",
+                value: value as u64,
+                max: max as u64,
+            }
+        );
+    }
+
+    const SMALL: usize = 1024 * 1024;
+
+    #[test]
+    fn test_frame_reader_rejects_oversized_payloads() {
+        for (crc, redundant) in [(None, false), (Some(0), false), (None, true)] {
+            let bytes = frame(u32::MAX, crc, redundant);
+            let (result, largest) =
+                largest_allocation(|| FrameReader::new(&bytes[..]).read_frame());
+            assert_limit_exceeded(result.unwrap_err(), u32::MAX, 16 * 1024 * 1024);
+            assert!(largest < SMALL, "allocated {} bytes", largest);
+        }
+
+        // A custom limit applies below the default.
+        let bytes = frame(64, None, false);
+        assert!(FrameReader::new(&bytes[..]).read_frame().unwrap().is_some());
+        let limits = Limits::default().with_max_payload_len(63);
+        let err = FrameReader::new(&bytes[..])
+            .with_limits(limits)
+            .read_frame()
+            .unwrap_err();
+        assert_limit_exceeded(err, 64, 63);
+    }
+
+    #[test]
+    fn test_redundant_recovery_skips_oversized_crc_copies() {
+        let bytes = frame(u32::MAX, Some(0), true);
+        let (result, largest) = largest_allocation(|| FrameHeaderV2::decode_redundant(&bytes));
+        assert!(result.is_err());
+        assert!(largest < SMALL, "allocated {} bytes", largest);
+    }
+
+    #[test]
+    fn test_log_reader_rejects_oversized_payloads() {
+        let bytes = frame(u32::MAX, None, false);
+        let (result, largest) = largest_allocation(|| {
+            let mut reader = LogReader::new(&bytes[..]);
+            (reader.read_frame(), reader.valid_len())
+        });
+        let (result, valid_len) = result;
+        assert_limit_exceeded(result.unwrap_err(), u32::MAX, 16 * 1024 * 1024);
+        assert_eq!(valid_len, 0);
+        assert!(largest < SMALL, "allocated {} bytes", largest);
+
+        let bytes = frame(64, None, false);
+        let err = LogReader::new(&bytes[..])
+            .with_limits(Limits::default().with_max_payload_len(63))
+            .read_frame()
+            .unwrap_err();
+        assert_limit_exceeded(err, 64, 63);
+    }
+}
@@ -0,0 +1,412 @@
+use crate::{DecodeError, FrameHeaderError, FrameHeaderV2, FrameWriter, Limits};
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, Read};
+use std::path::Path;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SyncPolicy {
+    // Data reaches disk only on `sync` or `finish`.
+    Manual,
+    // Flush to the OS after every frame without forcing it to disk.
+    FlushEveryFrame,
+    SyncEveryFrame,
+    SyncEveryFrames(u32),
+}
+
+pub struct LogWriter {
+    frames: FrameWriter<BufWriter<File>>,
+    policy: SyncPolicy,
+    unsynced_frames: u32,
+}
+
+impl LogWriter {
+    pub fn create<P: AsRef<Path>>(path: P, policy: SyncPolicy) -> io::Result<Self> {
+        Self::from_file(File::create(path)?, 0, policy)
+    }
+
+    /// Opens an existing log for appending. Run `LogReader::recover` with
+    /// truncation first if the previous writer may not have shut down cleanly.
+    pub fn append<P: AsRef<Path>>(path: P, policy: SyncPolicy) -> io::Result<Self> {
+        let file = OpenOptions::new().append(true).create(true).open(path)?;
+        let position = file.metadata()?.len();
+        Self::from_file(file, position, policy)
+    }
+
+    fn from_file(file: File, position: u64, policy: SyncPolicy) -> io::Result<Self> {
+        if policy == SyncPolicy::SyncEveryFrames(0) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Sync interval must be at least one frame",
+            ));
+        }
+        Ok(LogWriter {
+            frames: FrameWriter::with_position(BufWriter::new(file), position),
+            policy,
+            unsynced_frames: 0,
+        })
+    }
+
+    pub fn write_frame(&mut self, header: &FrameHeaderV2, payload: &[u8]) -> io::Result<u64> {
+        let offset = self.frames.write_frame(header, payload)?;
+        self.unsynced_frames += 1;
+        match self.policy {
+            SyncPolicy::Manual => {}
+            SyncPolicy::FlushEveryFrame => self.frames.flush()?,
+            SyncPolicy::SyncEveryFrame => self.sync()?,
+            SyncPolicy::SyncEveryFrames(interval) => {
+                if self.unsynced_frames >= interval {
+                    self.sync()?;
+                }
+            }
+        }
+        Ok(offset)
+    }
+
+    pub fn sync(&mut self) -> io::Result<()> {
+        self.frames.flush()?;
+        self.frames.get_ref().get_ref().sync_data()?;
+        self.unsynced_frames = 0;
+        Ok(())
+    }
+
+    pub fn position(&self) -> u64 {
+        self.frames.position()
+    }
+
+    pub fn finish(mut self) -> io::Result<()> {
+        self.sync()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LogRecovery {
+    pub frames: u64,
+    // Byte offset where the last complete, valid frame ends.
+    pub valid_len: u64,
+    pub file_len: u64,
+    // Why the scan stopped before the end of the file, if it did.
+    pub tail_error: Option<FrameHeaderError>,
+    pub truncated: bool,
+}
+
+impl LogRecovery {
+    pub fn has_damaged_tail(&self) -> bool {
+        self.valid_len < self.file_len
+    }
+}
+
+/// Reads frames until the end of the log or the first truncated or corrupt
+/// frame, which is treated as the end of valid data rather than an error.
+/// A payload over the limit is an error instead, so `recover` never
+/// truncates frames that are merely larger than expected.
+pub struct LogReader<R: Read> {
+    reader: R,
+    valid_len: u64,
+    tail_error: Option<FrameHeaderError>,
+    limits: Limits,
+    frames: u64,
+}
+
+impl LogReader<BufReader<File>> {
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(LogReader::new(BufReader::new(File::open(path)?)))
+    }
+
+    pub fn recover<P: AsRef<Path>>(path: P, truncate: bool) -> io::Result<LogRecovery> {
+        let path = path.as_ref();
+        let file_len = std::fs::metadata(path)?.len();
+
+        let mut reader = LogReader::open(path)?;
+        let mut frames = 0;
+        while reader.read_frame()?.is_some() {
+            frames += 1;
+        }
+
+        let valid_len = reader.valid_len();
+        let truncated = truncate && valid_len < file_len;
+        if truncated {
+            let file = OpenOptions::new().write(true).open(path)?;
+            file.set_len(valid_len)?;
+            file.sync_all()?;
+        }
+
+        Ok(LogRecovery {
+            frames,
+            valid_len,
+            file_len,
+            tail_error: reader.tail_error,
+            truncated,
+        })
+    }
+}
+
+impl<R: Read> LogReader<R> {
+    pub fn new(reader: R) -> Self {
+        LogReader {
+            reader,
+            valid_len: 0,
+            tail_error: None,
+            limits: Limits::default(),
+            frames: 0,
+        }
+    }
+
+    pub fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Errors that are not a damaged tail carry the frame's position as a
+    /// `DecodeError`; a damaged tail starts at `valid_len`.
+    pub fn read_frame(&mut self) -> io::Result<Option<(FrameHeaderV2, Vec<u8>)>> {
+        let (frame, offset) = (self.frames, self.valid_len);
+        let next = self
+            .next_frame()
+            .map_err(|err| DecodeError::wrap(err, frame, offset, None))?;
+        if next.is_some() {
+            self.frames += 1;
+        }
+        Ok(next)
+    }
+
+    fn next_frame(&mut self) -> io::Result<Option<(FrameHeaderV2, Vec<u8>)>> {
+        if self.tail_error.is_some() {
+            return Ok(None);
+        }
+
+        let mut header_bytes = [0u8; FrameHeaderV2::MAX_SIZE];
+        let base = read_up_to(
+            &mut self.reader,
+            &mut header_bytes[..FrameHeaderV2::BASE_SIZE],
+        )?;
+        if base == 0 {
+            return Ok(None);
+        }
+        if base < FrameHeaderV2::BASE_SIZE {
+            return self.stop(FrameHeaderError::Truncated);
+        }
+
+        let prefix_size = match FrameHeaderV2::header_prefix_size(&header_bytes) {
+            Ok(prefix_size) => prefix_size,
+            Err(err) => return self.stop(FrameHeaderError::Invalid(err)),
+        };
+        let extension_word = read_up_to(
+            &mut self.reader,
+            &mut header_bytes[FrameHeaderV2::BASE_SIZE..prefix_size],
+        )?;
+        if FrameHeaderV2::BASE_SIZE + extension_word < prefix_size {
+            return self.stop(FrameHeaderError::Truncated);
+        }
+        let header_size = match FrameHeaderV2::header_size(&header_bytes) {
+            Ok(header_size) => header_size,
+            Err(err) => return self.stop(FrameHeaderError::Invalid(err)),
+        };
+        let rest = read_up_to(
+            &mut self.reader,
+            &mut header_bytes[prefix_size..header_size],
+        )?;
+        let header_bytes = &header_bytes[..prefix_size + rest];
+        if let Err(err) = FrameHeaderV2::validate_header_detailed(header_bytes) {
+            return self.stop(err);
+        }
+        let header = FrameHeaderV2::decode(&mut &header_bytes[..])?;
+
+        self.limits.check_payload_len(header.payload_size())?;
+        let mut payload = vec![0u8; header.payload_size() as usize];
+        if read_up_to(&mut self.reader, &mut payload)? < payload.len() {
+            return self.stop(FrameHeaderError::Truncated);
+        }
+        if header.packet_crc32_value().is_some()
+            && header.verify_packet_crc32(header_bytes, &payload) != Ok(true)
+        {
+            return self.stop(FrameHeaderError::Invalid(
+                "Packet CRC32 mismatch".to_string(),
+            ));
+        }
+
+        self.valid_len += (header_size + payload.len()) as u64;
+        Ok(Some((header, payload)))
+    }
+
+    pub fn valid_len(&self) -> u64 {
+        self.valid_len
+    }
+
+    pub fn tail_error(&self) -> Option<&FrameHeaderError> {
+        self.tail_error.as_ref()
+    }
+
+    fn stop(&mut self, err: FrameHeaderError) -> io::Result<Option<(FrameHeaderV2, Vec<u8>)>> {
+        self.tail_error = Some(err);
+        Ok(None)
+    }
+}
+
+impl<R: Read> Iterator for LogReader<R> {
+    type Item = io::Result<(FrameHeaderV2, Vec<u8>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_frame().transpose()
+    }
+}
+
+fn read_up_to<R: Read>(reader: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(read) => filled += read,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(filled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EncodingFlag, Endianness, FrameReader};
+    use std::path::PathBuf;
+
+    fn temp_log_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("frame-header-{}-{}.log", name, std::process::id()))
+    }
+
+    fn opus_frame(index: u64, crc: bool) -> (FrameHeaderV2, Vec<u8>) {
+        let payload = vec![index as u8; 24 + index as usize];
+        let header = FrameHeaderV2::new(
+            EncodingFlag::Opus,
+            payload.len() as u32,
+            960,
+            48000,
+            2,
+            0,
+            Endianness::LittleEndian,
+            Some(index),
+            Some(index * 960),
+            None,
+        )
+        .unwrap();
+        let header = if crc {
+            header.with_packet_crc32(&payload).unwrap()
+        } else {
+            header
+        };
+        (header, payload)
+    }
+
+    fn write_log(path: &Path, frames: u64, crc: bool) -> Vec<u64> {
+        let mut writer = LogWriter::create(path, SyncPolicy::SyncEveryFrames(2)).unwrap();
+        let offsets = (0..frames)
+            .map(|index| {
+                let (header, payload) = opus_frame(index, crc);
+                writer.write_frame(&header, &payload).unwrap()
+            })
+            .collect();
+        writer.finish().unwrap();
+        offsets
+    }
+
+    #[test]
+    fn test_recover_truncated_tail_at_every_offset() {
+        let path = temp_log_path("truncated-tail");
+        for crc in [false, true] {
+            let offsets = write_log(&path, 3, crc);
+            let complete = std::fs::read(&path).unwrap();
+            let last_start = offsets[2];
+
+            for cut in last_start + 1..complete.len() as u64 {
+                std::fs::write(&path, &complete[..cut as usize]).unwrap();
+
+                let recovery = LogReader::recover(&path, false).unwrap();
+                assert_eq!(recovery.frames, 2, "cut at {}", cut);
+                assert_eq!(recovery.valid_len, last_start);
+                assert_eq!(recovery.file_len, cut);
+                assert_eq!(recovery.tail_error, Some(FrameHeaderError::Truncated));
+                assert!(recovery.has_damaged_tail());
+                assert!(!recovery.truncated);
+                assert_eq!(std::fs::metadata(&path).unwrap().len(), cut);
+
+                let recovery = LogReader::recover(&path, true).unwrap();
+                assert!(recovery.truncated);
+                assert_eq!(std::fs::metadata(&path).unwrap().len(), last_start);
+
+                let mut writer = LogWriter::append(&path, SyncPolicy::SyncEveryFrame).unwrap();
+                let (header, payload) = opus_frame(2, crc);
+                assert_eq!(writer.write_frame(&header, &payload).unwrap(), last_start);
+                writer.finish().unwrap();
+                assert_eq!(std::fs::read(&path).unwrap(), complete);
+            }
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_recover_stops_at_corrupt_tail() {
+        let path = temp_log_path("corrupt-tail");
+        let offsets = write_log(&path, 3, true);
+        let complete = std::fs::read(&path).unwrap();
+
+        let recovery = LogReader::recover(&path, true).unwrap();
+        assert_eq!(recovery.frames, 3);
+        assert_eq!(recovery.valid_len, complete.len() as u64);
+        assert_eq!(recovery.tail_error, None);
+        assert!(!recovery.truncated);
+
+        // A flipped payload byte fails the packet CRC.
+        let mut corrupt = complete.clone();
+        *corrupt.last_mut().unwrap() ^= 0xFF;
+        std::fs::write(&path, &corrupt).unwrap();
+        let recovery = LogReader::recover(&path, false).unwrap();
+        assert_eq!(recovery.frames, 2);
+        assert_eq!(recovery.valid_len, offsets[2]);
+
+        // Garbage appended after a clean log is dropped.
+        let mut garbage = complete.clone();
+        garbage.extend_from_slice(&[0x00; 12]);
+        std::fs::write(&path, &garbage).unwrap();
+        let recovery = LogReader::recover(&path, true).unwrap();
+        assert_eq!(recovery.frames, 3);
+        assert!(matches!(
+            recovery.tail_error,
+            Some(FrameHeaderError::Invalid(_))
+        ));
+        assert_eq!(std::fs::read(&path).unwrap(), complete);
+
+        let frames = LogReader::open(&path)
+            .unwrap()
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(frames.len(), 3);
+        assert_eq!(frames[1], opus_frame(1, true));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_errors_carry_frame_positions() {
+        let mut bytes = Vec::new();
+        let mut writer = FrameWriter::new(&mut bytes);
+        for index in 0..3 {
+            let (header, payload) = opus_frame(index, false);
+            writer.write_frame(&header, &payload).unwrap();
+        }
+        let offset = FrameReader::new(&bytes[..])
+            .headers_with_offsets()
+            .nth(2)
+            .unwrap()
+            .unwrap()
+            .0;
+
+        // Frame 2's 26-byte payload is over the limit.
+        let mut reader =
+            LogReader::new(&bytes[..]).with_limits(Limits::default().with_max_payload_len(25));
+        let err = reader.by_ref().find_map(Result::err).unwrap();
+        let position = DecodeError::find(&err).unwrap();
+        assert_eq!(
+            (position.frame, position.offset, position.field),
+            (2, offset, Some("payload_len"))
+        );
+        assert_eq!(reader.valid_len(), offset);
+    }
+}
@@ -0,0 +1,113 @@
+use crate::{EncodingFlag, FrameHeader, FrameHeaderError};
+
+pub const LV2_ATOM_SOUND_URI: &str = "http://lv2plug.in/ns/ext/atom#Sound";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Lv2AtomSoundHeader {
+    pub sample_rate: f64,
+    pub channels: u32,
+    /// Samples per channel in each block.
+    pub block_length: u32,
+    pub audio_type_urid: u32,
+}
+
+impl Lv2AtomSoundHeader {
+    /// An `LV2_Atom` of type `audio_type_urid` whose body is the sample rate,
+    /// channel count and block length. Like every atom it is in host byte
+    /// order, and its 24 bytes keep the 64-bit alignment atoms require.
+    pub fn encode_atom(&self) -> Vec<u8> {
+        let mut atom = Vec::with_capacity(24);
+        atom.extend_from_slice(&16u32.to_ne_bytes());
+        atom.extend_from_slice(&self.audio_type_urid.to_ne_bytes());
+        atom.extend_from_slice(&self.sample_rate.to_ne_bytes());
+        atom.extend_from_slice(&self.channels.to_ne_bytes());
+        atom.extend_from_slice(&self.block_length.to_ne_bytes());
+        atom
+    }
+}
+
+impl FrameHeader {
+    /// `urid_map` stands in for the host's `LV2_URID_Map`.
+    pub fn to_lv2_atom_sound_header(
+        &self,
+        urid_map: &dyn Fn(&str) -> u32,
+    ) -> Result<Lv2AtomSoundHeader, FrameHeaderError> {
+        if !matches!(
+            self.encoding,
+            EncodingFlag::PCMSigned | EncodingFlag::PCMFloat
+        ) {
+            return Err(FrameHeaderError::InvalidEncoding(
+                self.encoding.code() as u32
+            ));
+        }
+        Ok(Lv2AtomSoundHeader {
+            sample_rate: self.sample_rate as f64,
+            channels: self.channels as u32,
+            block_length: self.sample_size as u32,
+            audio_type_urid: urid_map(LV2_ATOM_SOUND_URI),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Endianness;
+    use std::cell::RefCell;
+
+    fn header(encoding: EncodingFlag) -> FrameHeader {
+        FrameHeader::new(
+            encoding,
+            512,
+            96000,
+            6,
+            24,
+            Endianness::LittleEndian,
+            None,
+            None,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_lv2_atom_sound_header() {
+        let mapped = RefCell::new(Vec::new());
+        let urid_map = |uri: &str| {
+            mapped.borrow_mut().push(uri.to_string());
+            42
+        };
+        let sound = header(EncodingFlag::PCMSigned)
+            .to_lv2_atom_sound_header(&urid_map)
+            .unwrap();
+        assert_eq!(
+            sound,
+            Lv2AtomSoundHeader {
+                sample_rate: 96000.0,
+                channels: 6,
+                block_length: 512,
+                audio_type_urid: 42,
+            }
+        );
+        assert_eq!(*mapped.borrow(), [LV2_ATOM_SOUND_URI]);
+
+        let atom = sound.encode_atom();
+        assert_eq!(atom.len(), 24);
+        assert_eq!(u32::from_ne_bytes(atom[0..4].try_into().unwrap()), 16);
+        assert_eq!(u32::from_ne_bytes(atom[4..8].try_into().unwrap()), 42);
+        assert_eq!(f64::from_ne_bytes(atom[8..16].try_into().unwrap()), 96000.0);
+        assert_eq!(u32::from_ne_bytes(atom[16..20].try_into().unwrap()), 6);
+        assert_eq!(u32::from_ne_bytes(atom[20..24].try_into().unwrap()), 512);
+    }
+
+    #[test]
+    fn test_lv2_rejects_non_pcm() {
+        let urid_map = |_: &str| 1;
+        assert!(header(EncodingFlag::PCMFloat)
+            .to_lv2_atom_sound_header(&urid_map)
+            .is_ok());
+        assert_eq!(
+            header(EncodingFlag::FLAC).to_lv2_atom_sound_header(&urid_map),
+            Err(FrameHeaderError::InvalidEncoding(3))
+        );
+    }
+}
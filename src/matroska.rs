@@ -0,0 +1,449 @@
+use crate::{ChannelCount, EncodingFlag, Endianness, FrameHeader, FrameHeaderError, SampleRate};
+
+const TRACK_AUDIO_ID: u32 = 0xE1;
+const SAMPLING_FREQUENCY_ID: u32 = 0xB5;
+const CHANNELS_ID: u32 = 0x9F;
+const BIT_DEPTH_ID: u32 = 0x6264;
+
+/// An EBML element. `id` is the Matroska element ID as written in the spec,
+/// which already includes its VINT length marker, e.g. `0xE1` or `0x6264`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EbmlElement {
+    pub id: u32,
+    pub data: Vec<u8>,
+}
+
+impl EbmlElement {
+    pub fn new(id: u32, data: Vec<u8>) -> Self {
+        Self { id, data }
+    }
+
+    /// An unsigned integer element, in as few big-endian bytes as possible.
+    pub fn uint(id: u32, value: u64) -> Self {
+        let bytes = value.to_be_bytes();
+        let skip = (value.leading_zeros() as usize / 8).min(7);
+        Self::new(id, bytes[skip..].to_vec())
+    }
+
+    /// The ID, the data size as a VINT, then the data.
+    pub fn encode(&self) -> Vec<u8> {
+        let id_len = (4 - self.id.leading_zeros() as usize / 8).max(1);
+        let mut bytes = Vec::with_capacity(id_len + 8 + self.data.len());
+        bytes.extend_from_slice(&self.id.to_be_bytes()[4 - id_len..]);
+        write_size(&mut bytes, self.data.len() as u64);
+        bytes.extend_from_slice(&self.data);
+        bytes
+    }
+
+    /// Decodes one element from the front of `bytes`, returning it with the
+    /// number of bytes consumed.
+    pub fn decode(bytes: &[u8]) -> Result<(EbmlElement, usize), FrameHeaderError> {
+        let (id_len, _) = read_vint(bytes, 4)?;
+        let id = bytes[..id_len]
+            .iter()
+            .fold(0u32, |id, &byte| id << 8 | byte as u32);
+        let (size_len, size) = read_vint(&bytes[id_len..], 8)?;
+        // All value bits set means unknown size, which only streamed master
+        // elements like Segment and Cluster may use.
+        if size == (1 << (7 * size_len)) - 1 {
+            return Err(FrameHeaderError::Invalid(
+                "EBML element has unknown size".to_string(),
+            ));
+        }
+        let start = id_len + size_len;
+        let end = usize::try_from(size)
+            .ok()
+            .and_then(|size| start.checked_add(size))
+            .filter(|&end| end <= bytes.len())
+            .ok_or(FrameHeaderError::Truncated)?;
+        Ok((EbmlElement::new(id, bytes[start..end].to_vec()), end))
+    }
+
+    fn as_uint(&self) -> Result<u64, FrameHeaderError> {
+        if self.data.len() > 8 {
+            return Err(FrameHeaderError::Invalid(format!(
+                "EBML element {:#X} is not an unsigned integer",
+                self.id
+            )));
+        }
+        Ok(self
+            .data
+            .iter()
+            .fold(0u64, |value, &byte| value << 8 | byte as u64))
+    }
+
+    fn as_float(&self) -> Result<f64, FrameHeaderError> {
+        match self.data.len() {
+            0 => Ok(0.0),
+            4 => Ok(f32::from_be_bytes(self.data[..].try_into().unwrap()) as f64),
+            8 => Ok(f64::from_be_bytes(self.data[..].try_into().unwrap())),
+            _ => Err(FrameHeaderError::Invalid(format!(
+                "EBML element {:#X} is not a float",
+                self.id
+            ))),
+        }
+    }
+}
+
+// Shortest VINT for `size`, skipping the all-ones value reserved for
+// unknown sizes.
+fn write_size(bytes: &mut Vec<u8>, size: u64) {
+    let len = (1..8).find(|len| size < (1 << (7 * len)) - 1).unwrap_or(8);
+    let vint = size | 1 << (7 * len);
+    bytes.extend_from_slice(&vint.to_be_bytes()[8 - len..]);
+}
+
+// Returns the VINT length and its value with the length marker removed.
+fn read_vint(bytes: &[u8], max_len: usize) -> Result<(usize, u64), FrameHeaderError> {
+    let first = *bytes.first().ok_or(FrameHeaderError::Truncated)?;
+    let len = first.leading_zeros() as usize + 1;
+    if len > max_len {
+        return Err(FrameHeaderError::Invalid(format!(
+            "EBML VINT longer than {} bytes",
+            max_len
+        )));
+    }
+    let vint = bytes.get(..len).ok_or(FrameHeaderError::Truncated)?;
+    let value = vint
+        .iter()
+        .fold(0u64, |value, &byte| value << 8 | byte as u64);
+    Ok((len, value & !(1 << (7 * len))))
+}
+
+impl FrameHeader {
+    /// The Matroska `Audio` element (0xE1) of a `TrackEntry`, with
+    /// `SamplingFrequency`, `Channels` and `BitDepth`. The codec goes in the
+    /// entry's `CodecID`, from `EncodingFlag::matroska_codec_id`.
+    pub fn to_ebml_track_audio_element(&self) -> Result<Vec<u8>, FrameHeaderError> {
+        if self.encoding == EncodingFlag::H264 {
+            return Err(FrameHeaderError::InvalidEncoding(
+                self.encoding.code() as u32
+            ));
+        }
+        let mut data = EbmlElement::new(
+            SAMPLING_FREQUENCY_ID,
+            (self.sample_rate as f64).to_be_bytes().to_vec(),
+        )
+        .encode();
+        data.extend(EbmlElement::uint(CHANNELS_ID, self.channels as u64).encode());
+        data.extend(EbmlElement::uint(BIT_DEPTH_ID, self.bits_per_sample as u64).encode());
+        Ok(EbmlElement::new(TRACK_AUDIO_ID, data).encode())
+    }
+
+    /// Reads an `Audio` element written by `to_ebml_track_audio_element` or
+    /// a muxer. The element does not carry the codec or frame size, so the
+    /// header is little-endian signed PCM with no samples per channel; set
+    /// those from the track's `CodecID` and blocks. Missing `Channels`
+    /// defaults to 1, as in the Matroska spec, and unknown children such as
+    /// `OutputSamplingFrequency` are skipped.
+    pub fn from_ebml_track_audio_element(bytes: &[u8]) -> Result<FrameHeader, FrameHeaderError> {
+        let (audio, _) = EbmlElement::decode(bytes)?;
+        if audio.id != TRACK_AUDIO_ID {
+            return Err(FrameHeaderError::Invalid(format!(
+                "Expected EBML Audio element, found {:#X}",
+                audio.id
+            )));
+        }
+
+        let mut sample_rate = None;
+        let mut channels = 1;
+        let mut bits_per_sample = None;
+        let mut rest = &audio.data[..];
+        while !rest.is_empty() {
+            let (child, len) = EbmlElement::decode(rest)?;
+            match child.id {
+                SAMPLING_FREQUENCY_ID => sample_rate = Some(child.as_float()?),
+                CHANNELS_ID => channels = child.as_uint()?,
+                BIT_DEPTH_ID => bits_per_sample = Some(child.as_uint()?),
+                _ => {}
+            }
+            rest = &rest[len..];
+        }
+
+        let sample_rate = sample_rate
+            .filter(|rate| rate.fract() == 0.0 && *rate > 0.0 && *rate <= u32::MAX as f64)
+            .ok_or(FrameHeaderError::FieldOutOfRange("sample_rate"))?;
+        let channels =
+            u8::try_from(channels).map_err(|_| FrameHeaderError::FieldOutOfRange("channels"))?;
+        let bits_per_sample = bits_per_sample
+            .and_then(|bits| u8::try_from(bits).ok())
+            .ok_or(FrameHeaderError::FieldOutOfRange("bits_per_sample"))?;
+
+        Ok(FrameHeader::new_typed(
+            EncodingFlag::PCMSigned,
+            0,
+            SampleRate::try_from(sample_rate as u32)?,
+            ChannelCount::try_from(channels)?,
+            bits_per_sample,
+            Endianness::LittleEndian,
+            None,
+            None,
+        )?)
+    }
+}
+
+impl EncodingFlag {
+    /// Matroska `CodecID` for this encoding. `endianness` only matters for
+    /// PCM; Matroska float PCM is always little-endian.
+    pub fn matroska_codec_id(
+        &self,
+        endianness: Endianness,
+    ) -> Result<&'static str, FrameHeaderError> {
+        Ok(match (self, endianness) {
+            (EncodingFlag::PCMSigned, Endianness::LittleEndian) => "A_PCM/INT/LIT",
+            (EncodingFlag::PCMSigned, Endianness::BigEndian) => "A_PCM/INT/BIG",
+            (EncodingFlag::PCMFloat, Endianness::LittleEndian) => "A_PCM/FLOAT/IEEE",
+            (EncodingFlag::PCMFloat, Endianness::BigEndian) => {
+                return Err(FrameHeaderError::FieldOutOfRange("endianness"))
+            }
+            (EncodingFlag::Opus, _) => "A_OPUS",
+            (EncodingFlag::FLAC, _) => "A_FLAC",
+            (EncodingFlag::AAC, _) => "A_AAC",
+            (EncodingFlag::H264, _) => "V_MPEG4/ISO/AVC",
+            (EncodingFlag::Metadata | EncodingFlag::Unknown(_), _) => {
+                return Err(FrameHeaderError::InvalidEncoding(self.code() as u32))
+            }
+        })
+    }
+
+    /// The encoding for a Matroska `CodecID`, with the byte order for PCM
+    /// IDs. Legacy profile-specific AAC IDs such as `A_AAC/MPEG4/LC` map to
+    /// AAC.
+    pub fn from_matroska_codec_id(
+        codec_id: &str,
+    ) -> Result<(EncodingFlag, Option<Endianness>), FrameHeaderError> {
+        Ok(match codec_id {
+            "A_PCM/INT/LIT" => (EncodingFlag::PCMSigned, Some(Endianness::LittleEndian)),
+            "A_PCM/INT/BIG" => (EncodingFlag::PCMSigned, Some(Endianness::BigEndian)),
+            "A_PCM/FLOAT/IEEE" => (EncodingFlag::PCMFloat, Some(Endianness::LittleEndian)),
+            "A_OPUS" => (EncodingFlag::Opus, None),
+            "A_FLAC" => (EncodingFlag::FLAC, None),
+            "A_AAC" => (EncodingFlag::AAC, None),
+            id if id.starts_with("A_AAC/") => (EncodingFlag::AAC, None),
+            "V_MPEG4/ISO/AVC" => (EncodingFlag::H264, None),
+            _ => {
+                return Err(FrameHeaderError::Invalid(format!(
+                    "Unsupported Matroska codec ID: {}",
+                    codec_id
+                )))
+            }
+        })
+    }
+
+    /// Whether a Matroska track of this encoding needs `CodecPrivate`:
+    /// `OpusHead` for Opus, the `fLaC` marker and STREAMINFO for FLAC, the
+    /// AudioSpecificConfig for AAC and `avcC` for H.264. Headers do not carry
+    /// this data, so muxers must take it from the codec.
+    pub fn matroska_requires_codec_private(&self) -> bool {
+        matches!(
+            self,
+            EncodingFlag::Opus | EncodingFlag::FLAC | EncodingFlag::AAC | EncodingFlag::H264
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Endianness::{BigEndian, LittleEndian};
+
+    #[test]
+    fn test_matroska_codec_ids() {
+        for (encoding, endianness, codec_id, codec_private) in [
+            (
+                EncodingFlag::PCMSigned,
+                Some(LittleEndian),
+                "A_PCM/INT/LIT",
+                false,
+            ),
+            (
+                EncodingFlag::PCMSigned,
+                Some(BigEndian),
+                "A_PCM/INT/BIG",
+                false,
+            ),
+            (
+                EncodingFlag::PCMFloat,
+                Some(LittleEndian),
+                "A_PCM/FLOAT/IEEE",
+                false,
+            ),
+            (EncodingFlag::Opus, None, "A_OPUS", true),
+            (EncodingFlag::FLAC, None, "A_FLAC", true),
+            (EncodingFlag::AAC, None, "A_AAC", true),
+            (EncodingFlag::H264, None, "V_MPEG4/ISO/AVC", true),
+        ] {
+            assert_eq!(
+                encoding.matroska_codec_id(endianness.unwrap_or(LittleEndian)),
+                Ok(codec_id)
+            );
+            assert_eq!(
+                EncodingFlag::from_matroska_codec_id(codec_id),
+                Ok((encoding, endianness))
+            );
+            assert_eq!(encoding.matroska_requires_codec_private(), codec_private);
+        }
+
+        // Compressed IDs do not depend on byte order.
+        assert_eq!(
+            EncodingFlag::Opus.matroska_codec_id(BigEndian),
+            Ok("A_OPUS")
+        );
+        assert_eq!(
+            EncodingFlag::from_matroska_codec_id("A_AAC/MPEG4/LC/SBR"),
+            Ok((EncodingFlag::AAC, None))
+        );
+    }
+
+    #[test]
+    fn test_matroska_unmapped_codecs() {
+        assert_eq!(
+            EncodingFlag::PCMFloat.matroska_codec_id(BigEndian),
+            Err(FrameHeaderError::FieldOutOfRange("endianness"))
+        );
+        assert_eq!(
+            EncodingFlag::Unknown(7).matroska_codec_id(LittleEndian),
+            Err(FrameHeaderError::InvalidEncoding(7))
+        );
+        assert!(!EncodingFlag::Unknown(7).matroska_requires_codec_private());
+        for codec_id in ["A_VORBIS", "A_MPEG/L3", "V_VP9", "a_opus", "A_AAC2", ""] {
+            assert!(
+                EncodingFlag::from_matroska_codec_id(codec_id).is_err(),
+                "{}",
+                codec_id
+            );
+        }
+    }
+
+    fn header(encoding: EncodingFlag, channels: u8, bits: u8) -> FrameHeader {
+        FrameHeader::new(
+            encoding,
+            960,
+            48000,
+            channels,
+            bits,
+            LittleEndian,
+            Some(1),
+            Some(2),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_ebml_element_encoding() {
+        assert_eq!(EbmlElement::uint(0x9F, 2).encode(), [0x9F, 0x81, 0x02]);
+        assert_eq!(
+            EbmlElement::uint(0x6264, 24).encode(),
+            [0x62, 0x64, 0x81, 0x18]
+        );
+        assert_eq!(EbmlElement::uint(0x9F, 0).data, [0x00]);
+        assert_eq!(EbmlElement::uint(0x9F, 0x1234).data, [0x12, 0x34]);
+
+        // 127 is the reserved 1-byte unknown size, so it takes two bytes.
+        for (len, size_bytes) in [
+            (0, vec![0x80]),
+            (126, vec![0xFE]),
+            (127, vec![0x40, 0x7F]),
+            (300, vec![0x41, 0x2C]),
+        ] {
+            let element = EbmlElement::new(0xA3, vec![0; len]);
+            let bytes = element.encode();
+            assert_eq!(bytes[1..1 + size_bytes.len()], size_bytes[..]);
+            assert_eq!(EbmlElement::decode(&bytes), Ok((element, bytes.len())));
+        }
+    }
+
+    #[test]
+    fn test_ebml_track_audio_roundtrip() {
+        let bytes = header(EncodingFlag::PCMSigned, 2, 24)
+            .to_ebml_track_audio_element()
+            .unwrap();
+        let mut expected = vec![0xE1, 0x91, 0xB5, 0x88];
+        expected.extend_from_slice(&48000f64.to_be_bytes());
+        expected.extend_from_slice(&[0x9F, 0x81, 0x02, 0x62, 0x64, 0x81, 0x18]);
+        assert_eq!(bytes, expected);
+
+        for encoding in [
+            EncodingFlag::PCMSigned,
+            EncodingFlag::PCMFloat,
+            EncodingFlag::Opus,
+            EncodingFlag::FLAC,
+            EncodingFlag::AAC,
+        ] {
+            for (channels, bits) in [(1, 16), (6, 24), (16, 32)] {
+                let original = header(encoding, channels, bits);
+                let decoded = FrameHeader::from_ebml_track_audio_element(
+                    &original.to_ebml_track_audio_element().unwrap(),
+                )
+                .unwrap();
+                assert_eq!(decoded.sample_rate(), original.sample_rate());
+                assert_eq!(decoded.channels(), channels);
+                assert_eq!(decoded.bits_per_sample(), bits);
+                assert_eq!(decoded.encoding(), EncodingFlag::PCMSigned);
+                assert_eq!(decoded.samples_per_channel(), 0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ebml_track_audio_from_muxer() {
+        // 4-byte float frequency, no Channels, and an OutputSamplingFrequency
+        // (0x78B5) child to skip.
+        let mut data = vec![0xB5, 0x84];
+        data.extend_from_slice(&44100f32.to_be_bytes());
+        data.extend_from_slice(&[0x78, 0xB5, 0x84]);
+        data.extend_from_slice(&88200f32.to_be_bytes());
+        data.extend(EbmlElement::uint(0x6264, 16).encode());
+        let bytes = EbmlElement::new(0xE1, data).encode();
+
+        let header = FrameHeader::from_ebml_track_audio_element(&bytes).unwrap();
+        assert_eq!(header.sample_rate(), 44100);
+        assert_eq!(header.channels(), 1);
+        assert_eq!(header.bits_per_sample(), 16);
+    }
+
+    #[test]
+    fn test_ebml_track_audio_errors() {
+        assert_eq!(
+            header(EncodingFlag::H264, 2, 16).to_ebml_track_audio_element(),
+            Err(FrameHeaderError::InvalidEncoding(5))
+        );
+
+        let bytes = header(EncodingFlag::Opus, 2, 16)
+            .to_ebml_track_audio_element()
+            .unwrap();
+        for len in 0..bytes.len() {
+            assert!(FrameHeader::from_ebml_track_audio_element(&bytes[..len]).is_err());
+        }
+
+        let video = EbmlElement::new(0xE0, vec![]).encode();
+        assert!(matches!(
+            FrameHeader::from_ebml_track_audio_element(&video),
+            Err(FrameHeaderError::Invalid(_))
+        ));
+        let unknown_size = [0xE1, 0xFF];
+        assert!(matches!(
+            FrameHeader::from_ebml_track_audio_element(&unknown_size),
+            Err(FrameHeaderError::Invalid(_))
+        ));
+        let no_bit_depth = EbmlElement::new(
+            0xE1,
+            EbmlElement::new(0xB5, 48000f64.to_be_bytes().to_vec()).encode(),
+        )
+        .encode();
+        assert_eq!(
+            FrameHeader::from_ebml_track_audio_element(&no_bit_depth),
+            Err(FrameHeaderError::FieldOutOfRange("bits_per_sample"))
+        );
+        for rate in [22050.0, 48000.5, -48000.0, f64::NAN] {
+            let mut data = EbmlElement::new(0xB5, f64::to_be_bytes(rate).to_vec()).encode();
+            data.extend(EbmlElement::uint(0x6264, 16).encode());
+            let bytes = EbmlElement::new(0xE1, data).encode();
+            assert!(
+                FrameHeader::from_ebml_track_audio_element(&bytes).is_err(),
+                "{}",
+                rate
+            );
+        }
+    }
+}
@@ -0,0 +1,248 @@
+use crate::{EncodingFlag, FrameHeaderError, FrameHeaderV2, Limits};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// How a metadata frame's document is encoded, given by the first byte of
+/// its payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MetadataFormat {
+    Json,
+    Cbor,
+}
+
+impl MetadataFormat {
+    pub const fn code(&self) -> u8 {
+        match self {
+            MetadataFormat::Json => 0,
+            MetadataFormat::Cbor => 1,
+        }
+    }
+
+    pub fn from_code(code: u8) -> Option<MetadataFormat> {
+        match code {
+            0 => Some(MetadataFormat::Json),
+            1 => Some(MetadataFormat::Cbor),
+            _ => None,
+        }
+    }
+}
+
+fn invalid(err: impl std::fmt::Display) -> FrameHeaderError {
+    FrameHeaderError::Invalid(format!("Invalid metadata document: {}", err))
+}
+
+impl FrameHeaderV2 {
+    /// A metadata frame for `document`, using this header as the template:
+    /// the ID, PTS, stream fields and redundancy are kept, the frame count
+    /// is zero, and a packet CRC is recomputed if the template has one.
+    pub fn metadata_frame<T: Serialize + ?Sized>(
+        &self,
+        format: MetadataFormat,
+        document: &T,
+        limits: &Limits,
+    ) -> Result<(FrameHeaderV2, Vec<u8>), FrameHeaderError> {
+        let mut payload = vec![format.code()];
+        match format {
+            MetadataFormat::Json => {
+                serde_json::to_writer(&mut payload, document).map_err(invalid)?
+            }
+            MetadataFormat::Cbor => {
+                ciborium::into_writer(document, &mut payload).map_err(invalid)?
+            }
+        }
+        limits.check_metadata_len(payload.len())?;
+
+        let mut header = FrameHeaderV2 {
+            encoding: EncodingFlag::Metadata,
+            payload_size: payload.len() as u32,
+            frame_count: 0,
+            ..self.clone()
+        };
+        if header.packet_crc32.is_some() {
+            header = header.with_packet_crc32(&payload)?;
+        }
+        Ok((header, payload))
+    }
+
+    /// Decodes the document in a metadata frame's payload.
+    pub fn parse_metadata<T: DeserializeOwned>(
+        &self,
+        payload: &[u8],
+        limits: &Limits,
+    ) -> Result<T, FrameHeaderError> {
+        if !self.is_metadata() {
+            return Err(FrameHeaderError::InvalidEncoding(
+                self.encoding.code() as u32
+            ));
+        }
+        limits.check_metadata_len(payload.len())?;
+        let (&code, document) = payload.split_first().ok_or(FrameHeaderError::Truncated)?;
+        match MetadataFormat::from_code(code) {
+            Some(MetadataFormat::Json) => serde_json::from_slice(document).map_err(invalid),
+            Some(MetadataFormat::Cbor) => ciborium::from_reader(document).map_err(invalid),
+            None => Err(FrameHeaderError::Invalid(format!(
+                "Unknown metadata format: {}",
+                code
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Endianness, FrameReader, FrameWriter};
+    use serde::Deserialize;
+    use std::collections::BTreeMap;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Marker {
+        label: String,
+        at_us: u64,
+        speakers: Vec<Speaker>,
+        tags: BTreeMap<String, Option<f64>>,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Speaker {
+        name: String,
+        channels: Vec<u8>,
+    }
+
+    fn marker() -> Marker {
+        Marker {
+            label: "Chapter 2 — \"Night\"".to_string(),
+            at_us: 90_000_000,
+            speakers: vec![
+                Speaker {
+                    name: "Ada".to_string(),
+                    channels: vec![0],
+                },
+                Speaker {
+                    name: "Grace".to_string(),
+                    channels: vec![1, 2],
+                },
+            ],
+            tags: BTreeMap::from([("gain".to_string(), Some(-3.5)), ("mood".to_string(), None)]),
+        }
+    }
+
+    fn audio(payload_size: u32, pts: u64) -> FrameHeaderV2 {
+        FrameHeaderV2::new(
+            EncodingFlag::Opus,
+            payload_size,
+            960,
+            48000,
+            2,
+            0,
+            Endianness::LittleEndian,
+            Some(9),
+            Some(pts),
+            None,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_metadata_roundtrip() {
+        let limits = Limits::default();
+        let template = audio(0, 20_000).with_redundant_copy(true);
+        for format in [MetadataFormat::Json, MetadataFormat::Cbor] {
+            let (header, payload) = template.metadata_frame(format, &marker(), &limits).unwrap();
+            assert!(header.is_metadata() && !header.is_media());
+            assert_eq!(payload[0], format.code());
+            assert_eq!(header.payload_size() as usize, payload.len());
+            assert_eq!(header.frame_count(), 0);
+            assert_eq!((header.id(), header.pts()), (Some(9), Some(20_000)));
+            assert!(header.is_redundant());
+            assert_eq!(
+                header.parse_metadata::<Marker>(&payload, &limits),
+                Ok(marker())
+            );
+        }
+
+        // A CRC on the template is recomputed for the document.
+        let crc_template = audio(3, 0).with_packet_crc32(&[1, 2, 3]).unwrap();
+        let (header, payload) = crc_template
+            .metadata_frame(MetadataFormat::Cbor, &marker(), &limits)
+            .unwrap();
+        assert_eq!(
+            header.packet_crc32_value(),
+            Some(header.compute_packet_crc32(&payload).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_readers_skip_metadata_frames() {
+        let limits = Limits::default();
+        let mut writer = FrameWriter::new(Vec::new());
+        for index in 0..4u64 {
+            let header = audio(2, index * 20_000);
+            writer.write_frame(&header, &[index as u8; 2]).unwrap();
+            if index % 2 == 1 {
+                let (meta, payload) = header
+                    .metadata_frame(MetadataFormat::Json, &marker(), &limits)
+                    .unwrap();
+                writer.write_frame(&meta, &payload).unwrap();
+            }
+        }
+        let frames: Vec<_> = FrameReader::new(&writer.into_inner()[..])
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(frames.len(), 6);
+
+        let media: Vec<u8> = frames
+            .iter()
+            .filter(|(header, _)| header.is_media())
+            .map(|(_, payload)| payload[0])
+            .collect();
+        assert_eq!(media, [0, 1, 2, 3]);
+        for (header, payload) in frames.iter().filter(|(header, _)| header.is_metadata()) {
+            assert_eq!(
+                header.parse_metadata::<Marker>(payload, &limits),
+                Ok(marker())
+            );
+        }
+    }
+
+    #[test]
+    fn test_metadata_errors() {
+        let limits = Limits::default().with_max_metadata_len(64);
+        let template = audio(0, 0);
+        assert!(matches!(
+            template.metadata_frame(MetadataFormat::Json, &marker(), &limits),
+            Err(FrameHeaderError::LimitExceeded {
+                limit: "metadata_len",
+                max: 64,
+                ..
+            })
+        ));
+
+        let (header, payload) = template
+            .metadata_frame(MetadataFormat::Json, &marker(), &Limits::default())
+            .unwrap();
+        assert!(matches!(
+            header.parse_metadata::<Marker>(&payload, &limits),
+            Err(FrameHeaderError::LimitExceeded { .. })
+        ));
+        assert_eq!(
+            template.parse_metadata::<Marker>(&payload, &Limits::default()),
+            Err(FrameHeaderError::InvalidEncoding(2))
+        );
+        assert_eq!(
+            header.parse_metadata::<Marker>(&[], &limits),
+            Err(FrameHeaderError::Truncated)
+        );
+        for bad in [&[7, b'{', b'}'][..], &[0, b'{'], &[1, 0xFF]] {
+            assert!(matches!(
+                header.parse_metadata::<Marker>(bad, &limits),
+                Err(FrameHeaderError::Invalid(_))
+            ));
+        }
+        // The document must match the requested type.
+        assert!(matches!(
+            header.parse_metadata::<Vec<u8>>(&payload, &Limits::default()),
+            Err(FrameHeaderError::Invalid(_))
+        ));
+    }
+}
@@ -0,0 +1,228 @@
+use crate::{EncodingFlag, Endianness, FrameHeader, FrameHeaderBuilder, FrameHeaderError};
+
+impl FrameHeader {
+    /// Parses a `Content-Type` value such as `audio/opus; rate=48000; channels=2`
+    /// or MediaRecorder's `audio/webm;codecs=opus`. Parameters that are absent
+    /// keep the builder defaults, except Opus which defaults to 48 kHz.
+    pub fn from_http_content_type(
+        content_type: &str,
+        sample_size: u16,
+    ) -> Result<FrameHeaderBuilder, FrameHeaderError> {
+        let mut parts = content_type.split(';');
+        let mime = parts.next().unwrap_or("").trim().to_ascii_lowercase();
+
+        let mut rate = None;
+        let mut channels = None;
+        let mut format = None;
+        let mut codec = None;
+        for param in parts {
+            let Some((name, value)) = param.split_once('=') else {
+                continue;
+            };
+            let value = value.trim().trim_matches('"').to_ascii_lowercase();
+            match name.trim().to_ascii_lowercase().as_str() {
+                "rate" => rate = Some(value),
+                "channels" => channels = Some(value),
+                "format" => format = Some(value),
+                // Only the first listed codec is used.
+                "codecs" => codec = value.split(',').next().map(|c| c.trim().to_string()),
+                _ => {}
+            }
+        }
+
+        let mut builder = FrameHeader::builder().sample_size(sample_size);
+        builder = match (mime.as_str(), codec.as_deref()) {
+            ("audio/opus", _) | ("audio/ogg" | "audio/webm", Some("opus") | None) => {
+                builder.encoding(EncodingFlag::Opus).sample_rate(48000)
+            }
+            ("audio/flac" | "audio/x-flac", _) | ("audio/ogg", Some("flac")) => {
+                builder.encoding(EncodingFlag::FLAC)
+            }
+            ("audio/aac" | "audio/aacp", _) | ("audio/mp4", None) => {
+                builder.encoding(EncodingFlag::AAC)
+            }
+            ("audio/mp4" | "audio/webm", Some(codec)) if codec.starts_with("mp4a") => {
+                builder.encoding(EncodingFlag::AAC)
+            }
+            // Chrome records 32-bit float samples for `codecs=pcm`.
+            ("audio/webm", Some("pcm")) => {
+                builder.encoding(EncodingFlag::PCMFloat).bits_per_sample(32)
+            }
+            ("audio/x-wav" | "audio/wav" | "audio/wave" | "audio/vnd.wave", _) => {
+                builder.encoding(EncodingFlag::PCMSigned)
+            }
+            // RFC 2586 linear PCM is big-endian.
+            ("audio/l16", _) => builder
+                .encoding(EncodingFlag::PCMSigned)
+                .bits_per_sample(16)
+                .endianness(Endianness::BigEndian),
+            ("audio/l24", _) => builder
+                .encoding(EncodingFlag::PCMSigned)
+                .bits_per_sample(24)
+                .endianness(Endianness::BigEndian),
+            _ => {
+                return Err(FrameHeaderError::Invalid(format!(
+                    "Unsupported content type: {}",
+                    content_type
+                )))
+            }
+        };
+
+        if let Some(rate) = rate {
+            builder = builder.sample_rate(
+                rate.parse()
+                    .map_err(|_| FrameHeaderError::FieldOutOfRange("rate"))?,
+            );
+        }
+        if let Some(channels) = channels {
+            builder = builder.channels(
+                channels
+                    .parse()
+                    .map_err(|_| FrameHeaderError::FieldOutOfRange("channels"))?,
+            );
+        }
+        if let Some(format) = format {
+            let (encoding, bits_per_sample, endianness) =
+                parse_pcm_format(&format).ok_or(FrameHeaderError::FieldOutOfRange("format"))?;
+            builder = builder
+                .encoding(encoding)
+                .bits_per_sample(bits_per_sample)
+                .endianness(endianness);
+        }
+
+        Ok(builder)
+    }
+
+    pub fn to_http_content_type(&self) -> String {
+        let mime = match self.encoding {
+            EncodingFlag::Opus => "audio/opus",
+            EncodingFlag::FLAC => "audio/flac",
+            EncodingFlag::AAC => "audio/aac",
+            EncodingFlag::PCMSigned | EncodingFlag::PCMFloat => "audio/x-wav",
+            EncodingFlag::H264 => return "video/h264".to_string(),
+            EncodingFlag::Metadata | EncodingFlag::Unknown(_) => {
+                return "application/octet-stream".to_string()
+            }
+        };
+
+        let mut content_type = format!(
+            "{}; rate={}; channels={}",
+            mime, self.sample_rate, self.channels
+        );
+        if let EncodingFlag::PCMSigned | EncodingFlag::PCMFloat = self.encoding {
+            content_type.push_str(&format!(
+                "; format={}{}{}",
+                if self.encoding == EncodingFlag::PCMFloat {
+                    'f'
+                } else {
+                    's'
+                },
+                self.bits_per_sample,
+                if self.endianness == Endianness::BigEndian {
+                    "be"
+                } else {
+                    "le"
+                }
+            ));
+        }
+        content_type
+    }
+}
+
+// FFmpeg-style sample formats such as `s16le` or `f32be`.
+fn parse_pcm_format(format: &str) -> Option<(EncodingFlag, u8, Endianness)> {
+    let encoding = match format.get(..1)? {
+        "s" => EncodingFlag::PCMSigned,
+        "f" => EncodingFlag::PCMFloat,
+        _ => return None,
+    };
+    let (bits, endianness) = match format.get(1..)? {
+        rest if rest.ends_with("le") => (&rest[..rest.len() - 2], Endianness::LittleEndian),
+        rest if rest.ends_with("be") => (&rest[..rest.len() - 2], Endianness::BigEndian),
+        _ => return None,
+    };
+    Some((encoding, bits.parse().ok()?, endianness))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(content_type: &str) -> FrameHeader {
+        FrameHeader::from_http_content_type(content_type, 960)
+            .unwrap()
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_from_media_recorder_content_types() {
+        for content_type in [
+            "audio/webm;codecs=opus",
+            "audio/webm; codecs=\"opus\"",
+            "audio/ogg; codecs=opus",
+            "audio/ogg",
+            "audio/opus; rate=48000; channels=2",
+        ] {
+            let header = parse(content_type);
+            assert_eq!(header.encoding(), EncodingFlag::Opus, "{}", content_type);
+            assert_eq!(header.sample_rate(), 48000);
+            assert_eq!(header.samples_per_channel(), 960);
+        }
+        assert_eq!(parse("audio/opus; rate=48000; channels=2").channels(), 2);
+
+        assert_eq!(parse("audio/mp4").encoding(), EncodingFlag::AAC);
+        assert_eq!(
+            parse("audio/mp4;codecs=mp4a.40.2").encoding(),
+            EncodingFlag::AAC
+        );
+        assert_eq!(
+            parse("audio/ogg; codecs=flac").encoding(),
+            EncodingFlag::FLAC
+        );
+
+        let pcm = parse("audio/webm;codecs=pcm");
+        assert_eq!(pcm.encoding(), EncodingFlag::PCMFloat);
+        assert_eq!(pcm.bits_per_sample(), 32);
+
+        let wav = parse("audio/x-wav; rate=44100; channels=1; format=s24be");
+        assert_eq!(wav.encoding(), EncodingFlag::PCMSigned);
+        assert_eq!(wav.sample_rate(), 44100);
+        assert_eq!(wav.bits_per_sample(), 24);
+        assert_eq!(wav.endianness(), Endianness::BigEndian);
+
+        let l16 = parse("audio/L16; rate=16000; channels=1");
+        assert_eq!(l16.bits_per_sample(), 16);
+        assert_eq!(l16.endianness(), Endianness::BigEndian);
+    }
+
+    #[test]
+    fn test_content_type_errors_and_roundtrip() {
+        assert!(FrameHeader::from_http_content_type("video/webm;codecs=vp9", 960).is_err());
+        assert_eq!(
+            FrameHeader::from_http_content_type("audio/opus; channels=two", 960),
+            Err(FrameHeaderError::FieldOutOfRange("channels"))
+        );
+        assert_eq!(
+            FrameHeader::from_http_content_type("audio/x-wav; format=u8", 960),
+            Err(FrameHeaderError::FieldOutOfRange("format"))
+        );
+        // Parses, but 22.05 kHz is not a v1 sample rate.
+        assert!(
+            FrameHeader::from_http_content_type("audio/flac; rate=22050", 960)
+                .unwrap()
+                .build()
+                .is_err()
+        );
+
+        for content_type in [
+            "audio/opus; rate=48000; channels=2",
+            "audio/flac; rate=96000; channels=6",
+            "audio/aac; rate=44100; channels=2",
+            "audio/x-wav; rate=16000; channels=1; format=s16le",
+            "audio/x-wav; rate=48000; channels=2; format=f32be",
+        ] {
+            assert_eq!(parse(content_type).to_http_content_type(), content_type);
+        }
+    }
+}
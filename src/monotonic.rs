@@ -0,0 +1,294 @@
+use crate::{FrameHeader, FrameHeaderV2};
+use std::collections::HashMap;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum MonotonicPolicy {
+    /// Yield a `PtsViolation` in place of the offending frame.
+    Error,
+    /// Skip the offending frame.
+    Drop,
+    /// Rewrite the PTS to follow the previous frame of the stream.
+    Clamp,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct PtsViolation {
+    pub id: Option<u64>,
+    pub previous: u64,
+    pub pts: u64,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct MonotonicCounters {
+    pub violations: u64,
+    pub dropped: u64,
+    pub clamped: u64,
+    /// Discontinuities that were allowed to move the PTS backwards.
+    pub resets: u64,
+}
+
+/// A header that `ensure_monotonic` can check. Streams are told apart by ID.
+pub trait MonotonicHeader: Clone {
+    fn stream_id(&self) -> Option<u64>;
+    fn monotonic_pts(&self) -> Option<u64>;
+    /// PTS the next frame of the stream is expected to carry.
+    fn expected_next_pts(&self) -> Option<u64>;
+    fn is_discontinuity(&self) -> bool;
+    fn set_monotonic_pts(&mut self, pts: u64);
+}
+
+impl MonotonicHeader for FrameHeader {
+    fn stream_id(&self) -> Option<u64> {
+        self.id
+    }
+
+    fn monotonic_pts(&self) -> Option<u64> {
+        self.pts
+    }
+
+    fn expected_next_pts(&self) -> Option<u64> {
+        self.next_pts()
+    }
+
+    // v1 headers have no discontinuity flag.
+    fn is_discontinuity(&self) -> bool {
+        false
+    }
+
+    fn set_monotonic_pts(&mut self, pts: u64) {
+        self.pts = Some(pts);
+    }
+}
+
+impl MonotonicHeader for FrameHeaderV2 {
+    fn stream_id(&self) -> Option<u64> {
+        self.id
+    }
+
+    fn monotonic_pts(&self) -> Option<u64> {
+        self.pts
+    }
+
+    // v2 PTS counts sample frames.
+    fn expected_next_pts(&self) -> Option<u64> {
+        self.pts?.checked_add(self.frame_count as u64)
+    }
+
+    fn is_discontinuity(&self) -> bool {
+        self.packet_flags & FrameHeaderV2::FLAG_DISCONTINUITY != 0
+    }
+
+    fn set_monotonic_pts(&mut self, pts: u64) {
+        self.pts = Some(pts);
+    }
+}
+
+pub struct EnsureMonotonic<I, H> {
+    inner: I,
+    policy: MonotonicPolicy,
+    last: HashMap<Option<u64>, H>,
+    counters: MonotonicCounters,
+}
+
+/// Checks that PTS strictly increases within each stream of `iter`. Frames
+/// without a PTS pass through unchecked, and a frame flagged as a
+/// discontinuity may restart its stream at any PTS.
+pub fn ensure_monotonic<I, H, P>(iter: I, policy: MonotonicPolicy) -> EnsureMonotonic<I, H>
+where
+    I: Iterator<Item = (H, P)>,
+    H: MonotonicHeader,
+{
+    EnsureMonotonic {
+        inner: iter,
+        policy,
+        last: HashMap::new(),
+        counters: MonotonicCounters::default(),
+    }
+}
+
+impl<I, H> EnsureMonotonic<I, H> {
+    pub fn counters(&self) -> MonotonicCounters {
+        self.counters
+    }
+}
+
+impl<I, H, P> Iterator for EnsureMonotonic<I, H>
+where
+    I: Iterator<Item = (H, P)>,
+    H: MonotonicHeader,
+{
+    type Item = Result<(H, P), PtsViolation>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (mut header, payload) = self.inner.next()?;
+            let Some(pts) = header.monotonic_pts() else {
+                return Some(Ok((header, payload)));
+            };
+            let id = header.stream_id();
+            let previous = self.last.get(&id).and_then(|last| last.monotonic_pts());
+
+            if let Some(previous) = previous.filter(|&previous| pts <= previous) {
+                if header.is_discontinuity() {
+                    self.counters.resets += 1;
+                } else {
+                    self.counters.violations += 1;
+                    match self.policy {
+                        MonotonicPolicy::Error => {
+                            return Some(Err(PtsViolation { id, previous, pts }));
+                        }
+                        MonotonicPolicy::Drop => {
+                            self.counters.dropped += 1;
+                            continue;
+                        }
+                        MonotonicPolicy::Clamp => {
+                            // A zero-length previous frame still moves PTS on by one.
+                            let next = self.last[&id]
+                                .expected_next_pts()
+                                .unwrap_or(u64::MAX)
+                                .max(previous.saturating_add(1));
+                            header.set_monotonic_pts(next);
+                            self.counters.clamped += 1;
+                        }
+                    }
+                }
+            }
+
+            self.last.insert(id, header.clone());
+            return Some(Ok((header, payload)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EncodingFlag, Endianness};
+
+    // 960 samples at 48kHz last 20ms.
+    fn v1(id: u64, pts: u64) -> (FrameHeader, u64) {
+        let header = FrameHeader::new(
+            EncodingFlag::Opus,
+            960,
+            48000,
+            2,
+            16,
+            Endianness::LittleEndian,
+            Some(id),
+            Some(pts),
+        )
+        .unwrap();
+        (header, pts)
+    }
+
+    // Stream 1 jumps back at its third frame; stream 2 is interleaved and fine.
+    fn sequence() -> Vec<(FrameHeader, u64)> {
+        vec![
+            v1(1, 0),
+            v1(2, 500_000),
+            v1(1, 20_000),
+            v1(2, 520_000),
+            v1(1, 10_000),
+            v1(1, 60_000),
+        ]
+    }
+
+    fn pts<H: MonotonicHeader, P>(items: &[(H, P)]) -> Vec<u64> {
+        items
+            .iter()
+            .map(|(header, _)| header.monotonic_pts().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_error_policy_yields_violation() {
+        let mut checked = ensure_monotonic(sequence().into_iter(), MonotonicPolicy::Error);
+        let items: Vec<_> = checked.by_ref().collect();
+        assert_eq!(items.len(), 6);
+        assert_eq!(
+            items[4],
+            Err(PtsViolation {
+                id: Some(1),
+                previous: 20_000,
+                pts: 10_000,
+            })
+        );
+        // The violation does not become the new reference.
+        assert!(items[5].is_ok());
+        assert_eq!(
+            checked.counters(),
+            MonotonicCounters {
+                violations: 1,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_drop_policy_skips_offender() {
+        let mut checked = ensure_monotonic(sequence().into_iter(), MonotonicPolicy::Drop);
+        let items: Vec<_> = checked.by_ref().map(Result::unwrap).collect();
+        assert_eq!(pts(&items), [0, 500_000, 20_000, 520_000, 60_000]);
+        assert_eq!(checked.counters().violations, 1);
+        assert_eq!(checked.counters().dropped, 1);
+    }
+
+    #[test]
+    fn test_clamp_policy_rewrites_pts() {
+        let mut checked = ensure_monotonic(sequence().into_iter(), MonotonicPolicy::Clamp);
+        let items: Vec<_> = checked.by_ref().map(Result::unwrap).collect();
+        assert_eq!(pts(&items), [0, 500_000, 20_000, 520_000, 40_000, 60_000]);
+        // The payload travels with its frame untouched.
+        assert_eq!(items[4].1, 10_000);
+        assert_eq!(checked.counters().clamped, 1);
+        assert_eq!(checked.counters().violations, 1);
+    }
+
+    #[test]
+    fn test_discontinuity_allows_reset() {
+        let v2 = |pts: u64, flags: u8| {
+            let header = FrameHeaderV2::new(
+                EncodingFlag::Opus,
+                100,
+                960,
+                48000,
+                2,
+                0,
+                Endianness::LittleEndian,
+                Some(3),
+                Some(pts),
+                None,
+            )
+            .unwrap()
+            .with_packet_flags(flags)
+            .unwrap();
+            (header, ())
+        };
+        let frames = vec![
+            v2(9600, 0),
+            v2(10560, 0),
+            v2(0, FrameHeaderV2::FLAG_DISCONTINUITY),
+            v2(960, 0),
+            v2(0, 0),
+        ];
+
+        for policy in [
+            MonotonicPolicy::Error,
+            MonotonicPolicy::Drop,
+            MonotonicPolicy::Clamp,
+        ] {
+            let mut checked = ensure_monotonic(frames.clone().into_iter(), policy);
+            let items: Vec<_> = checked.by_ref().collect();
+            assert!(items[..4].iter().all(Result::is_ok));
+            let counters = checked.counters();
+            assert_eq!((counters.resets, counters.violations), (1, 1));
+            match policy {
+                MonotonicPolicy::Error => assert!(items[4].is_err()),
+                MonotonicPolicy::Drop => assert_eq!(items.len(), 4),
+                MonotonicPolicy::Clamp => {
+                    assert_eq!(items[4].as_ref().unwrap().0.pts(), Some(1920))
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use crate::{ChannelCount, EncodingFlag, Endianness, FrameHeader, FrameHeaderError, SampleRate};
+
+// MPEG-4 channel configurations (ISO/IEC 14496-3, Table 1.19) a header can
+// describe: index, channel count and name. Configuration 0 defers to the
+// program config element and 8-10 are reserved. 12 and 14 also carry eight
+// channels, so only 7 is used for 7.1.
+const CHANNEL_CONFIGS: [(u8, u8, &str); 8] = [
+    (1, 1, "center"),
+    (2, 2, "stereo"),
+    (3, 3, "front-3.0"),
+    (4, 4, "surround-4.0"),
+    (5, 5, "surround-5.0"),
+    (6, 6, "surround-5.1"),
+    (11, 7, "surround-6.1"),
+    (7, 8, "surround-7.1"),
+];
+
+impl FrameHeader {
+    /// The MPEG-4 channel configuration index for this header's channel
+    /// count, as written in an AAC AudioSpecificConfig or ADTS header.
+    pub fn mpeg4_channel_config(&self) -> Result<u8, FrameHeaderError> {
+        self.mpeg4_channel_config_entry()
+            .map(|(config, _, _)| config)
+    }
+
+    pub fn mpeg4_channel_config_name(&self) -> Result<&'static str, FrameHeaderError> {
+        self.mpeg4_channel_config_entry().map(|(_, _, name)| name)
+    }
+
+    fn mpeg4_channel_config_entry(&self) -> Result<(u8, u8, &'static str), FrameHeaderError> {
+        CHANNEL_CONFIGS
+            .into_iter()
+            .find(|&(_, channels, _)| channels == self.channels)
+            .ok_or(FrameHeaderError::FieldOutOfRange("channels"))
+    }
+
+    pub fn from_mpeg4_channel_config(
+        config: u8,
+        encoding: EncodingFlag,
+        rate: u32,
+        bits: u8,
+        sample_size: u16,
+    ) -> Result<FrameHeader, FrameHeaderError> {
+        let (_, channels, _) = CHANNEL_CONFIGS
+            .into_iter()
+            .find(|&(index, _, _)| index == config)
+            .ok_or(FrameHeaderError::FieldOutOfRange("channel_config"))?;
+        Ok(FrameHeader::new_typed(
+            encoding,
+            sample_size,
+            SampleRate::try_from(rate)?,
+            ChannelCount::try_from(channels)?,
+            bits,
+            Endianness::LittleEndian,
+            None,
+            None,
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aac(config: u8) -> Result<FrameHeader, FrameHeaderError> {
+        FrameHeader::from_mpeg4_channel_config(config, EncodingFlag::AAC, 48000, 16, 1024)
+    }
+
+    #[test]
+    fn test_mpeg4_channel_configs() {
+        let expected = [
+            (1, 1, "center"),
+            (2, 2, "stereo"),
+            (3, 3, "front-3.0"),
+            (4, 4, "surround-4.0"),
+            (5, 5, "surround-5.0"),
+            (6, 6, "surround-5.1"),
+            (7, 8, "surround-7.1"),
+        ];
+        for (config, channels, name) in expected {
+            let header = aac(config).unwrap();
+            assert_eq!(header.channels(), channels);
+            assert_eq!(header.encoding(), EncodingFlag::AAC);
+            assert_eq!(header.samples_per_channel(), 1024);
+            assert_eq!(header.mpeg4_channel_config(), Ok(config));
+            assert_eq!(header.mpeg4_channel_config_name(), Ok(name));
+        }
+
+        // 6.1 uses the later configuration 11; 8 is reserved.
+        assert_eq!(aac(11).unwrap().channels(), 7);
+        assert_eq!(
+            aac(11).unwrap().mpeg4_channel_config_name(),
+            Ok("surround-6.1")
+        );
+        for config in [0, 8, 9, 12, 15] {
+            assert_eq!(
+                aac(config),
+                Err(FrameHeaderError::FieldOutOfRange("channel_config"))
+            );
+        }
+    }
+
+    #[test]
+    fn test_mpeg4_channel_config_errors() {
+        let header = FrameHeader::new(
+            EncodingFlag::AAC,
+            1024,
+            48000,
+            16,
+            16,
+            Endianness::LittleEndian,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            header.mpeg4_channel_config(),
+            Err(FrameHeaderError::FieldOutOfRange("channels"))
+        );
+        assert_eq!(
+            header.mpeg4_channel_config_name(),
+            Err(FrameHeaderError::FieldOutOfRange("channels"))
+        );
+        assert_eq!(
+            FrameHeader::from_mpeg4_channel_config(2, EncodingFlag::AAC, 22050, 16, 1024),
+            Err(FrameHeaderError::FieldOutOfRange("sample_rate"))
+        );
+    }
+}
@@ -0,0 +1,245 @@
+use crate::{FrameHeader, FrameHeaderError};
+
+/// MIDI Timecode frame rates. 29.97 fps is drop-frame, as MTC defines it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MtcFrameRate {
+    Fps24,
+    Fps25,
+    Fps2997,
+    Fps30,
+}
+
+impl MtcFrameRate {
+    // Frames per second as a fraction.
+    fn ratio(self) -> (u128, u128) {
+        match self {
+            MtcFrameRate::Fps24 => (24, 1),
+            MtcFrameRate::Fps25 => (25, 1),
+            MtcFrameRate::Fps2997 => (30000, 1001),
+            MtcFrameRate::Fps30 => (30, 1),
+        }
+    }
+
+    // Frame labels per second; drop-frame labels count at 30.
+    fn nominal_fps(self) -> u64 {
+        match self {
+            MtcFrameRate::Fps24 => 24,
+            MtcFrameRate::Fps25 => 25,
+            MtcFrameRate::Fps2997 | MtcFrameRate::Fps30 => 30,
+        }
+    }
+
+    /// The rate bits of the MTC hours byte.
+    pub fn code(self) -> u8 {
+        match self {
+            MtcFrameRate::Fps24 => 0,
+            MtcFrameRate::Fps25 => 1,
+            MtcFrameRate::Fps2997 => 2,
+            MtcFrameRate::Fps30 => 3,
+        }
+    }
+}
+
+// Drop-frame skips labels 0 and 1 at the start of every minute except each
+// tenth, so ten minutes hold 17982 frames.
+pub(crate) const DROP_FRAMES_PER_10_MINUTES: u64 = 17982;
+pub(crate) const DROP_FRAMES_PER_MINUTE: u64 = 1798;
+
+impl FrameHeader {
+    /// The MTC time of the frame playing at `pts_us`. Hours wrap at 24, as
+    /// MTC cannot express more.
+    pub fn pts_to_mtc(pts_us: u64, frame_rate: MtcFrameRate) -> (u8, u8, u8, u8) {
+        let (num, den) = frame_rate.ratio();
+        let mut frame = (pts_us as u128 * num / (den * 1_000_000)) as u64;
+        if frame_rate == MtcFrameRate::Fps2997 {
+            let tens = frame / DROP_FRAMES_PER_10_MINUTES;
+            let rest = frame % DROP_FRAMES_PER_10_MINUTES;
+            let dropped = 18 * tens + 2 * ((rest.max(2) - 2) / DROP_FRAMES_PER_MINUTE);
+            frame += dropped;
+        }
+
+        let fps = frame_rate.nominal_fps();
+        let seconds = frame / fps;
+        (
+            (seconds / 3600 % 24) as u8,
+            (seconds / 60 % 60) as u8,
+            (seconds % 60) as u8,
+            (frame % fps) as u8,
+        )
+    }
+
+    /// The first microsecond of an MTC time, so `pts_to_mtc` maps it back
+    /// to the same time. Labels that drop-frame skips are out of range.
+    pub fn pts_from_mtc(
+        h: u8,
+        m: u8,
+        s: u8,
+        f: u8,
+        rate: MtcFrameRate,
+    ) -> Result<u64, FrameHeaderError> {
+        let fps = rate.nominal_fps();
+        if h >= 24 {
+            return Err(FrameHeaderError::FieldOutOfRange("hours"));
+        }
+        if m >= 60 {
+            return Err(FrameHeaderError::FieldOutOfRange("minutes"));
+        }
+        if s >= 60 {
+            return Err(FrameHeaderError::FieldOutOfRange("seconds"));
+        }
+        if f as u64 >= fps
+            || (rate == MtcFrameRate::Fps2997 && s == 0 && f < 2 && !m.is_multiple_of(10))
+        {
+            return Err(FrameHeaderError::FieldOutOfRange("frames"));
+        }
+
+        let minutes = h as u64 * 60 + m as u64;
+        let mut frame = (minutes * 60 + s as u64) * fps + f as u64;
+        if rate == MtcFrameRate::Fps2997 {
+            frame -= 2 * (minutes - minutes / 10);
+        }
+        let (num, den) = rate.ratio();
+        Ok((frame as u128 * den * 1_000_000).div_ceil(num) as u64)
+    }
+
+    /// The SysEx full-frame message locating a receiver at this header's
+    /// PTS, addressed to all devices. `None` without a PTS.
+    pub fn encode_mtc_full_frame_message(&self, rate: MtcFrameRate) -> Option<[u8; 10]> {
+        let (h, m, s, f) = Self::pts_to_mtc(self.pts?, rate);
+        Some([
+            0xF0,
+            0x7F,
+            0x7F,
+            0x01,
+            0x01,
+            rate.code() << 5 | h,
+            m,
+            s,
+            f,
+            0xF7,
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EncodingFlag, Endianness};
+
+    const RATES: [MtcFrameRate; 4] = [
+        MtcFrameRate::Fps24,
+        MtcFrameRate::Fps25,
+        MtcFrameRate::Fps2997,
+        MtcFrameRate::Fps30,
+    ];
+
+    #[test]
+    fn test_pts_to_mtc() {
+        assert_eq!(
+            FrameHeader::pts_to_mtc(0, MtcFrameRate::Fps25),
+            (0, 0, 0, 0)
+        );
+        // 1h 2m 3s and 10 frames of 40ms.
+        let pts = ((3600 + 120 + 3) * 1000 + 400) * 1000;
+        assert_eq!(
+            FrameHeader::pts_to_mtc(pts, MtcFrameRate::Fps25),
+            (1, 2, 3, 10)
+        );
+        assert_eq!(
+            FrameHeader::pts_to_mtc(pts, MtcFrameRate::Fps24),
+            (1, 2, 3, 9)
+        );
+        assert_eq!(
+            FrameHeader::pts_to_mtc(pts, MtcFrameRate::Fps30),
+            (1, 2, 3, 12)
+        );
+        assert_eq!(
+            FrameHeader::pts_to_mtc(25 * 3600 * 1_000_000, MtcFrameRate::Fps30),
+            (1, 0, 0, 0)
+        );
+
+        // Drop-frame: frame 1800 is labelled 00:01:00;02, and frame 17982
+        // is exactly ten minutes.
+        let frame_start = |frame: u64| (frame * 1001 * 1_000_000).div_ceil(30000);
+        for (frame, mtc) in [
+            (1799, (0, 0, 59, 29)),
+            (1800, (0, 1, 0, 2)),
+            (17981, (0, 9, 59, 29)),
+            (17982, (0, 10, 0, 0)),
+            (17982 + 1800, (0, 11, 0, 2)),
+        ] {
+            assert_eq!(
+                FrameHeader::pts_to_mtc(frame_start(frame), MtcFrameRate::Fps2997),
+                mtc
+            );
+        }
+    }
+
+    #[test]
+    fn test_mtc_roundtrip() {
+        for rate in RATES {
+            for frame in (0..200_000u64).step_by(7) {
+                let (num, den) = rate.ratio();
+                let pts = (frame as u128 * den * 1_000_000).div_ceil(num) as u64;
+                let (h, m, s, f) = FrameHeader::pts_to_mtc(pts, rate);
+                assert_eq!(FrameHeader::pts_from_mtc(h, m, s, f, rate), Ok(pts));
+                // Any PTS within the frame maps to the same time.
+                assert_eq!(FrameHeader::pts_to_mtc(pts + 30_000, rate), (h, m, s, f));
+            }
+        }
+    }
+
+    #[test]
+    fn test_pts_from_mtc_rejects_invalid_times() {
+        for (time, field) in [
+            ((24, 0, 0, 0), "hours"),
+            ((0, 60, 0, 0), "minutes"),
+            ((0, 0, 60, 0), "seconds"),
+            ((0, 0, 0, 25), "frames"),
+        ] {
+            let (h, m, s, f) = time;
+            assert_eq!(
+                FrameHeader::pts_from_mtc(h, m, s, f, MtcFrameRate::Fps25),
+                Err(FrameHeaderError::FieldOutOfRange(field))
+            );
+        }
+        assert!(FrameHeader::pts_from_mtc(0, 0, 0, 24, MtcFrameRate::Fps24).is_err());
+
+        // Drop-frame skips ;00 and ;01 except every tenth minute.
+        assert!(FrameHeader::pts_from_mtc(0, 1, 0, 0, MtcFrameRate::Fps2997).is_err());
+        assert!(FrameHeader::pts_from_mtc(0, 1, 0, 1, MtcFrameRate::Fps2997).is_err());
+        assert!(FrameHeader::pts_from_mtc(0, 1, 0, 2, MtcFrameRate::Fps2997).is_ok());
+        assert!(FrameHeader::pts_from_mtc(0, 10, 0, 0, MtcFrameRate::Fps2997).is_ok());
+        assert!(FrameHeader::pts_from_mtc(0, 1, 0, 0, MtcFrameRate::Fps30).is_ok());
+    }
+
+    #[test]
+    fn test_full_frame_message() {
+        let header = |pts| {
+            FrameHeader::new(
+                EncodingFlag::PCMSigned,
+                960,
+                48000,
+                2,
+                24,
+                Endianness::LittleEndian,
+                None,
+                pts,
+            )
+            .unwrap()
+        };
+        let pts = FrameHeader::pts_from_mtc(13, 45, 30, 12, MtcFrameRate::Fps25).unwrap();
+        assert_eq!(
+            header(Some(pts)).encode_mtc_full_frame_message(MtcFrameRate::Fps25),
+            Some([0xF0, 0x7F, 0x7F, 0x01, 0x01, 0x20 | 13, 45, 30, 12, 0xF7])
+        );
+        let message = header(Some(0))
+            .encode_mtc_full_frame_message(MtcFrameRate::Fps2997)
+            .unwrap();
+        assert_eq!(message[5], 0x40);
+        assert_eq!(
+            header(None).encode_mtc_full_frame_message(MtcFrameRate::Fps30),
+            None
+        );
+    }
+}
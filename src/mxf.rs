@@ -0,0 +1,178 @@
+use crate::{EncodingFlag, FrameHeader, FrameHeaderError};
+
+// Wave Audio Essence Descriptor set key (SMPTE 382M), as a local set with
+// 2-byte tags and lengths.
+const WAVE_AUDIO_DESCRIPTOR_KEY: [u8; 16] = [
+    0x06, 0x0E, 0x2B, 0x34, 0x02, 0x53, 0x01, 0x01, 0x0D, 0x01, 0x01, 0x01, 0x01, 0x01, 0x48, 0x00,
+];
+
+// Static local tags from SMPTE 377M.
+const AUDIO_SAMPLING_RATE_TAG: u16 = 0x3D03;
+const CHANNEL_COUNT_TAG: u16 = 0x3D07;
+const QUANTIZATION_BITS_TAG: u16 = 0x3D01;
+const BLOCK_ALIGN_TAG: u16 = 0x3D0A;
+const AVG_BPS_TAG: u16 = 0x3D09;
+const LINKED_TRACK_ID_TAG: u16 = 0x3006;
+
+/// The audio fields of an MXF Wave Audio Essence Descriptor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MxfWaveAudioDescriptor {
+    /// Numerator and denominator.
+    pub sampling_rate: (u32, u32),
+    pub channel_count: u32,
+    pub quantization_bits: u32,
+    /// Bytes per sample frame across all channels; a UInt16 in MXF.
+    pub block_align: u16,
+    pub avg_bytes_per_sec: u32,
+    pub linked_track_id: u32,
+}
+
+impl MxfWaveAudioDescriptor {
+    /// The descriptor as a KLV packet holding only these fields: the set
+    /// key, a 4-byte BER length, then one local tag per field. A complete
+    /// descriptor also needs the InstanceUID and essence container label.
+    pub fn encode_klv(&self) -> Vec<u8> {
+        let mut value = Vec::with_capacity(50);
+        let mut item = |tag: u16, data: &[u8]| {
+            value.extend_from_slice(&tag.to_be_bytes());
+            value.extend_from_slice(&(data.len() as u16).to_be_bytes());
+            value.extend_from_slice(data);
+        };
+        let (numerator, denominator) = self.sampling_rate;
+        item(
+            AUDIO_SAMPLING_RATE_TAG,
+            &[numerator.to_be_bytes(), denominator.to_be_bytes()].concat(),
+        );
+        item(CHANNEL_COUNT_TAG, &self.channel_count.to_be_bytes());
+        item(QUANTIZATION_BITS_TAG, &self.quantization_bits.to_be_bytes());
+        item(BLOCK_ALIGN_TAG, &self.block_align.to_be_bytes());
+        item(AVG_BPS_TAG, &self.avg_bytes_per_sec.to_be_bytes());
+        item(LINKED_TRACK_ID_TAG, &self.linked_track_id.to_be_bytes());
+
+        let mut packet = Vec::with_capacity(16 + 4 + value.len());
+        packet.extend_from_slice(&WAVE_AUDIO_DESCRIPTOR_KEY);
+        packet.push(0x83);
+        packet.extend_from_slice(&(value.len() as u32).to_be_bytes()[1..]);
+        packet.extend_from_slice(&value);
+        packet
+    }
+}
+
+impl FrameHeader {
+    /// The Wave Audio Essence Descriptor for a PCM header. The linked track
+    /// ID is 1; set it to the sound track's TrackID when there are several.
+    pub fn to_mxf_wave_descriptor(&self) -> Result<MxfWaveAudioDescriptor, FrameHeaderError> {
+        if !matches!(
+            self.encoding,
+            EncodingFlag::PCMSigned | EncodingFlag::PCMFloat
+        ) {
+            return Err(FrameHeaderError::InvalidEncoding(
+                self.encoding.code() as u32
+            ));
+        }
+        let block_align = self.channels as u16 * self.bytes_per_sample() as u16;
+        Ok(MxfWaveAudioDescriptor {
+            sampling_rate: (self.sample_rate, 1),
+            channel_count: self.channels as u32,
+            quantization_bits: self.bits_per_sample as u32,
+            block_align,
+            avg_bytes_per_sec: self.sample_rate * block_align as u32,
+            linked_track_id: 1,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Endianness;
+
+    fn pcm(sample_rate: u32, channels: u8, bits: u8) -> FrameHeader {
+        FrameHeader::new(
+            EncodingFlag::PCMSigned,
+            480,
+            sample_rate,
+            channels,
+            bits,
+            Endianness::LittleEndian,
+            None,
+            None,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_mxf_wave_descriptor_klv() {
+        let descriptor = pcm(48000, 2, 24).to_mxf_wave_descriptor().unwrap();
+        assert_eq!(
+            descriptor,
+            MxfWaveAudioDescriptor {
+                sampling_rate: (48000, 1),
+                channel_count: 2,
+                quantization_bits: 24,
+                block_align: 6,
+                avg_bytes_per_sec: 288_000,
+                linked_track_id: 1,
+            }
+        );
+
+        #[rustfmt::skip]
+        let expected: &[u8] = &[
+            // Key and BER length.
+            0x06, 0x0E, 0x2B, 0x34, 0x02, 0x53, 0x01, 0x01,
+            0x0D, 0x01, 0x01, 0x01, 0x01, 0x01, 0x48, 0x00,
+            0x83, 0x00, 0x00, 0x32,
+            // Audio sampling rate 48000/1.
+            0x3D, 0x03, 0x00, 0x08, 0x00, 0x00, 0xBB, 0x80, 0x00, 0x00, 0x00, 0x01,
+            // Channel count, quantization bits, block align, average bytes
+            // per second and linked track ID.
+            0x3D, 0x07, 0x00, 0x04, 0x00, 0x00, 0x00, 0x02,
+            0x3D, 0x01, 0x00, 0x04, 0x00, 0x00, 0x00, 0x18,
+            0x3D, 0x0A, 0x00, 0x02, 0x00, 0x06,
+            0x3D, 0x09, 0x00, 0x04, 0x00, 0x04, 0x65, 0x00,
+            0x30, 0x06, 0x00, 0x04, 0x00, 0x00, 0x00, 0x01,
+        ];
+        assert_eq!(descriptor.encode_klv(), expected);
+
+        let mono = pcm(44100, 1, 16).to_mxf_wave_descriptor().unwrap();
+        assert_eq!(mono.block_align, 2);
+        assert_eq!(mono.avg_bytes_per_sec, 88_200);
+        let klv = mono.encode_klv();
+        assert_eq!(klv[24..28], 44100u32.to_be_bytes());
+        assert_eq!(klv.len(), expected.len());
+    }
+
+    #[test]
+    fn test_mxf_wave_descriptor_rejects_non_pcm() {
+        let opus = FrameHeader::new(
+            EncodingFlag::Opus,
+            960,
+            48000,
+            2,
+            16,
+            Endianness::LittleEndian,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            opus.to_mxf_wave_descriptor(),
+            Err(FrameHeaderError::InvalidEncoding(2))
+        );
+
+        let float = FrameHeader::new(
+            EncodingFlag::PCMFloat,
+            480,
+            96000,
+            16,
+            32,
+            Endianness::LittleEndian,
+            None,
+            None,
+        )
+        .unwrap();
+        let descriptor = float.to_mxf_wave_descriptor().unwrap();
+        assert_eq!(descriptor.block_align, 64);
+        assert_eq!(descriptor.avg_bytes_per_sec, 6_144_000);
+    }
+}
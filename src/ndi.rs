@@ -0,0 +1,181 @@
+use crate::{ChannelCount, EncodingFlag, Endianness, FrameHeader, FrameHeaderError, SampleRate};
+
+/// NDI's `NDIlib_send_timecode_synthesize`: no timecode, let the sender
+/// make one up.
+pub const NDI_TIMECODE_SYNTHESIZE: i64 = i64::MAX;
+
+/// The fields of an NDI audio frame apart from its data pointer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NdiAudioFrameDescriptor {
+    pub sample_rate: i32,
+    pub no_channels: i32,
+    pub no_samples: i32,
+    /// 100ns units since the NDI (Unix) epoch.
+    pub timecode: i64,
+    pub reference_level: i32,
+}
+
+impl FrameHeader {
+    /// PTS microseconds, taken to be since the Unix epoch, become NDI
+    /// timecodes; without a PTS the timecode is left for NDI to synthesize.
+    pub fn to_ndi_audio_descriptor(&self) -> Result<NdiAudioFrameDescriptor, FrameHeaderError> {
+        if !matches!(
+            self.encoding,
+            EncodingFlag::PCMSigned | EncodingFlag::PCMFloat
+        ) {
+            return Err(FrameHeaderError::InvalidEncoding(
+                self.encoding.code() as u32
+            ));
+        }
+        let timecode = match self.pts {
+            Some(pts) => pts
+                .checked_mul(10)
+                .and_then(|timecode| i64::try_from(timecode).ok())
+                .filter(|&timecode| timecode != NDI_TIMECODE_SYNTHESIZE)
+                .ok_or(FrameHeaderError::FieldOutOfRange("timecode"))?,
+            None => NDI_TIMECODE_SYNTHESIZE,
+        };
+
+        Ok(NdiAudioFrameDescriptor {
+            sample_rate: self.sample_rate as i32,
+            no_channels: self.channels as i32,
+            no_samples: self.sample_size as i32,
+            timecode,
+            reference_level: 0,
+        })
+    }
+
+    /// NDI samples are little-endian; 32-bit samples are taken to be float,
+    /// NDI's native format. Timecodes are truncated to whole microseconds
+    /// and `reference_level` is not carried.
+    pub fn from_ndi_audio_descriptor(
+        desc: &NdiAudioFrameDescriptor,
+        bits_per_sample: u8,
+    ) -> Result<FrameHeader, FrameHeaderError> {
+        let sample_rate = u32::try_from(desc.sample_rate)
+            .map_err(|_| FrameHeaderError::FieldOutOfRange("sample_rate"))?;
+        let channels = u8::try_from(desc.no_channels)
+            .map_err(|_| FrameHeaderError::FieldOutOfRange("no_channels"))?;
+        let sample_size = u16::try_from(desc.no_samples)
+            .map_err(|_| FrameHeaderError::FieldOutOfRange("no_samples"))?;
+        let pts = match desc.timecode {
+            NDI_TIMECODE_SYNTHESIZE => None,
+            timecode => Some(
+                u64::try_from(timecode)
+                    .map_err(|_| FrameHeaderError::FieldOutOfRange("timecode"))?
+                    / 10,
+            ),
+        };
+        let encoding = if bits_per_sample == 32 {
+            EncodingFlag::PCMFloat
+        } else {
+            EncodingFlag::PCMSigned
+        };
+
+        Ok(FrameHeader::new_typed(
+            encoding,
+            sample_size,
+            SampleRate::try_from(sample_rate)?,
+            ChannelCount::try_from(channels)?,
+            bits_per_sample,
+            Endianness::LittleEndian,
+            None,
+            pts,
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(encoding: EncodingFlag, pts: Option<u64>) -> FrameHeader {
+        FrameHeader::new(
+            encoding,
+            1600,
+            48000,
+            2,
+            32,
+            Endianness::LittleEndian,
+            None,
+            pts,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_ndi_descriptor_roundtrip() {
+        let pcm = header(EncodingFlag::PCMFloat, Some(1_700_000_000_000_000));
+        let desc = pcm.to_ndi_audio_descriptor().unwrap();
+        assert_eq!(
+            desc,
+            NdiAudioFrameDescriptor {
+                sample_rate: 48000,
+                no_channels: 2,
+                no_samples: 1600,
+                timecode: 17_000_000_000_000_000,
+                reference_level: 0,
+            }
+        );
+        assert_eq!(FrameHeader::from_ndi_audio_descriptor(&desc, 32), Ok(pcm));
+
+        let untimed = header(EncodingFlag::PCMFloat, None);
+        let desc = untimed.to_ndi_audio_descriptor().unwrap();
+        assert_eq!(desc.timecode, NDI_TIMECODE_SYNTHESIZE);
+        assert_eq!(
+            FrameHeader::from_ndi_audio_descriptor(&desc, 32),
+            Ok(untimed)
+        );
+
+        let signed = FrameHeader::from_ndi_audio_descriptor(
+            &NdiAudioFrameDescriptor {
+                timecode: 15,
+                ..desc
+            },
+            16,
+        )
+        .unwrap();
+        assert_eq!(signed.encoding(), EncodingFlag::PCMSigned);
+        assert_eq!(signed.pts(), Some(1));
+    }
+
+    #[test]
+    fn test_ndi_descriptor_rejects_unrepresentable_values() {
+        assert_eq!(
+            header(EncodingFlag::Opus, None).to_ndi_audio_descriptor(),
+            Err(FrameHeaderError::InvalidEncoding(2))
+        );
+        assert_eq!(
+            header(EncodingFlag::PCMSigned, Some(u64::MAX / 10 + 1)).to_ndi_audio_descriptor(),
+            Err(FrameHeaderError::FieldOutOfRange("timecode"))
+        );
+
+        let desc = header(EncodingFlag::PCMFloat, None)
+            .to_ndi_audio_descriptor()
+            .unwrap();
+        for bad in [
+            NdiAudioFrameDescriptor {
+                sample_rate: -48000,
+                ..desc
+            },
+            NdiAudioFrameDescriptor {
+                sample_rate: 22050,
+                ..desc
+            },
+            NdiAudioFrameDescriptor {
+                no_channels: 0,
+                ..desc
+            },
+            NdiAudioFrameDescriptor {
+                no_samples: 70_000,
+                ..desc
+            },
+            NdiAudioFrameDescriptor {
+                timecode: -1,
+                ..desc
+            },
+        ] {
+            assert!(FrameHeader::from_ndi_audio_descriptor(&bad, 32).is_err());
+        }
+    }
+}
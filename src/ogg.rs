@@ -0,0 +1,99 @@
+use crate::{FrameHeader, FrameHeaderError};
+use std::io;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OggPacket {
+    pub granule_position: u64,
+    pub data: Vec<u8>,
+    pub is_beginning_of_stream: bool,
+    pub is_end_of_stream: bool,
+}
+
+impl FrameHeader {
+    /// Wraps the encoded header and payload in an Ogg packet whose granule
+    /// position is the PTS in samples. Stream boundary flags are left unset.
+    pub fn to_ogg_packet(&self, payload: &[u8]) -> Result<OggPacket, FrameHeaderError> {
+        let granule_position = self.pts_to_sample_offset().ok_or_else(|| {
+            FrameHeaderError::Invalid("Ogg packets need a PTS for the granule position".to_string())
+        })?;
+
+        let mut data = Vec::with_capacity(self.size() + payload.len());
+        self.encode(&mut data)?;
+        data.extend_from_slice(payload);
+
+        Ok(OggPacket {
+            granule_position,
+            data,
+            is_beginning_of_stream: false,
+            is_end_of_stream: false,
+        })
+    }
+
+    pub fn from_ogg_packet(packet: &OggPacket) -> io::Result<(FrameHeader, &[u8])> {
+        let valid = FrameHeader::validate_header(&packet.data)
+            .map_err(|err| io::Error::new(io::ErrorKind::UnexpectedEof, err))?;
+        if !valid {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Ogg packet does not start with a frame header",
+            ));
+        }
+
+        let header = FrameHeader::decode(&mut &packet.data[..])?;
+        let payload = &packet.data[header.size()..];
+        Ok((header, payload))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{EncodingFlag, Endianness, FrameHeader, FrameHeaderError};
+    use std::io;
+
+    fn opus_header(pts: Option<u64>) -> FrameHeader {
+        FrameHeader::new(
+            EncodingFlag::Opus,
+            960,
+            48000,
+            2,
+            16,
+            Endianness::LittleEndian,
+            Some(9),
+            pts,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_ogg_packet_roundtrip() {
+        let header = opus_header(Some(1_000_000));
+        let payload = [0x11, 0x22, 0x33, 0x44, 0x55];
+        let mut packet = header.to_ogg_packet(&payload).unwrap();
+        assert_eq!(packet.granule_position, 48_000);
+        assert_eq!(packet.data.len(), header.size() + payload.len());
+        assert!(!packet.is_beginning_of_stream && !packet.is_end_of_stream);
+
+        packet.is_end_of_stream = true;
+        let (decoded, decoded_payload) = FrameHeader::from_ogg_packet(&packet).unwrap();
+        assert_eq!(decoded, header);
+        assert_eq!(decoded_payload, payload);
+    }
+
+    #[test]
+    fn test_ogg_packet_requires_pts_and_magic_word() {
+        assert!(matches!(
+            opus_header(None).to_ogg_packet(&[]),
+            Err(FrameHeaderError::Invalid(_))
+        ));
+
+        let mut packet = opus_header(Some(0)).to_ogg_packet(&[1, 2, 3]).unwrap();
+        packet.data[0] ^= 0x80;
+        assert_eq!(
+            FrameHeader::from_ogg_packet(&packet).unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+
+        packet.data.truncate(2);
+        assert!(FrameHeader::from_ogg_packet(&packet).is_err());
+    }
+}
@@ -0,0 +1,119 @@
+use crate::{EncodingFlag, FrameHeader, FrameHeaderError};
+
+/// Opus audio bandwidths (RFC 6716, section 2).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum OpusBandwidth {
+    Narrowband,
+    Mediumband,
+    Wideband,
+    SuperWideband,
+    Fullband,
+}
+
+impl OpusBandwidth {
+    /// Upper edge of the coded audio band.
+    pub fn cutoff_hz(self) -> u32 {
+        match self {
+            OpusBandwidth::Narrowband => 4000,
+            OpusBandwidth::Mediumband => 6000,
+            OpusBandwidth::Wideband => 8000,
+            OpusBandwidth::SuperWideband => 12000,
+            OpusBandwidth::Fullband => 20000,
+        }
+    }
+
+    /// Widest bandwidth Opus codes at a sample rate. 96kHz is resampled to
+    /// 48kHz, so it is fullband; rates Opus does not take, such as 44.1kHz,
+    /// have none.
+    pub fn max_for_sample_rate(sample_rate: u32) -> Option<OpusBandwidth> {
+        match sample_rate {
+            8000 => Some(OpusBandwidth::Narrowband),
+            12000 => Some(OpusBandwidth::Mediumband),
+            16000 => Some(OpusBandwidth::Wideband),
+            24000 => Some(OpusBandwidth::SuperWideband),
+            48000 | 96000 => Some(OpusBandwidth::Fullband),
+            _ => None,
+        }
+    }
+}
+
+impl FrameHeader {
+    pub fn opus_max_bandwidth(&self) -> Result<OpusBandwidth, FrameHeaderError> {
+        if self.encoding != EncodingFlag::Opus {
+            return Err(FrameHeaderError::InvalidEncoding(
+                self.encoding.code() as u32
+            ));
+        }
+        OpusBandwidth::max_for_sample_rate(self.sample_rate)
+            .ok_or(FrameHeaderError::FieldOutOfRange("sample_rate"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Endianness;
+
+    fn header(encoding: EncodingFlag, sample_rate: u32) -> FrameHeader {
+        FrameHeader::new(
+            encoding,
+            960,
+            sample_rate,
+            2,
+            16,
+            Endianness::LittleEndian,
+            None,
+            None,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_opus_bandwidth_by_sample_rate() {
+        for (sample_rate, bandwidth, cutoff_hz) in [
+            (8000, OpusBandwidth::Narrowband, 4000),
+            (12000, OpusBandwidth::Mediumband, 6000),
+            (16000, OpusBandwidth::Wideband, 8000),
+            (24000, OpusBandwidth::SuperWideband, 12000),
+            (48000, OpusBandwidth::Fullband, 20000),
+            (96000, OpusBandwidth::Fullband, 20000),
+        ] {
+            assert_eq!(
+                OpusBandwidth::max_for_sample_rate(sample_rate),
+                Some(bandwidth)
+            );
+            assert_eq!(bandwidth.cutoff_hz(), cutoff_hz);
+            // The band always fits below Nyquist.
+            assert!(cutoff_hz <= sample_rate / 2);
+        }
+        for sample_rate in [0, 22050, 32000, 44100, 192000] {
+            assert_eq!(OpusBandwidth::max_for_sample_rate(sample_rate), None);
+        }
+    }
+
+    #[test]
+    fn test_opus_max_bandwidth() {
+        // v1 headers only carry 16, 44.1, 48 and 96kHz.
+        assert_eq!(
+            header(EncodingFlag::Opus, 16000).opus_max_bandwidth(),
+            Ok(OpusBandwidth::Wideband)
+        );
+        assert_eq!(
+            header(EncodingFlag::Opus, 48000).opus_max_bandwidth(),
+            Ok(OpusBandwidth::Fullband)
+        );
+        assert_eq!(
+            header(EncodingFlag::Opus, 96000).opus_max_bandwidth(),
+            Ok(OpusBandwidth::Fullband)
+        );
+        assert_eq!(
+            header(EncodingFlag::Opus, 44100).opus_max_bandwidth(),
+            Err(FrameHeaderError::FieldOutOfRange("sample_rate"))
+        );
+        assert_eq!(
+            header(EncodingFlag::FLAC, 48000).opus_max_bandwidth(),
+            Err(FrameHeaderError::InvalidEncoding(3))
+        );
+        assert!(OpusBandwidth::Narrowband < OpusBandwidth::Fullband);
+    }
+}
@@ -0,0 +1,228 @@
+use crate::{FrameHeaderV2, HeadersOnly, IndexEntry, IndexFile};
+use rayon::prelude::*;
+use std::io::{self, Cursor};
+
+// Frames that must parse back to back before an offset inside a chunk is
+// taken as a frame boundary. A false boundary only costs stitching time.
+const SYNC_FRAMES: usize = 3;
+
+// The frames found by following one chunk's chain of frames.
+struct ChunkScan {
+    entries: Vec<IndexEntry>,
+    // Where the chain left the chunk, or the frame it could not parse.
+    end: usize,
+}
+
+// The frame at `offset` and the offset after it, as `FrameReader` reads it.
+fn frame_at(buf: &[u8], offset: usize) -> io::Result<Option<(FrameHeaderV2, usize)>> {
+    let mut headers = HeadersOnly::new(Cursor::new(&buf[offset..]));
+    let Some((_, header)) = headers.next_header()? else {
+        return Ok(None);
+    };
+    let next = offset + headers.position() as usize;
+    // Seeking does not notice a truncated payload, so check it here.
+    if next > buf.len() {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "Stream ended inside a frame payload",
+        ));
+    }
+    Ok(Some((header, next)))
+}
+
+fn is_frame_boundary(buf: &[u8], mut offset: usize) -> bool {
+    for _ in 0..SYNC_FRAMES {
+        match frame_at(buf, offset) {
+            Ok(Some((_, next))) => offset = next,
+            Ok(None) => return true,
+            Err(_) => return false,
+        }
+    }
+    true
+}
+
+fn scan_chunk(buf: &[u8], start: usize, end: usize, known_boundary: bool) -> ChunkScan {
+    let first = if known_boundary {
+        Some(start)
+    } else {
+        // Only offsets with a magic word are tried. A frame whose first
+        // copy has a damaged magic is missed here and read while stitching.
+        (start..end).find(|&offset| {
+            buf[offset] >> 2 == FrameHeaderV2::MAGIC_WORD as u8 && is_frame_boundary(buf, offset)
+        })
+    };
+    let mut entries = Vec::new();
+    let mut offset = first.unwrap_or(end);
+    while offset < end {
+        match frame_at(buf, offset) {
+            Ok(Some((header, next))) => {
+                entries.push(IndexEntry::from_header(offset as u64, &header, false));
+                offset = next;
+            }
+            Ok(None) | Err(_) => break,
+        }
+    }
+    ChunkScan {
+        entries,
+        end: offset,
+    }
+}
+
+impl IndexFile {
+    /// `build_from_stream` for an in-memory stream, indexing `chunk_size`
+    /// byte chunks in parallel. Each chunk guesses its first frame boundary,
+    /// and the guesses are checked against the true chain of frames from
+    /// offset 0, so the index is identical to the sequential one. Where a
+    /// guess is wrong, frames are read sequentially until the chains meet.
+    pub fn build_parallel(buf: &[u8], chunk_size: usize) -> io::Result<Self> {
+        let chunk_size = chunk_size.max(1);
+        let bounds: Vec<(usize, usize)> = (0..buf.len())
+            .step_by(chunk_size)
+            .map(|start| (start, (start + chunk_size).min(buf.len())))
+            .collect();
+        let scans: Vec<ChunkScan> = bounds
+            .par_iter()
+            .enumerate()
+            .map(|(index, &(start, end))| scan_chunk(buf, start, end, index == 0))
+            .collect();
+
+        // Frames are deterministic given their start, so once the true
+        // chain reaches any offset a chunk found, it follows that chunk's
+        // chain to the chunk's end.
+        let mut entries = Vec::new();
+        let mut offset = 0;
+        for (scan, &(_, end)) in scans.iter().zip(&bounds) {
+            while offset < end {
+                if let Ok(index) = scan
+                    .entries
+                    .binary_search_by_key(&(offset as u64), |entry| entry.offset)
+                {
+                    entries.extend_from_slice(&scan.entries[index..]);
+                    // Past `end` unless the chain stopped at a bad frame,
+                    // which the sequential read below reports.
+                    offset = scan.end;
+                    if offset >= end {
+                        break;
+                    }
+                }
+                match frame_at(buf, offset)? {
+                    Some((header, next)) => {
+                        entries.push(IndexEntry::from_header(offset as u64, &header, false));
+                        offset = next;
+                    }
+                    None => break,
+                }
+            }
+        }
+        Ok(Self::from_entries(entries))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EncodingFlag, Endianness, FrameWriter};
+
+    struct XorShift(u64);
+
+    impl XorShift {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, n: u64) -> u64 {
+            self.next() % n
+        }
+    }
+
+    // Frames of random sizes and shapes, with payloads of random bytes that
+    // may look like headers.
+    fn random_stream(rng: &mut XorShift, frames: u64) -> Vec<u8> {
+        let mut writer = FrameWriter::new(Vec::new());
+        for index in 0..frames {
+            let payload: Vec<u8> = (0..rng.below(300)).map(|_| rng.next() as u8).collect();
+            let mut header = FrameHeaderV2::new(
+                EncodingFlag::Opus,
+                payload.len() as u32,
+                960,
+                48000,
+                2,
+                0,
+                Endianness::LittleEndian,
+                (rng.below(2) == 0).then_some(index),
+                (rng.below(4) != 0).then_some(index * 960),
+                None,
+            )
+            .unwrap()
+            .with_redundant_copy(rng.below(3) == 0);
+            if rng.below(2) == 0 {
+                header = header.with_packet_crc32(&payload).unwrap();
+            }
+            writer.write_frame(&header, &payload).unwrap();
+        }
+        writer.into_inner()
+    }
+
+    fn sequential(buf: &[u8]) -> io::Result<Vec<u8>> {
+        IndexFile::build_from_stream(buf)?.write_to(Vec::new())
+    }
+
+    fn parallel(buf: &[u8], chunk_size: usize) -> io::Result<Vec<u8>> {
+        IndexFile::build_parallel(buf, chunk_size)?.write_to(Vec::new())
+    }
+
+    #[test]
+    fn test_parallel_index_matches_sequential() {
+        let mut rng = XorShift(0x2545_F491_4F6C_DD1D);
+        for _ in 0..16 {
+            let frames = 1 + rng.below(100);
+            let stream = random_stream(&mut rng, frames);
+            let expected = sequential(&stream).unwrap();
+            for chunk_size in [1, 7, 64, 1 + rng.below(2000) as usize, stream.len()] {
+                assert_eq!(
+                    parallel(&stream, chunk_size).unwrap(),
+                    expected,
+                    "chunk size {}",
+                    chunk_size
+                );
+            }
+        }
+        assert_eq!(IndexFile::build_parallel(&[], 64).unwrap().len(), 0);
+    }
+
+    #[test]
+    fn test_parallel_index_matches_sequential_on_damage() {
+        let mut rng = XorShift(0x9E37_79B9_7F4A_7C15);
+        for _ in 0..100 {
+            let frames = 1 + rng.below(60);
+            let mut stream = random_stream(&mut rng, frames);
+            match rng.below(3) {
+                0 => {
+                    let len = rng.below(stream.len() as u64) as usize;
+                    stream.truncate(len);
+                }
+                _ => {
+                    for _ in 0..1 + rng.below(3) {
+                        let at = rng.below(stream.len() as u64) as usize;
+                        stream[at] ^= 1 << rng.below(8);
+                    }
+                }
+            }
+            let expected = sequential(&stream);
+            let chunk_size = 1 + rng.below(500) as usize;
+            match (parallel(&stream, chunk_size), expected) {
+                (Ok(actual), Ok(expected)) => assert_eq!(actual, expected),
+                (Err(_), Err(_)) => {}
+                (actual, expected) => panic!(
+                    "chunk size {}: parallel {:?}, sequential {:?}",
+                    chunk_size,
+                    actual.map(|bytes| bytes.len()),
+                    expected.map(|bytes| bytes.len())
+                ),
+            }
+        }
+    }
+}
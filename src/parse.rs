@@ -0,0 +1,264 @@
+use crate::{FrameHeader, FrameHeaderError};
+#[cfg(feature = "winnow")]
+use winnow::{
+    combinator::peek,
+    error::{FromExternalError, ParserError},
+    stream::{Stream, StreamIsPartial},
+    token::take,
+    Parser,
+};
+
+impl FrameHeader {
+    /// Decodes the header at the front of `input` and returns the bytes that
+    /// follow it, the convention parser-combinator libraries use.
+    pub fn parse(input: &[u8]) -> Result<(&[u8], FrameHeader), FrameHeaderError> {
+        let size = Self::encoded_size(input)?;
+        if input.len() < size {
+            return Err(FrameHeaderError::Truncated);
+        }
+        let header = FrameHeader::decode(&mut &input[..size])?;
+        Ok((&input[size..], header))
+    }
+
+    /// Decodes the header at `buf[offset..]` and returns it with the offset
+    /// just past it.
+    pub fn decode_at(buf: &[u8], offset: usize) -> Result<(FrameHeader, usize), FrameHeaderError> {
+        let input = buf.get(offset..).ok_or(FrameHeaderError::Truncated)?;
+        let (rest, header) = Self::parse(input)?;
+        Ok((header, buf.len() - rest.len()))
+    }
+
+    /// Decodes back-to-back headers from the start of `buf`. A corrupt
+    /// header is reported and skipped by the size its first word announces;
+    /// a truncated one ends the scan.
+    pub fn decode_all_at(buf: &[u8]) -> Vec<Result<FrameHeader, FrameHeaderError>> {
+        let mut results = Vec::new();
+        let mut offset = 0;
+        while offset < buf.len() {
+            match Self::decode_at(buf, offset) {
+                Ok((header, next)) => {
+                    results.push(Ok(header));
+                    offset = next;
+                }
+                Err(e) => {
+                    results.push(Err(e));
+                    match Self::encoded_size(&buf[offset..]) {
+                        Ok(size) if offset + size <= buf.len() => offset += size,
+                        _ => break,
+                    }
+                }
+            }
+        }
+        results
+    }
+
+    // Size announced by the ID and PTS bits of the first word.
+    pub(crate) fn encoded_size(input: &[u8]) -> Result<usize, FrameHeaderError> {
+        let word = input.get(..4).ok_or(FrameHeaderError::Truncated)?;
+        let header = u32::from_be_bytes(word.try_into().unwrap());
+        let has_id = header & Self::ID_MASK != 0;
+        let has_pts = header & Self::PTS_MASK != 0;
+        Ok(4 + (has_id as usize) * 8 + (has_pts as usize) * 8)
+    }
+}
+
+/// A winnow parser for a v1 header. On a `Partial` stream, short input is
+/// reported as `Incomplete` with the number of bytes still needed.
+#[cfg(feature = "winnow")]
+pub fn frame_header_parser<I, E>() -> impl Parser<I, FrameHeader, E>
+where
+    I: Stream<Token = u8> + StreamIsPartial,
+    <I as Stream>::Slice: AsRef<[u8]>,
+    E: ParserError<I> + FromExternalError<I, FrameHeaderError>,
+{
+    move |input: &mut I| {
+        let word = peek(take(4usize)).parse_next(input)?;
+        let word = word.as_ref();
+        // Reject a bad first word before asking a streaming caller for more bytes.
+        FrameHeader::validate_header_with_reason(word)
+            .map_err(|e| E::from_external_error(input, e))?;
+        let size = FrameHeader::encoded_size(word).map_err(|e| E::from_external_error(input, e))?;
+
+        take(size)
+            .try_map(|bytes: <I as Stream>::Slice| {
+                FrameHeader::decode(&mut bytes.as_ref()).map_err(FrameHeaderError::from)
+            })
+            .parse_next(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EncodingFlag, Endianness};
+
+    fn encoded(id: Option<u64>, pts: Option<u64>) -> (FrameHeader, Vec<u8>) {
+        let header = FrameHeader::new(
+            EncodingFlag::Opus,
+            960,
+            48000,
+            2,
+            16,
+            Endianness::LittleEndian,
+            id,
+            pts,
+        )
+        .unwrap();
+        let mut bytes = Vec::new();
+        header.encode(&mut bytes).unwrap();
+        (header, bytes)
+    }
+
+    #[test]
+    fn test_parse_returns_remaining_input() {
+        let (header, mut bytes) = encoded(Some(7), Some(20_000));
+        bytes.extend_from_slice(b"payload");
+
+        let (rest, parsed) = FrameHeader::parse(&bytes).unwrap();
+        assert_eq!(parsed, header);
+        assert_eq!(rest, b"payload");
+
+        for len in 0..header.size() {
+            assert_eq!(
+                FrameHeader::parse(&bytes[..len]),
+                Err(FrameHeaderError::Truncated)
+            );
+        }
+        assert!(FrameHeader::parse(&[0u8; 20]).is_err());
+    }
+
+    #[test]
+    fn test_decode_at_consecutive_headers() {
+        let frames: Vec<_> = [
+            (None, None),
+            (Some(1), None),
+            (None, Some(20_000)),
+            (Some(3), Some(40_000)),
+            (None, None),
+        ]
+        .into_iter()
+        .map(|(id, pts)| encoded(id, pts))
+        .collect();
+        let buf: Vec<u8> = frames.iter().flat_map(|(_, bytes)| bytes.clone()).collect();
+
+        let mut offset = 0;
+        for (header, bytes) in &frames {
+            let (decoded, next) = FrameHeader::decode_at(&buf, offset).unwrap();
+            assert_eq!(&decoded, header);
+            assert_eq!(next, offset + bytes.len());
+            offset = next;
+        }
+        assert_eq!(offset, buf.len());
+        assert_eq!(
+            FrameHeader::decode_at(&buf, offset),
+            Err(FrameHeaderError::Truncated)
+        );
+        assert_eq!(
+            FrameHeader::decode_at(&buf, buf.len() + 1),
+            Err(FrameHeaderError::Truncated)
+        );
+
+        let decoded = FrameHeader::decode_all_at(&buf);
+        assert_eq!(decoded.len(), 5);
+        for ((header, _), result) in frames.iter().zip(decoded) {
+            assert_eq!(result.as_ref(), Ok(header));
+        }
+        assert!(FrameHeader::decode_all_at(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_decode_all_at_skips_corrupt_headers() {
+        let (first, mut buf) = encoded(Some(1), None);
+        let (_, corrupt) = encoded(None, Some(20_000));
+        let corrupt_at = buf.len();
+        buf.extend_from_slice(&corrupt);
+        // Break the magic but keep the PTS flag, so the size is still known.
+        buf[corrupt_at] ^= 0x80;
+        let (last, bytes) = encoded(None, None);
+        buf.extend_from_slice(&bytes);
+
+        let decoded = FrameHeader::decode_all_at(&buf);
+        assert_eq!(decoded.len(), 3);
+        assert_eq!(decoded[0], Ok(first));
+        assert!(decoded[1].is_err());
+        assert_eq!(decoded[2], Ok(last.clone()));
+
+        // A truncated header at the end is reported once and ends the scan.
+        buf.extend_from_slice(&encoded(Some(9), Some(9)).1[..10]);
+        let decoded = FrameHeader::decode_all_at(&buf);
+        assert_eq!(decoded.len(), 4);
+        assert_eq!(decoded[2], Ok(last));
+        assert_eq!(decoded[3], Err(FrameHeaderError::Truncated));
+    }
+
+    #[cfg(feature = "winnow")]
+    mod winnow_tests {
+        use super::*;
+        use winnow::{
+            binary::{be_u16, length_take, u8},
+            error::{ContextError, ErrMode, Needed},
+            Partial,
+        };
+
+        struct Envelope<'a> {
+            channel: u8,
+            header: FrameHeader,
+            payload: &'a [u8],
+        }
+
+        // tag, frame header, u16 length-prefixed payload
+        fn envelope<'a>(input: &mut &'a [u8]) -> winnow::Result<Envelope<'a>> {
+            (u8, frame_header_parser(), length_take(be_u16))
+                .map(|(channel, header, payload)| Envelope {
+                    channel,
+                    header,
+                    payload,
+                })
+                .parse_next(input)
+        }
+
+        #[test]
+        fn test_composes_inside_envelope() {
+            let (header, bytes) = encoded(None, Some(40_000));
+            let mut input = vec![3];
+            input.extend_from_slice(&bytes);
+            input.extend_from_slice(&[0, 2, 0xAB, 0xCD, 0xFF]);
+
+            let mut stream = &input[..];
+            let parsed = envelope(&mut stream).unwrap();
+            assert_eq!(parsed.channel, 3);
+            assert_eq!(parsed.header, header);
+            assert_eq!(parsed.payload, &[0xAB, 0xCD]);
+            assert_eq!(stream, &[0xFF]);
+
+            let mut garbage = &[3u8, 0, 0, 0, 0, 0, 0][..];
+            assert!(envelope(&mut garbage).is_err());
+            assert_eq!(garbage.len(), 6);
+        }
+
+        #[test]
+        fn test_incomplete_input_reports_needed() {
+            let (header, bytes) = encoded(Some(1), Some(2));
+            let mut parser = frame_header_parser::<_, ErrMode<ContextError>>();
+
+            assert_eq!(
+                parser.parse_next(&mut Partial::new(&bytes[..2])),
+                Err(ErrMode::Incomplete(Needed::new(2)))
+            );
+            assert_eq!(
+                parser.parse_next(&mut Partial::new(&bytes[..10])),
+                Err(ErrMode::Incomplete(Needed::new(10)))
+            );
+
+            let mut stream = Partial::new(&bytes[..]);
+            assert_eq!(parser.parse_next(&mut stream), Ok(header));
+            assert!(stream.is_empty());
+
+            // Complete input fails outright rather than asking for more.
+            assert!(matches!(
+                frame_header_parser::<_, ErrMode<ContextError>>().parse_next(&mut &bytes[..10]),
+                Err(ErrMode::Backtrack(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use crate::{EncodingFlag, Endianness, FrameHeader, FrameHeaderError};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameHeaderParts {
+    pub encoding: EncodingFlag,
+    pub sample_size: u16,
+    pub sample_rate: u32,
+    pub channels: u8,
+    pub bits_per_sample: u8,
+    pub endianness: Endianness,
+    pub id: Option<u64>,
+    pub pts: Option<u64>,
+}
+
+impl FrameHeader {
+    /// All fields, in the same order as `new` takes them.
+    pub fn into_parts(
+        self,
+    ) -> (
+        EncodingFlag,
+        u16,
+        u32,
+        u8,
+        u8,
+        Endianness,
+        Option<u64>,
+        Option<u64>,
+    ) {
+        (
+            self.encoding,
+            self.sample_size,
+            self.sample_rate,
+            self.channels,
+            self.bits_per_sample,
+            self.endianness,
+            self.id,
+            self.pts,
+        )
+    }
+
+    pub fn from_parts(
+        parts: (
+            EncodingFlag,
+            u16,
+            u32,
+            u8,
+            u8,
+            Endianness,
+            Option<u64>,
+            Option<u64>,
+        ),
+    ) -> Result<FrameHeader, FrameHeaderError> {
+        let (encoding, sample_size, sample_rate, channels, bits_per_sample, endianness, id, pts) =
+            parts;
+        Ok(FrameHeader::new(
+            encoding,
+            sample_size,
+            sample_rate,
+            channels,
+            bits_per_sample,
+            endianness,
+            id,
+            pts,
+        )?)
+    }
+
+    pub fn into_header_parts(self) -> FrameHeaderParts {
+        FrameHeaderParts {
+            encoding: self.encoding,
+            sample_size: self.sample_size,
+            sample_rate: self.sample_rate,
+            channels: self.channels,
+            bits_per_sample: self.bits_per_sample,
+            endianness: self.endianness,
+            id: self.id,
+            pts: self.pts,
+        }
+    }
+
+    pub fn from_header_parts(parts: FrameHeaderParts) -> Result<FrameHeader, FrameHeaderError> {
+        Ok(FrameHeader::new(
+            parts.encoding,
+            parts.sample_size,
+            parts.sample_rate,
+            parts.channels,
+            parts.bits_per_sample,
+            parts.endianness,
+            parts.id,
+            parts.pts,
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parts_roundtrip() {
+        let header = FrameHeader::new(
+            EncodingFlag::FLAC,
+            4000,
+            96000,
+            6,
+            24,
+            Endianness::BigEndian,
+            Some(9),
+            Some(123_456),
+        )
+        .unwrap();
+
+        let parts = header.clone().into_parts();
+        assert_eq!(parts.1, 4000);
+        assert_eq!(parts.7, Some(123_456));
+        assert_eq!(FrameHeader::from_parts(parts).unwrap(), header);
+
+        let mut parts = header.clone().into_header_parts();
+        assert_eq!(parts.channels, 6);
+        assert_eq!(
+            FrameHeader::from_header_parts(parts.clone()).unwrap(),
+            header
+        );
+
+        parts.sample_rate = 22050;
+        assert!(FrameHeader::from_header_parts(parts).is_err());
+    }
+}
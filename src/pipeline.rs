@@ -0,0 +1,155 @@
+use crate::{FrameHeader, FrameHeaderError};
+
+impl FrameHeader {
+    /// Whether a stage producing `self` can feed one producing `next`.
+    /// Encoding, sample format and frame size may change between stages;
+    /// sample rate and channel count may not, since a codec stage does not
+    /// resample or remix.
+    pub fn compatible_with(&self, next: &FrameHeader) -> Result<(), FrameHeaderError> {
+        let mut mismatches = Vec::new();
+        if self.sample_rate != next.sample_rate {
+            mismatches.push(format!(
+                "sample rate {} -> {}",
+                self.sample_rate, next.sample_rate
+            ));
+        }
+        if self.channels != next.channels {
+            mismatches.push(format!("channels {} -> {}", self.channels, next.channels));
+        }
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(FrameHeaderError::Invalid(mismatches.join(", ")))
+        }
+    }
+}
+
+/// The format at each stage of a source → codecs → sink pipeline.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PipelineConfig {
+    pub source: FrameHeader,
+    pub codec_chain: Vec<FrameHeader>,
+    pub sink: FrameHeader,
+}
+
+impl PipelineConfig {
+    pub fn builder(source: FrameHeader) -> PipelineConfigBuilder {
+        PipelineConfigBuilder {
+            source,
+            codec_chain: Vec::new(),
+        }
+    }
+
+    /// Stages in order, from the source to the sink.
+    pub fn stages(&self) -> impl Iterator<Item = &FrameHeader> {
+        std::iter::once(&self.source)
+            .chain(&self.codec_chain)
+            .chain(std::iter::once(&self.sink))
+    }
+
+    /// Checks every adjacent pair of stages with `compatible_with`, returning
+    /// one error per incompatible pair. Stage 0 is the source.
+    pub fn validate(&self) -> Result<(), Vec<FrameHeaderError>> {
+        let stages: Vec<&FrameHeader> = self.stages().collect();
+        let errors: Vec<FrameHeaderError> = stages
+            .windows(2)
+            .enumerate()
+            .filter_map(|(index, pair)| {
+                pair[0].compatible_with(pair[1]).err().map(|err| {
+                    FrameHeaderError::Invalid(format!("Stage {} -> {}: {}", index, index + 1, err))
+                })
+            })
+            .collect();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PipelineConfigBuilder {
+    source: FrameHeader,
+    codec_chain: Vec<FrameHeader>,
+}
+
+impl PipelineConfigBuilder {
+    /// Adds a codec stage producing `codec_output`.
+    pub fn then(mut self, codec_output: FrameHeader) -> Self {
+        self.codec_chain.push(codec_output);
+        self
+    }
+
+    pub fn build(self, sink: FrameHeader) -> PipelineConfig {
+        PipelineConfig {
+            source: self.source,
+            codec_chain: self.codec_chain,
+            sink,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EncodingFlag, Endianness};
+
+    fn header(
+        encoding: EncodingFlag,
+        sample_size: u16,
+        sample_rate: u32,
+        channels: u8,
+    ) -> FrameHeader {
+        FrameHeader::new(
+            encoding,
+            sample_size,
+            sample_rate,
+            channels,
+            16,
+            Endianness::LittleEndian,
+            None,
+            None,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_valid_pipeline() {
+        // PCM capture → Opus encode → Opus decode → PCM playback.
+        let pipeline = PipelineConfig::builder(header(EncodingFlag::PCMSigned, 480, 48000, 2))
+            .then(header(EncodingFlag::Opus, 960, 48000, 2))
+            .then(header(EncodingFlag::PCMSigned, 960, 48000, 2))
+            .build(header(EncodingFlag::PCMSigned, 960, 48000, 2));
+        assert_eq!(pipeline.codec_chain.len(), 2);
+        assert_eq!(pipeline.stages().count(), 4);
+        assert_eq!(pipeline.validate(), Ok(()));
+
+        let direct = PipelineConfig::builder(header(EncodingFlag::PCMSigned, 480, 16000, 1))
+            .build(header(EncodingFlag::PCMSigned, 480, 16000, 1));
+        assert_eq!(direct.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_invalid_rate_conversion_is_detected() {
+        let pipeline = PipelineConfig::builder(header(EncodingFlag::PCMSigned, 441, 44100, 2))
+            .then(header(EncodingFlag::Opus, 960, 48000, 2))
+            .then(header(EncodingFlag::PCMSigned, 960, 48000, 1))
+            .build(header(EncodingFlag::PCMSigned, 960, 48000, 1));
+        assert_eq!(
+            pipeline.validate(),
+            Err(vec![
+                FrameHeaderError::Invalid("Stage 0 -> 1: sample rate 44100 -> 48000".to_string()),
+                FrameHeaderError::Invalid("Stage 1 -> 2: channels 2 -> 1".to_string()),
+            ])
+        );
+
+        let source = header(EncodingFlag::PCMSigned, 480, 96000, 2);
+        assert_eq!(
+            source.compatible_with(&header(EncodingFlag::FLAC, 480, 16000, 1)),
+            Err(FrameHeaderError::Invalid(
+                "sample rate 96000 -> 16000, channels 2 -> 1".to_string()
+            ))
+        );
+    }
+}
@@ -0,0 +1,63 @@
+use crate::FrameHeader;
+
+impl FrameHeader {
+    /// Header for a concealment frame replacing the one after `self`: same
+    /// codec fields, the next PTS and no ID.
+    pub fn plc_substitute(&self) -> FrameHeader {
+        FrameHeader {
+            id: None,
+            pts: self.next_pts(),
+            ..self.clone()
+        }
+    }
+
+    pub fn generate_plc_sequence(&self, count: u32) -> Vec<FrameHeader> {
+        let mut sequence = Vec::with_capacity(count as usize);
+        let mut previous = self.clone();
+        for _ in 0..count {
+            previous = previous.plc_substitute();
+            sequence.push(previous.clone());
+        }
+        sequence
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{EncodingFlag, Endianness, FrameHeader};
+
+    #[test]
+    fn test_plc_sequence_continues_pts() {
+        let header = FrameHeader::new(
+            EncodingFlag::Opus,
+            960,
+            48000,
+            2,
+            16,
+            Endianness::LittleEndian,
+            Some(77),
+            Some(100_000),
+        )
+        .unwrap();
+
+        let substitute = header.plc_substitute();
+        assert_eq!(substitute.pts(), Some(120_000));
+        assert_eq!(substitute.id(), None);
+        assert_eq!(substitute.encoding(), header.encoding());
+        assert_eq!(
+            substitute.samples_per_channel(),
+            header.samples_per_channel()
+        );
+        assert_eq!(substitute.sample_rate(), header.sample_rate());
+        assert_eq!(substitute.channels(), header.channels());
+
+        let sequence = header.generate_plc_sequence(5);
+        assert_eq!(sequence.len(), 5);
+        assert_eq!(sequence[0], substitute);
+        for (index, frame) in sequence.iter().enumerate() {
+            assert_eq!(frame.pts(), Some(100_000 + (index as u64 + 1) * 20_000));
+            assert_eq!(frame.id(), None);
+        }
+        assert!(header.generate_plc_sequence(0).is_empty());
+    }
+}
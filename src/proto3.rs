@@ -0,0 +1,311 @@
+use crate::{encoding_from_code, Endianness, FrameHeader, FrameHeaderError};
+
+const WIRE_VARINT: u8 = 0;
+const WIRE_FIXED64: u8 = 1;
+const WIRE_LENGTH_DELIMITED: u8 = 2;
+const WIRE_FIXED32: u8 = 5;
+
+const FIELD_ENCODING: u64 = 1;
+const FIELD_SAMPLE_SIZE: u64 = 2;
+const FIELD_SAMPLE_RATE: u64 = 3;
+const FIELD_CHANNELS: u64 = 4;
+const FIELD_BITS_PER_SAMPLE: u64 = 5;
+const FIELD_ENDIANNESS: u64 = 6;
+const FIELD_ID: u64 = 7;
+const FIELD_PTS: u64 = 8;
+
+pub(crate) fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+pub(crate) fn read_varint(bytes: &[u8], pos: &mut usize) -> Result<u64, FrameHeaderError> {
+    let mut value = 0u64;
+    let mut shift = 0;
+
+    loop {
+        let byte = *bytes.get(*pos).ok_or(FrameHeaderError::Truncated)?;
+        *pos += 1;
+
+        // The tenth byte may only contribute the final bit of a u64.
+        if shift == 63 && byte > 1 {
+            return Err(FrameHeaderError::VarintOverflow);
+        }
+        value |= ((byte & 0x7F) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+        shift += 7;
+    }
+}
+
+fn write_key(out: &mut Vec<u8>, field: u64, wire_type: u8) {
+    write_varint(out, (field << 3) | wire_type as u64);
+}
+
+fn write_uint_field(out: &mut Vec<u8>, field: u64, value: u64) {
+    // proto3 omits scalar fields holding their default value.
+    if value != 0 {
+        write_key(out, field, WIRE_VARINT);
+        write_varint(out, value);
+    }
+}
+
+fn write_optional_uint_field(out: &mut Vec<u8>, field: u64, value: Option<u64>) {
+    if let Some(value) = value {
+        write_key(out, field, WIRE_VARINT);
+        write_varint(out, value);
+    }
+}
+
+fn skip_field(bytes: &[u8], pos: &mut usize, wire_type: u8) -> Result<(), FrameHeaderError> {
+    let len = match wire_type {
+        WIRE_VARINT => {
+            read_varint(bytes, pos)?;
+            return Ok(());
+        }
+        WIRE_FIXED64 => 8,
+        WIRE_LENGTH_DELIMITED => {
+            usize::try_from(read_varint(bytes, pos)?).map_err(|_| FrameHeaderError::Truncated)?
+        }
+        WIRE_FIXED32 => 4,
+        _ => return Err(FrameHeaderError::UnsupportedWireType(wire_type)),
+    };
+
+    if bytes.len() - *pos < len {
+        return Err(FrameHeaderError::Truncated);
+    }
+    *pos += len;
+    Ok(())
+}
+
+impl FrameHeader {
+    pub fn encode_proto3(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(40);
+        write_uint_field(&mut out, FIELD_ENCODING, self.encoding.code() as u64);
+        write_uint_field(&mut out, FIELD_SAMPLE_SIZE, self.sample_size as u64);
+        write_uint_field(&mut out, FIELD_SAMPLE_RATE, self.sample_rate as u64);
+        write_uint_field(&mut out, FIELD_CHANNELS, self.channels as u64);
+        write_uint_field(&mut out, FIELD_BITS_PER_SAMPLE, self.bits_per_sample as u64);
+        write_uint_field(&mut out, FIELD_ENDIANNESS, self.endianness as u64);
+        write_optional_uint_field(&mut out, FIELD_ID, self.id);
+        write_optional_uint_field(&mut out, FIELD_PTS, self.pts);
+        out
+    }
+
+    pub fn decode_proto3(bytes: &[u8]) -> Result<FrameHeader, FrameHeaderError> {
+        let mut encoding = 0;
+        let mut sample_size = 0;
+        let mut sample_rate = 0;
+        let mut channels = 0;
+        let mut bits_per_sample = 0;
+        let mut endianness = 0;
+        let mut id = None;
+        let mut pts = None;
+
+        let mut pos = 0;
+        while pos < bytes.len() {
+            let key = read_varint(bytes, &mut pos)?;
+            let field = key >> 3;
+            let wire_type = (key & 0x7) as u8;
+
+            if (FIELD_ENCODING..=FIELD_PTS).contains(&field) && wire_type != WIRE_VARINT {
+                return Err(FrameHeaderError::UnsupportedWireType(wire_type));
+            }
+
+            match field {
+                FIELD_ENCODING => encoding = read_varint(bytes, &mut pos)?,
+                FIELD_SAMPLE_SIZE => sample_size = read_varint(bytes, &mut pos)?,
+                FIELD_SAMPLE_RATE => sample_rate = read_varint(bytes, &mut pos)?,
+                FIELD_CHANNELS => channels = read_varint(bytes, &mut pos)?,
+                FIELD_BITS_PER_SAMPLE => bits_per_sample = read_varint(bytes, &mut pos)?,
+                FIELD_ENDIANNESS => endianness = read_varint(bytes, &mut pos)?,
+                FIELD_ID => id = Some(read_varint(bytes, &mut pos)?),
+                FIELD_PTS => pts = Some(read_varint(bytes, &mut pos)?),
+                _ => skip_field(bytes, &mut pos, wire_type)?,
+            }
+        }
+
+        let encoding_code =
+            u32::try_from(encoding).map_err(|_| FrameHeaderError::FieldOutOfRange("encoding"))?;
+        let encoding = encoding_from_code(encoding_code)
+            .ok_or(FrameHeaderError::InvalidEncoding(encoding_code))?;
+        let endianness = match endianness {
+            0 => Endianness::LittleEndian,
+            1 => Endianness::BigEndian,
+            _ => return Err(FrameHeaderError::FieldOutOfRange("endianness")),
+        };
+
+        Ok(FrameHeader::new(
+            encoding,
+            u16::try_from(sample_size)
+                .map_err(|_| FrameHeaderError::FieldOutOfRange("sample_size"))?,
+            u32::try_from(sample_rate)
+                .map_err(|_| FrameHeaderError::FieldOutOfRange("sample_rate"))?,
+            u8::try_from(channels).map_err(|_| FrameHeaderError::FieldOutOfRange("channels"))?,
+            u8::try_from(bits_per_sample)
+                .map_err(|_| FrameHeaderError::FieldOutOfRange("bits_per_sample"))?,
+            endianness,
+            id,
+            pts,
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EncodingFlag;
+
+    const REFERENCE_PROTO: &str = include_str!("../proto/frame_header.proto");
+
+    #[test]
+    fn test_proto3_matches_reference_schema() {
+        for declaration in [
+            "Encoding encoding = 1;",
+            "uint32 sample_size = 2;",
+            "uint32 sample_rate = 3;",
+            "uint32 channels = 4;",
+            "uint32 bits_per_sample = 5;",
+            "Endianness endianness = 6;",
+            "optional uint64 id = 7;",
+            "optional uint64 pts = 8;",
+        ] {
+            assert!(
+                REFERENCE_PROTO.contains(declaration),
+                "Reference schema missing `{}`",
+                declaration
+            );
+        }
+        for code in 0..16 {
+            let Some(encoding) = encoding_from_code(code) else {
+                continue;
+            };
+            let name = match encoding {
+                EncodingFlag::PCMSigned => "PCM_SIGNED",
+                EncodingFlag::PCMFloat => "PCM_FLOAT",
+                EncodingFlag::Opus => "OPUS",
+                EncodingFlag::FLAC => "FLAC",
+                EncodingFlag::AAC => "AAC",
+                EncodingFlag::H264 => "H264",
+                EncodingFlag::Metadata => "METADATA",
+                EncodingFlag::Unknown(_) => unreachable!(),
+            };
+            let declaration = format!("{} = {};", name, code);
+            assert!(
+                REFERENCE_PROTO.contains(&declaration),
+                "Reference schema missing `{}`",
+                declaration
+            );
+        }
+
+        // Bytes as produced by protoc-generated encoders for the reference schema.
+        let header = FrameHeader::new(
+            EncodingFlag::Opus,
+            960,
+            48000,
+            2,
+            16,
+            Endianness::LittleEndian,
+            Some(0),
+            Some(300),
+        )
+        .unwrap();
+        assert_eq!(
+            header.encode_proto3(),
+            [
+                0x08, 0x02, 0x10, 0xC0, 0x07, 0x18, 0x80, 0xF7, 0x02, 0x20, 0x02, 0x28, 0x10, 0x38,
+                0x00, 0x40, 0xAC, 0x02,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_proto3_roundtrip() {
+        for (id, pts) in [
+            (None, None),
+            (Some(u64::MAX), None),
+            (None, Some(0)),
+            (Some(0xDEADBEEF), Some(0xCAFEBABE)),
+        ] {
+            let header = FrameHeader::new(
+                EncodingFlag::PCMSigned,
+                1024,
+                96000,
+                16,
+                24,
+                Endianness::BigEndian,
+                id,
+                pts,
+            )
+            .unwrap();
+            let decoded = FrameHeader::decode_proto3(&header.encode_proto3()).unwrap();
+            assert_eq!(decoded, header);
+        }
+    }
+
+    #[test]
+    fn test_proto3_skips_unknown_fields() {
+        let header = FrameHeader::new(
+            EncodingFlag::FLAC,
+            4095,
+            44100,
+            1,
+            32,
+            Endianness::LittleEndian,
+            None,
+            Some(7),
+        )
+        .unwrap();
+        let mut bytes = header.encode_proto3();
+        // field 9 (varint), field 10 (length-delimited), field 11 (fixed32)
+        bytes.extend_from_slice(&[0x48, 0x96, 0x01]);
+        bytes.extend_from_slice(&[0x52, 0x03, b'a', b'b', b'c']);
+        bytes.extend_from_slice(&[0x5D, 0x01, 0x02, 0x03, 0x04]);
+
+        assert_eq!(FrameHeader::decode_proto3(&bytes).unwrap(), header);
+    }
+
+    #[test]
+    fn test_proto3_rejects_malformed_input() {
+        let header = FrameHeader::new(
+            EncodingFlag::Opus,
+            960,
+            48000,
+            2,
+            16,
+            Endianness::LittleEndian,
+            None,
+            Some(960),
+        )
+        .unwrap();
+        let bytes = header.encode_proto3();
+
+        assert_eq!(
+            FrameHeader::decode_proto3(&bytes[..bytes.len() - 1]),
+            Err(FrameHeaderError::Truncated)
+        );
+        assert_eq!(
+            FrameHeader::decode_proto3(&[0x08, 0x09, 0x18, 0x80, 0xF7, 0x02]),
+            Err(FrameHeaderError::InvalidEncoding(9))
+        );
+        assert_eq!(
+            FrameHeader::decode_proto3(&[0x0A, 0x01, 0x02]),
+            Err(FrameHeaderError::UnsupportedWireType(WIRE_LENGTH_DELIMITED))
+        );
+        let mut overflowing_pts = vec![0x40];
+        overflowing_pts.extend_from_slice(&[0xFF; 9]);
+        overflowing_pts.push(0x02);
+        assert_eq!(
+            FrameHeader::decode_proto3(&overflowing_pts),
+            Err(FrameHeaderError::VarintOverflow)
+        );
+        assert!(matches!(
+            FrameHeader::decode_proto3(&[]),
+            Err(FrameHeaderError::Invalid(_))
+        ));
+    }
+}
@@ -0,0 +1,107 @@
+use crate::FrameHeader;
+use std::cmp::Ordering;
+
+// MPEG-TS timestamps: 33 bits at 90 kHz, wrapping every ~26.5 hours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Pts33(u64);
+
+impl Pts33 {
+    pub const MODULUS: u64 = 1 << 33;
+    const MASK: u64 = Self::MODULUS - 1;
+
+    /// Truncates a full 90 kHz timestamp to 33 bits.
+    pub fn from_full(full: u64) -> Self {
+        Pts33(full & Self::MASK)
+    }
+
+    pub fn value(self) -> u64 {
+        self.0
+    }
+
+    /// Unwraps to the full timestamp closest to `reference`, never going
+    /// below zero.
+    pub fn to_full(self, reference: u64) -> u64 {
+        reference
+            .checked_add_signed(self.wrapping_sub(Pts33::from_full(reference)))
+            .unwrap_or(self.0)
+    }
+
+    /// Signed distance from `other` to `self`, taking the shorter way round
+    /// the wrap.
+    pub fn wrapping_sub(self, other: Pts33) -> i64 {
+        let delta = self.0.wrapping_sub(other.0) & Self::MASK;
+        if delta >= Self::MODULUS / 2 {
+            delta as i64 - Self::MODULUS as i64
+        } else {
+            delta as i64
+        }
+    }
+
+    pub fn wrapping_cmp(self, other: Pts33) -> Ordering {
+        self.wrapping_sub(other).cmp(&0)
+    }
+}
+
+impl FrameHeader {
+    /// PTS converted from microseconds to a wrapped 90 kHz MPEG-TS timestamp.
+    pub fn pts33(&self) -> Option<Pts33> {
+        self.pts
+            .map(|pts| Pts33::from_full((pts as u128 * 9 / 100) as u64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EncodingFlag, Endianness};
+
+    #[test]
+    fn test_pts33_straddles_wrap() {
+        let before = Pts33::from_full(Pts33::MODULUS - 900);
+        let after = Pts33::from_full(Pts33::MODULUS + 900);
+        assert_eq!(after.value(), 900);
+
+        assert_eq!(after.wrapping_sub(before), 1_800);
+        assert_eq!(before.wrapping_sub(after), -1_800);
+        assert_eq!(before.wrapping_cmp(after), Ordering::Less);
+        assert_eq!(after.wrapping_cmp(before), Ordering::Greater);
+        assert_eq!(after.wrapping_cmp(after), Ordering::Equal);
+        assert!(after.value() < before.value());
+
+        // Unwrapping forwards across the wrap and backwards across it.
+        assert_eq!(after.to_full(Pts33::MODULUS - 100), Pts33::MODULUS + 900);
+        assert_eq!(before.to_full(Pts33::MODULUS + 100), Pts33::MODULUS - 900);
+        let third_period = 2 * Pts33::MODULUS;
+        assert_eq!(before.to_full(third_period + 5), third_period - 900);
+        assert_eq!(after.to_full(third_period - 5), third_period + 900);
+
+        // Nothing unwraps below zero.
+        assert_eq!(before.to_full(100), Pts33::MODULUS - 900);
+        assert_eq!(Pts33::from_full(50).to_full(10), 50);
+    }
+
+    #[test]
+    fn test_frame_header_pts33() {
+        let header = FrameHeader::new(
+            EncodingFlag::AAC,
+            1024,
+            48000,
+            2,
+            16,
+            Endianness::LittleEndian,
+            None,
+            Some(1_000_000),
+        )
+        .unwrap();
+        assert_eq!(header.pts33(), Some(Pts33::from_full(90_000)));
+
+        // 27 hours in microseconds lands just past one 33-bit wrap.
+        let header = FrameHeader {
+            pts: Some(27 * 3_600 * 1_000_000),
+            ..header
+        };
+        let ticks = 27 * 3_600 * 90_000;
+        assert_eq!(header.pts33().unwrap().value(), ticks - Pts33::MODULUS);
+        assert_eq!(header.pts33().unwrap().to_full(ticks - 1_000), ticks);
+    }
+}
@@ -0,0 +1,142 @@
+use crate::FrameHeader;
+
+/// Spacing of consecutive v1 PTS values, in microseconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PtsStatistics {
+    pub mean_interval_us: f64,
+    /// Population standard deviation of the intervals.
+    pub std_dev_interval_us: f64,
+    pub max_interval_us: u64,
+    pub min_interval_us: u64,
+    /// RFC 3550 interarrival jitter, with each interval compared against
+    /// the duration of the frame before it instead of arrival times.
+    pub jitter_us: f64,
+}
+
+impl PtsStatistics {
+    pub fn is_within_tolerance(&self, expected_interval_us: u64, tolerance_pct: f64) -> bool {
+        self.jitter_us < expected_interval_us as f64 * tolerance_pct / 100.0
+    }
+}
+
+impl FrameHeader {
+    /// Statistics over the intervals between consecutive headers with a
+    /// PTS, skipping those without one. A PTS that goes backwards counts as
+    /// a zero interval. `None` if fewer than two headers have a PTS.
+    pub fn compute_pts_statistics(headers: &[FrameHeader]) -> Option<PtsStatistics> {
+        let mut intervals = Vec::with_capacity(headers.len());
+        let mut jitter = 0.0;
+        let mut previous: Option<(u64, &FrameHeader)> = None;
+        for header in headers {
+            let Some(pts) = header.pts() else {
+                continue;
+            };
+            if let Some((previous_pts, previous)) = previous {
+                let interval = pts.saturating_sub(previous_pts);
+                let deviation = interval as f64 - previous.audio_duration_us() as f64;
+                jitter += (deviation.abs() - jitter) / 16.0;
+                intervals.push(interval);
+            }
+            previous = Some((pts, header));
+        }
+        if intervals.is_empty() {
+            return None;
+        }
+
+        let count = intervals.len() as f64;
+        let mean = intervals
+            .iter()
+            .map(|&interval| interval as f64)
+            .sum::<f64>()
+            / count;
+        let variance = intervals
+            .iter()
+            .map(|&interval| (interval as f64 - mean).powi(2))
+            .sum::<f64>()
+            / count;
+        Some(PtsStatistics {
+            mean_interval_us: mean,
+            std_dev_interval_us: variance.sqrt(),
+            max_interval_us: *intervals.iter().max().unwrap(),
+            min_interval_us: *intervals.iter().min().unwrap(),
+            jitter_us: jitter,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EncodingFlag, Endianness};
+
+    // 480 samples at 48kHz last 10ms.
+    fn header(pts: Option<u64>) -> FrameHeader {
+        FrameHeader::new(
+            EncodingFlag::PCMSigned,
+            480,
+            48000,
+            2,
+            16,
+            Endianness::LittleEndian,
+            None,
+            pts,
+        )
+        .unwrap()
+    }
+
+    fn headers(pts: &[u64]) -> Vec<FrameHeader> {
+        pts.iter().map(|&pts| header(Some(pts))).collect()
+    }
+
+    #[test]
+    fn test_pts_statistics_steady_stream() {
+        let pts: Vec<u64> = (0..100).map(|index| index * 10_000).collect();
+        let stats = FrameHeader::compute_pts_statistics(&headers(&pts)).unwrap();
+        assert_eq!(
+            stats,
+            PtsStatistics {
+                mean_interval_us: 10_000.0,
+                std_dev_interval_us: 0.0,
+                max_interval_us: 10_000,
+                min_interval_us: 10_000,
+                jitter_us: 0.0,
+            }
+        );
+        assert!(stats.is_within_tolerance(10_000, 0.1));
+    }
+
+    #[test]
+    fn test_pts_statistics_jitter() {
+        let mut stream = headers(&[0, 10_000, 20_500, 30_000]);
+        // Headers without a PTS are skipped.
+        stream.insert(2, header(None));
+        stream.push(header(None));
+        stream.push(header(Some(40_000)));
+        let stats = FrameHeader::compute_pts_statistics(&stream).unwrap();
+        assert_eq!(stats.mean_interval_us, 10_000.0);
+        assert_eq!(stats.std_dev_interval_us, 125_000f64.sqrt());
+        assert_eq!(stats.min_interval_us, 9_500);
+        assert_eq!(stats.max_interval_us, 10_500);
+        // Deviations of 0, 500, 500 and 0 in the RFC 3550 estimator.
+        let jitter = 500.0 / 16.0;
+        let jitter = jitter + (500.0 - jitter) / 16.0;
+        let jitter = jitter - jitter / 16.0;
+        assert_eq!(stats.jitter_us, jitter);
+        assert!((stats.jitter_us - 56.76).abs() < 0.01);
+
+        assert!(stats.is_within_tolerance(10_000, 1.0));
+        assert!(!stats.is_within_tolerance(10_000, 0.5));
+    }
+
+    #[test]
+    fn test_pts_statistics_needs_two_pts() {
+        assert_eq!(FrameHeader::compute_pts_statistics(&[]), None);
+        let stream = [header(Some(5)), header(None), header(None)];
+        assert_eq!(FrameHeader::compute_pts_statistics(&stream), None);
+
+        // A step backwards is a zero interval.
+        let stats = FrameHeader::compute_pts_statistics(&headers(&[20_000, 10_000])).unwrap();
+        assert_eq!(stats.min_interval_us, 0);
+        assert_eq!(stats.jitter_us, 10_000.0 / 16.0);
+    }
+}
@@ -0,0 +1,217 @@
+use crate::{ChannelCount, EncodingFlag, Endianness, FrameHeader, FrameHeaderError, SampleRate};
+
+/// The PCM members of PulseAudio's `pa_sample_format_t`, with their C values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PaSampleFormat {
+    U8 = 0,
+    S16LE = 3,
+    S16BE = 4,
+    F32LE = 5,
+    F32BE = 6,
+    S32LE = 9,
+    S32BE = 10,
+    S24LE = 13,
+    S24BE = 14,
+}
+
+impl PaSampleFormat {
+    /// The name `pa_sample_format_to_string` gives the format.
+    pub fn name(&self) -> &'static str {
+        match self {
+            PaSampleFormat::U8 => "u8",
+            PaSampleFormat::S16LE => "s16le",
+            PaSampleFormat::S16BE => "s16be",
+            PaSampleFormat::F32LE => "float32le",
+            PaSampleFormat::F32BE => "float32be",
+            PaSampleFormat::S32LE => "s32le",
+            PaSampleFormat::S32BE => "s32be",
+            PaSampleFormat::S24LE => "s24le",
+            PaSampleFormat::S24BE => "s24be",
+        }
+    }
+
+    /// Parses the names `name` returns. The 4-byte `s24-32le`/`s24-32be`
+    /// formats are not variants, as headers describe 24-bit samples as
+    /// packed 3-byte `s24le`/`s24be`.
+    pub fn from_name(name: &str) -> Option<PaSampleFormat> {
+        [
+            PaSampleFormat::U8,
+            PaSampleFormat::S16LE,
+            PaSampleFormat::S16BE,
+            PaSampleFormat::F32LE,
+            PaSampleFormat::F32BE,
+            PaSampleFormat::S32LE,
+            PaSampleFormat::S32BE,
+            PaSampleFormat::S24LE,
+            PaSampleFormat::S24BE,
+        ]
+        .into_iter()
+        .find(|format| format.name() == name)
+    }
+}
+
+impl FrameHeader {
+    /// The `pa_sample_spec` fields `(format, rate, channels)` for a PCM
+    /// header. 24-bit samples map to packed `S24`.
+    pub fn to_pa_sample_spec(&self) -> Result<(PaSampleFormat, u32, u8), FrameHeaderError> {
+        let little = self.endianness == Endianness::LittleEndian;
+        let format = match (self.encoding, self.bits_per_sample) {
+            (EncodingFlag::PCMSigned, 16) if little => PaSampleFormat::S16LE,
+            (EncodingFlag::PCMSigned, 16) => PaSampleFormat::S16BE,
+            (EncodingFlag::PCMSigned, 24) if little => PaSampleFormat::S24LE,
+            (EncodingFlag::PCMSigned, 24) => PaSampleFormat::S24BE,
+            (EncodingFlag::PCMSigned, 32) if little => PaSampleFormat::S32LE,
+            (EncodingFlag::PCMSigned, 32) => PaSampleFormat::S32BE,
+            (EncodingFlag::PCMFloat, 32) if little => PaSampleFormat::F32LE,
+            (EncodingFlag::PCMFloat, 32) => PaSampleFormat::F32BE,
+            (EncodingFlag::PCMFloat, _) => {
+                return Err(FrameHeaderError::FieldOutOfRange("bits_per_sample"))
+            }
+            (encoding, _) => return Err(FrameHeaderError::InvalidEncoding(encoding.code() as u32)),
+        };
+        Ok((format, self.sample_rate, self.channels))
+    }
+
+    /// `U8` has no header equivalent, as headers carry at least 16 bits.
+    pub fn from_pa_sample_spec(
+        format: PaSampleFormat,
+        rate: u32,
+        channels: u8,
+        sample_size: u16,
+    ) -> Result<FrameHeader, FrameHeaderError> {
+        let (encoding, bits_per_sample, endianness) = match format {
+            PaSampleFormat::U8 => return Err(FrameHeaderError::FieldOutOfRange("format")),
+            PaSampleFormat::S16LE => (EncodingFlag::PCMSigned, 16, Endianness::LittleEndian),
+            PaSampleFormat::S16BE => (EncodingFlag::PCMSigned, 16, Endianness::BigEndian),
+            PaSampleFormat::S24LE => (EncodingFlag::PCMSigned, 24, Endianness::LittleEndian),
+            PaSampleFormat::S24BE => (EncodingFlag::PCMSigned, 24, Endianness::BigEndian),
+            PaSampleFormat::S32LE => (EncodingFlag::PCMSigned, 32, Endianness::LittleEndian),
+            PaSampleFormat::S32BE => (EncodingFlag::PCMSigned, 32, Endianness::BigEndian),
+            PaSampleFormat::F32LE => (EncodingFlag::PCMFloat, 32, Endianness::LittleEndian),
+            PaSampleFormat::F32BE => (EncodingFlag::PCMFloat, 32, Endianness::BigEndian),
+        };
+        Ok(FrameHeader::new_typed(
+            encoding,
+            sample_size,
+            SampleRate::try_from(rate)?,
+            ChannelCount::try_from(channels)?,
+            bits_per_sample,
+            endianness,
+            None,
+            None,
+        )?)
+    }
+
+    pub fn from_pa_format_name(
+        name: &str,
+        rate: u32,
+        channels: u8,
+        sample_size: u16,
+    ) -> Result<FrameHeader, FrameHeaderError> {
+        let format =
+            PaSampleFormat::from_name(name).ok_or(FrameHeaderError::FieldOutOfRange("format"))?;
+        Self::from_pa_sample_spec(format, rate, channels, sample_size)
+    }
+
+    pub fn to_pa_format_name(&self) -> Result<&'static str, FrameHeaderError> {
+        Ok(self.to_pa_sample_spec()?.0.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_pa_sample_spec_covers_every_format() {
+        let mut reached = HashSet::new();
+        for encoding in [EncodingFlag::PCMSigned, EncodingFlag::PCMFloat] {
+            for bits in [16, 24, 32] {
+                for endianness in [Endianness::LittleEndian, Endianness::BigEndian] {
+                    let header =
+                        FrameHeader::new(encoding, 441, 44100, 2, bits, endianness, None, None)
+                            .unwrap();
+                    let Ok((format, rate, channels)) = header.to_pa_sample_spec() else {
+                        assert_eq!(encoding, EncodingFlag::PCMFloat);
+                        continue;
+                    };
+                    assert_eq!((rate, channels), (44100, 2));
+                    assert_eq!(
+                        FrameHeader::from_pa_sample_spec(format, rate, channels, 441),
+                        Ok(header)
+                    );
+                    reached.insert(format);
+                }
+            }
+        }
+        assert_eq!(reached.len(), 8);
+        assert!(!reached.contains(&PaSampleFormat::U8));
+        assert_eq!(
+            FrameHeader::from_pa_sample_spec(PaSampleFormat::U8, 44100, 2, 441),
+            Err(FrameHeaderError::FieldOutOfRange("format"))
+        );
+        assert_eq!(PaSampleFormat::S24BE as u32, 14);
+    }
+
+    #[test]
+    fn test_pa_sample_spec_rejects_non_pcm() {
+        let opus = FrameHeader::new(
+            EncodingFlag::Opus,
+            960,
+            48000,
+            2,
+            16,
+            Endianness::LittleEndian,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            opus.to_pa_sample_spec(),
+            Err(FrameHeaderError::InvalidEncoding(2))
+        );
+        assert!(FrameHeader::from_pa_sample_spec(PaSampleFormat::S16LE, 22050, 2, 441).is_err());
+    }
+
+    #[test]
+    fn test_pa_format_names() {
+        use EncodingFlag::{PCMFloat, PCMSigned};
+        use Endianness::{BigEndian, LittleEndian};
+        for (name, encoding, bits, endianness) in [
+            ("s16le", PCMSigned, 16, LittleEndian),
+            ("s16be", PCMSigned, 16, BigEndian),
+            ("s24le", PCMSigned, 24, LittleEndian),
+            ("s24be", PCMSigned, 24, BigEndian),
+            ("s32le", PCMSigned, 32, LittleEndian),
+            ("s32be", PCMSigned, 32, BigEndian),
+            ("float32le", PCMFloat, 32, LittleEndian),
+            ("float32be", PCMFloat, 32, BigEndian),
+        ] {
+            let header = FrameHeader::from_pa_format_name(name, 48000, 2, 480).unwrap();
+            assert_eq!(
+                (
+                    header.encoding(),
+                    header.bits_per_sample(),
+                    header.endianness()
+                ),
+                (encoding, bits, endianness),
+                "{}",
+                name
+            );
+            // Packed: s24le samples are 3 bytes each.
+            assert_eq!(header.bytes_per_sample(), bits / 8);
+            assert_eq!(header.to_pa_format_name(), Ok(name));
+        }
+
+        assert_eq!(PaSampleFormat::from_name("u8"), Some(PaSampleFormat::U8));
+        for name in ["u8", "s24-32le", "s24-32be", "ulaw", "S16LE"] {
+            assert_eq!(
+                FrameHeader::from_pa_format_name(name, 48000, 2, 480),
+                Err(FrameHeaderError::FieldOutOfRange("format")),
+                "{}",
+                name
+            );
+        }
+    }
+}
@@ -0,0 +1,425 @@
+use crate::redundant::{fill, read_header_recovering};
+use crate::{FrameHeaderError, FrameHeaderV2, Limits};
+use std::borrow::Cow;
+use std::io::{self, Read};
+
+/// What `FrameParser` found in the bytes pushed to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FrameEvent<'a> {
+    HeaderParsed(FrameHeaderV2),
+    /// Part of the current frame's payload, borrowed from the pushed bytes
+    /// unless it had to be buffered, e.g. to check a redundant header's CRC.
+    PayloadChunk(Cow<'a, [u8]>),
+    FrameComplete,
+    /// After a recoverable error (a payload over the limit) the frame is
+    /// skipped and parsing goes on; otherwise nothing more is parsed until
+    /// `reset`.
+    Error {
+        error: FrameHeaderError,
+        recoverable: bool,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Header,
+    Payload { remaining: u64 },
+    Skip { remaining: u64 },
+    Failed,
+}
+
+// Hands out pushed bytes, then reports `WouldBlock` until more are pushed,
+// or end of stream once the caller has said there are no more.
+struct Pushed<'a, 'b> {
+    input: &'a mut &'b [u8],
+    finished: bool,
+}
+
+impl Read for Pushed<'_, '_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.input.is_empty() && !self.finished {
+            return Err(io::ErrorKind::WouldBlock.into());
+        }
+        self.input.read(buf)
+    }
+}
+
+/// A v2 frame parser that is fed bytes instead of reading them, for event
+/// loops that own their buffers. Header recovery and limits match
+/// `FrameReader`: a header that would wait on more input is retried on the
+/// next `push`, so it is read from the same bytes either way. Only header
+/// bytes are buffered, plus payload bytes a CRC check must see first.
+#[derive(Debug, Clone)]
+pub struct FrameParser {
+    limits: Limits,
+    buffer: Vec<u8>,
+    state: State,
+}
+
+impl Default for FrameParser {
+    fn default() -> Self {
+        FrameParser::new()
+    }
+}
+
+impl FrameParser {
+    pub fn new() -> Self {
+        FrameParser {
+            limits: Limits::default(),
+            buffer: Vec::new(),
+            state: State::Header,
+        }
+    }
+
+    pub fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    pub fn push<'a>(&mut self, input: &'a [u8]) -> impl Iterator<Item = FrameEvent<'a>> {
+        self.run(input, false).into_iter()
+    }
+
+    /// Ends the stream, reporting a frame left incomplete.
+    pub fn finish(&mut self) -> impl Iterator<Item = FrameEvent<'static>> {
+        self.run(&[], true).into_iter()
+    }
+
+    /// Drops any partial frame and starts again at a frame boundary.
+    pub fn reset(&mut self) {
+        self.buffer.clear();
+        self.state = State::Header;
+    }
+
+    /// Bytes held for a header that is not complete yet.
+    pub fn buffered(&self) -> usize {
+        self.buffer.len()
+    }
+
+    fn run<'a>(&mut self, mut input: &'a [u8], finished: bool) -> Vec<FrameEvent<'a>> {
+        let mut events = Vec::new();
+        loop {
+            match self.state {
+                State::Failed => break,
+                State::Payload { remaining } | State::Skip { remaining } if remaining == 0 => {
+                    if matches!(self.state, State::Payload { .. }) {
+                        events.push(FrameEvent::FrameComplete);
+                    }
+                    self.state = State::Header;
+                }
+                State::Payload { remaining } | State::Skip { remaining } => {
+                    if input.is_empty() {
+                        if finished {
+                            self.fail(&mut events, payload_eof());
+                        }
+                        break;
+                    }
+                    let (chunk, rest) = input.split_at(remaining.min(input.len() as u64) as usize);
+                    input = rest;
+                    let remaining = remaining - chunk.len() as u64;
+                    self.state = match self.state {
+                        State::Payload { .. } => {
+                            events.push(FrameEvent::PayloadChunk(Cow::Borrowed(chunk)));
+                            State::Payload { remaining }
+                        }
+                        _ => State::Skip { remaining },
+                    };
+                }
+                State::Header => {
+                    if input.is_empty() && self.buffer.is_empty() {
+                        break;
+                    }
+                    match self.read_header(&mut input, finished) {
+                        Ok(None) => break,
+                        Ok(Some((header, header_size))) => {
+                            self.start_frame(header, header_size, &mut events)
+                        }
+                        Err(err) if err.kind() == io::ErrorKind::WouldBlock => break,
+                        Err(err) => {
+                            self.fail(&mut events, err);
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+        events
+    }
+
+    fn read_header(
+        &mut self,
+        input: &mut &[u8],
+        finished: bool,
+    ) -> io::Result<Option<(FrameHeaderV2, usize)>> {
+        let mut reader = Pushed { input, finished };
+        if !fill(&mut reader, &mut self.buffer, FrameHeaderV2::BASE_SIZE)? {
+            if self.buffer.is_empty() {
+                return Ok(None);
+            }
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "Stream ended inside a frame header",
+            ));
+        }
+        let (header, _, header_size) =
+            read_header_recovering(&mut reader, &mut self.buffer, &self.limits)?;
+        Ok(Some((header, header_size)))
+    }
+
+    // Emits the header and any payload bytes recovery buffered, keeping
+    // bytes past the frame for the next header.
+    fn start_frame<'a>(
+        &mut self,
+        header: FrameHeaderV2,
+        header_size: usize,
+        events: &mut Vec<FrameEvent<'a>>,
+    ) {
+        let payload_size = header.payload_size() as u64;
+        let frame_size = header_size as u64 + payload_size;
+        let mut buffered = std::mem::take(&mut self.buffer).split_off(header_size);
+        if buffered.len() as u64 > payload_size {
+            self.buffer = buffered.split_off(payload_size as usize);
+        }
+        let remaining = frame_size - (header_size + buffered.len()) as u64;
+
+        if let Err(error) = self.limits.check_payload_len(header.payload_size()) {
+            events.push(FrameEvent::Error {
+                error,
+                recoverable: true,
+            });
+            self.state = State::Skip { remaining };
+            return;
+        }
+        events.push(FrameEvent::HeaderParsed(header));
+        if !buffered.is_empty() {
+            events.push(FrameEvent::PayloadChunk(Cow::Owned(buffered)));
+        }
+        self.state = State::Payload { remaining };
+    }
+
+    fn fail(&mut self, events: &mut Vec<FrameEvent<'_>>, err: io::Error) {
+        events.push(FrameEvent::Error {
+            error: err.into(),
+            recoverable: false,
+        });
+        self.buffer.clear();
+        self.state = State::Failed;
+    }
+}
+
+fn payload_eof() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::UnexpectedEof,
+        "Stream ended inside a frame payload",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EncodingFlag, Endianness, FrameReader, FrameWriter};
+
+    type Frames = Vec<(FrameHeaderV2, Vec<u8>)>;
+
+    struct XorShift(u64);
+
+    impl XorShift {
+        fn below(&mut self, n: u64) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0 % n
+        }
+    }
+
+    fn stream(rng: &mut XorShift, frames: u64) -> Vec<u8> {
+        let mut writer = FrameWriter::new(Vec::new());
+        for index in 0..frames {
+            let payload: Vec<u8> = (0..rng.below(120)).map(|i| (i * index) as u8).collect();
+            let mut header = FrameHeaderV2::new(
+                EncodingFlag::Opus,
+                payload.len() as u32,
+                960,
+                48000,
+                2,
+                0,
+                Endianness::LittleEndian,
+                Some(index),
+                (rng.below(2) == 0).then_some(index * 960),
+                None,
+            )
+            .unwrap()
+            .with_redundant_copy(rng.below(2) == 0);
+            if rng.below(2) == 0 {
+                header = header.with_packet_crc32(&payload).unwrap();
+            }
+            writer.write_frame(&header, &payload).unwrap();
+        }
+        writer.into_inner()
+    }
+
+    // Frames up to the first error, and whether there was one.
+    fn pull(bytes: &[u8], limits: Limits) -> (Frames, bool) {
+        let mut frames = Vec::new();
+        for frame in FrameReader::new(bytes).with_limits(limits) {
+            match frame {
+                Ok(frame) => frames.push(frame),
+                Err(_) => return (frames, true),
+            }
+        }
+        (frames, false)
+    }
+
+    fn push(
+        bytes: &[u8],
+        chunk_sizes: &mut dyn FnMut() -> usize,
+        limits: Limits,
+    ) -> (Frames, bool) {
+        let mut parser = FrameParser::new().with_limits(limits);
+        let mut frames = Vec::new();
+        let mut current: Option<(FrameHeaderV2, Vec<u8>)> = None;
+        let mut failed = false;
+        // `FrameReader` stops at its first error, so stop recording there.
+        let mut handle = |event: FrameEvent<'_>| match event {
+            _ if failed => {}
+            FrameEvent::HeaderParsed(header) => current = Some((header, Vec::new())),
+            FrameEvent::PayloadChunk(chunk) => {
+                current.as_mut().unwrap().1.extend_from_slice(&chunk)
+            }
+            FrameEvent::FrameComplete => frames.push(current.take().unwrap()),
+            FrameEvent::Error { .. } => failed = true,
+        };
+        let mut rest = bytes;
+        while !rest.is_empty() {
+            let (chunk, tail) = rest.split_at(chunk_sizes().clamp(1, rest.len()));
+            rest = tail;
+            parser.push(chunk).for_each(&mut handle);
+        }
+        parser.finish().for_each(&mut handle);
+        (frames, failed)
+    }
+
+    #[test]
+    fn test_push_matches_pull_for_any_chunking() {
+        let mut rng = XorShift(0x9E37_79B9_7F4A_7C15);
+        for round in 0..60 {
+            let frames = 1 + rng.below(12);
+            let mut bytes = stream(&mut rng, frames);
+            match round % 4 {
+                // Damage a byte, which redundant copies may recover from.
+                1 => {
+                    let at = rng.below(bytes.len() as u64) as usize;
+                    bytes[at] ^= 0x10;
+                }
+                2 => {
+                    let len = rng.below(bytes.len() as u64) as usize;
+                    bytes.truncate(len);
+                }
+                _ => {}
+            }
+            let limits =
+                Limits::default().with_max_payload_len(if round % 4 == 3 { 100 } else { 1 << 20 });
+
+            let expected = pull(&bytes, limits);
+            assert_eq!(push(&bytes, &mut || 1, limits), expected, "round {}", round);
+            assert_eq!(
+                push(&bytes, &mut || bytes.len(), limits),
+                expected,
+                "round {}",
+                round
+            );
+            let mut sizes = XorShift(round + 1);
+            assert_eq!(
+                push(&bytes, &mut || 1 + sizes.below(40) as usize, limits),
+                expected,
+                "round {}",
+                round
+            );
+        }
+    }
+
+    #[test]
+    fn test_payloads_pass_through_and_errors() {
+        let header = FrameHeaderV2::new(
+            EncodingFlag::Opus,
+            4,
+            960,
+            48000,
+            2,
+            0,
+            Endianness::LittleEndian,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        let mut writer = FrameWriter::new(Vec::new());
+        for payload in [[1, 2, 3, 4], [5, 6, 7, 8]] {
+            writer.write_frame(&header, &payload).unwrap();
+        }
+        let bytes = writer.into_inner();
+        let size = header.size();
+
+        let mut parser = FrameParser::new();
+        let events: Vec<_> = parser.push(&bytes[..size + 2]).collect();
+        assert_eq!(
+            events,
+            [
+                FrameEvent::HeaderParsed(header.clone()),
+                FrameEvent::PayloadChunk(Cow::Borrowed(&[1, 2][..])),
+            ]
+        );
+        assert!(matches!(
+            events[1],
+            FrameEvent::PayloadChunk(Cow::Borrowed(_))
+        ));
+        let events: Vec<_> = parser.push(&bytes[size + 2..size + 6]).collect();
+        assert_eq!(
+            events,
+            [
+                FrameEvent::PayloadChunk(Cow::Borrowed(&[3, 4][..])),
+                FrameEvent::FrameComplete,
+            ]
+        );
+        assert_eq!(parser.buffered(), 2);
+
+        // A payload over the limit is skipped, and the next frame parsed.
+        let mut parser = FrameParser::new().with_limits(Limits::default().with_max_payload_len(3));
+        let events: Vec<_> = parser.push(&bytes).collect();
+        assert_eq!(events.len(), 2);
+        assert!(events.iter().all(|event| matches!(
+            event,
+            FrameEvent::Error {
+                error: FrameHeaderError::LimitExceeded { .. },
+                recoverable: true,
+            }
+        )));
+
+        // A corrupt header stops the parser until it is reset.
+        let mut parser = FrameParser::new();
+        assert_eq!(parser.push(&[0; 16]).count(), 0);
+        let events: Vec<_> = parser.finish().collect();
+        assert!(matches!(
+            events[..],
+            [FrameEvent::Error {
+                recoverable: false,
+                ..
+            }]
+        ));
+        assert_eq!(parser.push(&bytes).count(), 0);
+        parser.reset();
+        assert_eq!(parser.push(&bytes).count(), 6);
+
+        // Ending inside a frame is reported by `finish`.
+        let mut parser = FrameParser::new();
+        assert_eq!(parser.push(&bytes[..size - 1]).count(), 0);
+        assert_eq!(
+            parser.finish().collect::<Vec<_>>(),
+            [FrameEvent::Error {
+                error: FrameHeaderError::Truncated,
+                recoverable: false,
+            }]
+        );
+        assert_eq!(FrameParser::new().finish().count(), 0);
+    }
+}
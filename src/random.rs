@@ -0,0 +1,324 @@
+use crate::{EncodingFlag, Endianness, FrameHeader, FrameHeaderError};
+use rand::seq::IndexedRandom;
+use rand::Rng;
+use std::ops::RangeInclusive;
+
+const ENCODINGS: [EncodingFlag; 6] = [
+    EncodingFlag::PCMSigned,
+    EncodingFlag::PCMFloat,
+    EncodingFlag::Opus,
+    EncodingFlag::FLAC,
+    EncodingFlag::AAC,
+    EncodingFlag::H264,
+];
+const BITS: [u8; 3] = [16, 24, 32];
+
+/// Limits for `FrameHeader::random_with`. The default allows every valid v1
+/// header.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RandomConstraints {
+    /// Pin the encoding; `None` picks any known encoding.
+    pub encoding: Option<EncodingFlag>,
+    /// Rates to pick from; rates a v1 header cannot carry are ignored.
+    pub sample_rates: Vec<u32>,
+    pub channels: RangeInclusive<u8>,
+    pub id_probability: f64,
+    pub pts_probability: f64,
+    /// Use only the codec's standard frame sizes (and rates that have them),
+    /// and 32 bits for float PCM.
+    pub codec_constraints: bool,
+}
+
+impl Default for RandomConstraints {
+    fn default() -> Self {
+        RandomConstraints {
+            encoding: None,
+            sample_rates: FrameHeader::VALID_SAMPLE_RATES.to_vec(),
+            channels: 1..=16,
+            id_probability: 0.5,
+            pts_probability: 0.5,
+            codec_constraints: false,
+        }
+    }
+}
+
+impl FrameHeader {
+    pub fn random(rng: &mut impl Rng) -> FrameHeader {
+        Self::random_with(rng, &RandomConstraints::default())
+            .expect("default constraints are satisfiable")
+    }
+
+    pub fn random_with(
+        rng: &mut impl Rng,
+        constraints: &RandomConstraints,
+    ) -> Result<FrameHeader, FrameHeaderError> {
+        for (probability, field) in [
+            (constraints.id_probability, "id_probability"),
+            (constraints.pts_probability, "pts_probability"),
+        ] {
+            if !(0.0..=1.0).contains(&probability) {
+                return Err(FrameHeaderError::FieldOutOfRange(field));
+            }
+        }
+        let channels =
+            (*constraints.channels.start()).max(1)..=(*constraints.channels.end()).min(16);
+        if channels.is_empty() {
+            return Err(FrameHeaderError::FieldOutOfRange("channels"));
+        }
+
+        let pinned;
+        let encodings = match constraints.encoding {
+            Some(encoding) => {
+                pinned = [encoding];
+                &pinned[..]
+            }
+            None => &ENCODINGS[..],
+        };
+        let choices: Vec<(EncodingFlag, Vec<u32>)> = encodings
+            .iter()
+            .map(|&encoding| (encoding, usable_rates(encoding, constraints)))
+            .filter(|(_, rates)| !rates.is_empty())
+            .collect();
+        let (encoding, rates) = choices
+            .choose(rng)
+            .ok_or(FrameHeaderError::FieldOutOfRange("sample_rates"))?;
+        let sample_rate = *rates.choose(rng).unwrap();
+
+        let frame_sizes = frame_sizes(*encoding, sample_rate, constraints);
+        let sample_size = if frame_sizes.is_empty() {
+            rng.random_range(1..=Self::MAX_SAMPLE_SIZE)
+        } else {
+            *frame_sizes.choose(rng).unwrap()
+        };
+        let bits_per_sample =
+            if constraints.codec_constraints && *encoding == EncodingFlag::PCMFloat {
+                32
+            } else {
+                *BITS.choose(rng).unwrap()
+            };
+        let endianness = if rng.random_bool(0.5) {
+            Endianness::LittleEndian
+        } else {
+            Endianness::BigEndian
+        };
+        let id = rng
+            .random_bool(constraints.id_probability)
+            .then(|| rng.random());
+        let pts = rng
+            .random_bool(constraints.pts_probability)
+            .then(|| rng.random());
+
+        Ok(FrameHeader::new(
+            *encoding,
+            sample_size,
+            sample_rate,
+            rng.random_range(channels),
+            bits_per_sample,
+            endianness,
+            id,
+            pts,
+        )?)
+    }
+
+    /// `n` headers of one stream: the format and ID of a header drawn from
+    /// `constraints`, with a PTS on every header that advances by each
+    /// frame's duration.
+    pub fn random_stream(
+        rng: &mut impl Rng,
+        n: usize,
+        constraints: &RandomConstraints,
+    ) -> Result<Vec<FrameHeader>, FrameHeaderError> {
+        let template = Self::random_with(rng, constraints)?;
+        let frame_sizes = frame_sizes(template.encoding, template.sample_rate, constraints);
+        let start = rng.random_range(0..=u32::MAX as u64);
+
+        let mut samples = 0u64;
+        let mut stream = Vec::with_capacity(n);
+        for _ in 0..n {
+            let mut header = template.clone();
+            if let Some(&size) = frame_sizes.choose(rng) {
+                header.sample_size = size;
+            }
+            // Computed from the running sample count so rounding does not drift.
+            header.pts = Some(start + samples * 1_000_000 / template.sample_rate as u64);
+            samples += header.sample_size as u64;
+            stream.push(header);
+        }
+        Ok(stream)
+    }
+}
+
+fn usable_rates(encoding: EncodingFlag, constraints: &RandomConstraints) -> Vec<u32> {
+    let has_frame_sizes = FrameHeader::VALID_SAMPLE_RATES
+        .iter()
+        .any(|&rate| !encoding.standard_frame_sizes(rate).is_empty());
+    constraints
+        .sample_rates
+        .iter()
+        .copied()
+        .filter(|rate| FrameHeader::VALID_SAMPLE_RATES.contains(rate))
+        .filter(|&rate| {
+            !constraints.codec_constraints
+                || !has_frame_sizes
+                || !frame_sizes(encoding, rate, constraints).is_empty()
+        })
+        .collect()
+}
+
+fn frame_sizes(
+    encoding: EncodingFlag,
+    sample_rate: u32,
+    constraints: &RandomConstraints,
+) -> Vec<u16> {
+    if !constraints.codec_constraints {
+        return Vec::new();
+    }
+    encoding
+        .standard_frame_sizes(sample_rate)
+        .iter()
+        .copied()
+        .filter(|&size| size <= FrameHeader::MAX_SAMPLE_SIZE)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_random_headers_are_valid_and_varied() {
+        let mut rng = StdRng::seed_from_u64(0x2A);
+        let mut encodings = HashSet::new();
+        let mut formats = HashSet::new();
+        let mut optional = HashSet::new();
+        for _ in 0..100_000 {
+            let header = FrameHeader::random(&mut rng);
+            let (encoding, sample_size, rate, channels, bits, endianness, id, pts) =
+                header.clone().into_parts();
+            assert_eq!(
+                FrameHeader::new(
+                    encoding,
+                    sample_size,
+                    rate,
+                    channels,
+                    bits,
+                    endianness,
+                    id,
+                    pts
+                ),
+                Ok(header.clone())
+            );
+
+            let mut bytes = Vec::new();
+            header.encode(&mut bytes).unwrap();
+            assert_eq!(FrameHeader::decode(&mut &bytes[..]).unwrap(), header);
+
+            encodings.insert(encoding.code());
+            formats.insert((rate, channels, bits, endianness == Endianness::BigEndian));
+            optional.insert((id.is_some(), pts.is_some()));
+        }
+        assert_eq!(encodings.len(), ENCODINGS.len());
+        assert_eq!(formats.len(), 4 * 16 * 3 * 2);
+        assert_eq!(optional.len(), 4);
+    }
+
+    #[test]
+    fn test_random_with_constraints() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let constraints = RandomConstraints {
+            encoding: Some(EncodingFlag::Opus),
+            channels: 1..=2,
+            id_probability: 1.0,
+            pts_probability: 0.0,
+            codec_constraints: true,
+            ..Default::default()
+        };
+        let mut sizes = HashSet::new();
+        for _ in 0..10_000 {
+            let header = FrameHeader::random_with(&mut rng, &constraints).unwrap();
+            assert_eq!(header.encoding, EncodingFlag::Opus);
+            assert!(EncodingFlag::Opus
+                .standard_frame_sizes(header.sample_rate)
+                .contains(&header.sample_size));
+            assert!((1..=2).contains(&header.channels));
+            assert!(header.id.is_some() && header.pts.is_none());
+            sizes.insert((header.sample_rate, header.sample_size));
+        }
+        // Opus has no frame sizes at 44.1kHz or 96kHz.
+        assert_eq!(sizes.len(), 12);
+
+        let float = RandomConstraints {
+            encoding: Some(EncodingFlag::PCMFloat),
+            codec_constraints: true,
+            ..Default::default()
+        };
+        for _ in 0..1000 {
+            let header = FrameHeader::random_with(&mut rng, &float).unwrap();
+            assert_eq!(header.bits_per_sample, 32);
+        }
+
+        for constraints in [
+            RandomConstraints {
+                sample_rates: vec![22050],
+                ..Default::default()
+            },
+            RandomConstraints {
+                encoding: Some(EncodingFlag::Opus),
+                sample_rates: vec![44100],
+                codec_constraints: true,
+                ..Default::default()
+            },
+            RandomConstraints {
+                channels: 17..=20,
+                ..Default::default()
+            },
+            RandomConstraints {
+                pts_probability: 1.5,
+                ..Default::default()
+            },
+        ] {
+            assert!(FrameHeader::random_with(&mut rng, &constraints).is_err());
+        }
+    }
+
+    #[test]
+    fn test_random_stream_is_coherent() {
+        let mut rng = StdRng::seed_from_u64(99);
+        for codec_constraints in [false, true] {
+            let constraints = RandomConstraints {
+                codec_constraints,
+                ..Default::default()
+            };
+            for _ in 0..100 {
+                let stream = FrameHeader::random_stream(&mut rng, 50, &constraints).unwrap();
+                assert_eq!(stream.len(), 50);
+                let first = &stream[0];
+                for pair in stream.windows(2) {
+                    let (previous, header) = (&pair[0], &pair[1]);
+                    assert_eq!(
+                        (
+                            header.encoding,
+                            header.sample_rate,
+                            header.channels,
+                            header.id
+                        ),
+                        (first.encoding, first.sample_rate, first.channels, first.id)
+                    );
+                    assert!(header.pts > previous.pts);
+                    let elapsed = header.pts.unwrap() - previous.pts.unwrap();
+                    let duration =
+                        previous.sample_size as u64 * 1_000_000 / previous.sample_rate as u64;
+                    assert!(elapsed == duration || elapsed == duration + 1);
+                }
+            }
+        }
+        assert!(
+            FrameHeader::random_stream(&mut rng, 0, &RandomConstraints::default())
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
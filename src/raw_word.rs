@@ -0,0 +1,279 @@
+use crate::{EncodingFlag, Endianness, FrameHeader, FrameHeaderError};
+#[cfg(feature = "zerocopy")]
+use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout};
+
+/// The 4-byte base word of a v1 header, for fixed-size slots that are
+/// parsed and patched in place. The ID and PTS that may follow it are not
+/// part of the word.
+#[cfg_attr(
+    feature = "zerocopy",
+    derive(FromBytes, IntoBytes, Immutable, KnownLayout)
+)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct RawHeaderWord(pub [u8; 4]);
+
+/// Everything the base word carries; `has_id` and `has_pts` say whether an
+/// ID or PTS follows it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RawHeaderFields {
+    pub encoding: EncodingFlag,
+    pub sample_size: u16,
+    pub sample_rate: u32,
+    pub channels: u8,
+    pub bits_per_sample: u8,
+    pub endianness: Endianness,
+    pub has_id: bool,
+    pub has_pts: bool,
+}
+
+impl RawHeaderWord {
+    pub fn validate(&self) -> Result<(), FrameHeaderError> {
+        FrameHeader::validate_header_with_reason(&self.0)
+    }
+
+    pub fn to_header_partial(&self) -> Result<RawHeaderFields, FrameHeaderError> {
+        FrameHeader::classify_word(u32::from_be_bytes(self.0)).ok_or_else(|| {
+            self.validate()
+                .expect_err("classify_word rejects invalid words")
+        })
+    }
+
+    /// Size of the full header this word starts, including ID and PTS.
+    pub fn header_size(&self) -> usize {
+        FrameHeader::encoded_size(&self.0).expect("word is four bytes")
+    }
+
+    pub fn set_encoding(&mut self, encoding: EncodingFlag) -> Result<(), String> {
+        FrameHeader::patch_encoding(&mut self.0, encoding)
+    }
+
+    pub fn set_sample_size(&mut self, sample_size: u16) -> Result<(), String> {
+        FrameHeader::patch_sample_size(&mut self.0, sample_size)
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: u32) -> Result<(), String> {
+        FrameHeader::patch_sample_rate(&mut self.0, sample_rate)
+    }
+
+    pub fn set_channels(&mut self, channels: u8) -> Result<(), String> {
+        FrameHeader::patch_channels(&mut self.0, channels)
+    }
+
+    pub fn set_bits_per_sample(&mut self, bits: u8) -> Result<(), String> {
+        FrameHeader::patch_bits_per_sample(&mut self.0, bits)
+    }
+}
+
+impl FrameHeader {
+    /// `validate_header` on a base word already read as big-endian, without
+    /// branches. Sample rate codes and channel counts use every value their
+    /// bits can hold, so only the magic, a bits code of 3 and encoding 7
+    /// need checking, each as one mask compare.
+    #[inline]
+    pub const fn validate_word(word: u32) -> bool {
+        let unused_encoding = 0b111 << Self::ENCODING_SHIFT;
+        (word & Self::MAGIC_MASK == Self::MAGIC_WORD << Self::MAGIC_SHIFT)
+            & (word & Self::BITS_MASK != Self::BITS_MASK)
+            & (word & unused_encoding != unused_encoding)
+    }
+
+    /// Validates a base word and extracts its fields with table lookups in
+    /// place of per-field matches. `None` exactly when `validate_word` is
+    /// false.
+    #[inline]
+    pub fn classify_word(word: u32) -> Option<RawHeaderFields> {
+        // Indexed by the 3-bit code; code 7 is rejected above.
+        const ENCODINGS: [EncodingFlag; 8] = [
+            EncodingFlag::PCMSigned,
+            EncodingFlag::PCMFloat,
+            EncodingFlag::Opus,
+            EncodingFlag::FLAC,
+            EncodingFlag::AAC,
+            EncodingFlag::H264,
+            EncodingFlag::Metadata,
+            EncodingFlag::PCMSigned,
+        ];
+
+        if !Self::validate_word(word) {
+            return None;
+        }
+        let bits_code = (word & Self::BITS_MASK) >> Self::BITS_SHIFT;
+        Some(RawHeaderFields {
+            encoding: ENCODINGS[((word & Self::ENCODING_MASK) >> Self::ENCODING_SHIFT) as usize],
+            sample_size: (word & Self::SAMPLE_SIZE_MASK) as u16,
+            sample_rate: Self::VALID_SAMPLE_RATES
+                [((word & Self::SAMPLE_RATE_MASK) >> Self::SAMPLE_RATE_SHIFT) as usize],
+            channels: ((word & Self::CHANNELS_MASK) >> Self::CHANNELS_SHIFT) as u8 + 1,
+            bits_per_sample: 16 + 8 * bits_code as u8,
+            endianness: if word & Self::ENDIAN_MASK == 0 {
+                Endianness::LittleEndian
+            } else {
+                Endianness::BigEndian
+            },
+            has_id: word & Self::ID_MASK != 0,
+            has_pts: word & Self::PTS_MASK != 0,
+        })
+    }
+}
+
+impl From<&FrameHeader> for RawHeaderWord {
+    fn from(header: &FrameHeader) -> Self {
+        let mut bytes = Vec::with_capacity(header.size());
+        header
+            .encode(&mut bytes)
+            .expect("FrameHeader fields are validated on construction");
+        RawHeaderWord(bytes[..4].try_into().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(sample_size: u16, pts: Option<u64>) -> FrameHeader {
+        FrameHeader::new(
+            EncodingFlag::Opus,
+            sample_size,
+            48000,
+            2,
+            16,
+            Endianness::LittleEndian,
+            None,
+            pts,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_raw_word_fields_and_patches() {
+        let mut word = RawHeaderWord::from(&header(960, Some(1)));
+        assert_eq!(word.validate(), Ok(()));
+        assert_eq!(word.header_size(), 12);
+
+        let fields = word.to_header_partial().unwrap();
+        assert_eq!(fields.encoding, EncodingFlag::Opus);
+        assert_eq!(fields.sample_size, 960);
+        assert!(!fields.has_id);
+        assert!(fields.has_pts);
+
+        word.set_sample_size(480).unwrap();
+        word.set_channels(1).unwrap();
+        word.set_sample_rate(16000).unwrap();
+        word.set_bits_per_sample(24).unwrap();
+        word.set_encoding(EncodingFlag::FLAC).unwrap();
+        assert!(word.set_channels(17).is_err());
+
+        let fields = word.to_header_partial().unwrap();
+        assert_eq!(
+            (
+                fields.encoding,
+                fields.sample_size,
+                fields.sample_rate,
+                fields.channels,
+                fields.bits_per_sample,
+                fields.has_pts,
+            ),
+            (EncodingFlag::FLAC, 480, 16000, 1, 24, true)
+        );
+
+        assert!(RawHeaderWord([0; 4]).validate().is_err());
+        assert!(RawHeaderWord([0; 4]).to_header_partial().is_err());
+    }
+
+    #[test]
+    fn test_word_checks_match_decode() {
+        // Every value of the 20 bits above the sample size, with the sample
+        // size varying alongside. Decoding is the reference, with the ID and
+        // PTS it may announce zeroed.
+        for high in 0..1u32 << 20 {
+            let word = high << 12 | (high.wrapping_mul(0x9E37_79B9) >> 20);
+            let mut bytes = [0u8; 20];
+            bytes[..4].copy_from_slice(&word.to_be_bytes());
+            let decoded = FrameHeader::decode(&mut &bytes[..]);
+
+            assert_eq!(
+                FrameHeader::validate_word(word),
+                decoded.is_ok(),
+                "{:#010x}",
+                word
+            );
+            assert_eq!(
+                FrameHeader::validate_header_with_reason(&bytes).is_ok(),
+                decoded.is_ok()
+            );
+            match (FrameHeader::classify_word(word), decoded) {
+                (Some(fields), Ok(header)) => {
+                    assert_eq!(
+                        (
+                            fields.encoding,
+                            fields.sample_size,
+                            fields.sample_rate,
+                            fields.channels,
+                            fields.bits_per_sample,
+                            fields.endianness,
+                            fields.has_id,
+                            fields.has_pts,
+                        ),
+                        (
+                            header.encoding,
+                            header.sample_size,
+                            header.sample_rate,
+                            header.channels,
+                            header.bits_per_sample,
+                            header.endianness,
+                            header.id.is_some(),
+                            header.pts.is_some(),
+                        ),
+                        "{:#010x}",
+                        word
+                    );
+                }
+                (None, Err(_)) => {}
+                (fields, decoded) => panic!("{:#010x}: {:?} vs {:?}", word, fields, decoded),
+            }
+        }
+    }
+
+    #[cfg(feature = "zerocopy")]
+    #[test]
+    fn test_reinterpret_ring_slots() {
+        let mut ring = Vec::new();
+        for sample_size in [120u16, 240, 480, 960] {
+            ring.extend_from_slice(&RawHeaderWord::from(&header(sample_size, None)).0);
+        }
+        ring.extend_from_slice(&[0xFF; 4]);
+
+        let words = <[RawHeaderWord]>::ref_from_bytes(&ring).unwrap();
+        assert_eq!(words.len(), 5);
+        for (word, slot) in words.iter().zip(ring.chunks(4)) {
+            assert_eq!(
+                word.validate().is_ok(),
+                FrameHeader::validate_header(slot).unwrap()
+            );
+            match word.to_header_partial() {
+                Ok(fields) => {
+                    assert_eq!(
+                        fields.sample_size,
+                        FrameHeader::extract_sample_count(slot).unwrap()
+                    );
+                    assert_eq!(
+                        fields.encoding,
+                        FrameHeader::extract_encoding(slot).unwrap()
+                    );
+                }
+                Err(_) => assert!(FrameHeader::extract_sample_count(slot).is_err()),
+            }
+        }
+
+        let words = <[RawHeaderWord]>::mut_from_bytes(&mut ring[..16]).unwrap();
+        for word in words.iter_mut() {
+            word.set_sample_size(2880).unwrap();
+        }
+        assert!(ring
+            .chunks(4)
+            .take(4)
+            .all(|slot| FrameHeader::extract_sample_count(slot) == Ok(2880)));
+        assert_eq!([RawHeaderWord([1, 2, 3, 4])].as_bytes(), [1, 2, 3, 4]);
+    }
+}
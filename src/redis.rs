@@ -0,0 +1,179 @@
+use crate::{EncodingFlag, Endianness, FrameHeader, FrameHeaderError};
+
+impl FrameHeader {
+    /// Field-value pairs for `HSET`, named as in `FIELD_NAMES`, with the
+    /// encoding and endianness under their serde names. Unset IDs and PTS
+    /// are left out, as hashes have no null. The pairs work as `redis`
+    /// crate arguments as they are:
+    ///
+    /// ```ignore
+    /// use redis::Commands;
+    ///
+    /// let mut con = redis::Client::open("redis://127.0.0.1/")?.get_connection()?;
+    /// let id: String = con.xadd("audio:headers", "*", &header.to_redis_xadd_fields())?;
+    /// ```
+    pub fn to_redis_hash_fields(&self) -> Vec<(&'static str, String)> {
+        let encoding = match self.encoding.serde_name() {
+            Some(name) => name.to_string(),
+            None => format!("unknown:{}", self.encoding.code()),
+        };
+        let mut fields = vec![
+            ("encoding", encoding),
+            ("sample_size", self.sample_size.to_string()),
+            ("sample_rate", self.sample_rate.to_string()),
+            ("channels", self.channels.to_string()),
+            ("bits_per_sample", self.bits_per_sample.to_string()),
+            ("endianness", self.endianness.serde_name().to_string()),
+        ];
+        fields.extend(self.id.map(|id| ("id", id.to_string())));
+        fields.extend(self.pts.map(|pts| ("pts", pts.to_string())));
+        fields
+    }
+
+    /// The same pairs as `to_redis_hash_fields`, for the field list of an
+    /// `XADD` stream entry.
+    pub fn to_redis_xadd_fields(&self) -> Vec<(&'static str, String)> {
+        self.to_redis_hash_fields()
+    }
+
+    /// Reads the fields written by `to_redis_hash_fields`. Other fields,
+    /// such as a payload stored in the same stream entry, are ignored.
+    pub fn from_redis_hash_fields(
+        fields: &[(&str, &str)],
+    ) -> Result<FrameHeader, FrameHeaderError> {
+        let get = |name: &str| {
+            fields
+                .iter()
+                .find(|(field, _)| *field == name)
+                .map(|(_, value)| *value)
+        };
+        let required = |name: &'static str| {
+            get(name)
+                .ok_or_else(|| FrameHeaderError::Invalid(format!("Missing Redis field: {}", name)))
+        };
+
+        let encoding = required("encoding")?;
+        let encoding = EncodingFlag::from_serde_name(encoding)
+            .ok_or_else(|| FrameHeaderError::Invalid(format!("Unknown encoding: {}", encoding)))?;
+        let endianness = required("endianness")?;
+        let endianness = Endianness::from_serde_name(endianness).ok_or_else(|| {
+            FrameHeaderError::Invalid(format!("Unknown endianness: {}", endianness))
+        })?;
+        let optional = |name: &'static str| get(name).map(|value| number(value, name)).transpose();
+
+        Ok(FrameHeader::new(
+            encoding,
+            number(required("sample_size")?, "sample_size")?,
+            number(required("sample_rate")?, "sample_rate")?,
+            number(required("channels")?, "channels")?,
+            number(required("bits_per_sample")?, "bits_per_sample")?,
+            endianness,
+            optional("id")?,
+            optional("pts")?,
+        )?)
+    }
+}
+
+fn number<T: std::str::FromStr>(value: &str, name: &'static str) -> Result<T, FrameHeaderError> {
+    value
+        .parse()
+        .map_err(|_| FrameHeaderError::FieldOutOfRange(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(encoding: EncodingFlag, id: Option<u64>, pts: Option<u64>) -> FrameHeader {
+        FrameHeader::new(encoding, 960, 48000, 2, 16, Endianness::BigEndian, id, pts).unwrap()
+    }
+
+    fn borrowed<'a>(fields: &'a [(&'static str, String)]) -> Vec<(&'static str, &'a str)> {
+        fields
+            .iter()
+            .map(|(name, value)| (*name, value.as_str()))
+            .collect()
+    }
+
+    // `fields` with `name` removed, then set to `value` if given.
+    fn replace<'a>(
+        fields: &[(&'a str, &'a str)],
+        name: &'a str,
+        value: Option<&'a str>,
+    ) -> Vec<(&'a str, &'a str)> {
+        let mut replaced: Vec<_> = fields
+            .iter()
+            .copied()
+            .filter(|(field, _)| *field != name)
+            .collect();
+        replaced.extend(value.map(|value| (name, value)));
+        replaced
+    }
+
+    #[test]
+    fn test_redis_fields_roundtrip() {
+        let full = header(EncodingFlag::Opus, Some(7), Some(123_456));
+        let fields = full.to_redis_hash_fields();
+        assert_eq!(
+            borrowed(&fields),
+            [
+                ("encoding", "opus"),
+                ("sample_size", "960"),
+                ("sample_rate", "48000"),
+                ("channels", "2"),
+                ("bits_per_sample", "16"),
+                ("endianness", "be"),
+                ("id", "7"),
+                ("pts", "123456"),
+            ]
+        );
+        assert_eq!(full.to_redis_xadd_fields(), fields);
+        assert_eq!(
+            FrameHeader::from_redis_hash_fields(&borrowed(&fields)),
+            Ok(full)
+        );
+
+        let bare = header(EncodingFlag::Unknown(7), None, None);
+        let fields = bare.to_redis_hash_fields();
+        assert_eq!(fields.len(), 6);
+        assert_eq!(fields[0], ("encoding", "unknown:7".to_string()));
+        assert_eq!(
+            FrameHeader::from_redis_hash_fields(&borrowed(&fields)),
+            Ok(bare)
+        );
+    }
+
+    #[test]
+    fn test_from_redis_fields_errors() {
+        let fields = header(EncodingFlag::Opus, None, Some(5)).to_redis_hash_fields();
+        let mut fields = borrowed(&fields);
+        // Entries often carry more than the header.
+        fields.insert(0, ("payload", "\u{1}\u{2}"));
+        assert!(FrameHeader::from_redis_hash_fields(&fields).is_ok());
+
+        assert_eq!(
+            FrameHeader::from_redis_hash_fields(&replace(&fields, "sample_rate", None)),
+            Err(FrameHeaderError::Invalid(
+                "Missing Redis field: sample_rate".to_string()
+            ))
+        );
+
+        let with = |name, value| replace(&fields, name, Some(value));
+        assert_eq!(
+            FrameHeader::from_redis_hash_fields(&with("pts", "soon")),
+            Err(FrameHeaderError::FieldOutOfRange("pts"))
+        );
+        assert_eq!(
+            FrameHeader::from_redis_hash_fields(&with("channels", "300")),
+            Err(FrameHeaderError::FieldOutOfRange("channels"))
+        );
+        assert!(matches!(
+            FrameHeader::from_redis_hash_fields(&with("encoding", "mp3")),
+            Err(FrameHeaderError::Invalid(_))
+        ));
+        assert!(matches!(
+            FrameHeader::from_redis_hash_fields(&with("endianness", "middle")),
+            Err(FrameHeaderError::Invalid(_))
+        ));
+    }
+}
@@ -0,0 +1,361 @@
+use crate::{DecodeOptions, FrameHeaderError, FrameHeaderV2, Limits};
+use std::io::{self, Read};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeaderCopy {
+    First,
+    Second,
+}
+
+impl FrameHeaderV2 {
+    /// Decodes the header at the start of `frame` (header then payload). For
+    /// a redundant header, falls back to the second copy when the first fails
+    /// validation or its packet CRC. Returns the copy used and the size of
+    /// both copies together. A CRC is only checked for payloads within the
+    /// default `Limits`.
+    pub fn decode_redundant(
+        frame: &[u8],
+    ) -> Result<(FrameHeaderV2, HeaderCopy, usize), FrameHeaderError> {
+        let mut buffer = frame.to_vec();
+        if buffer.len() < Self::BASE_SIZE {
+            return Err(FrameHeaderError::Truncated);
+        }
+        Ok(read_header_recovering(
+            &mut io::empty(),
+            &mut buffer,
+            &Limits::default(),
+        )?)
+    }
+}
+
+// `buffer` holds at least the base header and is extended from `reader` as
+// needed. On success it holds at least the whole header.
+pub(crate) fn read_header_recovering<R: Read>(
+    reader: &mut R,
+    buffer: &mut Vec<u8>,
+    limits: &Limits,
+) -> io::Result<(FrameHeaderV2, HeaderCopy, usize)> {
+    let ended = || {
+        io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "Stream ended inside a frame header",
+        )
+    };
+    let mut first = None;
+    let mut first_size = None;
+    if let Ok(prefix_size) = FrameHeaderV2::header_prefix_size(buffer) {
+        if !fill(reader, buffer, prefix_size)? {
+            return Err(ended());
+        }
+    }
+    if let Ok(size) = FrameHeaderV2::header_size(buffer) {
+        if !fill(reader, buffer, size)? {
+            return Err(ended());
+        }
+        if let Ok(header) = FrameHeaderV2::decode(&mut &buffer[..size]) {
+            if !header.is_redundant() {
+                return Ok((header, HeaderCopy::First, size));
+            }
+            // A CRC settles whether this copy is damaged. Without one, copies
+            // that differ are a sign of damage, so prefer an intact second
+            // copy and fall back to this one.
+            let copy_size = size / 2;
+            if header.packet_crc32_value().is_some() {
+                if crc_matches(reader, buffer, &header, 0, size, limits)? {
+                    return Ok((header, HeaderCopy::First, size));
+                }
+            } else if buffer[..copy_size] == buffer[copy_size..size] {
+                return Ok((header, HeaderCopy::First, size));
+            } else {
+                first = Some((header, size));
+            }
+        }
+        first_size = Some(size / 2);
+    }
+
+    // The first copy is damaged, possibly in the fields that give its size,
+    // so look for the second copy after every size the first could have had.
+    for copy_size in first_size.into_iter().chain(copy_sizes()) {
+        if !fill(reader, buffer, copy_size + FrameHeaderV2::BASE_SIZE)? {
+            break;
+        }
+        let prefix_size = FrameHeaderV2::header_prefix_size(&buffer[copy_size..]).unwrap_or(0);
+        if !fill(reader, buffer, copy_size + prefix_size)? {
+            continue;
+        }
+        if FrameHeaderV2::header_size(&buffer[copy_size..]) != Ok(2 * copy_size)
+            || !fill(reader, buffer, 2 * copy_size)?
+        {
+            continue;
+        }
+        let Ok(header) = FrameHeaderV2::decode_copy(
+            &mut &buffer[copy_size..2 * copy_size],
+            DecodeOptions::default(),
+        ) else {
+            continue;
+        };
+        if crc_matches(reader, buffer, &header, copy_size, 2 * copy_size, limits)? {
+            return Ok((header, HeaderCopy::Second, 2 * copy_size));
+        }
+    }
+
+    match first {
+        Some((header, size)) => Ok((header, HeaderCopy::First, size)),
+        None => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "No intact v2 frame header copy",
+        )),
+    }
+}
+
+fn crc_matches<R: Read>(
+    reader: &mut R,
+    buffer: &mut Vec<u8>,
+    header: &FrameHeaderV2,
+    copy_start: usize,
+    header_size: usize,
+    limits: &Limits,
+) -> io::Result<bool> {
+    if header.packet_crc32_value().is_none() {
+        return Ok(true);
+    }
+    // A copy announcing an oversized payload is treated as damaged rather
+    // than read, so a corrupt length cannot force a large allocation.
+    if limits.check_payload_len(header.payload_size()).is_err() {
+        return Ok(false);
+    }
+    let frame_end = header_size + header.payload_size() as usize;
+    if !fill(reader, buffer, frame_end)? {
+        return Ok(false);
+    }
+    Ok(header
+        .verify_packet_crc32(&buffer[copy_start..], &buffer[header_size..frame_end])
+        .unwrap_or(false))
+}
+
+// Every size a single header copy can have, smallest first.
+fn copy_sizes() -> impl Iterator<Item = usize> {
+    let mut sizes = Vec::new();
+    for extended in [0, FrameHeaderV2::EXTENDED_SIZE_BYTES] {
+        for id in [0, 4, 8] {
+            for pts in [0, 8] {
+                // No extension word, or one with any mix of the timecode,
+                // user data, gapless trim, sample offset and auth tag.
+                let extensions = (0..=36)
+                    .step_by(2)
+                    .map(|fields| FrameHeaderV2::EXTENSION_WORD_BYTES + fields);
+                for extensions in std::iter::once(0).chain(extensions) {
+                    for crc in [0, 4] {
+                        sizes.push(
+                            FrameHeaderV2::BASE_SIZE + extended + id + pts + extensions + crc,
+                        );
+                    }
+                }
+            }
+        }
+    }
+    sizes.sort_unstable();
+    sizes.dedup();
+    sizes.into_iter()
+}
+
+/// Reads from `reader` until `buffer` holds `len` bytes, without reading
+/// past them. Returns false if the stream ends first.
+pub(crate) fn fill<R: Read>(reader: &mut R, buffer: &mut Vec<u8>, len: usize) -> io::Result<bool> {
+    while buffer.len() < len {
+        let start = buffer.len();
+        buffer.resize(len, 0);
+        match reader.read(&mut buffer[start..]) {
+            Ok(0) => {
+                buffer.truncate(start);
+                return Ok(false);
+            }
+            Ok(read) => buffer.truncate(start + read),
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => buffer.truncate(start),
+            Err(err) => {
+                buffer.truncate(start);
+                return Err(err);
+            }
+        }
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EncodingFlag, Endianness, FrameReader, FrameWriter};
+
+    const PAYLOAD: [u8; 6] = [1, 2, 3, 4, 5, 6];
+
+    fn redundant_header(with_crc: bool) -> FrameHeaderV2 {
+        let header = FrameHeaderV2::new(
+            EncodingFlag::Opus,
+            PAYLOAD.len() as u32,
+            960,
+            48000,
+            2,
+            0,
+            Endianness::LittleEndian,
+            Some(7),
+            Some(96_000),
+            None,
+        )
+        .unwrap()
+        .with_redundant_copy(true);
+        if with_crc {
+            header.with_packet_crc32(&PAYLOAD).unwrap()
+        } else {
+            header
+        }
+    }
+
+    fn frame(header: &FrameHeaderV2) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        header.encode(&mut bytes).unwrap();
+        bytes.extend_from_slice(&PAYLOAD);
+        bytes
+    }
+
+    #[test]
+    fn test_redundant_encoding_layout() {
+        let header = redundant_header(true);
+        let plain = header.clone().with_redundant_copy(false);
+        // Each copy carries an extension word with the redundancy bit.
+        let copy_size = plain.size() + FrameHeaderV2::EXTENSION_WORD_BYTES;
+        assert_eq!(header.size(), 2 * copy_size);
+
+        let bytes = frame(&header);
+        assert_eq!(bytes[..copy_size], bytes[copy_size..2 * copy_size]);
+        assert_eq!(bytes[8..12], 1u32.to_be_bytes());
+        assert_eq!(FrameHeaderV2::header_size(&bytes), Ok(header.size()));
+        assert_eq!(FrameHeaderV2::extract_pts(&bytes), Ok(Some(96_000)));
+
+        // The size needs the extension word as well as the base header.
+        assert_eq!(FrameHeaderV2::header_prefix_size(&bytes), Ok(12));
+        assert_eq!(
+            FrameHeaderV2::header_size(&bytes[..8]),
+            Err("Need 12 bytes to read the v2 header size, got 8".to_string())
+        );
+        assert_eq!(
+            FrameHeaderV2::validate_header_detailed(&bytes[..8]),
+            Err(FrameHeaderError::Truncated)
+        );
+
+        let decoded = FrameHeaderV2::decode(&mut &bytes[..]).unwrap();
+        assert_eq!(decoded, header);
+        assert!(decoded.is_redundant());
+        assert!(decoded
+            .verify_packet_crc32(&bytes, &bytes[header.size()..])
+            .unwrap());
+
+        let mut little = Vec::new();
+        header
+            .encode_with(&mut little, crate::WireByteOrder::LittleEndian)
+            .unwrap();
+        assert_eq!(little.len(), header.size());
+        assert_eq!(
+            FrameHeaderV2::decode_with(&mut &little[..], crate::WireByteOrder::LittleEndian)
+                .unwrap(),
+            header
+        );
+
+        // Plain v2 readers see an unknown version rather than a short header.
+        let mut plain_bytes = Vec::new();
+        plain.encode(&mut plain_bytes).unwrap();
+        assert_ne!(bytes[0], plain_bytes[0]);
+        assert_eq!(bytes[12..copy_size], plain_bytes[8..]);
+    }
+
+    #[test]
+    fn test_recovers_from_either_damaged_copy() {
+        for with_crc in [false, true] {
+            let header = redundant_header(with_crc);
+            let copy_size = header.size() / 2;
+            let intact = frame(&header);
+
+            let decode = |bytes: &[u8]| {
+                FrameHeaderV2::decode_redundant(bytes).map(|(header, copy, _)| (header, copy))
+            };
+            assert_eq!(decode(&intact), Ok((header.clone(), HeaderCopy::First)));
+
+            // Magic in the first copy.
+            let mut bytes = intact.clone();
+            bytes[0] ^= 0xFF;
+            assert_eq!(decode(&bytes), Ok((header.clone(), HeaderCopy::Second)));
+
+            // Version 3 to 2 in the first copy. Its redundancy bit still
+            // disagrees, so it does not pass for a whole plain header.
+            let mut bytes = intact.clone();
+            bytes[0] ^= 0x01;
+            assert!(FrameHeaderV2::decode(&mut &bytes[..]).is_err());
+            assert_eq!(decode(&bytes), Ok((header.clone(), HeaderCopy::Second)));
+
+            // The redundancy bit in the first copy.
+            let mut bytes = intact.clone();
+            bytes[11] ^= 0x01;
+            assert_eq!(decode(&bytes), Ok((header.clone(), HeaderCopy::Second)));
+
+            // Flags in the first copy, changing its apparent size.
+            let mut bytes = intact.clone();
+            bytes[1] ^= 0x01;
+            assert_eq!(decode(&bytes), Ok((header.clone(), HeaderCopy::Second)));
+
+            // Second copy.
+            let mut bytes = intact.clone();
+            bytes[copy_size] ^= 0xFF;
+            bytes[copy_size + 10] ^= 0xFF;
+            assert_eq!(decode(&bytes), Ok((header.clone(), HeaderCopy::First)));
+
+            // Both copies.
+            let mut bytes = intact.clone();
+            bytes[0] ^= 0xFF;
+            bytes[copy_size] ^= 0xFF;
+            assert!(decode(&bytes).is_err());
+        }
+    }
+
+    #[test]
+    fn test_crc_catches_damage_that_still_decodes() {
+        let header = redundant_header(true);
+        let copy_size = header.size() / 2;
+        let pts_offset = FrameHeaderV2::pts_offset(&frame(&header)).unwrap().unwrap();
+
+        let mut bytes = frame(&header);
+        bytes[pts_offset + 7] ^= 0x01;
+        assert!(FrameHeaderV2::decode(&mut &bytes[..]).is_ok());
+        assert_eq!(
+            FrameHeaderV2::decode_redundant(&bytes),
+            Ok((header.clone(), HeaderCopy::Second, header.size()))
+        );
+
+        bytes[copy_size + pts_offset + 7] ^= 0x01;
+        assert!(FrameHeaderV2::decode_redundant(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_frame_reader_counts_recoveries() {
+        let mut writer = FrameWriter::new(Vec::new());
+        for _ in 0..3 {
+            writer
+                .write_frame(&redundant_header(true), &PAYLOAD)
+                .unwrap();
+        }
+        let mut bytes = writer.into_inner();
+        let frame_size = bytes.len() / 3;
+        let copy_size = redundant_header(true).size() / 2;
+        bytes[0] ^= 0xFF;
+        bytes[frame_size + copy_size + 3] ^= 0xFF;
+        bytes[2 * frame_size + 1] ^= 0x04;
+
+        let mut reader = FrameReader::new(&bytes[..]);
+        for _ in 0..3 {
+            let (header, payload) = reader.read_frame().unwrap().unwrap();
+            assert_eq!(header, redundant_header(true));
+            assert_eq!(payload, PAYLOAD);
+        }
+        assert!(reader.read_frame().unwrap().is_none());
+        assert_eq!(reader.recoveries(), 2);
+        assert_eq!(reader.position(), bytes.len() as u64);
+    }
+}
@@ -0,0 +1,194 @@
+use crate::{FrameHeader, FrameHeaderError};
+
+// Validates the base header and returns its ID and PTS flags, checking that
+// the buffer holds the fields they announce.
+fn optional_fields(header_bytes: &[u8]) -> Result<(bool, bool), FrameHeaderError> {
+    FrameHeader::validate_header_with_reason(header_bytes)?;
+    let word = u32::from_be_bytes(header_bytes[..4].try_into().unwrap());
+    let has_id = word & FrameHeader::ID_MASK != 0;
+    let has_pts = word & FrameHeader::PTS_MASK != 0;
+    if header_bytes.len() < 4 + 8 * (has_id as usize + has_pts as usize) {
+        return Err(FrameHeaderError::Truncated);
+    }
+    Ok((has_id, has_pts))
+}
+
+// Inserts or removes the 8-byte field at `offset` so its presence matches
+// `wanted`. Bytes after the field, such as a payload, move with it.
+fn resize_field(header_bytes: &mut Vec<u8>, offset: usize, present: bool, wanted: bool) {
+    match (present, wanted) {
+        (false, true) => {
+            header_bytes.splice(offset..offset, [0; 8]);
+        }
+        (true, false) => {
+            header_bytes.drain(offset..offset + 8);
+        }
+        _ => {}
+    }
+}
+
+impl FrameHeader {
+    /// Like `patch_pts`, but inserts or removes the PTS field when its
+    /// presence changes.
+    pub fn patch_pts_reallocate(
+        header_bytes: &mut Vec<u8>,
+        pts: Option<u64>,
+    ) -> Result<(), FrameHeaderError> {
+        let (has_id, has_pts) = optional_fields(header_bytes)?;
+        resize_field(
+            header_bytes,
+            4 + 8 * has_id as usize,
+            has_pts,
+            pts.is_some(),
+        );
+        Ok(Self::patch_pts(header_bytes, pts)?)
+    }
+
+    /// Like `patch_id`, but inserts or removes the ID field when its presence
+    /// changes, keeping any PTS after it.
+    pub fn patch_id_reallocate(
+        header_bytes: &mut Vec<u8>,
+        id: Option<u64>,
+    ) -> Result<(), FrameHeaderError> {
+        let (has_id, _) = optional_fields(header_bytes)?;
+        resize_field(header_bytes, 4, has_id, id.is_some());
+        Ok(Self::patch_id(header_bytes, id)?)
+    }
+
+    /// Sets the PTS of buffer `i` to `start_pts + i * frame_duration_us`. All
+    /// buffers are validated first, so none change on error.
+    pub fn batch_patch_pts(
+        buffers: &mut [Vec<u8>],
+        start_pts: u64,
+        frame_duration_us: u64,
+    ) -> Result<(), FrameHeaderError> {
+        for buffer in buffers.iter() {
+            optional_fields(buffer)?;
+        }
+        if let Some(last) = buffers.len().checked_sub(1) {
+            (last as u64)
+                .checked_mul(frame_duration_us)
+                .and_then(|offset| start_pts.checked_add(offset))
+                .ok_or(FrameHeaderError::FieldOutOfRange("pts"))?;
+        }
+
+        for (i, buffer) in buffers.iter_mut().enumerate() {
+            let pts = start_pts + i as u64 * frame_duration_us;
+            Self::patch_pts_reallocate(buffer, Some(pts))?;
+        }
+        Ok(())
+    }
+
+    /// Sets the ID of buffer `i` to `start_id + i`. All buffers are validated
+    /// first, so none change on error.
+    pub fn batch_patch_id_sequential(
+        buffers: &mut [Vec<u8>],
+        start_id: u64,
+    ) -> Result<(), FrameHeaderError> {
+        for buffer in buffers.iter() {
+            optional_fields(buffer)?;
+        }
+        if let Some(last) = buffers.len().checked_sub(1) {
+            start_id
+                .checked_add(last as u64)
+                .ok_or(FrameHeaderError::FieldOutOfRange("id"))?;
+        }
+
+        for (i, buffer) in buffers.iter_mut().enumerate() {
+            Self::patch_id_reallocate(buffer, Some(start_id + i as u64))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EncodingFlag, Endianness};
+
+    fn buffers(count: usize) -> Vec<Vec<u8>> {
+        (0..count)
+            .map(|i| {
+                // Mix headers with and without the optional fields.
+                let header = FrameHeader::new(
+                    EncodingFlag::Opus,
+                    960,
+                    48000,
+                    2,
+                    16,
+                    Endianness::LittleEndian,
+                    (i % 2 == 0).then_some(99),
+                    (i % 3 == 0).then_some(5),
+                )
+                .unwrap();
+                let mut buffer = Vec::new();
+                header.encode(&mut buffer).unwrap();
+                buffer.extend_from_slice(&[0xAB; 3]);
+                buffer
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_batch_patch_pts_and_ids() {
+        let mut frames = buffers(10);
+        FrameHeader::batch_patch_pts(&mut frames, 1_000_000, 20_000).unwrap();
+        FrameHeader::batch_patch_id_sequential(&mut frames, 500).unwrap();
+
+        for (i, pts, id) in [
+            (0, 1_000_000, 500),
+            (1, 1_020_000, 501),
+            (9, 1_180_000, 509),
+        ] {
+            let frame = &frames[i];
+            assert_eq!(FrameHeader::extract_pts(frame), Ok(Some(pts)));
+            assert_eq!(FrameHeader::extract_id(frame), Ok(Some(id)));
+            assert_eq!(frame.len(), 20 + 3);
+            assert_eq!(&frame[20..], &[0xAB; 3]);
+            let header = FrameHeader::decode(&mut &frame[..]).unwrap();
+            assert_eq!(header.samples_per_channel(), 960);
+        }
+    }
+
+    #[test]
+    fn test_reallocate_removes_fields() {
+        let mut frame = buffers(1).remove(0);
+        FrameHeader::patch_id_reallocate(&mut frame, None).unwrap();
+        assert_eq!(FrameHeader::extract_pts(&frame), Ok(Some(5)));
+        FrameHeader::patch_pts_reallocate(&mut frame, None).unwrap();
+        assert_eq!(frame.len(), 4 + 3);
+        assert_eq!(FrameHeader::extract_id(&frame), Ok(None));
+        assert_eq!(FrameHeader::extract_pts(&frame), Ok(None));
+    }
+
+    #[test]
+    fn test_batch_patch_errors_leave_buffers_untouched() {
+        let mut frames = buffers(3);
+        frames[2][0] = 0;
+        let before = frames.clone();
+        assert!(matches!(
+            FrameHeader::batch_patch_pts(&mut frames, 0, 20_000),
+            Err(FrameHeaderError::InvalidMagic(_))
+        ));
+        assert!(FrameHeader::batch_patch_id_sequential(&mut frames, 0).is_err());
+        assert_eq!(frames, before);
+
+        let mut frames = buffers(3);
+        frames[0].truncate(10);
+        assert_eq!(
+            FrameHeader::batch_patch_pts(&mut frames, 0, 1),
+            Err(FrameHeaderError::Truncated)
+        );
+
+        let mut frames = buffers(3);
+        assert_eq!(
+            FrameHeader::batch_patch_pts(&mut frames, u64::MAX - 1, 1),
+            Err(FrameHeaderError::FieldOutOfRange("pts"))
+        );
+        assert_eq!(
+            FrameHeader::batch_patch_id_sequential(&mut frames, u64::MAX - 1),
+            Err(FrameHeaderError::FieldOutOfRange("id"))
+        );
+        assert_eq!(FrameHeader::batch_patch_pts(&mut [], u64::MAX, 1), Ok(()));
+    }
+}
@@ -0,0 +1,38 @@
+use crate::FrameHeaderV2;
+
+/// Bytes skipped to get back in sync after a corrupt record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResyncEvent {
+    /// Offset of the first skipped byte, relative to the decoded input.
+    pub start_offset: u64,
+    pub skipped_bytes: u64,
+    /// The header decoding resumed at, or `None` if the input ended first.
+    pub next_valid_header: Option<FrameHeaderV2>,
+}
+
+/// Called on every resync, for example to log where capture data goes bad.
+pub trait ResyncObserver {
+    fn on_resync(&mut self, event: &ResyncEvent);
+}
+
+impl<F: FnMut(&ResyncEvent)> ResyncObserver for F {
+    fn on_resync(&mut self, event: &ResyncEvent) {
+        self(event)
+    }
+}
+
+/// Running totals over resync events.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ResyncStats {
+    pub skipped_bytes: u64,
+    pub resyncs: u64,
+    pub largest_gap: u64,
+}
+
+impl ResyncStats {
+    pub(crate) fn record(&mut self, event: &ResyncEvent) {
+        self.skipped_bytes += event.skipped_bytes;
+        self.resyncs += 1;
+        self.largest_gap = self.largest_gap.max(event.skipped_bytes);
+    }
+}
@@ -0,0 +1,391 @@
+use crate::{packet_crc32, FrameHeaderError, FrameHeaderV2};
+use std::io::{self, Cursor, Read, Seek, SeekFrom, Write};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RetimePolicy {
+    Clamp,
+    Error,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct PtsRange {
+    pub min: u64,
+    pub max: u64,
+}
+
+impl PtsRange {
+    fn include(range: &mut Option<PtsRange>, pts: u64) {
+        *range = Some(match *range {
+            Some(range) => PtsRange {
+                min: range.min.min(pts),
+                max: range.max.max(pts),
+            },
+            None => PtsRange { min: pts, max: pts },
+        });
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct RetimeSummary {
+    pub frames: u64,
+    pub frames_without_pts: u64,
+    pub clamped: u64,
+    pub before: Option<PtsRange>,
+    pub after: Option<PtsRange>,
+}
+
+/// Shifts the PTS of every v2 frame from the current position to the end of
+/// the stream. Only the PTS bytes (and the packet CRC32, when present) are
+/// rewritten. Under `RetimePolicy::Error` the stream is left untouched if any
+/// shifted PTS would fall outside the u64 range. A signed frame with a PTS
+/// fails the whole call, also before anything is written, since only its key
+/// could re-sign it.
+pub fn retime_stream<S: Read + Write + Seek>(
+    stream: &mut S,
+    offset: i64,
+    policy: RetimePolicy,
+) -> io::Result<RetimeSummary> {
+    let start = stream.stream_position()?;
+    let end = stream.seek(SeekFrom::End(0))?;
+    let mut summary = RetimeSummary::default();
+
+    walk_frames(stream, start, end, |pts| {
+        summary.frames += 1;
+        match pts {
+            Some(pts) => PtsRange::include(&mut summary.before, pts),
+            None => summary.frames_without_pts += 1,
+        }
+        None
+    })?;
+
+    if let (RetimePolicy::Error, Some(range)) = (policy, summary.before) {
+        for pts in [range.min, range.max] {
+            if pts.checked_add_signed(offset).is_none() {
+                return Err(FrameHeaderError::PtsOutOfRange { pts, offset }.into());
+            }
+        }
+    }
+
+    walk_frames(stream, start, end, |pts| {
+        let shifted = pts?.checked_add_signed(offset).unwrap_or_else(|| {
+            summary.clamped += 1;
+            if offset < 0 {
+                0
+            } else {
+                u64::MAX
+            }
+        });
+        PtsRange::include(&mut summary.after, shifted);
+        Some(shifted)
+    })?;
+
+    stream.seek(SeekFrom::Start(end))?;
+    Ok(summary)
+}
+
+pub fn retime_slice(
+    bytes: &mut [u8],
+    offset: i64,
+    policy: RetimePolicy,
+) -> Result<RetimeSummary, FrameHeaderError> {
+    Ok(retime_stream(&mut Cursor::new(bytes), offset, policy)?)
+}
+
+fn walk_frames<S: Read + Write + Seek>(
+    stream: &mut S,
+    start: u64,
+    end: u64,
+    mut visit: impl FnMut(Option<u64>) -> Option<u64>,
+) -> io::Result<()> {
+    let invalid = |err: String| io::Error::new(io::ErrorKind::InvalidData, err);
+    let mut header = [0u8; FrameHeaderV2::MAX_SIZE];
+    let mut frame_start = start;
+
+    while frame_start < end {
+        stream.seek(SeekFrom::Start(frame_start))?;
+        stream.read_exact(&mut header[..FrameHeaderV2::BASE_SIZE])?;
+        let prefix_size = FrameHeaderV2::header_prefix_size(&header).map_err(invalid)?;
+        stream.read_exact(&mut header[FrameHeaderV2::BASE_SIZE..prefix_size])?;
+        let header_size = FrameHeaderV2::header_size(&header).map_err(invalid)?;
+        stream.read_exact(&mut header[prefix_size..header_size])?;
+        let header_bytes = &mut header[..header_size];
+
+        let payload_size = FrameHeaderV2::extract_payload_size(header_bytes).map_err(invalid)?;
+        let next_frame = frame_start + header_size as u64 + payload_size as u64;
+        if next_frame > end {
+            return Err(FrameHeaderError::Truncated.into());
+        }
+
+        let pts_offset = FrameHeaderV2::pts_offset(header_bytes).map_err(invalid)?;
+        let pts = pts_offset
+            .map(|offset| u64::from_be_bytes(header_bytes[offset..offset + 8].try_into().unwrap()));
+        if pts.is_some()
+            && FrameHeaderV2::extract_auth_tag(header_bytes)
+                .map_err(invalid)?
+                .is_some()
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "v2 auth tag covers the PTS; re-sign the frame",
+            ));
+        }
+
+        if let (Some(pts_offset), Some(new_pts)) = (pts_offset, visit(pts)) {
+            // A redundant header carries the PTS and CRC in both copies.
+            let copy_size = FrameHeaderV2::header_copy_size(header_bytes).map_err(invalid)?;
+            let mut crc = None;
+            if FrameHeaderV2::has_packet_crc32(header_bytes).map_err(invalid)? {
+                header_bytes[pts_offset..pts_offset + 8].copy_from_slice(&new_pts.to_be_bytes());
+                let mut payload = vec![0u8; payload_size as usize];
+                stream.seek(SeekFrom::Start(frame_start + header_size as u64))?;
+                stream.read_exact(&mut payload)?;
+                crc = Some(packet_crc32(&header_bytes[..copy_size - 4], &payload));
+            }
+
+            for copy_start in (0..header_size).step_by(copy_size) {
+                let copy_start = frame_start + copy_start as u64;
+                stream.seek(SeekFrom::Start(copy_start + pts_offset as u64))?;
+                stream.write_all(&new_pts.to_be_bytes())?;
+                if let Some(crc) = crc {
+                    stream.seek(SeekFrom::Start(copy_start + copy_size as u64 - 4))?;
+                    stream.write_all(&crc.to_be_bytes())?;
+                }
+            }
+        }
+
+        frame_start = next_frame;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EncodingFlag, Endianness};
+
+    fn push_frame(
+        stream: &mut Vec<u8>,
+        id: Option<u64>,
+        pts: Option<u64>,
+        payload: &[u8],
+        with_crc: bool,
+    ) {
+        let mut header = FrameHeaderV2::new(
+            EncodingFlag::Opus,
+            payload.len() as u32,
+            960,
+            48000,
+            2,
+            0,
+            Endianness::LittleEndian,
+            id,
+            pts,
+            None,
+        )
+        .unwrap();
+        if with_crc {
+            header = header.with_packet_crc32(payload).unwrap();
+        }
+        header.encode(stream).unwrap();
+        stream.extend_from_slice(payload);
+    }
+
+    fn test_stream() -> Vec<u8> {
+        let mut stream = Vec::new();
+        push_frame(&mut stream, None, Some(1_000), &[1; 10], false);
+        push_frame(&mut stream, Some(7), Some(1_960), &[2; 3], false);
+        push_frame(
+            &mut stream,
+            Some(u64::MAX),
+            Some(2_920),
+            &[3; 70_000],
+            false,
+        );
+        push_frame(&mut stream, Some(9), None, &[4; 5], false);
+        push_frame(&mut stream, Some(10), Some(3_880), &[5; 8], true);
+        stream
+    }
+
+    fn decode_all(mut bytes: &[u8]) -> Vec<(FrameHeaderV2, Vec<u8>)> {
+        let mut frames = Vec::new();
+        while !bytes.is_empty() {
+            let header = FrameHeaderV2::decode(&mut bytes).unwrap();
+            let (payload, rest) = bytes.split_at(header.payload_size() as usize);
+            frames.push((header, payload.to_vec()));
+            bytes = rest;
+        }
+        frames
+    }
+
+    #[test]
+    fn test_retime_slice_shifts_only_pts() {
+        let original = test_stream();
+        let mut retimed = original.clone();
+
+        let summary = retime_slice(&mut retimed, -500, RetimePolicy::Error).unwrap();
+        assert_eq!(summary.frames, 5);
+        assert_eq!(summary.frames_without_pts, 1);
+        assert_eq!(summary.clamped, 0);
+        assert_eq!(
+            summary.before,
+            Some(PtsRange {
+                min: 1_000,
+                max: 3_880
+            })
+        );
+        assert_eq!(
+            summary.after,
+            Some(PtsRange {
+                min: 500,
+                max: 3_380
+            })
+        );
+
+        let before = decode_all(&original);
+        let after = decode_all(&retimed);
+        assert_eq!(retimed.len(), original.len());
+        for ((old, old_payload), (new, new_payload)) in before.iter().zip(&after) {
+            assert_eq!(new.pts(), old.pts().map(|pts| pts - 500));
+            assert_eq!(new.id(), old.id());
+            assert_eq!(new_payload, old_payload);
+        }
+
+        let (crc_header, _) = &after[4];
+        let crc_frame_start = retimed.len() - crc_header.size() - 8;
+        assert!(crc_header
+            .verify_packet_crc32(&retimed[crc_frame_start..], &[5; 8])
+            .unwrap());
+    }
+
+    #[test]
+    fn test_retime_patches_both_redundant_copies() {
+        let header = FrameHeaderV2::new(
+            EncodingFlag::Opus,
+            4,
+            960,
+            48000,
+            2,
+            0,
+            Endianness::LittleEndian,
+            Some(3),
+            Some(10_000),
+            None,
+        )
+        .unwrap()
+        .with_redundant_copy(true)
+        .with_packet_crc32(&[6; 4])
+        .unwrap();
+        let mut stream = Vec::new();
+        header.encode(&mut stream).unwrap();
+        stream.extend_from_slice(&[6; 4]);
+        push_frame(&mut stream, None, Some(11_000), &[7; 2], true);
+
+        retime_slice(&mut stream, 2_000, RetimePolicy::Error).unwrap();
+
+        let copy_size = header.size() / 2;
+        assert_eq!(stream[..copy_size], stream[copy_size..2 * copy_size]);
+        let (retimed, copy, header_size) = FrameHeaderV2::decode_redundant(&stream).unwrap();
+        assert_eq!(copy, crate::HeaderCopy::First);
+        assert_eq!(retimed.pts(), Some(12_000));
+        assert!(retimed
+            .verify_packet_crc32(&stream[copy_size..], &stream[header_size..header_size + 4])
+            .unwrap());
+        assert_eq!(decode_all(&stream)[1].0.pts(), Some(13_000));
+    }
+
+    #[test]
+    fn test_retime_refuses_signed_frames() {
+        let signed = |pts| {
+            FrameHeaderV2::new(
+                EncodingFlag::Opus,
+                2,
+                960,
+                48000,
+                2,
+                0,
+                Endianness::LittleEndian,
+                None,
+                pts,
+                None,
+            )
+            .unwrap()
+            .with_auth_tag(Some([0x5A; 16]))
+        };
+
+        // A tagged frame without a PTS has nothing to rewrite.
+        let mut stream = test_stream();
+        signed(None).encode(&mut stream).unwrap();
+        stream.extend_from_slice(&[6; 2]);
+        retime_slice(&mut stream, 100, RetimePolicy::Error).unwrap();
+
+        for policy in [RetimePolicy::Error, RetimePolicy::Clamp] {
+            let mut stream = test_stream();
+            signed(Some(5_000)).encode(&mut stream).unwrap();
+            stream.extend_from_slice(&[6; 2]);
+            let original = stream.clone();
+            let err = retime_stream(&mut Cursor::new(&mut stream), 100, policy).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+            assert_eq!(
+                err.to_string(),
+                "v2 auth tag covers the PTS; re-sign the frame"
+            );
+            assert_eq!(stream, original);
+        }
+    }
+
+    #[test]
+    fn test_retime_underflow_policy() {
+        let original = test_stream();
+
+        let mut rejected = original.clone();
+        assert_eq!(
+            retime_slice(&mut rejected, -1_001, RetimePolicy::Error),
+            Err(FrameHeaderError::PtsOutOfRange {
+                pts: 1_000,
+                offset: -1_001
+            })
+        );
+        assert_eq!(rejected, original);
+
+        let mut clamped = original.clone();
+        let summary = retime_slice(&mut clamped, -2_000, RetimePolicy::Clamp).unwrap();
+        assert_eq!(summary.clamped, 2);
+        assert_eq!(summary.after, Some(PtsRange { min: 0, max: 1_880 }));
+        let pts: Vec<_> = decode_all(&clamped)
+            .iter()
+            .map(|(header, _)| header.pts())
+            .collect();
+        assert_eq!(pts, [Some(0), Some(0), Some(920), None, Some(1_880)]);
+    }
+
+    #[test]
+    fn test_retime_stream_from_current_position() {
+        let mut prefix = Vec::new();
+        push_frame(&mut prefix, None, Some(5), &[0; 4], false);
+        let mut bytes = prefix.clone();
+        bytes.extend_from_slice(&test_stream());
+
+        let mut stream = Cursor::new(bytes);
+        stream.set_position(prefix.len() as u64);
+        let summary = retime_stream(&mut stream, 40, RetimePolicy::Error).unwrap();
+        assert_eq!(
+            summary.after,
+            Some(PtsRange {
+                min: 1_040,
+                max: 3_920
+            })
+        );
+
+        let frames = decode_all(stream.get_ref());
+        assert_eq!(frames[0].0.pts(), Some(5));
+        assert_eq!(frames[1].0.pts(), Some(1_040));
+
+        let mut truncated = test_stream();
+        truncated.pop();
+        assert_eq!(
+            retime_slice(&mut truncated, 1, RetimePolicy::Error),
+            Err(FrameHeaderError::Truncated)
+        );
+    }
+}
@@ -0,0 +1,368 @@
+use crate::{EncodingFlag, Endianness, FrameHeader, FrameHeaderError};
+
+const RTP_VERSION: u8 = 2;
+const RTP_FIXED_HEADER_SIZE: usize = 12;
+const OPUS_RTP_CLOCK_RATE: u64 = 48000;
+// The first dynamic payload type (RFC 3551).
+const DEFAULT_PAYLOAD_TYPE: u8 = 96;
+
+/// Fields of an RTP fixed header (RFC 3550).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RtpHeaderFields {
+    pub marker: bool,
+    pub payload_type: u8,
+    pub sequence_number: u16,
+    pub timestamp: u32,
+    pub ssrc: u32,
+}
+
+impl RtpHeaderFields {
+    /// Parses the fixed header and returns the payload, skipping any CSRC
+    /// list and header extension and dropping trailing padding.
+    pub fn parse(rtp_packet: &[u8]) -> Result<(RtpHeaderFields, &[u8]), FrameHeaderError> {
+        if rtp_packet.len() < RTP_FIXED_HEADER_SIZE {
+            return Err(FrameHeaderError::Truncated);
+        }
+        if rtp_packet[0] >> 6 != RTP_VERSION {
+            return Err(FrameHeaderError::FieldOutOfRange("rtp_version"));
+        }
+        let padding = rtp_packet[0] & 0x20 != 0;
+        let extension = rtp_packet[0] & 0x10 != 0;
+        let csrc_count = (rtp_packet[0] & 0x0F) as usize;
+
+        let fields = RtpHeaderFields {
+            marker: rtp_packet[1] & 0x80 != 0,
+            payload_type: rtp_packet[1] & 0x7F,
+            sequence_number: u16::from_be_bytes([rtp_packet[2], rtp_packet[3]]),
+            timestamp: u32::from_be_bytes(rtp_packet[4..8].try_into().unwrap()),
+            ssrc: u32::from_be_bytes(rtp_packet[8..12].try_into().unwrap()),
+        };
+
+        let mut offset = RTP_FIXED_HEADER_SIZE + 4 * csrc_count;
+        if extension {
+            // 16-bit profile, then the extension length in 32-bit words.
+            let length = rtp_packet
+                .get(offset + 2..offset + 4)
+                .ok_or(FrameHeaderError::Truncated)?;
+            offset += 4 + 4 * u16::from_be_bytes([length[0], length[1]]) as usize;
+        }
+        let mut end = rtp_packet.len();
+        if padding {
+            let padding_len = *rtp_packet.last().unwrap() as usize;
+            if padding_len == 0 {
+                return Err(FrameHeaderError::Invalid(
+                    "RTP padding length is zero".to_string(),
+                ));
+            }
+            end = end.saturating_sub(padding_len);
+        }
+        if offset > end {
+            return Err(FrameHeaderError::Truncated);
+        }
+        Ok((fields, &rtp_packet[offset..end]))
+    }
+}
+
+// Samples per channel at 48kHz in an Opus packet, from its TOC byte and
+// frame count (RFC 6716 section 3.1).
+fn opus_packet_samples(payload: &[u8]) -> Result<u32, FrameHeaderError> {
+    let toc = *payload.first().ok_or(FrameHeaderError::Truncated)?;
+    let config = toc >> 3;
+    let frame_samples = match config {
+        0..=11 => [480, 960, 1920, 2880][config as usize % 4],
+        12..=15 => [480, 960][config as usize % 2],
+        _ => [120, 240, 480, 960][config as usize % 4],
+    };
+    let frames = match toc & 0x3 {
+        0 => 1,
+        1 | 2 => 2,
+        _ => match payload.get(1).ok_or(FrameHeaderError::Truncated)? & 0x3F {
+            0 => return Err(FrameHeaderError::FieldOutOfRange("frame_count")),
+            count => count as u32,
+        },
+    };
+    Ok(frame_samples * frames)
+}
+
+impl FrameHeader {
+    /// Reads an Opus RTP packet (RFC 7587) into a 48kHz stereo header, with
+    /// the sequence number as the ID and the RTP timestamp in µs as the PTS.
+    /// `sample_size` comes from the Opus TOC byte. Returns the Opus payload.
+    pub fn from_opus_rtp_packet(
+        rtp_packet: &[u8],
+    ) -> Result<(FrameHeader, &[u8]), FrameHeaderError> {
+        let (fields, payload) = RtpHeaderFields::parse(rtp_packet)?;
+        // Opus has no static payload type.
+        if !(96..=127).contains(&fields.payload_type) {
+            return Err(FrameHeaderError::FieldOutOfRange("payload_type"));
+        }
+
+        let sample_size = u16::try_from(opus_packet_samples(payload)?)
+            .map_err(|_| FrameHeaderError::FieldOutOfRange("sample_size"))?;
+        let pts = fields.timestamp as u64 * 1_000_000 / OPUS_RTP_CLOCK_RATE;
+        let header = FrameHeader::new(
+            EncodingFlag::Opus,
+            sample_size,
+            48000,
+            2,
+            16,
+            Endianness::LittleEndian,
+            Some(fields.sequence_number as u64),
+            Some(pts),
+        )?;
+        Ok((header, payload))
+    }
+
+    /// The RTP payload type Chrome negotiates for this header's codec, as in
+    /// `to_chrome_rtp_codec_params`, if it has one.
+    pub fn payload_type_u8(&self) -> Option<u8> {
+        self.to_chrome_rtp_codec_params()
+            .ok()
+            .map(|params| params.payload_type)
+    }
+
+    /// An RTP packet carrying this header and `payload`: a 12-byte fixed
+    /// header, the encoded header, then the payload. The timestamp is the
+    /// PTS in µs at the RTP clock rate, 48kHz for Opus and the sample rate
+    /// otherwise, or 0 without a PTS. The payload type is
+    /// `payload_type_u8`, or 96 if there is none.
+    pub fn wrap_in_rtp_packet(&self, payload: &[u8], ssrc: u32, seq: u16) -> Vec<u8> {
+        let clock_rate = match self.encoding {
+            EncodingFlag::Opus => OPUS_RTP_CLOCK_RATE,
+            _ => self.sample_rate as u64,
+        };
+        // RTP timestamps wrap at 32 bits.
+        let timestamp = self.pts.map_or(0, |pts| {
+            (pts as u128 * clock_rate as u128 / 1_000_000) as u32
+        });
+        let payload_type = self.payload_type_u8().unwrap_or(DEFAULT_PAYLOAD_TYPE);
+
+        let mut packet = Vec::with_capacity(RTP_FIXED_HEADER_SIZE + self.size() + payload.len());
+        packet.push(RTP_VERSION << 6);
+        packet.push(payload_type & 0x7F);
+        packet.extend_from_slice(&seq.to_be_bytes());
+        packet.extend_from_slice(&timestamp.to_be_bytes());
+        packet.extend_from_slice(&ssrc.to_be_bytes());
+        self.encode(&mut packet)
+            .expect("FrameHeader fields are validated on construction");
+        packet.extend_from_slice(payload);
+        packet
+    }
+
+    /// Reads a packet written by `wrap_in_rtp_packet`, returning the
+    /// sequence number, SSRC, header and payload.
+    pub fn unwrap_rtp_packet(
+        packet: &[u8],
+    ) -> Result<(u16, u32, FrameHeader, &[u8]), FrameHeaderError> {
+        let (fields, mut rtp_payload) = RtpHeaderFields::parse(packet)?;
+        let header = FrameHeader::decode(&mut rtp_payload)?;
+        Ok((fields.sequence_number, fields.ssrc, header, rtp_payload))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rtp_packet(first_byte: u8, extra: &[u8], payload: &[u8]) -> Vec<u8> {
+        let mut packet = vec![first_byte, 0x80 | 111, 0x12, 0x34];
+        packet.extend_from_slice(&96_000u32.to_be_bytes());
+        packet.extend_from_slice(&0xDEAD_BEEFu32.to_be_bytes());
+        packet.extend_from_slice(extra);
+        packet.extend_from_slice(payload);
+        packet
+    }
+
+    // CELT fullband 20ms, one frame.
+    const OPUS_20MS: [u8; 4] = [0xFC, 0x01, 0x02, 0x03];
+
+    #[test]
+    fn test_from_opus_rtp_packet() {
+        let packet = rtp_packet(0x80, &[], &OPUS_20MS);
+        let (header, payload) = FrameHeader::from_opus_rtp_packet(&packet).unwrap();
+        assert_eq!(payload, OPUS_20MS);
+        assert_eq!(header.encoding(), EncodingFlag::Opus);
+        assert_eq!(header.samples_per_channel(), 960);
+        assert_eq!(header.sample_rate(), 48000);
+        assert_eq!(header.channels(), 2);
+        assert_eq!(header.id(), Some(0x1234));
+        assert_eq!(header.pts(), Some(2_000_000));
+
+        let (fields, _) = RtpHeaderFields::parse(&packet).unwrap();
+        assert_eq!(
+            fields,
+            RtpHeaderFields {
+                marker: true,
+                payload_type: 111,
+                sequence_number: 0x1234,
+                timestamp: 96_000,
+                ssrc: 0xDEAD_BEEF,
+            }
+        );
+    }
+
+    #[test]
+    fn test_csrc_extension_and_padding() {
+        // Two CSRCs, a one-word extension and three bytes of padding.
+        let mut extra = vec![0; 8];
+        extra.extend_from_slice(&[0xBE, 0xDE, 0x00, 0x01, 0x10, 0xAA, 0x00, 0x00]);
+        let mut payload = OPUS_20MS.to_vec();
+        payload.extend_from_slice(&[0, 0, 3]);
+        let packet = rtp_packet(0x80 | 0x20 | 0x10 | 2, &extra, &payload);
+        let (header, payload) = FrameHeader::from_opus_rtp_packet(&packet).unwrap();
+        assert_eq!(payload, OPUS_20MS);
+        assert_eq!(header.samples_per_channel(), 960);
+    }
+
+    #[test]
+    fn test_opus_packet_durations() {
+        for (toc, count, samples) in [
+            (0x00, None, 480),        // SILK NB 10ms
+            (0x18, None, 2880),       // SILK NB 60ms
+            (0x68, None, 960),        // Hybrid SWB 20ms
+            (0x80, None, 120),        // CELT NB 2.5ms
+            (0xFD, None, 1920),       // CELT 20ms, two frames
+            (0xF3, Some(0x03), 1440), // CELT 10ms, three frames
+        ] {
+            let mut payload = vec![toc];
+            payload.extend(count);
+            assert_eq!(opus_packet_samples(&payload), Ok(samples), "{:#x}", toc);
+        }
+    }
+
+    #[test]
+    fn test_wrap_in_rtp_packet_layout() {
+        let header = FrameHeader::new(
+            EncodingFlag::Opus,
+            960,
+            48000,
+            2,
+            16,
+            Endianness::LittleEndian,
+            Some(7),
+            Some(2_000_000),
+        )
+        .unwrap();
+        let packet = header.wrap_in_rtp_packet(&OPUS_20MS, 0xDEAD_BEEF, 0x1234);
+
+        // V=2, P=0, X=0, CC=0; M=0, PT=111; sequence; timestamp; SSRC.
+        assert_eq!(
+            packet[..12],
+            [0x80, 111, 0x12, 0x34, 0x00, 0x01, 0x77, 0x00, 0xDE, 0xAD, 0xBE, 0xEF]
+        );
+        let mut encoded = Vec::new();
+        header.encode(&mut encoded).unwrap();
+        assert_eq!(packet[12..12 + encoded.len()], encoded);
+        assert_eq!(packet[12 + encoded.len()..], OPUS_20MS);
+
+        let (fields, _) = RtpHeaderFields::parse(&packet).unwrap();
+        assert_eq!(
+            fields,
+            RtpHeaderFields {
+                marker: false,
+                payload_type: 111,
+                sequence_number: 0x1234,
+                timestamp: 96_000,
+                ssrc: 0xDEAD_BEEF,
+            }
+        );
+        assert_eq!(
+            FrameHeader::unwrap_rtp_packet(&packet),
+            Ok((0x1234, 0xDEAD_BEEF, header, &OPUS_20MS[..]))
+        );
+    }
+
+    #[test]
+    fn test_wrap_in_rtp_packet_clock_and_payload_type() {
+        // L16 at 44.1kHz: the sample rate clock and payload type 96.
+        let pcm = FrameHeader::new(
+            EncodingFlag::PCMSigned,
+            441,
+            44100,
+            1,
+            16,
+            Endianness::BigEndian,
+            None,
+            Some(1_500_000),
+        )
+        .unwrap();
+        let packet = pcm.wrap_in_rtp_packet(&[0; 882], 1, 65535);
+        let (fields, _) = RtpHeaderFields::parse(&packet).unwrap();
+        assert_eq!(fields.payload_type, 96);
+        assert_eq!(fields.timestamp, 66_150);
+
+        // Codecs Chrome has no entry for also use 96, and no PTS means 0.
+        let flac = FrameHeader::new(
+            EncodingFlag::FLAC,
+            4095,
+            96000,
+            2,
+            24,
+            Endianness::LittleEndian,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(flac.payload_type_u8(), None);
+        let packet = flac.wrap_in_rtp_packet(&[1, 2], 9, 0);
+        assert_eq!(packet[1], 96);
+        assert_eq!(packet[4..8], [0; 4]);
+        let (seq, ssrc, decoded, payload) = FrameHeader::unwrap_rtp_packet(&packet).unwrap();
+        assert_eq!((seq, ssrc, payload), (0, 9, &[1, 2][..]));
+        assert_eq!(decoded, flac);
+
+        // The timestamp wraps at 32 bits.
+        let late = FrameHeader::new(
+            EncodingFlag::FLAC,
+            4095,
+            96000,
+            2,
+            24,
+            Endianness::LittleEndian,
+            None,
+            Some(50_000_000_000),
+        )
+        .unwrap();
+        let (fields, _) = RtpHeaderFields::parse(&late.wrap_in_rtp_packet(&[], 9, 0)).unwrap();
+        assert_eq!(fields.timestamp, (4_800_000_000u64 % (1 << 32)) as u32);
+
+        assert_eq!(
+            FrameHeader::unwrap_rtp_packet(&packet[..14]),
+            Err(FrameHeaderError::Truncated)
+        );
+    }
+
+    #[test]
+    fn test_rtp_errors() {
+        assert_eq!(
+            FrameHeader::from_opus_rtp_packet(&[0x80; 11]),
+            Err(FrameHeaderError::Truncated)
+        );
+        assert_eq!(
+            FrameHeader::from_opus_rtp_packet(&rtp_packet(0x40, &[], &OPUS_20MS)),
+            Err(FrameHeaderError::FieldOutOfRange("rtp_version"))
+        );
+        let mut packet = rtp_packet(0x80, &[], &OPUS_20MS);
+        packet[1] = 8;
+        assert_eq!(
+            FrameHeader::from_opus_rtp_packet(&packet),
+            Err(FrameHeaderError::FieldOutOfRange("payload_type"))
+        );
+        // CSRCs announced but missing.
+        assert_eq!(
+            FrameHeader::from_opus_rtp_packet(&rtp_packet(0x82, &[], &[0xFC])),
+            Err(FrameHeaderError::Truncated)
+        );
+        // No Opus TOC byte.
+        assert_eq!(
+            FrameHeader::from_opus_rtp_packet(&rtp_packet(0x80, &[], &[])),
+            Err(FrameHeaderError::Truncated)
+        );
+        // 120ms of audio does not fit the 12-bit sample size.
+        assert!(FrameHeader::from_opus_rtp_packet(&rtp_packet(0x80, &[], &[0x1B, 0x02])).is_err());
+        assert_eq!(
+            FrameHeader::from_opus_rtp_packet(&rtp_packet(0x80, &[], &[0x1B, 0x3F])),
+            Err(FrameHeaderError::FieldOutOfRange("sample_size"))
+        );
+    }
+}
@@ -0,0 +1,134 @@
+use crate::FrameHeaderV2;
+use std::borrow::Borrow;
+use std::collections::HashMap;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct SampleOffsetMismatch {
+    /// Position of the frame in the checked sequence.
+    pub index: usize,
+    pub id: Option<u64>,
+    pub expected: u64,
+    pub actual: u64,
+}
+
+/// Checks that each frame's sample offset is the previous offset plus the
+/// previous frame count within its stream, told apart by ID. Frames without
+/// a sample offset still advance the count; the first offset of a stream is
+/// taken as given, and checking carries on from each mismatched offset.
+pub fn validate_stream<I>(headers: I) -> Result<(), Vec<SampleOffsetMismatch>>
+where
+    I: IntoIterator,
+    I::Item: Borrow<FrameHeaderV2>,
+{
+    let mut expected: HashMap<Option<u64>, u64> = HashMap::new();
+    let mut mismatches = Vec::new();
+    for (index, header) in headers.into_iter().enumerate() {
+        let header = header.borrow();
+        let id = header.id();
+        let next = match (header.sample_offset(), expected.get(&id)) {
+            (Some(actual), Some(&expected)) if actual != expected => {
+                mismatches.push(SampleOffsetMismatch {
+                    index,
+                    id,
+                    expected,
+                    actual,
+                });
+                Some(actual)
+            }
+            (Some(actual), _) => Some(actual),
+            (None, expected) => expected.copied(),
+        };
+        if let Some(next) = next {
+            expected.insert(id, next.wrapping_add(header.frame_count() as u64));
+        }
+    }
+    if mismatches.is_empty() {
+        Ok(())
+    } else {
+        Err(mismatches)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EncodingFlag, Endianness, FrameReader, FrameWriter};
+
+    fn opus(id: u64, frame_count: u32) -> FrameHeaderV2 {
+        FrameHeaderV2::new(
+            EncodingFlag::Opus,
+            3,
+            frame_count,
+            48000,
+            2,
+            0,
+            Endianness::LittleEndian,
+            Some(id),
+            None,
+            None,
+        )
+        .unwrap()
+    }
+
+    fn stamped_stream() -> Vec<FrameHeaderV2> {
+        let mut writer = FrameWriter::new(Vec::new()).with_sample_offsets();
+        for index in 0..12u32 {
+            let header = opus(index as u64 % 2, 480 * (1 + index % 3));
+            let header = if index == 5 {
+                header.with_packet_crc32(&[1, 2, 3]).unwrap()
+            } else {
+                header
+            };
+            writer.write_frame(&header, &[1, 2, 3]).unwrap();
+        }
+        FrameReader::new(&writer.into_inner()[..])
+            .map(|frame| frame.unwrap().0)
+            .collect()
+    }
+
+    #[test]
+    fn test_writer_stamps_sample_offsets_per_id() {
+        let headers = stamped_stream();
+        let offsets: Vec<_> = headers
+            .iter()
+            .map(|header| header.sample_offset().unwrap())
+            .collect();
+        assert_eq!(
+            offsets,
+            [0, 0, 480, 960, 1920, 1440, 2880, 2880, 3360, 3840, 4800, 4320]
+        );
+        assert_eq!(
+            headers[5].packet_crc32_value(),
+            Some(headers[5].compute_packet_crc32(&[1, 2, 3]).unwrap())
+        );
+        assert_eq!(validate_stream(&headers), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_stream_catches_injected_offset() {
+        let mut headers = stamped_stream();
+        headers[6] = headers[6].clone().with_sample_offset(Some(2881));
+        assert_eq!(
+            validate_stream(&headers),
+            Err(vec![
+                SampleOffsetMismatch {
+                    index: 6,
+                    id: Some(0),
+                    expected: 2880,
+                    actual: 2881,
+                },
+                SampleOffsetMismatch {
+                    index: 8,
+                    id: Some(0),
+                    expected: 2881 + 480,
+                    actual: 3360,
+                },
+            ])
+        );
+
+        // A frame without an offset still counts towards the next one.
+        let mut headers = stamped_stream();
+        headers[2] = headers[2].clone().with_sample_offset(None);
+        assert_eq!(validate_stream(headers), Ok(()));
+    }
+}
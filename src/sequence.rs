@@ -0,0 +1,172 @@
+use crate::FrameHeader;
+use std::io::{self, Read, Write};
+
+// Forward jumps of up to this many packets count as loss, and backward
+// jumps of up to MAX_MISORDER as late or duplicate packets; anything
+// further is a sender restart. The limits RFC 3550 suggests for RTP.
+const MAX_DROPOUT: u32 = 3000;
+const MAX_MISORDER: u32 = 100;
+
+/// A header with the transport sequence number sent ahead of it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SequencedFrame {
+    pub seq: u32,
+    pub header: FrameHeader,
+}
+
+impl SequencedFrame {
+    pub fn encode<W: Write>(&self, writer: &mut W) -> io::Result<usize> {
+        self.header.encode_with_sequence_number(self.seq, writer)
+    }
+
+    pub fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let (seq, header) = FrameHeader::decode_with_sequence_number(reader)?;
+        Ok(SequencedFrame { seq, header })
+    }
+
+    /// Sequence numbers missing from `frames`, in the order they were
+    /// found, wrapping at `u32::MAX`. One that arrives late is no longer
+    /// missing. A jump of more than 3000 ahead, or more than 100 back, is
+    /// taken as a restart rather than loss.
+    pub fn detect_loss(frames: &[Self]) -> Vec<u32> {
+        let mut lost = Vec::new();
+        let Some(first) = frames.first() else {
+            return lost;
+        };
+        let mut expected = first.seq.wrapping_add(1);
+        for frame in &frames[1..] {
+            let ahead = frame.seq.wrapping_sub(expected);
+            if ahead <= MAX_DROPOUT {
+                lost.extend((0..ahead).map(|gap| expected.wrapping_add(gap)));
+                expected = frame.seq.wrapping_add(1);
+            } else if let Some(index) = lost.iter().position(|&seq| seq == frame.seq) {
+                lost.remove(index);
+            } else if expected.wrapping_sub(frame.seq) > MAX_MISORDER {
+                expected = frame.seq.wrapping_add(1);
+            }
+        }
+        lost
+    }
+}
+
+impl FrameHeader {
+    /// Writes `seq` as a big-endian u32 followed by the encoded header,
+    /// returning the bytes written. The sequence number is separate from the
+    /// header's ID.
+    pub fn encode_with_sequence_number<W: Write>(
+        &self,
+        seq: u32,
+        writer: &mut W,
+    ) -> io::Result<usize> {
+        let mut bytes = Vec::with_capacity(4 + self.size());
+        bytes.extend_from_slice(&seq.to_be_bytes());
+        self.encode(&mut bytes)?;
+        writer.write_all(&bytes)?;
+        Ok(bytes.len())
+    }
+
+    pub fn decode_with_sequence_number<R: Read>(reader: &mut R) -> io::Result<(u32, FrameHeader)> {
+        let mut seq = [0u8; 4];
+        reader.read_exact(&mut seq)?;
+        Ok((u32::from_be_bytes(seq), FrameHeader::decode(reader)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EncodingFlag, Endianness};
+
+    fn frame(seq: u32) -> SequencedFrame {
+        SequencedFrame {
+            seq,
+            header: FrameHeader::new(
+                EncodingFlag::Opus,
+                960,
+                48000,
+                2,
+                16,
+                Endianness::LittleEndian,
+                Some(3),
+                Some(seq as u64 * 20_000),
+            )
+            .unwrap(),
+        }
+    }
+
+    fn frames(seqs: &[u32]) -> Vec<SequencedFrame> {
+        seqs.iter().map(|&seq| frame(seq)).collect()
+    }
+
+    #[test]
+    fn test_sequence_number_roundtrip() {
+        let mut bytes = Vec::new();
+        let written = frame(0x0102_0304).encode(&mut bytes).unwrap();
+        assert_eq!(written, 4 + 20);
+        assert_eq!(bytes.len(), written);
+        assert_eq!(bytes[..4], [0x01, 0x02, 0x03, 0x04]);
+
+        let plain = FrameHeader::new(
+            EncodingFlag::PCMSigned,
+            480,
+            48000,
+            2,
+            16,
+            Endianness::LittleEndian,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(plain.encode_with_sequence_number(7, &mut bytes).unwrap(), 8);
+
+        let mut reader = &bytes[..];
+        assert_eq!(
+            SequencedFrame::decode(&mut reader).unwrap(),
+            frame(0x0102_0304)
+        );
+        assert_eq!(
+            FrameHeader::decode_with_sequence_number(&mut reader).unwrap(),
+            (7, plain)
+        );
+        assert!(reader.is_empty());
+        let err = SequencedFrame::decode(&mut &bytes[..3]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn test_detect_loss_gaps() {
+        assert_eq!(SequencedFrame::detect_loss(&[]), Vec::<u32>::new());
+        assert_eq!(
+            SequencedFrame::detect_loss(&frames(&[5, 6, 7, 8])),
+            Vec::<u32>::new()
+        );
+        assert_eq!(
+            SequencedFrame::detect_loss(&frames(&[10, 11, 14, 15, 17])),
+            [12, 13, 16]
+        );
+        // Across the wrap.
+        assert_eq!(
+            SequencedFrame::detect_loss(&frames(&[u32::MAX - 1, 1, 2])),
+            [u32::MAX, 0]
+        );
+    }
+
+    #[test]
+    fn test_detect_loss_reordering_and_restarts() {
+        // A late packet is no longer lost, and a duplicate is ignored.
+        assert_eq!(
+            SequencedFrame::detect_loss(&frames(&[1, 2, 5, 3, 6, 6, 2])),
+            [4]
+        );
+        // A sender restart far ahead or behind is not loss.
+        assert_eq!(
+            SequencedFrame::detect_loss(&frames(&[100, 101, 90_000, 90_002, 7, 8])),
+            [90_001]
+        );
+        // The largest gap still counted as loss.
+        assert_eq!(
+            SequencedFrame::detect_loss(&frames(&[0, MAX_DROPOUT + 1])).len(),
+            MAX_DROPOUT as usize
+        );
+    }
+}
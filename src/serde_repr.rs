@@ -0,0 +1,429 @@
+use crate::{encoding_from_code, EncodingFlag, Endianness};
+use serde::de::{self, Deserializer, Visitor};
+use serde::{Deserialize, Serialize, Serializer};
+use std::fmt;
+
+// Accepted spellings, compared case-insensitively. The first entry for each
+// value is the one it serializes as.
+const ENCODING_NAMES: [(EncodingFlag, &[&str]); 7] = [
+    (
+        EncodingFlag::PCMSigned,
+        &[
+            "pcm_signed",
+            "pcmsigned",
+            "pcm",
+            "pcm_s16",
+            "pcm_s24",
+            "pcm_s32",
+            "s16",
+            "s24",
+            "s32",
+        ],
+    ),
+    (
+        EncodingFlag::PCMFloat,
+        &["pcm_float", "pcmfloat", "pcm_f32", "f32", "float"],
+    ),
+    (EncodingFlag::Opus, &["opus"]),
+    (EncodingFlag::FLAC, &["flac"]),
+    (EncodingFlag::AAC, &["aac", "mp4a"]),
+    (EncodingFlag::H264, &["h264", "h.264", "avc"]),
+    (EncodingFlag::Metadata, &["metadata"]),
+];
+
+const ENDIANNESS_NAMES: [(Endianness, &[&str]); 2] = [
+    (
+        Endianness::LittleEndian,
+        &["le", "little", "little_endian", "littleendian"],
+    ),
+    (
+        Endianness::BigEndian,
+        &["be", "big", "big_endian", "bigendian"],
+    ),
+];
+
+const UNKNOWN_PREFIX: &str = "unknown:";
+
+impl EncodingFlag {
+    /// The lowercase name used by serde, or `None` for `Unknown`, which
+    /// serializes as `unknown:<code>`.
+    pub fn serde_name(&self) -> Option<&'static str> {
+        ENCODING_NAMES
+            .iter()
+            .find(|(encoding, _)| encoding == self)
+            .map(|(_, names)| names[0])
+    }
+
+    /// Looks up an encoding by its serde name or an alias, ignoring case.
+    pub fn from_serde_name(name: &str) -> Option<EncodingFlag> {
+        let name = name.to_ascii_lowercase();
+        if let Some(code) = name.strip_prefix(UNKNOWN_PREFIX) {
+            let code = code.parse::<u8>().ok()?;
+            return Some(encoding_from_code(code as u32).unwrap_or(EncodingFlag::Unknown(code)));
+        }
+        ENCODING_NAMES
+            .iter()
+            .find(|(_, names)| names.contains(&name.as_str()))
+            .map(|(encoding, _)| *encoding)
+    }
+}
+
+impl Endianness {
+    /// `"le"` or `"be"`, as used by serde.
+    pub fn serde_name(&self) -> &'static str {
+        match self {
+            Endianness::LittleEndian => "le",
+            Endianness::BigEndian => "be",
+        }
+    }
+
+    /// Looks up a byte order by its serde name or an alias, ignoring case.
+    pub fn from_serde_name(name: &str) -> Option<Endianness> {
+        let name = name.to_ascii_lowercase();
+        ENDIANNESS_NAMES
+            .iter()
+            .find(|(_, names)| names.contains(&name.as_str()))
+            .map(|(endianness, _)| *endianness)
+    }
+}
+
+// Compact formats such as bincode get the code as a u32, the same bytes as
+// the variant index the derived impls wrote before names, so data written
+// by 0.2 still decodes.
+impl Serialize for EncodingFlag {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if !serializer.is_human_readable() {
+            return serializer.serialize_u32(self.code() as u32);
+        }
+        match self.serde_name() {
+            Some(name) => serializer.serialize_str(name),
+            None => serializer.collect_str(&format_args!("{}{}", UNKNOWN_PREFIX, self.code())),
+        }
+    }
+}
+
+impl Serialize for Endianness {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if !serializer.is_human_readable() {
+            return serializer.serialize_u32(*self as u32);
+        }
+        serializer.serialize_str(self.serde_name())
+    }
+}
+
+struct NameVisitor<T> {
+    expecting: &'static str,
+    lookup: fn(&str) -> Option<T>,
+}
+
+impl<T> Visitor<'_> for NameVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(self.expecting)
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<T, E> {
+        (self.lookup)(value).ok_or_else(|| E::invalid_value(de::Unexpected::Str(value), &self))
+    }
+}
+
+impl<'de> Deserialize<'de> for EncodingFlag {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if !deserializer.is_human_readable() {
+            let code = u32::deserialize(deserializer)?;
+            return match u8::try_from(code) {
+                Ok(code) => {
+                    Ok(encoding_from_code(code as u32).unwrap_or(EncodingFlag::Unknown(code)))
+                }
+                Err(_) => Err(de::Error::invalid_value(
+                    de::Unexpected::Unsigned(code as u64),
+                    &"an encoding code up to 255",
+                )),
+            };
+        }
+        deserializer.deserialize_str(NameVisitor {
+            expecting: "an encoding name such as \"pcm_signed\" or \"opus\"",
+            lookup: EncodingFlag::from_serde_name,
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for Endianness {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if !deserializer.is_human_readable() {
+            return match u32::deserialize(deserializer)? {
+                0 => Ok(Endianness::LittleEndian),
+                1 => Ok(Endianness::BigEndian),
+                code => Err(de::Error::invalid_value(
+                    de::Unexpected::Unsigned(code as u64),
+                    &"0 or 1",
+                )),
+            };
+        }
+        deserializer.deserialize_str(NameVisitor {
+            expecting: "\"le\" or \"be\"",
+            lookup: Endianness::from_serde_name,
+        })
+    }
+}
+
+/// Numeric wire codes for `EncodingFlag`, for compact formats like bincode:
+/// `#[serde_as(as = "EncodingCode")]`.
+#[cfg(feature = "serde_with")]
+pub struct EncodingCode;
+
+/// Numeric representation of `Endianness`, 0 for little-endian and 1 for
+/// big-endian: `#[serde_as(as = "EndiannessCode")]`.
+#[cfg(feature = "serde_with")]
+pub struct EndiannessCode;
+
+#[cfg(feature = "serde_with")]
+impl serde_with::SerializeAs<EncodingFlag> for EncodingCode {
+    fn serialize_as<S: Serializer>(
+        source: &EncodingFlag,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(source.code())
+    }
+}
+
+#[cfg(feature = "serde_with")]
+impl<'de> serde_with::DeserializeAs<'de, EncodingFlag> for EncodingCode {
+    fn deserialize_as<D: Deserializer<'de>>(deserializer: D) -> Result<EncodingFlag, D::Error> {
+        let code = u8::deserialize(deserializer)?;
+        Ok(encoding_from_code(code as u32).unwrap_or(EncodingFlag::Unknown(code)))
+    }
+}
+
+#[cfg(feature = "serde_with")]
+impl serde_with::SerializeAs<Endianness> for EndiannessCode {
+    fn serialize_as<S: Serializer>(source: &Endianness, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(*source as u8)
+    }
+}
+
+#[cfg(feature = "serde_with")]
+impl<'de> serde_with::DeserializeAs<'de, Endianness> for EndiannessCode {
+    fn deserialize_as<D: Deserializer<'de>>(deserializer: D) -> Result<Endianness, D::Error> {
+        match u8::deserialize(deserializer)? {
+            0 => Ok(Endianness::LittleEndian),
+            1 => Ok(Endianness::BigEndian),
+            code => Err(de::Error::invalid_value(
+                de::Unexpected::Unsigned(code as u64),
+                &"0 or 1",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FrameHeader;
+
+    const ENCODINGS: [EncodingFlag; 8] = [
+        EncodingFlag::PCMSigned,
+        EncodingFlag::PCMFloat,
+        EncodingFlag::Opus,
+        EncodingFlag::FLAC,
+        EncodingFlag::AAC,
+        EncodingFlag::H264,
+        EncodingFlag::Metadata,
+        EncodingFlag::Unknown(7),
+    ];
+
+    fn json_and_bincode<T>(value: &T) -> (T, T)
+    where
+        T: Serialize + for<'de> Deserialize<'de>,
+    {
+        let json = serde_json::to_string(value).unwrap();
+        let bytes = bincode::serialize(value).unwrap();
+        (
+            serde_json::from_str(&json).unwrap(),
+            bincode::deserialize(&bytes).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_serde_names() {
+        for (encoding, name) in ENCODINGS.iter().zip([
+            "pcm_signed",
+            "pcm_float",
+            "opus",
+            "flac",
+            "aac",
+            "h264",
+            "metadata",
+            "unknown:7",
+        ]) {
+            assert_eq!(
+                serde_json::to_string(encoding).unwrap(),
+                format!("\"{}\"", name)
+            );
+            assert_eq!(json_and_bincode(encoding), (*encoding, *encoding));
+        }
+        for (endianness, name) in [
+            (Endianness::LittleEndian, "le"),
+            (Endianness::BigEndian, "be"),
+        ] {
+            assert_eq!(
+                serde_json::to_string(&endianness).unwrap(),
+                format!("\"{}\"", name)
+            );
+            assert_eq!(json_and_bincode(&endianness), (endianness, endianness));
+        }
+
+        let header = FrameHeader::new(
+            EncodingFlag::H264,
+            960,
+            48000,
+            2,
+            16,
+            Endianness::BigEndian,
+            Some(1),
+            None,
+        )
+        .unwrap();
+        let json = serde_json::to_string(&header).unwrap();
+        assert!(json.contains("\"encoding\":\"h264\""), "{}", json);
+        assert!(json.contains("\"endianness\":\"be\""), "{}", json);
+        assert_eq!(json_and_bincode(&header), (header.clone(), header));
+    }
+
+    #[test]
+    fn test_serde_aliases() {
+        for (encoding, names) in ENCODING_NAMES {
+            for name in names {
+                for name in [name.to_string(), name.to_ascii_uppercase()] {
+                    let json = serde_json::to_string(&name).unwrap();
+                    assert_eq!(
+                        serde_json::from_str::<EncodingFlag>(&json).unwrap(),
+                        encoding
+                    );
+                }
+            }
+        }
+        for (endianness, names) in ENDIANNESS_NAMES {
+            for name in names {
+                for name in [name.to_string(), name.to_ascii_uppercase()] {
+                    let json = serde_json::to_string(&name).unwrap();
+                    assert_eq!(
+                        serde_json::from_str::<Endianness>(&json).unwrap(),
+                        endianness
+                    );
+                }
+            }
+        }
+
+        // Variant names from before the lowercase representation still parse.
+        assert_eq!(
+            serde_json::from_str::<EncodingFlag>("\"PCMSigned\"").unwrap(),
+            EncodingFlag::PCMSigned
+        );
+        assert_eq!(
+            serde_json::from_str::<Endianness>("\"LittleEndian\"").unwrap(),
+            Endianness::LittleEndian
+        );
+        // A known code written as unknown comes back as its named variant.
+        assert_eq!(
+            EncodingFlag::from_serde_name("unknown:2"),
+            Some(EncodingFlag::Opus)
+        );
+    }
+
+    #[test]
+    fn test_bincode_matches_0_2() {
+        // `bincode::serialize` of this header by frame-header 0.2, whose
+        // derived impls wrote variant indexes.
+        #[rustfmt::skip]
+        let baseline: &[u8] = &[
+            5, 0, 0, 0,
+            0xC0, 0x03,
+            0x80, 0xBB, 0, 0,
+            2,
+            16,
+            1, 0, 0, 0,
+            1, 1, 0, 0, 0, 0, 0, 0, 0,
+            0,
+        ];
+        let header = FrameHeader::new(
+            EncodingFlag::H264,
+            960,
+            48000,
+            2,
+            16,
+            Endianness::BigEndian,
+            Some(1),
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            bincode::deserialize::<FrameHeader>(baseline).unwrap(),
+            header
+        );
+        assert_eq!(bincode::serialize(&header).unwrap(), baseline);
+
+        for encoding in ENCODINGS {
+            assert_eq!(
+                bincode::serialize(&encoding).unwrap(),
+                (encoding.code() as u32).to_le_bytes()
+            );
+        }
+        assert!(bincode::deserialize::<EncodingFlag>(&[0, 1, 0, 0]).is_err());
+        assert!(bincode::deserialize::<Endianness>(&[2, 0, 0, 0]).is_err());
+    }
+
+    #[test]
+    fn test_serde_rejects_unknown_names() {
+        for name in ["\"pcm_u8\"", "\"unknown:256\"", "\"unknown:\"", "\"\"", "2"] {
+            assert!(
+                serde_json::from_str::<EncodingFlag>(name).is_err(),
+                "{}",
+                name
+            );
+        }
+        for name in ["\"native\"", "\"l\"", "0"] {
+            assert!(
+                serde_json::from_str::<Endianness>(name).is_err(),
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[cfg(feature = "serde_with")]
+    #[test]
+    fn test_numeric_representation() {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Compact {
+            #[serde(with = "serde_with::As::<EncodingCode>")]
+            encoding: EncodingFlag,
+            #[serde(with = "serde_with::As::<EndiannessCode>")]
+            endianness: Endianness,
+        }
+
+        for encoding in ENCODINGS {
+            for endianness in [Endianness::LittleEndian, Endianness::BigEndian] {
+                let value = Compact {
+                    encoding,
+                    endianness,
+                };
+                let bytes = bincode::serialize(&value).unwrap();
+                assert_eq!(bytes, [encoding.code(), endianness as u8]);
+                assert_eq!(bincode::deserialize::<Compact>(&bytes).unwrap(), value);
+                let json = serde_json::to_string(&value).unwrap();
+                assert_eq!(
+                    json,
+                    format!(
+                        "{{\"encoding\":{},\"endianness\":{}}}",
+                        encoding.code(),
+                        endianness as u8
+                    )
+                );
+                assert_eq!(serde_json::from_str::<Compact>(&json).unwrap(), value);
+            }
+        }
+        assert!(bincode::deserialize::<Compact>(&[0, 2]).is_err());
+    }
+}
@@ -0,0 +1,117 @@
+use crate::{FrameHeader, FrameHeaderV2};
+
+// Signed PTS values are stored in the unsigned PTS field as their
+// two's-complement bit pattern, so -1 is u64::MAX and i64::MIN is 1 << 63.
+// Non-negative values are unchanged, so unsigned readers see the same PTS
+// for frames at or after zero.
+
+impl FrameHeader {
+    pub fn pts_signed(&self) -> Option<i64> {
+        self.pts.map(|pts| pts as i64)
+    }
+
+    pub fn with_pts_signed(mut self, pts: Option<i64>) -> Self {
+        self.pts = pts.map(|pts| pts as u64);
+        self
+    }
+
+    pub fn extract_pts_signed(header_bytes: &[u8]) -> Result<Option<i64>, String> {
+        Ok(Self::extract_pts(header_bytes)?.map(|pts| pts as i64))
+    }
+
+    pub fn patch_pts_signed(header_bytes: &mut [u8], pts: Option<i64>) -> Result<(), String> {
+        Self::patch_pts(header_bytes, pts.map(|pts| pts as u64))
+    }
+}
+
+impl FrameHeaderV2 {
+    pub fn pts_signed(&self) -> Option<i64> {
+        self.pts.map(|pts| pts as i64)
+    }
+
+    pub fn with_pts_signed(mut self, pts: Option<i64>) -> Self {
+        self.pts = pts.map(|pts| pts as u64);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{EncodingFlag, Endianness, FrameHeader, FrameHeaderV2};
+
+    fn header() -> FrameHeader {
+        FrameHeader::new(
+            EncodingFlag::Opus,
+            960,
+            48000,
+            2,
+            16,
+            Endianness::LittleEndian,
+            None,
+            None,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_signed_pts_mapping() {
+        for (signed, unsigned) in [
+            (0, 0),
+            (1, 1),
+            (-1, u64::MAX),
+            (i64::MIN, 1 << 63),
+            (i64::MAX, (1 << 63) - 1),
+        ] {
+            let header = header().with_pts_signed(Some(signed));
+            assert_eq!(header.pts_signed(), Some(signed));
+            assert_eq!(header.pts(), Some(unsigned));
+
+            let mut bytes = Vec::new();
+            header.encode(&mut bytes).unwrap();
+            assert_eq!(FrameHeader::extract_pts_signed(&bytes), Ok(Some(signed)));
+            assert_eq!(FrameHeader::decode(&mut &bytes[..]).unwrap(), header);
+
+            let v2 = FrameHeaderV2::new(
+                EncodingFlag::Opus,
+                10,
+                960,
+                48000,
+                2,
+                0,
+                Endianness::LittleEndian,
+                None,
+                None,
+                None,
+            )
+            .unwrap()
+            .with_pts_signed(Some(signed));
+            let mut bytes = Vec::new();
+            v2.encode(&mut bytes).unwrap();
+            let decoded = FrameHeaderV2::decode(&mut &bytes[..]).unwrap();
+            assert_eq!(decoded.pts_signed(), Some(signed));
+            assert_eq!(decoded.pts(), Some(unsigned));
+        }
+        assert_eq!(header().with_pts_signed(None).pts_signed(), None);
+    }
+
+    #[test]
+    fn test_patch_pts_signed() {
+        let mut bytes = Vec::new();
+        header()
+            .with_pts_signed(Some(0))
+            .encode(&mut bytes)
+            .unwrap();
+
+        FrameHeader::patch_pts_signed(&mut bytes, Some(-960)).unwrap();
+        assert_eq!(FrameHeader::extract_pts_signed(&bytes), Ok(Some(-960)));
+        assert_eq!(FrameHeader::extract_pts(&bytes), Ok(Some((-960i64) as u64)));
+
+        // Pre-roll frames order before zero once read back as signed.
+        let mut pts = [-1920, 0, -960, 960].map(|pts| header().with_pts_signed(Some(pts)));
+        pts.sort_by_key(|header| header.pts_signed());
+        assert_eq!(
+            pts.map(|header| header.pts_signed().unwrap()),
+            [-1920, -960, 0, 960]
+        );
+    }
+}
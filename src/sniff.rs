@@ -0,0 +1,137 @@
+use crate::{EncodingFlag, FrameHeader};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Magic {
+    Ogg,
+    Flac,
+    Mp3,
+    Mp4,
+}
+
+fn sniff_magic(payload: &[u8]) -> Option<Magic> {
+    match payload {
+        [b'O', b'g', b'g', b'S', ..] => Some(Magic::Ogg),
+        [b'f', b'L', b'a', b'C', ..] => Some(Magic::Flac),
+        [0xFF, 0xFB | 0xF3, ..] => Some(Magic::Mp3),
+        // The box size comes first, commonly 0x20.
+        [_, _, _, _, b'f', b't', b'y', b'p', ..] => Some(Magic::Mp4),
+        _ => None,
+    }
+}
+
+impl FrameHeader {
+    /// The encoding suggested by magic bytes at the start of `payload`: Ogg
+    /// is taken as Opus, the only Ogg codec an `EncodingFlag` names, and an
+    /// MP4 `ftyp` box as AAC. MP3 is recognized but has no `EncodingFlag`,
+    /// so gives `None`, as does a payload with no known magic.
+    pub fn sniff_payload_encoding(payload: &[u8]) -> Option<EncodingFlag> {
+        match sniff_magic(payload)? {
+            Magic::Ogg => Some(EncodingFlag::Opus),
+            Magic::Flac => Some(EncodingFlag::FLAC),
+            Magic::Mp3 => None,
+            Magic::Mp4 => Some(EncodingFlag::AAC),
+        }
+    }
+
+    /// Whether `payload` starts with magic bytes for this header's encoding,
+    /// or `None` if it starts with none that are known. Raw codec packets
+    /// and PCM carry no magic, so only a containerised payload, such as a
+    /// PCM header over an Ogg page, can be caught.
+    pub fn encoding_matches_payload(&self, payload: &[u8]) -> Option<bool> {
+        sniff_magic(payload)?;
+        Some(Self::sniff_payload_encoding(payload) == Some(self.encoding))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Endianness;
+
+    // The first page of an Ogg Opus stream, up to its OpusHead packet.
+    const OGG_OPUS: [u8; 36] = [
+        0x4F, 0x67, 0x67, 0x53, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x6B,
+        0x8B, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xC1, 0x5E, 0x7D, 0x3E, 0x01, 0x13, 0x4F, 0x70,
+        0x75, 0x73, 0x48, 0x65, 0x61, 0x64,
+    ];
+    // "fLaC" and a STREAMINFO metadata block header.
+    const FLAC: [u8; 8] = [0x66, 0x4C, 0x61, 0x43, 0x00, 0x00, 0x00, 0x22];
+    // MPEG-1 Layer III at 128kbps, 44.1kHz, and MPEG-2 Layer III.
+    const MP3: [u8; 4] = [0xFF, 0xFB, 0x90, 0x64];
+    const MP3_MPEG2: [u8; 4] = [0xFF, 0xF3, 0x48, 0xC4];
+    // An `ftyp` box with major brand M4A.
+    const MP4: [u8; 12] = [
+        0x00, 0x00, 0x00, 0x20, 0x66, 0x74, 0x79, 0x70, 0x4D, 0x34, 0x41, 0x20,
+    ];
+
+    fn header(encoding: EncodingFlag) -> FrameHeader {
+        FrameHeader::new(
+            encoding,
+            960,
+            48000,
+            2,
+            16,
+            Endianness::LittleEndian,
+            None,
+            None,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_sniff_payload_encoding() {
+        assert_eq!(
+            FrameHeader::sniff_payload_encoding(&OGG_OPUS),
+            Some(EncodingFlag::Opus)
+        );
+        assert_eq!(
+            FrameHeader::sniff_payload_encoding(&FLAC),
+            Some(EncodingFlag::FLAC)
+        );
+        assert_eq!(
+            FrameHeader::sniff_payload_encoding(&MP4),
+            Some(EncodingFlag::AAC)
+        );
+        assert_eq!(FrameHeader::sniff_payload_encoding(&MP3), None);
+        // PCM samples, an Opus TOC byte, short payloads and the Ogg magic
+        // cut short.
+        for payload in [
+            &[0x12, 0x34, 0x56, 0x78][..],
+            &[0xFC, 0xFF],
+            &[],
+            &OGG_OPUS[..3],
+        ] {
+            assert_eq!(FrameHeader::sniff_payload_encoding(payload), None);
+        }
+    }
+
+    #[test]
+    fn test_encoding_matches_payload() {
+        let pcm = header(EncodingFlag::PCMSigned);
+        for payload in [&OGG_OPUS[..], &FLAC, &MP3, &MP3_MPEG2, &MP4] {
+            assert_eq!(pcm.encoding_matches_payload(payload), Some(false));
+        }
+        assert_eq!(pcm.encoding_matches_payload(&[0; 16]), None);
+
+        assert_eq!(
+            header(EncodingFlag::Opus).encoding_matches_payload(&OGG_OPUS),
+            Some(true)
+        );
+        assert_eq!(
+            header(EncodingFlag::FLAC).encoding_matches_payload(&FLAC),
+            Some(true)
+        );
+        assert_eq!(
+            header(EncodingFlag::AAC).encoding_matches_payload(&MP4),
+            Some(true)
+        );
+        assert_eq!(
+            header(EncodingFlag::AAC).encoding_matches_payload(&MP3),
+            Some(false)
+        );
+        assert_eq!(
+            header(EncodingFlag::Opus).encoding_matches_payload(&FLAC),
+            Some(false)
+        );
+    }
+}
@@ -0,0 +1,563 @@
+use crate::redundant::{fill, read_header_recovering, HeaderCopy};
+#[cfg(feature = "auth")]
+use crate::AuthPolicy;
+use crate::{DecodeError, FrameHeaderV2, Limits};
+use std::collections::HashMap;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+
+pub struct FrameWriter<W: Write> {
+    writer: W,
+    position: u64,
+    // Sample frames written so far per stream ID, when stamping offsets.
+    sample_offsets: Option<HashMap<Option<u64>, u64>>,
+}
+
+impl<W: Write> FrameWriter<W> {
+    pub fn new(writer: W) -> Self {
+        FrameWriter {
+            writer,
+            position: 0,
+            sample_offsets: None,
+        }
+    }
+
+    pub fn with_position(writer: W, position: u64) -> Self {
+        FrameWriter {
+            writer,
+            position,
+            sample_offsets: None,
+        }
+    }
+
+    /// Sets each written header's sample offset to the frame counts written
+    /// before it with the same ID, recomputing any packet CRC.
+    pub fn with_sample_offsets(mut self) -> Self {
+        self.sample_offsets = Some(HashMap::new());
+        self
+    }
+
+    /// Returns the stream offset the frame was written at.
+    pub fn write_frame(&mut self, header: &FrameHeaderV2, payload: &[u8]) -> io::Result<u64> {
+        if header.payload_size() as usize != payload.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Payload length does not match header payload size",
+            ));
+        }
+
+        let stamped;
+        let header = match &mut self.sample_offsets {
+            Some(offsets) => {
+                let written = offsets.entry(header.id()).or_insert(0);
+                let mut next = header.clone().with_sample_offset(Some(*written));
+                if next.packet_crc32_value().is_some() {
+                    next = next
+                        .with_packet_crc32(payload)
+                        .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+                }
+                *written += header.frame_count() as u64;
+                stamped = next;
+                &stamped
+            }
+            None => header,
+        };
+
+        let offset = self.position;
+        let mut header_bytes = Vec::with_capacity(header.size());
+        header.encode(&mut header_bytes)?;
+        self.writer.write_all(&header_bytes)?;
+        self.writer.write_all(payload)?;
+        self.position += (header_bytes.len() + payload.len()) as u64;
+        Ok(offset)
+    }
+
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+pub struct FrameReader<R: Read> {
+    reader: R,
+    position: u64,
+    // Bytes read past a frame while searching for a redundant header copy.
+    pending: Vec<u8>,
+    recoveries: u64,
+    limits: Limits,
+    // Frames read or skipped so far, for error positions.
+    frames: u64,
+    #[cfg(feature = "auth")]
+    auth: Option<(Vec<u8>, AuthPolicy)>,
+    #[cfg(feature = "auth")]
+    auth_failures: u64,
+}
+
+impl<R: Read> FrameReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self::with_position(reader, 0)
+    }
+
+    pub fn with_position(reader: R, position: u64) -> Self {
+        FrameReader {
+            reader,
+            position,
+            pending: Vec::new(),
+            recoveries: 0,
+            limits: Limits::default(),
+            frames: 0,
+            #[cfg(feature = "auth")]
+            auth: None,
+            #[cfg(feature = "auth")]
+            auth_failures: 0,
+        }
+    }
+
+    pub fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Verifies each frame read by `read_frame` with `FrameHeaderV2::verify`,
+    /// handling failures by `policy`. Skipped frames are not checked.
+    #[cfg(feature = "auth")]
+    pub fn with_auth_key(mut self, key: &[u8], policy: AuthPolicy) -> Self {
+        self.auth = Some((key.to_vec(), policy));
+        self
+    }
+
+    /// Frames that failed verification, whether rejected or marked.
+    #[cfg(feature = "auth")]
+    pub fn auth_failures(&self) -> u64 {
+        self.auth_failures
+    }
+
+    /// Returns `Ok(None)` on a clean end of stream between frames. A
+    /// redundant header whose first copy is damaged is read from its second.
+    /// A payload over the limit is `InvalidData` wrapping `LimitExceeded`.
+    /// Errors carry the frame's position as a `DecodeError`.
+    pub fn read_frame(&mut self) -> io::Result<Option<(FrameHeaderV2, Vec<u8>)>> {
+        let (frame, offset) = (self.frames, self.position);
+        let at = |err, field| DecodeError::wrap(err, frame, offset, field);
+        #[cfg_attr(not(feature = "auth"), allow(unused_mut))]
+        let Some((mut header, header_size, mut buffer)) =
+            self.read_header().map_err(|e| at(e, None))?
+        else {
+            return Ok(None);
+        };
+        self.limits
+            .check_payload_len(header.payload_size())
+            .map_err(|e| at(e.into(), None))?;
+        let frame_size = header_size + header.payload_size() as usize;
+        if !fill(&mut self.reader, &mut buffer, frame_size).map_err(|e| at(e, Some("payload")))? {
+            return Err(at(payload_eof(), Some("payload")));
+        }
+        self.pending = buffer.split_off(frame_size);
+        let payload = buffer.split_off(header_size);
+        self.position += frame_size as u64;
+        self.frames += 1;
+        #[cfg(feature = "auth")]
+        if let Some((key, policy)) = &self.auth {
+            if let Err(err) = FrameHeaderV2::verify(&buffer, &payload, key) {
+                self.auth_failures += 1;
+                match policy {
+                    AuthPolicy::Reject => {
+                        let err = io::Error::new(io::ErrorKind::InvalidData, err);
+                        return Err(at(err, Some("auth_tag")));
+                    }
+                    AuthPolicy::Mark => header = header.with_payload_corrupt(true),
+                }
+            }
+        }
+        Ok(Some((header, payload)))
+    }
+
+    /// Reads the next header and discards its payload through a small
+    /// buffer, returning the frame's offset with it. Use `HeadersOnly` to
+    /// seek past payloads instead.
+    pub fn skip_frame(&mut self) -> io::Result<Option<(u64, FrameHeaderV2)>> {
+        self.skip_frame_with(|reader, len| {
+            let skipped = io::copy(&mut reader.by_ref().take(len), &mut io::sink())?;
+            if skipped < len {
+                return Err(payload_eof());
+            }
+            Ok(())
+        })
+    }
+
+    /// Iterates over `skip_frame`, without allocating payload buffers.
+    pub fn headers_with_offsets(
+        &mut self,
+    ) -> impl Iterator<Item = io::Result<(u64, FrameHeaderV2)>> + '_ {
+        std::iter::from_fn(|| self.skip_frame().transpose())
+    }
+
+    fn skip_frame_with(
+        &mut self,
+        skip: impl FnOnce(&mut R, u64) -> io::Result<()>,
+    ) -> io::Result<Option<(u64, FrameHeaderV2)>> {
+        let (frame, offset) = (self.frames, self.position);
+        let at = |err, field| DecodeError::wrap(err, frame, offset, field);
+        let Some((header, header_size, mut buffer)) =
+            self.read_header().map_err(|e| at(e, None))?
+        else {
+            return Ok(None);
+        };
+        // Recovery may already have buffered part of the payload, or more.
+        let frame_size = header_size + header.payload_size() as usize;
+        if buffer.len() >= frame_size {
+            self.pending = buffer.split_off(frame_size);
+        } else {
+            skip(&mut self.reader, (frame_size - buffer.len()) as u64)
+                .map_err(|e| at(e, Some("payload")))?;
+        }
+        self.position += frame_size as u64;
+        self.frames += 1;
+        Ok(Some((offset, header)))
+    }
+
+    // Returns the header, its size and the bytes read so far, which start
+    // with the header.
+    fn read_header(&mut self) -> io::Result<Option<(FrameHeaderV2, usize, Vec<u8>)>> {
+        let mut buffer = std::mem::take(&mut self.pending);
+        if !fill(&mut self.reader, &mut buffer, FrameHeaderV2::BASE_SIZE)? {
+            if buffer.is_empty() {
+                return Ok(None);
+            }
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "Stream ended inside a frame header",
+            ));
+        }
+
+        let (header, copy, header_size) =
+            read_header_recovering(&mut self.reader, &mut buffer, &self.limits)?;
+        if copy == HeaderCopy::Second {
+            self.recoveries += 1;
+        }
+        Ok(Some((header, header_size, buffer)))
+    }
+
+    /// Frames whose header was recovered from its redundant second copy.
+    pub fn recoveries(&self) -> u64 {
+        self.recoveries
+    }
+
+    /// Offset of the next frame, relative to where the reader started.
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: Read> Iterator for FrameReader<R> {
+    type Item = io::Result<(FrameHeaderV2, Vec<u8>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_frame().transpose()
+    }
+}
+
+fn payload_eof() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::UnexpectedEof,
+        "Stream ended inside a frame payload",
+    )
+}
+
+/// Yields each frame's offset and header, seeking past payloads so only
+/// header bytes are read. A truncated final payload is not detected, as
+/// seeking past the end of a stream succeeds.
+pub struct HeadersOnly<R: Read + Seek> {
+    frames: FrameReader<R>,
+}
+
+impl<R: Read + Seek> HeadersOnly<R> {
+    pub fn new(reader: R) -> Self {
+        Self::with_position(reader, 0)
+    }
+
+    /// `position` is the offset reported for the first frame, as in
+    /// `FrameReader::with_position`.
+    pub fn with_position(reader: R, position: u64) -> Self {
+        HeadersOnly {
+            frames: FrameReader::with_position(reader, position),
+        }
+    }
+
+    pub fn with_limits(mut self, limits: Limits) -> Self {
+        self.frames = self.frames.with_limits(limits);
+        self
+    }
+
+    pub fn next_header(&mut self) -> io::Result<Option<(u64, FrameHeaderV2)>> {
+        self.frames.skip_frame_with(|reader, len| {
+            reader.seek(SeekFrom::Current(len as i64))?;
+            Ok(())
+        })
+    }
+
+    pub fn recoveries(&self) -> u64 {
+        self.frames.recoveries()
+    }
+
+    pub fn position(&self) -> u64 {
+        self.frames.position()
+    }
+
+    pub fn into_inner(self) -> R {
+        self.frames.into_inner()
+    }
+}
+
+impl<R: Read + Seek> Iterator for HeadersOnly<R> {
+    type Item = io::Result<(u64, FrameHeaderV2)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_header().transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EncodingFlag, Endianness};
+
+    fn opus_header(payload_len: usize, pts: u64) -> FrameHeaderV2 {
+        FrameHeaderV2::new(
+            EncodingFlag::Opus,
+            payload_len as u32,
+            960,
+            48000,
+            2,
+            0,
+            Endianness::LittleEndian,
+            Some(1),
+            Some(pts),
+            None,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_frame_writer_reader_roundtrip() {
+        let mut writer = FrameWriter::new(Vec::new());
+        let mut offsets = Vec::new();
+        for index in 0..4u64 {
+            let payload = vec![index as u8; 10 + index as usize];
+            offsets.push(
+                writer
+                    .write_frame(&opus_header(payload.len(), index * 960), &payload)
+                    .unwrap(),
+            );
+        }
+        let bytes = writer.into_inner();
+
+        let mut reader = FrameReader::new(&bytes[..]);
+        for (index, &offset) in offsets.iter().enumerate() {
+            assert_eq!(reader.position(), offset);
+            let (header, payload) = reader.read_frame().unwrap().unwrap();
+            assert_eq!(header.pts(), Some(index as u64 * 960));
+            assert_eq!(payload, vec![index as u8; 10 + index]);
+        }
+        assert!(reader.read_frame().unwrap().is_none());
+        assert_eq!(reader.position(), bytes.len() as u64);
+
+        assert!(FrameReader::new(&bytes[..bytes.len() - 1])
+            .collect::<io::Result<Vec<_>>>()
+            .is_err());
+        assert!(FrameReader::new(&bytes[..3]).read_frame().is_err());
+    }
+
+    // Counts the bytes handed out by `read`.
+    struct CountingReader<R> {
+        inner: R,
+        bytes_read: u64,
+    }
+
+    impl<R: Read> Read for CountingReader<R> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let read = self.inner.read(buf)?;
+            self.bytes_read += read as u64;
+            Ok(read)
+        }
+    }
+
+    impl<R: Seek> Seek for CountingReader<R> {
+        fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+            self.inner.seek(pos)
+        }
+    }
+
+    fn large_stream(frames: u64, payload_len: usize) -> (Vec<u8>, Vec<(u64, FrameHeaderV2)>) {
+        let mut writer = FrameWriter::new(Vec::new());
+        let mut expected = Vec::new();
+        for index in 0..frames {
+            let header = opus_header(payload_len, index * 960);
+            let payload = vec![index as u8; payload_len];
+            expected.push((writer.write_frame(&header, &payload).unwrap(), header));
+        }
+        (writer.into_inner(), expected)
+    }
+
+    #[test]
+    fn test_headers_only_seeks_past_payloads() {
+        let (bytes, expected) = large_stream(2000, 4000);
+        let header_size = expected[0].1.size() as u64;
+
+        let mut headers = HeadersOnly::new(CountingReader {
+            inner: io::Cursor::new(&bytes),
+            bytes_read: 0,
+        });
+        let decoded = headers.by_ref().collect::<io::Result<Vec<_>>>().unwrap();
+        assert_eq!(decoded, expected);
+        assert_eq!(headers.position(), bytes.len() as u64);
+        assert_eq!(headers.into_inner().bytes_read, 2000 * header_size);
+    }
+
+    #[test]
+    fn test_skipping_after_header_recovery() {
+        // Checking a CRC reads the payload, and searching for a second copy
+        // can read past a short frame, so skipping must use those bytes.
+        let mut writer = FrameWriter::new(Vec::new());
+        for index in 0..6u64 {
+            let payload = vec![index as u8; index as usize];
+            let header = opus_header(payload.len(), index * 960)
+                .with_redundant_copy(true)
+                .with_packet_crc32(&payload)
+                .unwrap();
+            writer.write_frame(&header, &payload).unwrap();
+        }
+        let mut bytes = writer.into_inner();
+        for damaged in [0, 92, 113] {
+            bytes[damaged] ^= 0xFF;
+        }
+
+        let mut frames = FrameReader::new(&bytes[..]);
+        let mut expected = Vec::new();
+        while let Some((header, _)) = frames.read_frame().unwrap() {
+            expected.push(header);
+        }
+        assert_eq!(expected.len(), 6);
+        assert!(frames.recoveries() > 0);
+
+        let headers: Vec<_> = HeadersOnly::new(io::Cursor::new(&bytes))
+            .map(|frame| frame.unwrap().1)
+            .collect();
+        assert_eq!(headers, expected);
+        let headers: Vec<_> = FrameReader::new(&bytes[..])
+            .headers_with_offsets()
+            .map(|frame| frame.unwrap().1)
+            .collect();
+        assert_eq!(headers, expected);
+    }
+
+    #[test]
+    fn test_headers_with_offsets_without_seek() {
+        let (bytes, expected) = large_stream(50, 100_000);
+        let mut frames = FrameReader::new(&bytes[..]);
+        let decoded = frames
+            .headers_with_offsets()
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(decoded, expected);
+        assert_eq!(frames.position(), bytes.len() as u64);
+
+        // Skipping and reading frames can be mixed.
+        let mut frames = FrameReader::new(&bytes[..]);
+        assert_eq!(frames.skip_frame().unwrap(), Some(expected[0].clone()));
+        let (header, payload) = frames.read_frame().unwrap().unwrap();
+        assert_eq!(header, expected[1].1);
+        assert_eq!(payload, vec![1; 100_000]);
+
+        let err = FrameReader::new(&bytes[..bytes.len() - 1])
+            .headers_with_offsets()
+            .find_map(Result::err)
+            .unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn test_errors_carry_frame_positions() {
+        let mut writer = FrameWriter::new(Vec::new());
+        let mut offsets = Vec::new();
+        for index in 0..5u64 {
+            offsets.push(
+                writer
+                    .write_frame(&opus_header(40, index * 960), &[0; 40])
+                    .unwrap(),
+            );
+        }
+        let bytes = writer.into_inner();
+
+        // A damaged magic word in frame 3.
+        let mut damaged = bytes.clone();
+        damaged[offsets[3] as usize] ^= 0xFF;
+        let err = FrameReader::new(&damaged[..])
+            .find_map(Result::err)
+            .unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        let position = DecodeError::find(&err).unwrap();
+        assert_eq!((position.frame, position.offset), (3, offsets[3]));
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "frame 3 at offset {:#X}: No intact v2 frame header copy",
+                offsets[3]
+            )
+        );
+
+        // A payload cut short in frame 4, counted from a starting offset.
+        let mut frames = FrameReader::with_position(&bytes[..bytes.len() - 1], 0x2A4F_0000);
+        let err = frames.headers_with_offsets().find_map(Result::err).unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+        let position = DecodeError::find(&err).unwrap();
+        assert_eq!(
+            (position.frame, position.offset, position.field),
+            (4, 0x2A4F_0000 + offsets[4], Some("payload"))
+        );
+
+        // Limits name the field, and still convert to `LimitExceeded`.
+        let err = FrameReader::new(&bytes[..])
+            .with_limits(Limits::default().with_max_payload_len(39))
+            .read_frame()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "frame 0 at offset 0x0: payload_len: payload_len of 40 exceeds the limit of 39"
+        );
+        assert!(matches!(
+            crate::FrameHeaderError::from(err),
+            crate::FrameHeaderError::LimitExceeded { value: 40, .. }
+        ));
+    }
+
+    #[test]
+    fn test_frame_writer_rejects_payload_size_mismatch() {
+        let mut writer = FrameWriter::new(Vec::new());
+        assert!(writer.write_frame(&opus_header(4, 0), &[0; 5]).is_err());
+        assert_eq!(writer.position(), 0);
+        assert!(writer.get_ref().is_empty());
+    }
+}
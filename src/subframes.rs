@@ -0,0 +1,313 @@
+use crate::{FrameHeaderError, FrameHeaderV2};
+
+// A packed payload starts with a sub-frame count and a big-endian u16 length
+// per sub-frame, followed by the sub-frames back to back. Returns the
+// lengths once the table is checked against the payload.
+pub(crate) fn subframe_lengths(payload: &[u8]) -> Result<Vec<usize>, FrameHeaderError> {
+    let count = *payload.first().ok_or(FrameHeaderError::Truncated)? as usize;
+    if count == 0 {
+        return Err(FrameHeaderError::FieldOutOfRange("subframes"));
+    }
+    let table = payload
+        .get(1..1 + 2 * count)
+        .ok_or(FrameHeaderError::Truncated)?;
+    let lengths: Vec<usize> = table
+        .chunks_exact(2)
+        .map(|length| u16::from_be_bytes([length[0], length[1]]) as usize)
+        .collect();
+    let total: usize = lengths.iter().sum();
+    let available = payload.len() - table.len() - 1;
+    if total != available {
+        return Err(FrameHeaderError::Invalid(format!(
+            "Sub-frame lengths sum to {} bytes, payload holds {}",
+            total, available
+        )));
+    }
+    Ok(lengths)
+}
+
+impl FrameHeaderV2 {
+    /// Sub-frames one packed frame can hold, the most its count byte allows.
+    pub const MAX_SUBFRAMES: usize = u8::MAX as usize;
+
+    /// Whether the payload holds several sub-frames under this one header;
+    /// see `pack_frames`.
+    pub fn is_packed(&self) -> bool {
+        self.packed
+    }
+
+    /// Packs `subframes`, each lasting this header's frame count, into one
+    /// frame: the header covers them all, and the payload starts with a
+    /// count byte and a u16 length per sub-frame. A packet CRC is
+    /// recomputed if this header has one.
+    pub fn pack_frames(
+        &self,
+        subframes: &[&[u8]],
+    ) -> Result<(FrameHeaderV2, Vec<u8>), FrameHeaderError> {
+        if self.packed {
+            return Err(FrameHeaderError::Invalid(
+                "Header is already packed".to_string(),
+            ));
+        }
+        if subframes.is_empty() || subframes.len() > Self::MAX_SUBFRAMES {
+            return Err(FrameHeaderError::FieldOutOfRange("subframes"));
+        }
+        let data_len: usize = subframes.iter().map(|subframe| subframe.len()).sum();
+        let mut payload = Vec::with_capacity(1 + 2 * subframes.len() + data_len);
+        payload.push(subframes.len() as u8);
+        for subframe in subframes {
+            let length = u16::try_from(subframe.len())
+                .map_err(|_| FrameHeaderError::FieldOutOfRange("subframe_len"))?;
+            payload.extend_from_slice(&length.to_be_bytes());
+        }
+        for subframe in subframes {
+            payload.extend_from_slice(subframe);
+        }
+
+        let mut header = FrameHeaderV2 {
+            payload_size: u32::try_from(payload.len())
+                .map_err(|_| FrameHeaderError::FieldOutOfRange("payload_size"))?,
+            frame_count: self
+                .frame_count
+                .checked_mul(subframes.len() as u32)
+                .ok_or(FrameHeaderError::FieldOutOfRange("frame_count"))?,
+            packed: true,
+            ..self.clone()
+        };
+        header
+            .validate_fields()
+            .map_err(FrameHeaderError::Invalid)?;
+        if header.packet_crc32.is_some() {
+            header = header
+                .with_packet_crc32(&payload)
+                .map_err(FrameHeaderError::Invalid)?;
+        }
+        Ok((header, payload))
+    }
+
+    /// Splits a packed frame into one frame per sub-frame, sharing the frame
+    /// count equally and advancing the PTS and sample offset by it. The
+    /// encoder delay goes to the first sub-frame and the end padding to the
+    /// last. A frame that is not packed is returned as it is.
+    pub fn unpack_frames<'a>(
+        &self,
+        payload: &'a [u8],
+    ) -> Result<Vec<(FrameHeaderV2, &'a [u8])>, FrameHeaderError> {
+        if !self.packed {
+            return Ok(vec![(self.clone(), payload)]);
+        }
+        if payload.len() != self.payload_size as usize {
+            return Err(FrameHeaderError::Truncated);
+        }
+        let lengths = subframe_lengths(payload)?;
+        let count = lengths.len();
+        if !self.frame_count.is_multiple_of(count as u32) {
+            return Err(FrameHeaderError::Invalid(format!(
+                "{} frames do not split into {} sub-frames",
+                self.frame_count, count
+            )));
+        }
+        let frame_count = self.frame_count / count as u32;
+
+        let mut start = 1 + 2 * count;
+        let mut frames = Vec::with_capacity(count);
+        for (index, length) in lengths.into_iter().enumerate() {
+            let subframe = &payload[start..start + length];
+            start += length;
+            let elapsed = index as u64 * frame_count as u64;
+            let mut header = FrameHeaderV2 {
+                payload_size: length as u32,
+                frame_count,
+                pts: self.pts.map(|pts| pts.wrapping_add(elapsed)),
+                sample_offset: self
+                    .sample_offset
+                    .map(|offset| offset.wrapping_add(elapsed)),
+                encoder_delay: self.encoder_delay.filter(|_| index == 0),
+                end_padding: self.end_padding.filter(|_| index == count - 1),
+                packed: false,
+                ..self.clone()
+            };
+            if header.packet_crc32.is_some() {
+                header = header
+                    .with_packet_crc32(subframe)
+                    .map_err(FrameHeaderError::Invalid)?;
+            }
+            frames.push((header, subframe));
+        }
+        Ok(frames)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EncodingFlag, Endianness, FrameReader, FrameWriter};
+
+    fn opus_template() -> FrameHeaderV2 {
+        FrameHeaderV2::new(
+            EncodingFlag::Opus,
+            0,
+            960,
+            48000,
+            2,
+            0,
+            Endianness::LittleEndian,
+            Some(5),
+            Some(96_000),
+            None,
+        )
+        .unwrap()
+    }
+
+    fn subframes(count: usize) -> Vec<Vec<u8>> {
+        (0..count)
+            .map(|index| vec![index as u8; 20 + index * 7 % 90])
+            .collect()
+    }
+
+    #[test]
+    fn test_pack_unpack_roundtrip() {
+        for count in [1, 3, 48] {
+            let data = subframes(count);
+            let slices: Vec<&[u8]> = data.iter().map(Vec::as_slice).collect();
+            let template = opus_template().with_sample_offset(Some(4_800));
+            let (packed, payload) = template.pack_frames(&slices).unwrap();
+            assert!(packed.is_packed());
+            assert_eq!(packed.frame_count(), 960 * count as u32);
+            assert_eq!(packed.payload_size() as usize, payload.len());
+            assert_eq!(payload[0], count as u8);
+            assert_eq!(packed.validate_payload(&payload), Ok(()));
+
+            // Through the wire and back.
+            let mut writer = FrameWriter::new(Vec::new());
+            writer.write_frame(&packed, &payload).unwrap();
+            let stream = writer.into_inner();
+            let (decoded, decoded_payload) = FrameReader::new(&stream[..]).next().unwrap().unwrap();
+            assert_eq!(decoded, packed);
+
+            let frames = decoded.unpack_frames(&decoded_payload).unwrap();
+            assert_eq!(frames.len(), count);
+            for (index, (header, subframe)) in frames.iter().enumerate() {
+                assert_eq!(*subframe, slices[index]);
+                assert!(!header.is_packed());
+                assert_eq!(header.payload_size() as usize, subframe.len());
+                assert_eq!(header.frame_count(), 960);
+                assert_eq!(header.id(), Some(5));
+                assert_eq!(header.pts(), Some(96_000 + 960 * index as u64));
+                assert_eq!(header.sample_offset(), Some(4_800 + 960 * index as u64));
+            }
+        }
+    }
+
+    #[test]
+    fn test_pack_carries_crc_and_trim() {
+        let data = subframes(3);
+        let slices: Vec<&[u8]> = data.iter().map(Vec::as_slice).collect();
+        let template = opus_template()
+            .with_encoder_delay(Some(312))
+            .with_end_padding(Some(100))
+            .with_packet_crc32(&[])
+            .unwrap();
+        let (packed, payload) = template.pack_frames(&slices).unwrap();
+        assert_eq!(
+            packed.packet_crc32_value(),
+            Some(packed.compute_packet_crc32(&payload).unwrap())
+        );
+        assert_eq!(packed.trimmed_frame_count(), 3 * 960 - 412);
+
+        let frames = packed.unpack_frames(&payload).unwrap();
+        let trims: Vec<_> = frames
+            .iter()
+            .map(|(header, _)| (header.encoder_delay(), header.end_padding()))
+            .collect();
+        assert_eq!(trims, [(Some(312), None), (None, None), (None, Some(100))]);
+        for (header, subframe) in &frames {
+            assert_eq!(
+                header.packet_crc32_value(),
+                Some(header.compute_packet_crc32(subframe).unwrap())
+            );
+        }
+        let trimmed: u32 = frames
+            .iter()
+            .map(|(header, _)| header.trimmed_frame_count())
+            .sum();
+        assert_eq!(trimmed, packed.trimmed_frame_count());
+
+        // Unpacked frames pass through unchanged.
+        let plain = opus_template();
+        assert_eq!(
+            plain.unpack_frames(&[1, 2]).unwrap(),
+            [(plain, &[1, 2][..])]
+        );
+    }
+
+    #[test]
+    fn test_malformed_subframe_tables() {
+        let data = subframes(3);
+        let slices: Vec<&[u8]> = data.iter().map(Vec::as_slice).collect();
+        let (packed, payload) = opus_template().pack_frames(&slices).unwrap();
+
+        let with_payload = |payload: &[u8]| {
+            let header = FrameHeaderV2 {
+                payload_size: payload.len() as u32,
+                ..packed.clone()
+            };
+            header.unpack_frames(payload).map(|frames| frames.len())
+        };
+        assert_eq!(with_payload(&payload), Ok(3));
+
+        // Count of zero, a table cut short, and lengths that overrun or
+        // undershoot the payload.
+        let mut zero = payload.clone();
+        zero[0] = 0;
+        assert_eq!(
+            with_payload(&zero),
+            Err(FrameHeaderError::FieldOutOfRange("subframes"))
+        );
+        assert_eq!(with_payload(&[]), Err(FrameHeaderError::Truncated));
+        assert_eq!(with_payload(&[200, 0, 1]), Err(FrameHeaderError::Truncated));
+        let mut long = payload.clone();
+        long[2] += 1;
+        assert!(matches!(
+            with_payload(&long),
+            Err(FrameHeaderError::Invalid(_))
+        ));
+        assert!(matches!(
+            with_payload(&payload[..payload.len() - 1]),
+            Err(FrameHeaderError::Invalid(_))
+        ));
+        assert!(packed.validate_payload(&long).is_err());
+
+        // The payload must match the header's size.
+        assert_eq!(
+            packed.unpack_frames(&payload[..payload.len() - 1]),
+            Err(FrameHeaderError::Truncated)
+        );
+
+        // Frame counts must split evenly.
+        let uneven = FrameHeaderV2 {
+            frame_count: 1000,
+            ..packed.clone()
+        };
+        assert!(matches!(
+            uneven.unpack_frames(&payload),
+            Err(FrameHeaderError::Invalid(_))
+        ));
+
+        let too_many = vec![&[0u8][..]; FrameHeaderV2::MAX_SUBFRAMES + 1];
+        assert_eq!(
+            opus_template().pack_frames(&too_many),
+            Err(FrameHeaderError::FieldOutOfRange("subframes"))
+        );
+        assert_eq!(
+            opus_template().pack_frames(&[]),
+            Err(FrameHeaderError::FieldOutOfRange("subframes"))
+        );
+        let big = vec![0u8; 1 << 16];
+        assert_eq!(
+            opus_template().pack_frames(&[&big]),
+            Err(FrameHeaderError::FieldOutOfRange("subframe_len"))
+        );
+        assert!(packed.pack_frames(&slices).is_err());
+    }
+}
@@ -0,0 +1,179 @@
+use crate::{FrameHeader, FrameHeaderError};
+use std::io::{self, Write};
+
+/// What to do with an optional field when stamping a header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FieldOverride<T> {
+    #[default]
+    Keep,
+    Set(T),
+    Clear,
+}
+
+impl<T: Copy> FieldOverride<T> {
+    fn apply(&self, value: Option<T>) -> Option<T> {
+        match self {
+            FieldOverride::Keep => value,
+            FieldOverride::Set(value) => Some(*value),
+            FieldOverride::Clear => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Overrides {
+    pub sample_size: Option<u16>,
+    pub id: FieldOverride<u64>,
+    pub pts: FieldOverride<u64>,
+}
+
+/// A v1 header with the bits that never change between frames of a stream
+/// computed once, for stamping out headers with a new sample size, ID or PTS.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeaderTemplate {
+    word: u32,
+    sample_size: u16,
+    id: Option<u64>,
+    pts: Option<u64>,
+}
+
+impl HeaderTemplate {
+    pub fn new(header: &FrameHeader) -> Result<Self, FrameHeaderError> {
+        let mut bytes = [0u8; 4];
+        let base = FrameHeader {
+            sample_size: 0,
+            id: None,
+            pts: None,
+            ..header.clone()
+        };
+        base.encode(&mut &mut bytes[..])?;
+        Ok(HeaderTemplate {
+            word: u32::from_be_bytes(bytes),
+            sample_size: header.sample_size,
+            id: header.id,
+            pts: header.pts,
+        })
+    }
+
+    /// Writes the template's header with `overrides` applied and returns the
+    /// number of bytes written. Only the overridden fields are checked.
+    pub fn encode_with_overrides<W: Write>(
+        &self,
+        overrides: &Overrides,
+        writer: &mut W,
+    ) -> io::Result<usize> {
+        let sample_size = overrides.sample_size.unwrap_or(self.sample_size);
+        if sample_size > FrameHeader::MAX_SAMPLE_SIZE {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "Sample size exceeds maximum value ({})",
+                    FrameHeader::MAX_SAMPLE_SIZE
+                ),
+            ));
+        }
+        let id = overrides.id.apply(self.id);
+        let pts = overrides.pts.apply(self.pts);
+
+        let word = self.word
+            | (pts.is_some() as u32) << FrameHeader::PTS_SHIFT
+            | (id.is_some() as u32) << FrameHeader::ID_SHIFT
+            | sample_size as u32;
+
+        let mut bytes = [0u8; 20];
+        bytes[..4].copy_from_slice(&word.to_be_bytes());
+        let mut len = 4;
+        for value in [id, pts].into_iter().flatten() {
+            bytes[len..len + 8].copy_from_slice(&value.to_be_bytes());
+            len += 8;
+        }
+        writer.write_all(&bytes[..len])?;
+        Ok(len)
+    }
+}
+
+impl FrameHeader {
+    /// Encodes this header with `overrides` applied, without building a new
+    /// header; see `HeaderTemplate` for stamping many frames.
+    pub fn encode_with_overrides<W: Write>(
+        &self,
+        overrides: &Overrides,
+        writer: &mut W,
+    ) -> io::Result<usize> {
+        HeaderTemplate::new(self)?.encode_with_overrides(overrides, writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EncodingFlag, Endianness};
+
+    fn header(sample_size: u16, id: Option<u64>, pts: Option<u64>) -> Result<FrameHeader, String> {
+        FrameHeader::new(
+            EncodingFlag::Opus,
+            sample_size,
+            48000,
+            2,
+            16,
+            Endianness::BigEndian,
+            id,
+            pts,
+        )
+    }
+
+    #[test]
+    fn test_overrides_match_fresh_header() {
+        let optionals = [
+            FieldOverride::Keep,
+            FieldOverride::Set(0xDEAD_BEEF),
+            FieldOverride::Clear,
+        ];
+        for (base_id, base_pts) in [
+            (None, None),
+            (Some(1), None),
+            (None, Some(2)),
+            (Some(3), Some(4)),
+        ] {
+            let base = header(960, base_id, base_pts).unwrap();
+            let template = HeaderTemplate::new(&base).unwrap();
+            for sample_size in [None, Some(0), Some(480), Some(4095), Some(4096)] {
+                for id in optionals {
+                    for pts in optionals {
+                        let overrides = Overrides {
+                            sample_size,
+                            id,
+                            pts,
+                        };
+                        let expected = header(
+                            sample_size.unwrap_or(960),
+                            id.apply(base_id),
+                            pts.apply(base_pts),
+                        );
+
+                        let mut bytes = Vec::new();
+                        let result = base.encode_with_overrides(&overrides, &mut bytes);
+                        let mut stamped = Vec::new();
+                        let stamped_result =
+                            template.encode_with_overrides(&overrides, &mut stamped);
+                        match expected {
+                            Ok(expected) => {
+                                let mut golden = Vec::new();
+                                expected.encode(&mut golden).unwrap();
+                                assert_eq!(result.unwrap(), golden.len());
+                                assert_eq!(bytes, golden);
+                                assert_eq!(stamped_result.unwrap(), golden.len());
+                                assert_eq!(stamped, golden);
+                            }
+                            Err(_) => {
+                                assert!(result.is_err());
+                                assert!(stamped_result.is_err());
+                                assert!(bytes.is_empty());
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
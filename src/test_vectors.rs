@@ -0,0 +1,226 @@
+use crate::{EncodingFlag, Endianness, FrameHeader};
+
+/// Canonical v1 headers and their encoded bytes, for checking other
+/// implementations against this one. `verify_test_vectors` lists the field
+/// values each entry decodes to.
+pub const TEST_VECTORS: &[(&str, &[u8])] = &[
+    (
+        "pcm_signed, 480 samples, 48kHz, stereo, 16-bit, little-endian",
+        &[0xAA, 0x00, 0x11, 0xE0],
+    ),
+    (
+        "pcm_float, 480 samples, 48kHz, stereo, 32-bit, little-endian",
+        &[0xAA, 0x82, 0x11, 0xE0],
+    ),
+    (
+        "opus, 960 samples, 48kHz, stereo, 16-bit, little-endian",
+        &[0xAA, 0x04, 0x13, 0xC0],
+    ),
+    (
+        "flac, 1152 samples, 44.1kHz, stereo, 24-bit, big-endian",
+        &[0xA9, 0x47, 0x14, 0x80],
+    ),
+    (
+        "aac, 1024 samples, 44.1kHz, stereo, 16-bit, little-endian",
+        &[0xA9, 0x08, 0x14, 0x00],
+    ),
+    (
+        "h264, 0 samples, 48kHz, mono, 16-bit, little-endian",
+        &[0xAA, 0x0A, 0x00, 0x00],
+    ),
+    (
+        "metadata, 0 samples, 48kHz, mono, 16-bit, little-endian",
+        &[0xAA, 0x0C, 0x00, 0x00],
+    ),
+    (
+        "opus with ID 0x0102030405060708",
+        &[
+            0xAA, 0x14, 0x13, 0xC0, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08,
+        ],
+    ),
+    (
+        "opus with PTS 48000",
+        &[
+            0xAA, 0x24, 0x13, 0xC0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xBB, 0x80,
+        ],
+    ),
+    (
+        "opus with ID 0x0102030405060708 and PTS 48000",
+        &[
+            0xAA, 0x34, 0x13, 0xC0, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0xBB, 0x80,
+        ],
+    ),
+    (
+        "maximum values: metadata, 4095 samples, 96kHz, 16 channels, 32-bit, big-endian, ID and PTS u64::MAX",
+        &[
+            0xAB, 0xBD, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+            0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+        ],
+    ),
+    (
+        "minimum values: pcm_signed, 0 samples, 16kHz, mono, 16-bit, little-endian",
+        &[0xA8, 0x00, 0x00, 0x00],
+    ),
+    (
+        "minimum values with ID 0 and PTS 0",
+        &[
+            0xA8, 0x30, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ],
+    ),
+];
+
+// Encoding, samples per channel, sample rate, channels, bits per sample,
+// endianness, ID and PTS.
+type Fields = (
+    EncodingFlag,
+    u16,
+    u32,
+    u8,
+    u8,
+    Endianness,
+    Option<u64>,
+    Option<u64>,
+);
+
+// The fields of each entry of `TEST_VECTORS`, in the same order.
+const EXPECTED: &[Fields] = &[
+    (EncodingFlag::PCMSigned, 480, 48000, 2, 16, LE, None, None),
+    (EncodingFlag::PCMFloat, 480, 48000, 2, 32, LE, None, None),
+    (EncodingFlag::Opus, 960, 48000, 2, 16, LE, None, None),
+    (EncodingFlag::FLAC, 1152, 44100, 2, 24, BE, None, None),
+    (EncodingFlag::AAC, 1024, 44100, 2, 16, LE, None, None),
+    (EncodingFlag::H264, 0, 48000, 1, 16, LE, None, None),
+    (EncodingFlag::Metadata, 0, 48000, 1, 16, LE, None, None),
+    (EncodingFlag::Opus, 960, 48000, 2, 16, LE, Some(ID), None),
+    (EncodingFlag::Opus, 960, 48000, 2, 16, LE, None, Some(48000)),
+    (
+        EncodingFlag::Opus,
+        960,
+        48000,
+        2,
+        16,
+        LE,
+        Some(ID),
+        Some(48000),
+    ),
+    (
+        EncodingFlag::Metadata,
+        4095,
+        96000,
+        16,
+        32,
+        BE,
+        Some(u64::MAX),
+        Some(u64::MAX),
+    ),
+    (EncodingFlag::PCMSigned, 0, 16000, 1, 16, LE, None, None),
+    (
+        EncodingFlag::PCMSigned,
+        0,
+        16000,
+        1,
+        16,
+        LE,
+        Some(0),
+        Some(0),
+    ),
+];
+
+const LE: Endianness = Endianness::LittleEndian;
+const BE: Endianness = Endianness::BigEndian;
+const ID: u64 = 0x0102_0304_0506_0708;
+
+impl FrameHeader {
+    /// Decodes every entry of `TEST_VECTORS`, checks each field and that
+    /// re-encoding gives the same bytes, and returns one message per
+    /// mismatch.
+    pub fn verify_test_vectors() -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        for (&(description, bytes), expected) in TEST_VECTORS.iter().zip(EXPECTED) {
+            let header = match FrameHeader::decode(&mut &bytes[..]) {
+                Ok(header) => header,
+                Err(err) => {
+                    errors.push(format!("{}: {}", description, err));
+                    continue;
+                }
+            };
+            let (encoding, samples, rate, channels, bits, endianness, id, pts) = *expected;
+            let mut check = |field: &str, actual: String, expected: String| {
+                if actual != expected {
+                    errors.push(format!(
+                        "{}: {} is {}, expected {}",
+                        description, field, actual, expected
+                    ));
+                }
+            };
+            check(
+                "encoding",
+                format!("{:?}", header.encoding()),
+                format!("{:?}", encoding),
+            );
+            check(
+                "samples_per_channel",
+                header.samples_per_channel().to_string(),
+                samples.to_string(),
+            );
+            check(
+                "sample_rate",
+                header.sample_rate().to_string(),
+                rate.to_string(),
+            );
+            check(
+                "channels",
+                header.channels().to_string(),
+                channels.to_string(),
+            );
+            check(
+                "bits_per_sample",
+                header.bits_per_sample().to_string(),
+                bits.to_string(),
+            );
+            check(
+                "endianness",
+                format!("{:?}", header.endianness()),
+                format!("{:?}", endianness),
+            );
+            check("id", format!("{:?}", header.id()), format!("{:?}", id));
+            check("pts", format!("{:?}", header.pts()), format!("{:?}", pts));
+
+            let mut encoded = Vec::with_capacity(bytes.len());
+            if header.encode(&mut encoded).is_err() || encoded != bytes {
+                errors.push(format!("{}: re-encodes to {:02X?}", description, encoded));
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vectors_match_encoder() {
+        assert_eq!(TEST_VECTORS.len(), EXPECTED.len());
+        assert_eq!(FrameHeader::verify_test_vectors(), Ok(()));
+
+        for (
+            &(description, bytes),
+            &(encoding, samples, rate, channels, bits, endianness, id, pts),
+        ) in TEST_VECTORS.iter().zip(EXPECTED)
+        {
+            let header =
+                FrameHeader::new(encoding, samples, rate, channels, bits, endianness, id, pts)
+                    .unwrap();
+            let mut encoded = Vec::new();
+            header.encode(&mut encoded).unwrap();
+            assert_eq!(encoded, bytes, "{}", description);
+        }
+    }
+}
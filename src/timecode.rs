@@ -0,0 +1,305 @@
+use crate::mtc::{DROP_FRAMES_PER_10_MINUTES, DROP_FRAMES_PER_MINUTE};
+use crate::FrameHeaderError;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+const DROP_FRAME_BIT: u32 = 1 << 6;
+const FRAMES_MASK: u32 = 0x3F;
+
+/// An SMPTE HH:MM:SS:FF timecode. Drop-frame timecodes count at 29.97 fps,
+/// skipping frame labels 0 and 1 at the start of each minute except every
+/// tenth, which is why they cannot be recovered from a PTS alone.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(try_from = "u32", into = "u32")]
+pub struct Timecode {
+    hours: u8,
+    minutes: u8,
+    seconds: u8,
+    frames: u8,
+    drop_frame: bool,
+}
+
+impl Timecode {
+    /// Frame labels run up to 29, the most SMPTE timecode counts per second.
+    pub fn new(
+        hours: u8,
+        minutes: u8,
+        seconds: u8,
+        frames: u8,
+        drop_frame: bool,
+    ) -> Result<Timecode, FrameHeaderError> {
+        if hours >= 24 {
+            return Err(FrameHeaderError::FieldOutOfRange("hours"));
+        }
+        if minutes >= 60 {
+            return Err(FrameHeaderError::FieldOutOfRange("minutes"));
+        }
+        if seconds >= 60 {
+            return Err(FrameHeaderError::FieldOutOfRange("seconds"));
+        }
+        if frames >= 30 || (drop_frame && seconds == 0 && frames < 2 && !minutes.is_multiple_of(10))
+        {
+            return Err(FrameHeaderError::FieldOutOfRange("frames"));
+        }
+        Ok(Timecode {
+            hours,
+            minutes,
+            seconds,
+            frames,
+            drop_frame,
+        })
+    }
+
+    pub fn hours(&self) -> u8 {
+        self.hours
+    }
+
+    pub fn minutes(&self) -> u8 {
+        self.minutes
+    }
+
+    pub fn seconds(&self) -> u8 {
+        self.seconds
+    }
+
+    pub fn frames(&self) -> u8 {
+        self.frames
+    }
+
+    pub fn is_drop_frame(&self) -> bool {
+        self.drop_frame
+    }
+
+    /// The timecode `frame` frames after midnight at a nominal `fps`, wrapping
+    /// at 24 hours. Drop-frame timecode needs an `fps` of 30, for 29.97.
+    pub fn from_frame_count(
+        frame: u64,
+        fps: u8,
+        drop_frame: bool,
+    ) -> Result<Timecode, FrameHeaderError> {
+        check_fps(fps, drop_frame)?;
+        let fps = fps as u64;
+        let mut frame = frame % frames_per_day(fps, drop_frame);
+        if drop_frame {
+            let tens = frame / DROP_FRAMES_PER_10_MINUTES;
+            let rest = frame % DROP_FRAMES_PER_10_MINUTES;
+            frame += 18 * tens + 2 * ((rest.max(2) - 2) / DROP_FRAMES_PER_MINUTE);
+        }
+
+        let seconds = frame / fps;
+        Timecode::new(
+            (seconds / 3600) as u8,
+            (seconds / 60 % 60) as u8,
+            (seconds % 60) as u8,
+            (frame % fps) as u8,
+            drop_frame,
+        )
+    }
+
+    /// Frames since midnight at a nominal `fps`; the inverse of
+    /// `from_frame_count`.
+    pub fn to_frame_count(&self, fps: u8) -> Result<u64, FrameHeaderError> {
+        check_fps(fps, self.drop_frame)?;
+        if self.frames >= fps {
+            return Err(FrameHeaderError::FieldOutOfRange("frames"));
+        }
+        let minutes = self.hours as u64 * 60 + self.minutes as u64;
+        let mut frame = (minutes * 60 + self.seconds as u64) * fps as u64 + self.frames as u64;
+        if self.drop_frame {
+            frame -= 2 * (minutes - minutes / 10);
+        }
+        Ok(frame)
+    }
+
+    /// BCD digits, one byte each for hours, minutes, seconds and frames, most
+    /// significant first. Bit 6 of the frames byte is the drop-frame flag.
+    pub fn to_packed(&self) -> u32 {
+        let bcd = |value: u8| (((value / 10) << 4) | (value % 10)) as u32;
+        bcd(self.hours) << 24
+            | bcd(self.minutes) << 16
+            | bcd(self.seconds) << 8
+            | bcd(self.frames)
+            | if self.drop_frame { DROP_FRAME_BIT } else { 0 }
+    }
+
+    pub fn from_packed(packed: u32) -> Result<Timecode, FrameHeaderError> {
+        let bcd = |value: u32, field: &'static str| {
+            let (tens, units) = (value >> 4 & 0xF, value & 0xF);
+            if tens > 9 || units > 9 {
+                return Err(FrameHeaderError::FieldOutOfRange(field));
+            }
+            Ok((tens * 10 + units) as u8)
+        };
+        if packed & 0xFF & !(DROP_FRAME_BIT | FRAMES_MASK) != 0 {
+            return Err(FrameHeaderError::FieldOutOfRange("frames"));
+        }
+        Timecode::new(
+            bcd(packed >> 24, "hours")?,
+            bcd(packed >> 16 & 0xFF, "minutes")?,
+            bcd(packed >> 8 & 0xFF, "seconds")?,
+            bcd(packed & FRAMES_MASK, "frames")?,
+            packed & DROP_FRAME_BIT != 0,
+        )
+    }
+}
+
+fn check_fps(fps: u8, drop_frame: bool) -> Result<(), FrameHeaderError> {
+    if fps == 0 || fps > 30 || (drop_frame && fps != 30) {
+        return Err(FrameHeaderError::FieldOutOfRange("fps"));
+    }
+    Ok(())
+}
+
+fn frames_per_day(fps: u64, drop_frame: bool) -> u64 {
+    if drop_frame {
+        24 * 6 * DROP_FRAMES_PER_10_MINUTES
+    } else {
+        24 * 3600 * fps
+    }
+}
+
+/// `01:23:45:12`, with a `;` before the frames of a drop-frame timecode.
+impl fmt::Display for Timecode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:02}:{:02}:{:02}{}{:02}",
+            self.hours,
+            self.minutes,
+            self.seconds,
+            if self.drop_frame { ';' } else { ':' },
+            self.frames
+        )
+    }
+}
+
+impl TryFrom<u32> for Timecode {
+    type Error = FrameHeaderError;
+
+    fn try_from(packed: u32) -> Result<Self, Self::Error> {
+        Timecode::from_packed(packed)
+    }
+}
+
+impl From<Timecode> for u32 {
+    fn from(timecode: Timecode) -> u32 {
+        timecode.to_packed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn df(hours: u8, minutes: u8, seconds: u8, frames: u8) -> Timecode {
+        Timecode::new(hours, minutes, seconds, frames, true).unwrap()
+    }
+
+    #[test]
+    fn test_drop_frame_minute_boundaries() {
+        // 00:00:59;29 is followed by 00:01:00;02.
+        assert_eq!(
+            Timecode::from_frame_count(1799, 30, true),
+            Ok(df(0, 0, 59, 29))
+        );
+        assert_eq!(
+            Timecode::from_frame_count(1800, 30, true),
+            Ok(df(0, 1, 0, 2))
+        );
+        // Every tenth minute keeps its first two labels.
+        assert_eq!(
+            Timecode::from_frame_count(17_981, 30, true),
+            Ok(df(0, 9, 59, 29))
+        );
+        assert_eq!(
+            Timecode::from_frame_count(17_982, 30, true),
+            Ok(df(0, 10, 0, 0))
+        );
+        assert_eq!(
+            Timecode::from_frame_count(17_983, 30, true),
+            Ok(df(0, 10, 0, 1))
+        );
+        assert_eq!(
+            Timecode::from_frame_count(19_782, 30, true),
+            Ok(df(0, 11, 0, 2))
+        );
+        // One hour of 29.97 fps is 107892 frames.
+        assert_eq!(
+            Timecode::from_frame_count(107_892, 30, true),
+            Ok(df(1, 0, 0, 0))
+        );
+        // The day wraps after 23:59:59;29.
+        assert_eq!(
+            Timecode::from_frame_count(2_589_407, 30, true),
+            Ok(df(23, 59, 59, 29))
+        );
+        assert_eq!(
+            Timecode::from_frame_count(2_589_408, 30, true),
+            Ok(df(0, 0, 0, 0))
+        );
+
+        for frame in (0..2_589_408).step_by(997).chain(17_970..18_000) {
+            let timecode = Timecode::from_frame_count(frame, 30, true).unwrap();
+            assert_eq!(timecode.to_frame_count(30), Ok(frame), "{}", timecode);
+        }
+
+        for (minutes, seconds, frames) in [(1, 0, 0), (1, 0, 1), (59, 0, 1)] {
+            assert_eq!(
+                Timecode::new(0, minutes, seconds, frames, true),
+                Err(FrameHeaderError::FieldOutOfRange("frames"))
+            );
+        }
+        assert!(Timecode::new(0, 1, 0, 0, false).is_ok());
+    }
+
+    #[test]
+    fn test_non_drop_frame_counts() {
+        for fps in [24, 25, 30] {
+            for frame in (0..24 * 3600 * fps as u64).step_by(7919) {
+                let timecode = Timecode::from_frame_count(frame, fps, false).unwrap();
+                assert_eq!(timecode.to_frame_count(fps), Ok(frame));
+            }
+        }
+        let timecode = Timecode::from_frame_count(25 * 3661 + 24, 25, false).unwrap();
+        assert_eq!(timecode.to_string(), "01:01:01:24");
+        assert_eq!(
+            timecode.to_frame_count(24),
+            Err(FrameHeaderError::FieldOutOfRange("frames"))
+        );
+        for (fps, drop_frame) in [(0, false), (31, false), (25, true), (24, true)] {
+            assert_eq!(
+                Timecode::from_frame_count(0, fps, drop_frame),
+                Err(FrameHeaderError::FieldOutOfRange("fps"))
+            );
+        }
+    }
+
+    #[test]
+    fn test_timecode_packed_roundtrip() {
+        let timecode = df(1, 23, 45, 12);
+        assert_eq!(timecode.to_string(), "01:23:45;12");
+        assert_eq!(timecode.to_packed(), 0x0123_4552);
+        assert_eq!(Timecode::from_packed(0x0123_4552), Ok(timecode));
+
+        let non_drop = Timecode::new(23, 59, 59, 29, false).unwrap();
+        assert_eq!(non_drop.to_string(), "23:59:59:29");
+        assert_eq!(non_drop.to_packed(), 0x2359_5929);
+        assert_eq!(Timecode::from_packed(non_drop.to_packed()), Ok(non_drop));
+
+        for packed in [
+            0x2400_0000,
+            0x0A00_0000,
+            0x0060_0000,
+            0x0000_6000,
+            0x0000_0030,
+            0x0000_0080,
+        ] {
+            assert!(Timecode::from_packed(packed).is_err(), "{:#010x}", packed);
+        }
+
+        let json = serde_json::to_string(&timecode).unwrap();
+        assert_eq!(json, 0x0123_4552u32.to_string());
+        assert_eq!(serde_json::from_str::<Timecode>(&json).unwrap(), timecode);
+        assert!(serde_json::from_str::<Timecode>("603979776").is_err());
+    }
+}
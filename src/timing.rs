@@ -0,0 +1,271 @@
+use crate::FrameHeader;
+
+/// What a PTS counts in. V1 headers use microseconds and V2 headers sample
+/// frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PtsUnit {
+    Microseconds,
+    Nanoseconds,
+    Samples,
+}
+
+impl PtsUnit {
+    fn per_second(self, sample_rate: u32) -> u128 {
+        match self {
+            PtsUnit::Microseconds => 1_000_000,
+            PtsUnit::Nanoseconds => 1_000_000_000,
+            PtsUnit::Samples => sample_rate as u128,
+        }
+    }
+}
+
+impl FrameHeader {
+    /// PCM payload bytes of one frame, or `None` if it overflows a `u32`,
+    /// which no valid header does.
+    pub fn checked_pcm_payload_len(&self) -> Option<u32> {
+        (self.total_samples() as u64 * self.bytes_per_sample() as u64)
+            .try_into()
+            .ok()
+    }
+
+    /// Bytes of `frame_count` frames, headers included, or `None` on
+    /// overflow.
+    pub fn checked_total_stream_bytes(&self, frame_count: u64) -> Option<u64> {
+        (self.checked_pcm_payload_len()? as u64)
+            .checked_add(self.size() as u64)?
+            .checked_mul(frame_count)
+    }
+
+    /// Duration of `frame_count` frames in whole microseconds, rounded down
+    /// once rather than per frame, or `None` on overflow.
+    pub fn checked_duration_for_frames(&self, frame_count: u64) -> Option<u64> {
+        self.checked_frames_in(frame_count, PtsUnit::Microseconds)
+    }
+
+    /// PTS `frame_count` frames after this one, with the PTS counting in
+    /// `unit`. `None` without a PTS or on overflow.
+    pub fn checked_pts_after(&self, frame_count: u64, unit: PtsUnit) -> Option<u64> {
+        self.pts?
+            .checked_add(self.checked_frames_in(frame_count, unit)?)
+    }
+
+    // The u128 product fits: at most 2^64 frames × 2^12 samples × 2^30.
+    fn checked_frames_in(&self, frame_count: u64, unit: PtsUnit) -> Option<u64> {
+        let samples = frame_count as u128 * self.samples_per_channel() as u128;
+        (samples * unit.per_second(self.sample_rate) / self.sample_rate as u128)
+            .try_into()
+            .ok()
+    }
+
+    /// Duration of one frame in whole microseconds, rounded down.
+    pub fn audio_duration_us(&self) -> u64 {
+        self.checked_duration_for_frames(1)
+            .expect("one frame lasts under 2^64 microseconds")
+    }
+
+    /// PTS in microseconds converted to a sample offset, rounded down.
+    pub fn pts_to_sample_offset(&self) -> Option<u64> {
+        // Sample rates are below 1 MHz, so the result always fits in a u64.
+        self.pts
+            .map(|pts| (pts as u128 * self.sample_rate as u128 / 1_000_000) as u64)
+    }
+
+    /// PTS of the frame that follows this one, or `None` without a PTS or on
+    /// overflow.
+    pub fn next_pts(&self) -> Option<u64> {
+        self.checked_pts_after(1, PtsUnit::Microseconds)
+    }
+
+    pub fn align_pts_to_frame_boundary_us(&self, pts_us: u64) -> u64 {
+        match self.audio_duration_us() {
+            0 => pts_us,
+            duration => pts_us - pts_us % duration,
+        }
+    }
+
+    pub fn pts_is_frame_aligned(&self) -> bool {
+        match self.audio_duration_us() {
+            0 => true,
+            duration => self.pts.is_none_or(|pts| pts % duration == 0),
+        }
+    }
+
+    pub fn clone_with_aligned_pts(&self) -> FrameHeader {
+        FrameHeader {
+            pts: self.pts.map(|pts| self.align_pts_to_frame_boundary_us(pts)),
+            ..self.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{EncodingFlag, Endianness, FrameHeader, PtsUnit};
+
+    fn opus_header(sample_size: u16, pts: Option<u64>) -> FrameHeader {
+        FrameHeader::new(
+            EncodingFlag::Opus,
+            sample_size,
+            48000,
+            2,
+            16,
+            Endianness::LittleEndian,
+            Some(3),
+            pts,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_opus_frame_durations() {
+        for (sample_size, duration_us) in
+            [(480, 10_000), (960, 20_000), (1920, 40_000), (2880, 60_000)]
+        {
+            assert_eq!(
+                opus_header(sample_size, None).audio_duration_us(),
+                duration_us
+            );
+        }
+    }
+
+    #[test]
+    fn test_pts_to_sample_offset() {
+        assert_eq!(
+            opus_header(960, Some(20_000)).pts_to_sample_offset(),
+            Some(960)
+        );
+        assert_eq!(opus_header(960, Some(1)).pts_to_sample_offset(), Some(0));
+        assert_eq!(opus_header(960, None).pts_to_sample_offset(), None);
+        assert_eq!(
+            opus_header(960, Some(u64::MAX)).pts_to_sample_offset(),
+            Some((u64::MAX as u128 * 48_000 / 1_000_000) as u64)
+        );
+    }
+
+    #[test]
+    fn test_next_pts() {
+        assert_eq!(opus_header(960, Some(40_000)).next_pts(), Some(60_000));
+        assert_eq!(opus_header(960, None).next_pts(), None);
+        assert_eq!(opus_header(960, Some(u64::MAX)).next_pts(), None);
+    }
+
+    #[test]
+    fn test_align_pts_to_frame_boundary() {
+        let header = opus_header(960, Some(45_123));
+        assert_eq!(header.align_pts_to_frame_boundary_us(45_123), 40_000);
+        assert_eq!(header.align_pts_to_frame_boundary_us(40_000), 40_000);
+        assert_eq!(header.align_pts_to_frame_boundary_us(19_999), 0);
+        assert!(!header.pts_is_frame_aligned());
+
+        let aligned = header.clone_with_aligned_pts();
+        assert_eq!(aligned.pts(), Some(40_000));
+        assert!(aligned.pts_is_frame_aligned());
+        assert_eq!(aligned.id(), header.id());
+        assert_eq!(aligned.samples_per_channel(), header.samples_per_channel());
+
+        assert!(opus_header(960, None).pts_is_frame_aligned());
+        assert_eq!(opus_header(960, None).clone_with_aligned_pts().pts(), None);
+
+        let empty = opus_header(0, Some(12_345));
+        assert_eq!(empty.align_pts_to_frame_boundary_us(12_345), 12_345);
+        assert!(empty.pts_is_frame_aligned());
+    }
+
+    #[test]
+    fn test_checked_sizes_at_limits() {
+        let largest = FrameHeader::new(
+            EncodingFlag::PCMFloat,
+            4095,
+            96000,
+            16,
+            32,
+            Endianness::LittleEndian,
+            Some(1),
+            Some(2),
+        )
+        .unwrap();
+        let payload = 4095 * 16 * 4;
+        assert_eq!(largest.checked_pcm_payload_len(), Some(payload));
+        assert_eq!(largest.total_pcm_frame_bytes(), payload);
+
+        let frame = payload as u64 + 20;
+        let max_frames = u64::MAX / frame;
+        assert_eq!(
+            largest.checked_total_stream_bytes(max_frames),
+            Some(max_frames * frame)
+        );
+        assert_eq!(largest.checked_total_stream_bytes(max_frames + 1), None);
+        assert_eq!(largest.checked_total_stream_bytes(u64::MAX), None);
+        assert_eq!(largest.checked_total_stream_bytes(0), Some(0));
+    }
+
+    #[test]
+    fn test_checked_durations_at_limits() {
+        let header = opus_header(960, Some(0));
+        assert_eq!(header.checked_duration_for_frames(3), Some(60_000));
+
+        // frames × 960 × 10^6 overflows a u64 long before the result does.
+        let max_frames = u64::MAX / 20_000;
+        assert_eq!(
+            header.checked_duration_for_frames(max_frames),
+            Some(max_frames * 20_000)
+        );
+        assert_eq!(header.checked_duration_for_frames(max_frames + 1), None);
+        assert_eq!(header.checked_duration_for_frames(u64::MAX), None);
+
+        // Rounding happens once, not per frame: 441 samples at 44.1 kHz are
+        // exactly 10ms, but 440 samples are 9977.3µs.
+        let cd = FrameHeader::new(
+            EncodingFlag::PCMSigned,
+            440,
+            44100,
+            2,
+            16,
+            Endianness::LittleEndian,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(cd.audio_duration_us(), 9977);
+        assert_eq!(cd.checked_duration_for_frames(1000), Some(9_977_324));
+    }
+
+    #[test]
+    fn test_checked_pts_after() {
+        let header = opus_header(960, Some(40_000));
+        assert_eq!(
+            header.checked_pts_after(2, PtsUnit::Microseconds),
+            Some(80_000)
+        );
+        assert_eq!(
+            header.checked_pts_after(2, PtsUnit::Nanoseconds),
+            Some(40_000 + 40_000_000)
+        );
+        assert_eq!(header.checked_pts_after(2, PtsUnit::Samples), Some(41_920));
+        assert_eq!(
+            opus_header(960, None).checked_pts_after(1, PtsUnit::Samples),
+            None
+        );
+
+        let late = opus_header(960, Some(u64::MAX - 20_000));
+        assert_eq!(
+            late.checked_pts_after(1, PtsUnit::Microseconds),
+            Some(u64::MAX)
+        );
+        assert_eq!(late.checked_pts_after(2, PtsUnit::Microseconds), None);
+        for unit in [
+            PtsUnit::Microseconds,
+            PtsUnit::Nanoseconds,
+            PtsUnit::Samples,
+        ] {
+            assert_eq!(
+                opus_header(960, Some(0)).checked_pts_after(u64::MAX, unit),
+                None
+            );
+        }
+        assert_eq!(
+            opus_header(960, Some(0)).checked_pts_after(u64::MAX / 960, PtsUnit::Samples),
+            Some(u64::MAX / 960 * 960)
+        );
+    }
+}
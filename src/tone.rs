@@ -0,0 +1,127 @@
+use crate::{EncodingFlag, Endianness, FrameHeader, FrameHeaderError};
+
+impl FrameHeader {
+    /// A little-endian 32-bit float header for 20ms frames, as used for the
+    /// 1kHz -12 dBFS continuity tone.
+    pub fn continuity_tone_header(
+        sample_rate: u32,
+        channels: u8,
+    ) -> Result<FrameHeader, FrameHeaderError> {
+        let sample_size = u16::try_from(sample_rate / 50)
+            .map_err(|_| FrameHeaderError::FieldOutOfRange("sample_rate"))?;
+        Ok(FrameHeader::new(
+            EncodingFlag::PCMFloat,
+            sample_size,
+            sample_rate,
+            channels,
+            32,
+            Endianness::LittleEndian,
+            None,
+            None,
+        )?)
+    }
+
+    /// One frame of a sine starting at zero phase, with `amplitude` as a
+    /// fraction of full scale and the same sample on every channel.
+    pub fn generate_continuity_tone_payload(
+        header: &FrameHeader,
+        frequency_hz: f32,
+        amplitude: f32,
+    ) -> Result<Vec<u8>, FrameHeaderError> {
+        if header.encoding != EncodingFlag::PCMFloat {
+            return Err(FrameHeaderError::InvalidEncoding(
+                header.encoding.code() as u32
+            ));
+        }
+        if header.bits_per_sample != 32 {
+            return Err(FrameHeaderError::FieldOutOfRange("bits_per_sample"));
+        }
+        if !(frequency_hz > 0.0 && frequency_hz < header.sample_rate as f32 / 2.0) {
+            return Err(FrameHeaderError::FieldOutOfRange("frequency_hz"));
+        }
+        if !(0.0..=1.0).contains(&amplitude) {
+            return Err(FrameHeaderError::FieldOutOfRange("amplitude"));
+        }
+
+        let step = 2.0 * std::f64::consts::PI * frequency_hz as f64 / header.sample_rate as f64;
+        let mut payload = Vec::with_capacity(header.total_pcm_frame_bytes() as usize);
+        for n in 0..header.sample_size {
+            let sample = (amplitude as f64 * (step * n as f64).sin()) as f32;
+            let bytes = match header.endianness {
+                Endianness::LittleEndian => sample.to_le_bytes(),
+                Endianness::BigEndian => sample.to_be_bytes(),
+            };
+            for _ in 0..header.channels {
+                payload.extend_from_slice(&bytes);
+            }
+        }
+        Ok(payload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_continuity_tone() {
+        let amplitude = 10f32.powf(-12.0 / 20.0);
+        for (sample_rate, sample_size) in [(16000, 320), (44100, 882), (48000, 960), (96000, 1920)]
+        {
+            for channels in [1, 2, 16] {
+                let header = FrameHeader::continuity_tone_header(sample_rate, channels).unwrap();
+                assert_eq!(header.samples_per_channel(), sample_size);
+                assert_eq!(header.encoding(), EncodingFlag::PCMFloat);
+                let payload =
+                    FrameHeader::generate_continuity_tone_payload(&header, 1000.0, amplitude)
+                        .unwrap();
+                assert_eq!(payload.len(), header.total_pcm_frame_bytes() as usize);
+                let peak = FrameHeader::compute_peak_level_db(&header, &payload).unwrap();
+                assert!((peak + 12.0).abs() < 0.01, "{}", peak);
+            }
+        }
+
+        let mut header = FrameHeader::continuity_tone_header(48000, 2).unwrap();
+        header.endianness = Endianness::BigEndian;
+        let payload = FrameHeader::generate_continuity_tone_payload(&header, 1000.0, 1.0).unwrap();
+        // Sample 12 is a quarter cycle in: full scale on both channels.
+        assert_eq!(&payload[96..100], &1f32.to_be_bytes());
+        assert_eq!(&payload[100..104], &1f32.to_be_bytes());
+    }
+
+    #[test]
+    fn test_continuity_tone_errors() {
+        assert!(FrameHeader::continuity_tone_header(22050, 2).is_err());
+        assert!(FrameHeader::continuity_tone_header(48000, 0).is_err());
+
+        let header = FrameHeader::continuity_tone_header(48000, 2).unwrap();
+        for frequency in [0.0, -1.0, 24000.0, f32::NAN] {
+            assert_eq!(
+                FrameHeader::generate_continuity_tone_payload(&header, frequency, 0.5),
+                Err(FrameHeaderError::FieldOutOfRange("frequency_hz"))
+            );
+        }
+        for amplitude in [-0.1, 1.5, f32::NAN] {
+            assert_eq!(
+                FrameHeader::generate_continuity_tone_payload(&header, 1000.0, amplitude),
+                Err(FrameHeaderError::FieldOutOfRange("amplitude"))
+            );
+        }
+
+        let pcm = FrameHeader::new(
+            EncodingFlag::PCMSigned,
+            960,
+            48000,
+            2,
+            16,
+            Endianness::LittleEndian,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            FrameHeader::generate_continuity_tone_payload(&pcm, 1000.0, 0.5),
+            Err(FrameHeaderError::InvalidEncoding(0))
+        );
+    }
+}
@@ -0,0 +1,181 @@
+use crate::{EncodingFlag, Endianness, FrameHeader, FrameHeaderError};
+
+/// A sample rate a v1 header can carry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SampleRate(u32);
+
+impl SampleRate {
+    pub const R16K: SampleRate = SampleRate(16000);
+    pub const R44_1K: SampleRate = SampleRate(44100);
+    pub const R48K: SampleRate = SampleRate(48000);
+    pub const R96K: SampleRate = SampleRate(96000);
+
+    pub fn get(self) -> u32 {
+        self.0
+    }
+}
+
+impl TryFrom<u32> for SampleRate {
+    type Error = FrameHeaderError;
+
+    fn try_from(sample_rate: u32) -> Result<Self, Self::Error> {
+        if FrameHeader::VALID_SAMPLE_RATES.contains(&sample_rate) {
+            Ok(SampleRate(sample_rate))
+        } else {
+            Err(FrameHeaderError::FieldOutOfRange("sample_rate"))
+        }
+    }
+}
+
+impl From<SampleRate> for u32 {
+    fn from(sample_rate: SampleRate) -> u32 {
+        sample_rate.0
+    }
+}
+
+/// A channel count from 1 to 16.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ChannelCount(u8);
+
+impl ChannelCount {
+    pub fn get(self) -> u8 {
+        self.0
+    }
+}
+
+impl TryFrom<u8> for ChannelCount {
+    type Error = FrameHeaderError;
+
+    fn try_from(channels: u8) -> Result<Self, Self::Error> {
+        if (1..=16).contains(&channels) {
+            Ok(ChannelCount(channels))
+        } else {
+            Err(FrameHeaderError::InvalidChannels(channels))
+        }
+    }
+}
+
+impl From<ChannelCount> for u8 {
+    fn from(channels: ChannelCount) -> u8 {
+        channels.0
+    }
+}
+
+impl FrameHeader {
+    /// Like `new`, but the sample rate and channel count are already valid
+    /// and cannot be swapped.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_typed(
+        encoding: EncodingFlag,
+        samples_per_channel: u16,
+        sample_rate: SampleRate,
+        channels: ChannelCount,
+        bits_per_sample: u8,
+        endianness: Endianness,
+        id: Option<u64>,
+        pts: Option<u64>,
+    ) -> Result<Self, String> {
+        FrameHeader::new(
+            encoding,
+            samples_per_channel,
+            sample_rate.get(),
+            channels.get(),
+            bits_per_sample,
+            endianness,
+            id,
+            pts,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_typed_values_validate() {
+        for (raw, constant) in [
+            (16000, SampleRate::R16K),
+            (44100, SampleRate::R44_1K),
+            (48000, SampleRate::R48K),
+            (96000, SampleRate::R96K),
+        ] {
+            assert_eq!(SampleRate::try_from(raw), Ok(constant));
+            assert_eq!(u32::from(constant), raw);
+        }
+        for raw in [0, 24, 22050, 192000, u32::MAX] {
+            assert_eq!(
+                SampleRate::try_from(raw),
+                Err(FrameHeaderError::FieldOutOfRange("sample_rate"))
+            );
+        }
+
+        for raw in 1..=16 {
+            assert_eq!(ChannelCount::try_from(raw).unwrap().get(), raw);
+        }
+        for raw in [0, 17, u8::MAX] {
+            assert_eq!(
+                ChannelCount::try_from(raw),
+                Err(FrameHeaderError::InvalidChannels(raw))
+            );
+        }
+    }
+
+    #[test]
+    fn test_typed_and_untyped_constructors_agree() {
+        for sample_rate in [
+            SampleRate::R16K,
+            SampleRate::R44_1K,
+            SampleRate::R48K,
+            SampleRate::R96K,
+        ] {
+            for channels in [1, 2, 16] {
+                let channels = ChannelCount::try_from(channels).unwrap();
+                let typed = FrameHeader::new_typed(
+                    EncodingFlag::PCMSigned,
+                    960,
+                    sample_rate,
+                    channels,
+                    24,
+                    Endianness::LittleEndian,
+                    Some(1),
+                    Some(2),
+                );
+                let untyped = FrameHeader::new(
+                    EncodingFlag::PCMSigned,
+                    960,
+                    sample_rate.get(),
+                    channels.get(),
+                    24,
+                    Endianness::LittleEndian,
+                    Some(1),
+                    Some(2),
+                );
+                assert_eq!(typed, untyped);
+                let built = FrameHeader::builder()
+                    .encoding(EncodingFlag::PCMSigned)
+                    .samples_per_channel(960)
+                    .typed_sample_rate(sample_rate)
+                    .typed_channels(channels)
+                    .bits_per_sample(24)
+                    .id(Some(1))
+                    .pts(Some(2))
+                    .build();
+                assert_eq!(built, typed);
+            }
+        }
+
+        // Other fields are still checked.
+        assert!(FrameHeader::new_typed(
+            EncodingFlag::PCMSigned,
+            4096,
+            SampleRate::R48K,
+            ChannelCount::try_from(2).unwrap(),
+            24,
+            Endianness::LittleEndian,
+            None,
+            None,
+        )
+        .is_err());
+    }
+}
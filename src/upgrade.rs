@@ -0,0 +1,86 @@
+use crate::{EncodingFlag, FrameHeader};
+
+impl FrameHeader {
+    /// FLAC for audio that is not FLAC already: PCM is lossless as it is and
+    /// FLAC only packs it, while lossy audio can be kept losslessly from its
+    /// decoded output. `None` for FLAC, video, metadata and unknown
+    /// encodings.
+    pub fn suggested_lossless_upgrade(&self) -> Option<EncodingFlag> {
+        match self.encoding {
+            EncodingFlag::PCMSigned
+            | EncodingFlag::PCMFloat
+            | EncodingFlag::Opus
+            | EncodingFlag::AAC => Some(EncodingFlag::FLAC),
+            EncodingFlag::FLAC
+            | EncodingFlag::H264
+            | EncodingFlag::Metadata
+            | EncodingFlag::Unknown(_) => None,
+        }
+    }
+
+    pub fn clone_with_encoding_upgrade(&self) -> Option<FrameHeader> {
+        self.suggested_lossless_upgrade()
+            .map(|encoding| FrameHeader {
+                encoding,
+                ..self.clone()
+            })
+    }
+
+    /// False for unknown encodings, whose compression is not known.
+    pub fn is_lossy(&self) -> bool {
+        matches!(
+            self.encoding,
+            EncodingFlag::Opus | EncodingFlag::AAC | EncodingFlag::H264
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Endianness;
+
+    #[test]
+    fn test_lossless_upgrade_for_every_encoding() {
+        for (encoding, upgrade, lossy) in [
+            (EncodingFlag::PCMSigned, Some(EncodingFlag::FLAC), false),
+            (EncodingFlag::PCMFloat, Some(EncodingFlag::FLAC), false),
+            (EncodingFlag::Opus, Some(EncodingFlag::FLAC), true),
+            (EncodingFlag::FLAC, None, false),
+            (EncodingFlag::AAC, Some(EncodingFlag::FLAC), true),
+            (EncodingFlag::H264, None, true),
+            (EncodingFlag::Unknown(7), None, false),
+        ] {
+            let header = FrameHeader::new(
+                encoding,
+                1024,
+                48000,
+                2,
+                16,
+                Endianness::LittleEndian,
+                Some(5),
+                Some(10),
+            )
+            .unwrap();
+            assert_eq!(
+                header.suggested_lossless_upgrade(),
+                upgrade,
+                "{:?}",
+                encoding
+            );
+            assert_eq!(header.is_lossy(), lossy, "{:?}", encoding);
+
+            let upgraded = header.clone_with_encoding_upgrade();
+            assert_eq!(upgraded.as_ref().map(|h| h.encoding()), upgrade);
+            if let Some(upgraded) = upgraded {
+                assert_eq!(
+                    FrameHeader {
+                        encoding,
+                        ..upgraded
+                    },
+                    header
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,924 @@
+use crate::{EncodingFlag, Endianness, FrameHeader, FrameHeaderError, FrameHeaderV2, FrameReader};
+use std::fmt;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+
+const WAVE_FORMAT_PCM: u16 = 1;
+const WAVE_FORMAT_IEEE_FLOAT: u16 = 3;
+const WAVE_FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+const RIFF_SIZE_OFFSET: u64 = 4;
+const DATA_SIZE_OFFSET: u64 = 40;
+
+#[derive(Debug)]
+pub enum ExportError {
+    Io(io::Error),
+    /// The stream holds no frames, so there is no format to write.
+    Empty,
+    NonPcm {
+        offset: u64,
+        encoding: EncodingFlag,
+    },
+    /// A frame's format differs from the first frame's.
+    Incompatible {
+        offset: u64,
+    },
+    UnsupportedBits {
+        offset: u64,
+        bits_per_sample: u8,
+    },
+    /// The payload size does not match the frame count and format.
+    PayloadSize {
+        offset: u64,
+    },
+    /// The audio does not fit a WAV file's 32-bit sizes.
+    TooLarge,
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportError::Io(err) => write!(f, "{}", err),
+            ExportError::Empty => write!(f, "Frame stream is empty"),
+            ExportError::NonPcm { offset, encoding } => {
+                write!(f, "Frame at offset {} is {:?}, not PCM", offset, encoding)
+            }
+            ExportError::Incompatible { offset } => {
+                write!(f, "Frame at offset {} changes the audio format", offset)
+            }
+            ExportError::UnsupportedBits {
+                offset,
+                bits_per_sample,
+            } => write!(
+                f,
+                "Frame at offset {} has {}-bit samples, which WAV export does not support",
+                offset, bits_per_sample
+            ),
+            ExportError::PayloadSize { offset } => write!(
+                f,
+                "Frame at offset {} has a payload size that does not match its frame count",
+                offset
+            ),
+            ExportError::TooLarge => write!(f, "Audio is too large for a WAV file"),
+        }
+    }
+}
+
+impl std::error::Error for ExportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ExportError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ExportError {
+    fn from(err: io::Error) -> Self {
+        ExportError::Io(err)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WavSummary {
+    pub frames: u64,
+    /// Samples per channel.
+    pub sample_frames: u64,
+    pub sample_rate: u32,
+    pub channels: u8,
+    pub bits_per_sample: u8,
+    pub float: bool,
+    pub data_bytes: u32,
+}
+
+/// Writes the PCM frames of a v2 frame stream as a WAV file. Samples are
+/// converted to little-endian, and 24-bit samples carried in 4-byte
+/// containers are packed to 3 bytes. Signed 8-bit samples become WAV's
+/// unsigned 8-bit. Encoder delay and end padding are trimmed, and frames
+/// marked corrupt are written as silence.
+pub fn write_wav<R: Read, W: Write + Seek>(
+    frames: R,
+    mut out: W,
+) -> Result<WavSummary, ExportError> {
+    let mut reader = FrameReader::new(frames);
+    let start = out.stream_position()?;
+    let mut summary: Option<WavSummary> = None;
+    let mut data_bytes = 0u64;
+    let mut samples = Vec::new();
+
+    loop {
+        let offset = reader.position();
+        let Some((header, payload)) = reader.read_frame()? else {
+            break;
+        };
+        let format = wav_format(&header, offset)?;
+        let summary = match &mut summary {
+            Some(summary) if !summary.same_format(&format) => {
+                return Err(ExportError::Incompatible { offset });
+            }
+            Some(summary) => summary,
+            None => {
+                write_header(&mut out, &format)?;
+                summary.insert(format)
+            }
+        };
+
+        let block_align = header.channels() as usize * (header.bits_per_sample() as usize / 8);
+        if header.is_payload_corrupt() {
+            // WAV's 8-bit samples are unsigned, centred on 0x80.
+            let silence = if header.bits_per_sample() == 8 {
+                0x80
+            } else {
+                0
+            };
+            samples.clear();
+            samples.resize(header.frame_count() as usize * block_align, silence);
+        } else {
+            to_wav_samples(&header, &payload, &mut samples)
+                .ok_or(ExportError::PayloadSize { offset })?;
+        }
+        let kept = header.trimmed_frame_count() as usize * block_align;
+        let skip = (header.encoder_delay().unwrap_or(0) as usize * block_align).min(samples.len());
+        let kept = &samples[skip..skip + kept];
+        out.write_all(kept)?;
+        data_bytes += kept.len() as u64;
+        summary.frames += 1;
+        summary.sample_frames += header.trimmed_frame_count() as u64;
+    }
+
+    let mut summary = summary.ok_or(ExportError::Empty)?;
+    let padded = data_bytes + data_bytes % 2;
+    // RIFF size counts everything after its own field.
+    let riff_size = u32::try_from(padded + 36).map_err(|_| ExportError::TooLarge)?;
+    summary.data_bytes = data_bytes as u32;
+    if data_bytes % 2 == 1 {
+        out.write_all(&[0])?;
+    }
+    let end = out.stream_position()?;
+    out.seek(SeekFrom::Start(start + RIFF_SIZE_OFFSET))?;
+    out.write_all(&riff_size.to_le_bytes())?;
+    out.seek(SeekFrom::Start(start + DATA_SIZE_OFFSET))?;
+    out.write_all(&summary.data_bytes.to_le_bytes())?;
+    out.seek(SeekFrom::Start(end))?;
+    out.flush()?;
+    Ok(summary)
+}
+
+impl WavSummary {
+    fn same_format(&self, other: &WavSummary) -> bool {
+        (
+            self.sample_rate,
+            self.channels,
+            self.bits_per_sample,
+            self.float,
+        ) == (
+            other.sample_rate,
+            other.channels,
+            other.bits_per_sample,
+            other.float,
+        )
+    }
+}
+
+fn wav_format(header: &FrameHeaderV2, offset: u64) -> Result<WavSummary, ExportError> {
+    let float = match header.encoding() {
+        EncodingFlag::PCMSigned => false,
+        EncodingFlag::PCMFloat => true,
+        encoding => return Err(ExportError::NonPcm { offset, encoding }),
+    };
+    let bits_per_sample = header.bits_per_sample();
+    let supported = if float {
+        matches!(bits_per_sample, 32 | 64)
+    } else {
+        matches!(bits_per_sample, 8 | 16 | 24 | 32)
+    };
+    if !supported {
+        return Err(ExportError::UnsupportedBits {
+            offset,
+            bits_per_sample,
+        });
+    }
+    Ok(WavSummary {
+        frames: 0,
+        sample_frames: 0,
+        sample_rate: header.sample_rate(),
+        channels: header.channels(),
+        bits_per_sample,
+        float,
+        data_bytes: 0,
+    })
+}
+
+fn write_header<W: Write>(out: &mut W, format: &WavSummary) -> io::Result<()> {
+    let block_align = format.channels as u16 * (format.bits_per_sample / 8) as u16;
+    let mut header = Vec::with_capacity(44);
+    header.extend_from_slice(b"RIFF");
+    header.extend_from_slice(&0u32.to_le_bytes());
+    header.extend_from_slice(b"WAVEfmt ");
+    header.extend_from_slice(&16u32.to_le_bytes());
+    header.extend_from_slice(
+        &if format.float {
+            WAVE_FORMAT_IEEE_FLOAT
+        } else {
+            WAVE_FORMAT_PCM
+        }
+        .to_le_bytes(),
+    );
+    header.extend_from_slice(&(format.channels as u16).to_le_bytes());
+    header.extend_from_slice(&format.sample_rate.to_le_bytes());
+    header.extend_from_slice(&(format.sample_rate * block_align as u32).to_le_bytes());
+    header.extend_from_slice(&block_align.to_le_bytes());
+    header.extend_from_slice(&(format.bits_per_sample as u16).to_le_bytes());
+    header.extend_from_slice(b"data");
+    header.extend_from_slice(&0u32.to_le_bytes());
+    out.write_all(&header)
+}
+
+// Converts one frame's payload to WAV sample bytes, or `None` if the payload
+// size does not fit the frame count.
+fn to_wav_samples(header: &FrameHeaderV2, payload: &[u8], samples: &mut Vec<u8>) -> Option<()> {
+    let count = header.frame_count() as usize * header.channels() as usize;
+    let width = header.bits_per_sample() as usize / 8;
+    let container = match payload.len() {
+        len if len == count * width => width,
+        // 24-bit samples may travel in 4-byte containers.
+        len if width == 3 && len == count * 4 => 4,
+        _ => return None,
+    };
+    let big_endian = header.endianness() == Endianness::BigEndian;
+
+    samples.clear();
+    samples.reserve(count * width);
+    for sample in payload.chunks_exact(container) {
+        // The significant bytes of a widened 24-bit sample are its high three.
+        let sample = match (container, big_endian) {
+            (4, true) if width == 3 => &sample[..3],
+            (4, false) if width == 3 => &sample[1..],
+            _ => sample,
+        };
+        let start = samples.len();
+        if big_endian {
+            samples.extend(sample.iter().rev());
+        } else {
+            samples.extend_from_slice(sample);
+        }
+        if width == 1 {
+            samples[start] ^= 0x80;
+        }
+    }
+    Some(())
+}
+
+#[derive(Debug)]
+pub enum ImportError {
+    Io(io::Error),
+    /// The RIFF structure is broken; says what was wrong.
+    Malformed(&'static str),
+    /// `WAVE_FORMAT_EXTENSIBLE` files are not supported yet.
+    Extensible,
+    /// A compressed or otherwise non-PCM format tag.
+    UnsupportedFormat(u16),
+    /// The audio format or frame size does not fit a v1 header.
+    Header(FrameHeaderError),
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::Io(err) => write!(f, "{}", err),
+            ImportError::Malformed(reason) => write!(f, "Malformed WAV file: {}", reason),
+            ImportError::Extensible => write!(f, "WAVE_FORMAT_EXTENSIBLE is not supported"),
+            ImportError::UnsupportedFormat(tag) => {
+                write!(f, "WAV format tag {:#06x} is not PCM or IEEE float", tag)
+            }
+            ImportError::Header(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for ImportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ImportError::Io(err) => Some(err),
+            ImportError::Header(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ImportError {
+    fn from(err: io::Error) -> Self {
+        if err.kind() == io::ErrorKind::UnexpectedEof {
+            ImportError::Malformed("file ends inside a chunk header")
+        } else {
+            ImportError::Io(err)
+        }
+    }
+}
+
+impl From<FrameHeaderError> for ImportError {
+    fn from(err: FrameHeaderError) -> Self {
+        ImportError::Header(err)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct WavImportOptions {
+    /// PTS of the first frame, in microseconds.
+    pub base_pts: u64,
+    pub id: Option<u64>,
+}
+
+/// Frames of a WAV file, from `read_wav`.
+pub struct WavFrames<R: Read> {
+    wav: Option<R>,
+    samples_per_frame: u16,
+    options: WavImportOptions,
+    template: Option<FrameHeader>,
+    remaining: u64,
+    samples: u64,
+}
+
+/// Reads a PCM or IEEE float WAV file as v1 frames of `samples_per_frame`
+/// samples, the last frame holding whatever is left. PTS counts from
+/// `options.base_pts` by the samples before each frame. A format error is
+/// the first and only item.
+pub fn read_wav<R: Read>(
+    wav: R,
+    samples_per_frame: u16,
+    options: WavImportOptions,
+) -> WavFrames<R> {
+    WavFrames {
+        wav: Some(wav),
+        samples_per_frame,
+        options,
+        template: None,
+        remaining: 0,
+        samples: 0,
+    }
+}
+
+impl<R: Read> WavFrames<R> {
+    fn start(&mut self) -> Result<(), ImportError> {
+        let wav = self.wav.as_mut().unwrap();
+        let mut riff = [0u8; 12];
+        wav.read_exact(&mut riff)?;
+        if &riff[..4] != b"RIFF" || &riff[8..] != b"WAVE" {
+            return Err(ImportError::Malformed("not a RIFF WAVE file"));
+        }
+
+        let mut format = None;
+        loop {
+            let mut chunk = [0u8; 8];
+            wav.read_exact(&mut chunk)?;
+            let size = u32::from_le_bytes(chunk[4..].try_into().unwrap()) as u64;
+            match &chunk[..4] {
+                b"fmt " => {
+                    if size < 16 {
+                        return Err(ImportError::Malformed("fmt chunk is too short"));
+                    }
+                    let mut fmt = [0u8; 16];
+                    wav.read_exact(&mut fmt)?;
+                    skip(wav, size - 16 + size % 2)?;
+                    format = Some(fmt);
+                }
+                b"data" => {
+                    let fmt =
+                        format.ok_or(ImportError::Malformed("data chunk before fmt chunk"))?;
+                    self.template = Some(self.template_from(&fmt)?);
+                    self.remaining = size;
+                    return Ok(());
+                }
+                _ => skip(wav, size + size % 2)?,
+            }
+        }
+    }
+
+    fn template_from(&self, fmt: &[u8; 16]) -> Result<FrameHeader, ImportError> {
+        let field = |at: usize| u16::from_le_bytes([fmt[at], fmt[at + 1]]);
+        let encoding = match field(0) {
+            WAVE_FORMAT_PCM => EncodingFlag::PCMSigned,
+            WAVE_FORMAT_IEEE_FLOAT => EncodingFlag::PCMFloat,
+            WAVE_FORMAT_EXTENSIBLE => return Err(ImportError::Extensible),
+            tag => return Err(ImportError::UnsupportedFormat(tag)),
+        };
+        let channels =
+            u8::try_from(field(2)).map_err(|_| FrameHeaderError::InvalidChannels(u8::MAX))?;
+        let sample_rate = u32::from_le_bytes(fmt[4..8].try_into().unwrap());
+        let bits_per_sample = u8::try_from(field(14))
+            .map_err(|_| FrameHeaderError::FieldOutOfRange("bits_per_sample"))?;
+        if field(12) as usize != channels as usize * (bits_per_sample as usize).div_ceil(8) {
+            return Err(ImportError::Malformed(
+                "block align does not match the format",
+            ));
+        }
+        if self.samples_per_frame == 0 {
+            return Err(FrameHeaderError::FieldOutOfRange("samples_per_frame").into());
+        }
+
+        Ok(FrameHeader::new(
+            encoding,
+            self.samples_per_frame,
+            sample_rate,
+            channels,
+            bits_per_sample,
+            Endianness::LittleEndian,
+            self.options.id,
+            None,
+        )
+        .map_err(FrameHeaderError::from)?)
+    }
+
+    fn next_frame(&mut self) -> Result<Option<(FrameHeader, Vec<u8>)>, ImportError> {
+        if self.template.is_none() {
+            self.start()?;
+        }
+        let template = self.template.as_ref().unwrap();
+        let block_align = template.channels as u64 * template.bits_per_sample as u64 / 8;
+
+        let want = (self.samples_per_frame as u64 * block_align).min(self.remaining);
+        let mut payload = Vec::with_capacity(want as usize);
+        self.wav
+            .as_mut()
+            .unwrap()
+            .by_ref()
+            .take(want)
+            .read_to_end(&mut payload)?;
+        // A data chunk may claim more than the file holds; stop where it ends.
+        self.remaining = if (payload.len() as u64) < want {
+            0
+        } else {
+            self.remaining - want
+        };
+        if payload.is_empty() {
+            return Ok(None);
+        }
+        if !(payload.len() as u64).is_multiple_of(block_align) {
+            return Err(ImportError::Malformed("data ends inside a sample"));
+        }
+
+        let sample_size = (payload.len() as u64 / block_align) as u16;
+        let pts = self.samples as u128 * 1_000_000 / template.sample_rate as u128;
+        let pts = u64::try_from(pts)
+            .ok()
+            .and_then(|pts| self.options.base_pts.checked_add(pts))
+            .ok_or(FrameHeaderError::FieldOutOfRange("pts"))?;
+        self.samples += sample_size as u64;
+
+        let header = FrameHeader {
+            sample_size,
+            pts: Some(pts),
+            ..template.clone()
+        };
+        Ok(Some((header, payload)))
+    }
+}
+
+impl<R: Read> Iterator for WavFrames<R> {
+    type Item = Result<(FrameHeader, Vec<u8>), ImportError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.wav.as_ref()?;
+        let item = self.next_frame().transpose();
+        if !matches!(item, Some(Ok(_))) {
+            // Nothing follows an error or the end of the data.
+            self.wav = None;
+        }
+        item
+    }
+}
+
+fn skip<R: Read>(reader: &mut R, len: u64) -> io::Result<()> {
+    let skipped = io::copy(&mut reader.by_ref().take(len), &mut io::sink())?;
+    if skipped < len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FrameWriter;
+    use std::io::Cursor;
+
+    fn pcm_header(
+        encoding: EncodingFlag,
+        bits: u8,
+        endianness: Endianness,
+        payload: &[u8],
+        frame_count: u32,
+    ) -> FrameHeaderV2 {
+        FrameHeaderV2::new(
+            encoding,
+            payload.len() as u32,
+            frame_count,
+            48000,
+            2,
+            bits,
+            endianness,
+            None,
+            None,
+            None,
+        )
+        .unwrap()
+    }
+
+    fn export(frames: &[(FrameHeaderV2, Vec<u8>)]) -> Result<(WavSummary, Vec<u8>), ExportError> {
+        let mut writer = FrameWriter::new(Vec::new());
+        for (header, payload) in frames {
+            writer.write_frame(header, payload).unwrap();
+        }
+        let stream = writer.into_inner();
+        let mut wav = Cursor::new(Vec::new());
+        let summary = write_wav(&stream[..], &mut wav)?;
+        Ok((summary, wav.into_inner()))
+    }
+
+    #[test]
+    fn test_wav_roundtrip_through_hound() {
+        let samples: Vec<i32> = (0..96).map(|i| (i - 48) * 70_001).collect();
+
+        for bits in [16u8, 24, 32] {
+            let max = 1i64 << (bits - 1);
+            let values: Vec<i32> = samples.iter().map(|&s| (s as i64 % max) as i32).collect();
+
+            // Alternate byte orders, and 4-byte containers for 24-bit.
+            let frames: Vec<_> = values
+                .chunks(32)
+                .enumerate()
+                .map(|(index, chunk)| {
+                    let big = index % 2 == 1;
+                    let width = if bits == 24 && big {
+                        4
+                    } else {
+                        bits as usize / 8
+                    };
+                    let mut payload = Vec::new();
+                    for &value in chunk {
+                        let bytes = if width == 4 && bits == 24 {
+                            (value << 8).to_be_bytes().to_vec()
+                        } else if big {
+                            value.to_be_bytes()[4 - width..].to_vec()
+                        } else {
+                            value.to_le_bytes()[..width].to_vec()
+                        };
+                        payload.extend_from_slice(&bytes);
+                    }
+                    let endianness = if big {
+                        Endianness::BigEndian
+                    } else {
+                        Endianness::LittleEndian
+                    };
+                    let header = pcm_header(
+                        EncodingFlag::PCMSigned,
+                        bits,
+                        endianness,
+                        &payload,
+                        chunk.len() as u32 / 2,
+                    );
+                    (header, payload)
+                })
+                .collect();
+
+            let (summary, wav) = export(&frames).unwrap();
+            assert_eq!(summary.frames, 3);
+            assert_eq!(summary.sample_frames, 48);
+            assert_eq!(summary.data_bytes as usize, 96 * bits as usize / 8);
+
+            let mut reader = hound::WavReader::new(Cursor::new(wav)).unwrap();
+            let spec = reader.spec();
+            assert_eq!(
+                (spec.channels, spec.sample_rate, spec.bits_per_sample),
+                (2, 48000, bits as u16)
+            );
+            assert_eq!(spec.sample_format, hound::SampleFormat::Int);
+            let decoded: Vec<i32> = reader.samples::<i32>().map(Result::unwrap).collect();
+            assert_eq!(decoded, values, "{}-bit", bits);
+        }
+    }
+
+    #[test]
+    fn test_wav_trims_encoder_delay_and_end_padding() {
+        let values: Vec<i16> = (0..60).collect();
+        let frames: Vec<_> = values
+            .chunks(20)
+            .enumerate()
+            .map(|(index, chunk)| {
+                let payload: Vec<u8> = chunk.iter().flat_map(|v| v.to_le_bytes()).collect();
+                let header = pcm_header(
+                    EncodingFlag::PCMSigned,
+                    16,
+                    Endianness::LittleEndian,
+                    &payload,
+                    10,
+                );
+                let header = match index {
+                    0 => header.with_encoder_delay(Some(3)),
+                    2 => header.with_end_padding(Some(4)),
+                    _ => header,
+                };
+                (header, payload)
+            })
+            .collect();
+
+        let (summary, wav) = export(&frames).unwrap();
+        assert_eq!(summary.frames, 3);
+        assert_eq!(summary.sample_frames, 30 - 3 - 4);
+        assert_eq!(summary.data_bytes, 23 * 4);
+        let decoded: Vec<i16> = hound::WavReader::new(Cursor::new(wav))
+            .unwrap()
+            .samples::<i16>()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(decoded, values[6..52]);
+    }
+
+    #[test]
+    fn test_wav_conceals_corrupt_frames() {
+        let frames: Vec<_> = [1i16, 2, 3]
+            .into_iter()
+            .map(|value| {
+                let mut payload: Vec<u8> =
+                    [value; 8].iter().flat_map(|v| v.to_le_bytes()).collect();
+                // A corrupt frame's payload need not match its frame count.
+                if value == 2 {
+                    payload.truncate(3);
+                }
+                let header = pcm_header(
+                    EncodingFlag::PCMSigned,
+                    16,
+                    Endianness::LittleEndian,
+                    &payload,
+                    4,
+                );
+                (header.with_payload_corrupt(value == 2), payload)
+            })
+            .collect();
+
+        let (summary, wav) = export(&frames).unwrap();
+        assert_eq!(summary.sample_frames, 12);
+        let decoded: Vec<i16> = hound::WavReader::new(Cursor::new(wav))
+            .unwrap()
+            .samples::<i16>()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(decoded, [[1; 8], [0; 8], [3; 8]].concat());
+    }
+
+    #[test]
+    fn test_float_wav_roundtrip_through_hound() {
+        let values: Vec<f32> = (0..20).map(|i| i as f32 / 20.0 - 0.5).collect();
+        let payload: Vec<u8> = values.iter().flat_map(|v| v.to_be_bytes()).collect();
+        let header = pcm_header(
+            EncodingFlag::PCMFloat,
+            32,
+            Endianness::BigEndian,
+            &payload,
+            10,
+        );
+        let (summary, wav) = export(&[(header, payload)]).unwrap();
+        assert!(summary.float);
+
+        let mut reader = hound::WavReader::new(Cursor::new(wav)).unwrap();
+        assert_eq!(reader.spec().sample_format, hound::SampleFormat::Float);
+        let decoded: Vec<f32> = reader.samples::<f32>().map(Result::unwrap).collect();
+        assert_eq!(decoded, values);
+    }
+
+    #[test]
+    fn test_odd_data_size_is_padded() {
+        let payload = vec![1, 2, 3];
+        let header = FrameHeaderV2::new(
+            EncodingFlag::PCMSigned,
+            3,
+            1,
+            48000,
+            1,
+            24,
+            Endianness::LittleEndian,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        let (summary, wav) = export(&[(header, payload)]).unwrap();
+        assert_eq!(summary.data_bytes, 3);
+        assert_eq!(wav.len(), 44 + 4);
+        assert_eq!(u32::from_le_bytes(wav[4..8].try_into().unwrap()), 40);
+
+        let mut reader = hound::WavReader::new(Cursor::new(wav)).unwrap();
+        assert_eq!(reader.samples::<i32>().next().unwrap().unwrap(), 0x030201);
+    }
+
+    #[test]
+    fn test_export_errors_name_offending_frame() {
+        let payload = vec![0u8; 8];
+        let pcm = pcm_header(
+            EncodingFlag::PCMSigned,
+            16,
+            Endianness::LittleEndian,
+            &payload,
+            2,
+        );
+        let frame_size = (pcm.size() + payload.len()) as u64;
+
+        let opus = pcm_header(
+            EncodingFlag::Opus,
+            16,
+            Endianness::LittleEndian,
+            &payload,
+            2,
+        );
+        match export(&[(pcm.clone(), payload.clone()), (opus, payload.clone())]) {
+            Err(ExportError::NonPcm { offset, encoding }) => {
+                assert_eq!((offset, encoding), (frame_size, EncodingFlag::Opus))
+            }
+            other => panic!("expected NonPcm, got {:?}", other),
+        }
+
+        let mono = FrameHeaderV2::new(
+            EncodingFlag::PCMSigned,
+            8,
+            4,
+            48000,
+            1,
+            16,
+            Endianness::LittleEndian,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        assert!(matches!(
+            export(&[(pcm.clone(), payload.clone()), (mono, payload.clone())]),
+            Err(ExportError::Incompatible { offset }) if offset == frame_size
+        ));
+
+        let short = pcm_header(
+            EncodingFlag::PCMSigned,
+            16,
+            Endianness::LittleEndian,
+            &payload,
+            3,
+        );
+        assert!(matches!(
+            export(&[(short, payload.clone())]),
+            Err(ExportError::PayloadSize { offset: 0 })
+        ));
+        assert!(matches!(export(&[]), Err(ExportError::Empty)));
+    }
+
+    // Hound writes WAVE_FORMAT_EXTENSIBLE above 16 bits; rewrite its fmt
+    // chunk as the plain 16-byte form `read_wav` accepts.
+    fn hound_wav<S: hound::Sample + Copy>(spec: hound::WavSpec, samples: &[S]) -> Vec<u8> {
+        let mut wav = Cursor::new(Vec::new());
+        let mut writer = hound::WavWriter::new(&mut wav, spec).unwrap();
+        for &sample in samples {
+            writer.write_sample(sample).unwrap();
+        }
+        writer.finalize().unwrap();
+        let wav = wav.into_inner();
+
+        let fmt_size = u32::from_le_bytes(wav[16..20].try_into().unwrap()) as usize;
+        let mut fmt = wav[20..36].to_vec();
+        if fmt_size == 40 {
+            // The real tag leads the sub-format GUID.
+            fmt[..2].copy_from_slice(&wav[44..46]);
+        }
+        let mut plain = b"RIFF\0\0\0\0WAVEfmt ".to_vec();
+        plain.extend_from_slice(&16u32.to_le_bytes());
+        plain.extend_from_slice(&fmt);
+        plain.extend_from_slice(&wav[20 + fmt_size..]);
+        let riff_size = (plain.len() - 8) as u32;
+        plain[4..8].copy_from_slice(&riff_size.to_le_bytes());
+        plain
+    }
+
+    fn spec(bits_per_sample: u16, sample_format: hound::SampleFormat) -> hound::WavSpec {
+        hound::WavSpec {
+            channels: 2,
+            sample_rate: 44100,
+            bits_per_sample,
+            sample_format,
+        }
+    }
+
+    #[test]
+    fn test_read_wav_preserves_samples() {
+        let values: Vec<i32> = (0..2 * 1000)
+            .map(|i| (i * 7919) % 60_000 - 30_000)
+            .collect();
+        for bits in [16u16, 24, 32] {
+            let wav = hound_wav(spec(bits, hound::SampleFormat::Int), &values);
+            let options = WavImportOptions {
+                base_pts: 5_000_000,
+                id: Some(4),
+            };
+            let frames: Vec<_> = read_wav(&wav[..], 441, options)
+                .map(Result::unwrap)
+                .collect();
+
+            let sizes: Vec<u16> = frames
+                .iter()
+                .map(|(h, _)| h.samples_per_channel())
+                .collect();
+            assert_eq!(sizes, [441, 441, 118]);
+            let pts: Vec<_> = frames.iter().map(|(h, _)| h.pts().unwrap()).collect();
+            assert_eq!(pts, [5_000_000, 5_010_000, 5_020_000]);
+            for (header, payload) in &frames {
+                assert_eq!(header.encoding(), EncodingFlag::PCMSigned);
+                assert_eq!(header.bits_per_sample() as u16, bits);
+                assert_eq!(header.id(), Some(4));
+                assert_eq!(
+                    payload.len(),
+                    header.samples_per_channel() as usize * 2 * bits as usize / 8
+                );
+            }
+
+            let width = bits as usize / 8;
+            let decoded: Vec<i32> = frames
+                .iter()
+                .flat_map(|(_, payload)| payload.chunks(width))
+                .map(|sample| {
+                    let mut bytes = [0u8; 4];
+                    bytes[4 - width..].copy_from_slice(sample);
+                    i32::from_le_bytes(bytes) >> (32 - bits)
+                })
+                .collect();
+            assert_eq!(decoded, values, "{}-bit", bits);
+        }
+
+        let floats: Vec<f32> = (0..100).map(|i| i as f32 / 100.0).collect();
+        let wav = hound_wav(spec(32, hound::SampleFormat::Float), &floats);
+        let frames: Vec<_> = read_wav(&wav[..], 50, WavImportOptions::default())
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].0.encoding(), EncodingFlag::PCMFloat);
+        assert_eq!(frames[0].0.pts(), Some(0));
+        let decoded: Vec<f32> = frames[0]
+            .1
+            .chunks(4)
+            .map(|b| f32::from_le_bytes(b.try_into().unwrap()))
+            .collect();
+        assert_eq!(decoded, floats);
+    }
+
+    #[test]
+    fn test_read_wav_rejects_unsupported_files() {
+        let first_error = |wav: &[u8], samples_per_frame| {
+            let mut frames = read_wav(wav, samples_per_frame, WavImportOptions::default());
+            let error = frames.next().unwrap().unwrap_err();
+            assert!(frames.next().is_none());
+            error
+        };
+
+        // Hound's own 24-bit output uses WAVE_FORMAT_EXTENSIBLE.
+        let mut wav = Cursor::new(Vec::new());
+        let mut writer =
+            hound::WavWriter::new(&mut wav, spec(24, hound::SampleFormat::Int)).unwrap();
+        writer.write_sample(1i32).unwrap();
+        writer.write_sample(2i32).unwrap();
+        writer.finalize().unwrap();
+        assert!(matches!(
+            first_error(&wav.into_inner(), 441),
+            ImportError::Extensible
+        ));
+
+        let mut compressed = hound_wav(spec(16, hound::SampleFormat::Int), &[0i16; 4]);
+        compressed[20..22].copy_from_slice(&0x55u16.to_le_bytes());
+        assert!(matches!(
+            first_error(&compressed, 441),
+            ImportError::UnsupportedFormat(0x55)
+        ));
+
+        let wav = hound_wav(spec(16, hound::SampleFormat::Int), &[0i16; 4]);
+        assert!(matches!(first_error(&wav, 0), ImportError::Header(_)));
+        assert!(matches!(first_error(&wav, 4096), ImportError::Header(_)));
+        assert!(matches!(
+            first_error(&wav[..30], 441),
+            ImportError::Malformed(_)
+        ));
+        assert!(matches!(
+            first_error(b"RIFX\0\0\0\0WAVE", 441),
+            ImportError::Malformed(_)
+        ));
+        let mut torn = wav.clone();
+        torn.pop();
+        let mut frames = read_wav(&torn[..], 441, WavImportOptions::default());
+        assert!(matches!(
+            frames.next(),
+            Some(Err(ImportError::Malformed(_)))
+        ));
+
+        let mut eight_bit = Cursor::new(Vec::new());
+        let mut writer =
+            hound::WavWriter::new(&mut eight_bit, spec(8, hound::SampleFormat::Int)).unwrap();
+        writer.write_sample(1i8).unwrap();
+        writer.write_sample(2i8).unwrap();
+        writer.finalize().unwrap();
+        assert!(matches!(
+            first_error(&eight_bit.into_inner(), 441),
+            ImportError::Header(_)
+        ));
+    }
+}
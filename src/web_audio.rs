@@ -0,0 +1,217 @@
+use crate::{ChannelCount, EncodingFlag, Endianness, FrameHeader, FrameHeaderError, SampleRate};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+// Limits every Web Audio implementation must accept for AudioBuffer.
+const WEB_AUDIO_MIN_SAMPLE_RATE: f32 = 8000.0;
+const WEB_AUDIO_MAX_SAMPLE_RATE: f32 = 96000.0;
+const WEB_AUDIO_MAX_CHANNELS: usize = 32;
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AudioBufferConfig {
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = sampleRate))]
+    pub sample_rate: f32,
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = numberOfChannels))]
+    pub number_of_channels: usize,
+    pub length: usize,
+}
+
+impl AudioBufferConfig {
+    pub fn is_valid_for_web_audio(&self) -> bool {
+        (WEB_AUDIO_MIN_SAMPLE_RATE..=WEB_AUDIO_MAX_SAMPLE_RATE).contains(&self.sample_rate)
+            && (1..=WEB_AUDIO_MAX_CHANNELS).contains(&self.number_of_channels)
+            && self.length > 0
+    }
+}
+
+impl FrameHeader {
+    pub fn to_audio_buffer_config(&self) -> Result<AudioBufferConfig, FrameHeaderError> {
+        if !matches!(
+            self.encoding,
+            EncodingFlag::PCMSigned | EncodingFlag::PCMFloat
+        ) {
+            return Err(FrameHeaderError::InvalidEncoding(
+                self.encoding.code() as u32
+            ));
+        }
+        if self.sample_size == 0 {
+            return Err(FrameHeaderError::FieldOutOfRange("length"));
+        }
+
+        Ok(AudioBufferConfig {
+            sample_rate: self.sample_rate as f32,
+            number_of_channels: self.channels as usize,
+            length: self.sample_size as usize,
+        })
+    }
+
+    /// 32-bit samples are taken to be float, matching Web Audio's own buffers;
+    /// 16- and 24-bit samples are signed integers.
+    pub fn from_audio_buffer_config(
+        config: &AudioBufferConfig,
+        bits_per_sample: u8,
+        endianness: Endianness,
+    ) -> Result<FrameHeader, FrameHeaderError> {
+        if config.sample_rate.fract() != 0.0 || config.sample_rate < 0.0 {
+            return Err(FrameHeaderError::FieldOutOfRange("sample_rate"));
+        }
+        let channels = u8::try_from(config.number_of_channels)
+            .map_err(|_| FrameHeaderError::FieldOutOfRange("number_of_channels"))?;
+        let sample_size = u16::try_from(config.length)
+            .map_err(|_| FrameHeaderError::FieldOutOfRange("length"))?;
+        let encoding = if bits_per_sample == 32 {
+            EncodingFlag::PCMFloat
+        } else {
+            EncodingFlag::PCMSigned
+        };
+
+        Ok(FrameHeader::new_typed(
+            encoding,
+            sample_size,
+            SampleRate::try_from(config.sample_rate as u32)?,
+            ChannelCount::try_from(channels)?,
+            bits_per_sample,
+            endianness,
+            None,
+            None,
+        )?)
+    }
+}
+
+#[cfg(feature = "wasm")]
+#[wasm_bindgen(js_name = audioBufferConfigFromHeader)]
+pub fn audio_buffer_config_from_header(header_bytes: &[u8]) -> Result<AudioBufferConfig, JsError> {
+    let header = FrameHeader::decode(&mut &header_bytes[..])?;
+    Ok(header.to_audio_buffer_config()?)
+}
+
+#[cfg(feature = "wasm")]
+#[wasm_bindgen(js_name = headerFromAudioBufferConfig)]
+pub fn header_from_audio_buffer_config(
+    config: &AudioBufferConfig,
+    bits_per_sample: u8,
+    big_endian: bool,
+) -> Result<Vec<u8>, JsError> {
+    let endianness = if big_endian {
+        Endianness::BigEndian
+    } else {
+        Endianness::LittleEndian
+    };
+    let header = FrameHeader::from_audio_buffer_config(config, bits_per_sample, endianness)?;
+    let mut bytes = Vec::with_capacity(header.size());
+    header.encode(&mut bytes)?;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audio_buffer_config_roundtrip() {
+        let header = FrameHeader::new(
+            EncodingFlag::PCMFloat,
+            1024,
+            44100,
+            2,
+            32,
+            Endianness::LittleEndian,
+            None,
+            None,
+        )
+        .unwrap();
+
+        let config = header.to_audio_buffer_config().unwrap();
+        assert_eq!(
+            config,
+            AudioBufferConfig {
+                sample_rate: 44100.0,
+                number_of_channels: 2,
+                length: 1024,
+            }
+        );
+        assert!(config.is_valid_for_web_audio());
+        assert_eq!(
+            FrameHeader::from_audio_buffer_config(&config, 32, Endianness::LittleEndian).unwrap(),
+            header
+        );
+
+        let signed =
+            FrameHeader::from_audio_buffer_config(&config, 16, Endianness::BigEndian).unwrap();
+        assert_eq!(signed.encoding(), EncodingFlag::PCMSigned);
+    }
+
+    #[test]
+    fn test_audio_buffer_config_rejects_unrepresentable_values() {
+        let opus = FrameHeader::new(
+            EncodingFlag::Opus,
+            960,
+            48000,
+            2,
+            16,
+            Endianness::LittleEndian,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            opus.to_audio_buffer_config(),
+            Err(FrameHeaderError::InvalidEncoding(2))
+        );
+
+        let config = AudioBufferConfig {
+            sample_rate: 48000.0,
+            number_of_channels: 2,
+            length: 960,
+        };
+        let from = |config: AudioBufferConfig| {
+            FrameHeader::from_audio_buffer_config(&config, 16, Endianness::LittleEndian)
+        };
+        assert!(from(config).is_ok());
+        assert!(from(AudioBufferConfig {
+            sample_rate: 22050.0,
+            ..config
+        })
+        .is_err());
+        assert!(from(AudioBufferConfig {
+            sample_rate: 48000.5,
+            ..config
+        })
+        .is_err());
+        assert!(from(AudioBufferConfig {
+            number_of_channels: 300,
+            ..config
+        })
+        .is_err());
+        assert!(from(AudioBufferConfig {
+            length: 5000,
+            ..config
+        })
+        .is_err());
+        assert!(!AudioBufferConfig {
+            length: 0,
+            ..config
+        }
+        .is_valid_for_web_audio());
+    }
+}
+
+#[cfg(all(test, feature = "wasm", target_arch = "wasm32"))]
+mod wasm_tests {
+    use super::*;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[wasm_bindgen_test]
+    fn test_exported_config_is_valid_for_web_audio() {
+        let config = AudioBufferConfig {
+            sample_rate: 48000.0,
+            number_of_channels: 2,
+            length: 960,
+        };
+        let header_bytes = header_from_audio_buffer_config(&config, 32, false).unwrap();
+        let decoded = audio_buffer_config_from_header(&header_bytes).unwrap();
+        assert_eq!(decoded, config);
+        assert!(decoded.is_valid_for_web_audio());
+    }
+}
@@ -0,0 +1,273 @@
+use crate::{ChannelCount, EncodingFlag, Endianness, FrameHeader, FrameHeaderError, SampleRate};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+// Chrome's payload type and fmtp line for Opus in RTCRtpCapabilities.
+const CHROME_OPUS_PAYLOAD_TYPE: u8 = 111;
+const CHROME_OPUS_FMTP: &str = "minptime=10;useinbandfec=1";
+// Dynamic payload type for L16/L24, which Chrome does not negotiate itself.
+const PCM_PAYLOAD_TYPE: u8 = 96;
+
+/// One entry of `RTCRtpParameters.codecs`, with the same fields and units.
+#[cfg_attr(feature = "wasm", wasm_bindgen(getter_with_clone))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChromeRtpCodecParams {
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = payloadType))]
+    pub payload_type: u8,
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = mimeType))]
+    pub mime_type: String,
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = clockRate))]
+    pub clock_rate: u32,
+    pub channels: Option<u32>,
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = sdpFmtpLine))]
+    pub sdp_fmtp_line: Option<String>,
+}
+
+impl FrameHeader {
+    /// Opus maps to Chrome's own `audio/opus` entry, which always signals
+    /// two channels (RFC 7587). Signed big-endian 16- and 24-bit PCM maps to
+    /// `audio/L16` and `audio/L24`.
+    pub fn to_chrome_rtp_codec_params(&self) -> Result<ChromeRtpCodecParams, FrameHeaderError> {
+        match self.encoding {
+            EncodingFlag::Opus => Ok(ChromeRtpCodecParams {
+                payload_type: CHROME_OPUS_PAYLOAD_TYPE,
+                mime_type: "audio/opus".to_string(),
+                clock_rate: 48000,
+                channels: Some(2),
+                sdp_fmtp_line: Some(CHROME_OPUS_FMTP.to_string()),
+            }),
+            EncodingFlag::PCMSigned => {
+                // RTP PCM is always in network byte order.
+                if self.endianness != Endianness::BigEndian {
+                    return Err(FrameHeaderError::FieldOutOfRange("endianness"));
+                }
+                let mime_type = match self.bits_per_sample {
+                    16 => "audio/L16",
+                    24 => "audio/L24",
+                    _ => return Err(FrameHeaderError::FieldOutOfRange("bits_per_sample")),
+                };
+                Ok(ChromeRtpCodecParams {
+                    payload_type: PCM_PAYLOAD_TYPE,
+                    mime_type: mime_type.to_string(),
+                    clock_rate: self.sample_rate,
+                    channels: Some(self.channels as u32),
+                    sdp_fmtp_line: None,
+                })
+            }
+            encoding => Err(FrameHeaderError::InvalidEncoding(encoding.code() as u32)),
+        }
+    }
+
+    /// MIME types are matched case-insensitively, as browsers report them in
+    /// mixed case. Missing channel counts default to 2 for Opus and 1 for
+    /// PCM (RFC 3551).
+    pub fn from_chrome_rtp_codec_params(
+        params: &ChromeRtpCodecParams,
+        sample_size: u16,
+    ) -> Result<FrameHeader, FrameHeaderError> {
+        let (encoding, bits_per_sample, endianness, default_channels) =
+            match params.mime_type.to_ascii_lowercase().as_str() {
+                "audio/opus" => (EncodingFlag::Opus, 16, Endianness::LittleEndian, 2),
+                "audio/l16" => (EncodingFlag::PCMSigned, 16, Endianness::BigEndian, 1),
+                "audio/l24" => (EncodingFlag::PCMSigned, 24, Endianness::BigEndian, 1),
+                _ => {
+                    return Err(FrameHeaderError::Invalid(format!(
+                        "Unsupported RTP codec: {}",
+                        params.mime_type
+                    )))
+                }
+            };
+        if encoding == EncodingFlag::Opus && params.clock_rate != 48000 {
+            return Err(FrameHeaderError::FieldOutOfRange("clock_rate"));
+        }
+        let channels = u8::try_from(params.channels.unwrap_or(default_channels))
+            .map_err(|_| FrameHeaderError::FieldOutOfRange("channels"))?;
+
+        Ok(FrameHeader::new_typed(
+            encoding,
+            sample_size,
+            SampleRate::try_from(params.clock_rate)?,
+            ChannelCount::try_from(channels)?,
+            bits_per_sample,
+            endianness,
+            None,
+            None,
+        )?)
+    }
+}
+
+#[cfg(feature = "wasm")]
+#[wasm_bindgen(js_name = chromeRtpCodecParamsFromHeader)]
+pub fn chrome_rtp_codec_params_from_header(
+    header_bytes: &[u8],
+) -> Result<ChromeRtpCodecParams, JsError> {
+    let header = FrameHeader::decode(&mut &header_bytes[..])?;
+    Ok(header.to_chrome_rtp_codec_params()?)
+}
+
+#[cfg(feature = "wasm")]
+#[wasm_bindgen(js_name = headerFromChromeRtpCodecParams)]
+pub fn header_from_chrome_rtp_codec_params(
+    params: &ChromeRtpCodecParams,
+    sample_size: u16,
+) -> Result<Vec<u8>, JsError> {
+    let header = FrameHeader::from_chrome_rtp_codec_params(params, sample_size)?;
+    let mut bytes = Vec::with_capacity(header.size());
+    header.encode(&mut bytes)?;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(
+        encoding: EncodingFlag,
+        sample_rate: u32,
+        channels: u8,
+        bits: u8,
+        endianness: Endianness,
+    ) -> FrameHeader {
+        FrameHeader::new(
+            encoding,
+            960,
+            sample_rate,
+            channels,
+            bits,
+            endianness,
+            None,
+            None,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_opus_matches_chrome_capabilities() {
+        // RTCRtpSender.getCapabilities("audio").codecs[0] in Chrome:
+        // {clockRate: 48000, channels: 2, mimeType: "audio/opus",
+        //  sdpFmtpLine: "minptime=10;useinbandfec=1"}, payload type 111.
+        let chrome = ChromeRtpCodecParams {
+            payload_type: 111,
+            mime_type: "audio/opus".to_string(),
+            clock_rate: 48000,
+            channels: Some(2),
+            sdp_fmtp_line: Some("minptime=10;useinbandfec=1".to_string()),
+        };
+        for channels in [1, 2] {
+            let opus = header(
+                EncodingFlag::Opus,
+                48000,
+                channels,
+                16,
+                Endianness::LittleEndian,
+            );
+            assert_eq!(opus.to_chrome_rtp_codec_params(), Ok(chrome.clone()));
+        }
+        assert_eq!(
+            FrameHeader::from_chrome_rtp_codec_params(&chrome, 960),
+            Ok(header(
+                EncodingFlag::Opus,
+                48000,
+                2,
+                16,
+                Endianness::LittleEndian
+            ))
+        );
+    }
+
+    #[test]
+    fn test_pcm_roundtrip() {
+        for (bits, mime_type) in [(16, "audio/L16"), (24, "audio/L24")] {
+            let pcm = header(
+                EncodingFlag::PCMSigned,
+                44100,
+                2,
+                bits,
+                Endianness::BigEndian,
+            );
+            let params = pcm.to_chrome_rtp_codec_params().unwrap();
+            assert_eq!(params.mime_type, mime_type);
+            assert_eq!((params.clock_rate, params.channels), (44100, Some(2)));
+            assert_eq!(params.sdp_fmtp_line, None);
+            assert_eq!(
+                FrameHeader::from_chrome_rtp_codec_params(&params, 960),
+                Ok(pcm)
+            );
+        }
+
+        // Case-insensitive MIME type, channels defaulting to mono.
+        let params = ChromeRtpCodecParams {
+            payload_type: 97,
+            mime_type: "AUDIO/l16".to_string(),
+            clock_rate: 16000,
+            channels: None,
+            sdp_fmtp_line: None,
+        };
+        let mono = FrameHeader::from_chrome_rtp_codec_params(&params, 320).unwrap();
+        assert_eq!(mono.channels(), 1);
+        assert_eq!(mono.sample_rate(), 16000);
+    }
+
+    #[test]
+    fn test_chrome_params_errors() {
+        assert_eq!(
+            header(
+                EncodingFlag::PCMSigned,
+                48000,
+                2,
+                16,
+                Endianness::LittleEndian
+            )
+            .to_chrome_rtp_codec_params(),
+            Err(FrameHeaderError::FieldOutOfRange("endianness"))
+        );
+        assert_eq!(
+            header(EncodingFlag::PCMSigned, 48000, 2, 32, Endianness::BigEndian)
+                .to_chrome_rtp_codec_params(),
+            Err(FrameHeaderError::FieldOutOfRange("bits_per_sample"))
+        );
+        for encoding in [
+            EncodingFlag::PCMFloat,
+            EncodingFlag::FLAC,
+            EncodingFlag::AAC,
+        ] {
+            assert_eq!(
+                header(encoding, 48000, 2, 32, Endianness::BigEndian).to_chrome_rtp_codec_params(),
+                Err(FrameHeaderError::InvalidEncoding(encoding.code() as u32))
+            );
+        }
+
+        let mut params = ChromeRtpCodecParams {
+            payload_type: 0,
+            mime_type: "audio/PCMU".to_string(),
+            clock_rate: 8000,
+            channels: None,
+            sdp_fmtp_line: None,
+        };
+        assert!(matches!(
+            FrameHeader::from_chrome_rtp_codec_params(&params, 160),
+            Err(FrameHeaderError::Invalid(_))
+        ));
+        params.mime_type = "audio/opus".to_string();
+        assert_eq!(
+            FrameHeader::from_chrome_rtp_codec_params(&params, 160),
+            Err(FrameHeaderError::FieldOutOfRange("clock_rate"))
+        );
+        params.mime_type = "audio/L16".to_string();
+        assert_eq!(
+            FrameHeader::from_chrome_rtp_codec_params(&params, 160),
+            Err(FrameHeaderError::FieldOutOfRange("sample_rate"))
+        );
+        params.clock_rate = 48000;
+        params.channels = Some(300);
+        assert_eq!(
+            FrameHeader::from_chrome_rtp_codec_params(&params, 160),
+            Err(FrameHeaderError::FieldOutOfRange("channels"))
+        );
+        params.channels = Some(17);
+        assert_eq!(
+            FrameHeader::from_chrome_rtp_codec_params(&params, 160),
+            Err(FrameHeaderError::InvalidChannels(17))
+        );
+    }
+}
@@ -0,0 +1,269 @@
+use crate::{ChannelCount, EncodingFlag, Endianness, FrameHeader, FrameHeaderError, SampleRate};
+
+/// A caption cue timed on the same microsecond clock as header PTS.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimedTextCue {
+    pub start_pts_us: u64,
+    pub end_pts_us: u64,
+    pub text: String,
+    /// BCP 47 tag, or empty when unknown.
+    pub language: String,
+}
+
+fn format_timestamp(us: u64) -> String {
+    let ms = us / 1000;
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        ms % 1000
+    )
+}
+
+// `hh:mm:ss.ttt`, where the hours may be left out or run past two digits.
+fn parse_timestamp(timestamp: &str) -> Result<u64, FrameHeaderError> {
+    let invalid = || FrameHeaderError::Invalid(format!("Invalid WebVTT timestamp: {}", timestamp));
+    let (clock, millis) = timestamp.split_once('.').ok_or_else(invalid)?;
+    let parts: Vec<&str> = clock.split(':').collect();
+    let (hours, minutes, seconds) = match parts[..] {
+        [minutes, seconds] => ("0", minutes, seconds),
+        [hours, minutes, seconds] if hours.len() >= 2 => (hours, minutes, seconds),
+        _ => return Err(invalid()),
+    };
+    let number = |field: &str, digits: usize| {
+        if field.len() != digits || !field.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        field.parse::<u64>().map_err(|_| invalid())
+    };
+    let hours = hours.parse::<u64>().map_err(|_| invalid())?;
+    let (minutes, seconds, millis) = (number(minutes, 2)?, number(seconds, 2)?, number(millis, 3)?);
+    if minutes >= 60 || seconds >= 60 {
+        return Err(invalid());
+    }
+    hours
+        .checked_mul(3_600_000)
+        .and_then(|ms| ms.checked_add((minutes * 60 + seconds) * 1000 + millis))
+        .and_then(|ms| ms.checked_mul(1000))
+        .ok_or_else(invalid)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+impl TimedTextCue {
+    /// A cue block: the timing line, then the text with its language as a
+    /// `<lang>` span. Times are truncated to milliseconds, and blank lines,
+    /// which would end the cue, are dropped from the text.
+    pub fn encode_webvtt(&self) -> String {
+        let text = self
+            .text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(escape)
+            .collect::<Vec<_>>()
+            .join("\n");
+        let text = if self.language.is_empty() {
+            text
+        } else {
+            format!("<lang {}>{}</lang>", self.language, text)
+        };
+        format!(
+            "{} --> {}\n{}\n",
+            format_timestamp(self.start_pts_us),
+            format_timestamp(self.end_pts_us),
+            text
+        )
+    }
+
+    /// Parses one cue block, with or without an identifier line. Cue
+    /// settings after the timing line are ignored, and a `<lang>` span
+    /// around the whole text becomes the language.
+    pub fn decode_webvtt(block: &str) -> Result<TimedTextCue, FrameHeaderError> {
+        let mut lines = block.trim_matches('\n').lines();
+        let mut timing = lines.next().unwrap_or("");
+        if !timing.contains("-->") {
+            timing = lines.next().unwrap_or("");
+        }
+        let (start, rest) = timing.split_once("-->").ok_or_else(|| {
+            FrameHeaderError::Invalid(format!("Missing WebVTT timing line: {}", timing))
+        })?;
+        let end = rest.split_whitespace().next().unwrap_or("");
+        let start_pts_us = parse_timestamp(start.trim())?;
+        let end_pts_us = parse_timestamp(end)?;
+        if end_pts_us < start_pts_us {
+            return Err(FrameHeaderError::FieldOutOfRange("end_pts_us"));
+        }
+
+        let mut text = lines.collect::<Vec<_>>().join("\n");
+        let mut language = String::new();
+        if let Some(inner) = text
+            .strip_prefix("<lang ")
+            .and_then(|rest| rest.strip_suffix("</lang>"))
+        {
+            if let Some((tag, inner)) = inner.split_once('>') {
+                language = tag.trim().to_string();
+                text = inner.to_string();
+            }
+        }
+        Ok(TimedTextCue {
+            start_pts_us,
+            end_pts_us,
+            text: unescape(&text),
+            language,
+        })
+    }
+}
+
+impl FrameHeader {
+    /// A header for the audio a cue is shown over: PTS at the cue's start
+    /// and as many samples per channel as the cue lasts. Cues longer than
+    /// one frame can hold are rejected.
+    pub fn from_timed_text_cue(
+        cue: &TimedTextCue,
+        encoding: EncodingFlag,
+        sample_rate: u32,
+        channels: u8,
+        bits: u8,
+    ) -> Result<FrameHeader, FrameHeaderError> {
+        let duration_us = cue
+            .end_pts_us
+            .checked_sub(cue.start_pts_us)
+            .ok_or(FrameHeaderError::FieldOutOfRange("end_pts_us"))?;
+        let sample_rate = SampleRate::try_from(sample_rate)?;
+        let samples = duration_us as u128 * sample_rate.get() as u128 / 1_000_000;
+        let sample_size =
+            u16::try_from(samples).map_err(|_| FrameHeaderError::FieldOutOfRange("sample_size"))?;
+
+        Ok(FrameHeader::new_typed(
+            encoding,
+            sample_size,
+            sample_rate,
+            ChannelCount::try_from(channels)?,
+            bits,
+            Endianness::LittleEndian,
+            None,
+            Some(cue.start_pts_us),
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cue(text: &str, language: &str) -> TimedTextCue {
+        TimedTextCue {
+            start_pts_us: 3_723_456_000,
+            end_pts_us: 3_725_000_000,
+            text: text.to_string(),
+            language: language.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_webvtt_roundtrip() {
+        let cue = cue("Hello <world> & \"friends\"\nsecond line", "en-GB");
+        let block = cue.encode_webvtt();
+        assert_eq!(
+            block,
+            "01:02:03.456 --> 01:02:05.000\n\
+             <lang en-GB>Hello &lt;world&gt; &amp; \"friends\"\nsecond line</lang>\n"
+        );
+        assert_eq!(TimedTextCue::decode_webvtt(&block), Ok(cue));
+
+        let plain = self::cue("no language", "");
+        assert_eq!(
+            plain.encode_webvtt(),
+            "01:02:03.456 --> 01:02:05.000\nno language\n"
+        );
+        assert_eq!(
+            TimedTextCue::decode_webvtt(&plain.encode_webvtt()),
+            Ok(plain)
+        );
+    }
+
+    #[test]
+    fn test_decode_webvtt_from_files() {
+        // An identifier line, short timestamps and cue settings.
+        let cue = TimedTextCue::decode_webvtt("intro\n00:01.500 --> 00:04.250 align:start\nHi\n")
+            .unwrap();
+        assert_eq!(
+            cue,
+            TimedTextCue {
+                start_pts_us: 1_500_000,
+                end_pts_us: 4_250_000,
+                text: "Hi".to_string(),
+                language: String::new(),
+            }
+        );
+
+        for block in [
+            "",
+            "just text",
+            "00:01.500 -> 00:04.250\nHi",
+            "00:01.5 --> 00:04.250\nHi",
+            "00:61.500 --> 00:04.250\nHi",
+            "1:00:01.500 --> 1:00:04.250\nHi",
+        ] {
+            assert!(
+                matches!(
+                    TimedTextCue::decode_webvtt(block),
+                    Err(FrameHeaderError::Invalid(_))
+                ),
+                "{:?}",
+                block
+            );
+        }
+        assert_eq!(
+            TimedTextCue::decode_webvtt("00:04.000 --> 00:01.000\nHi"),
+            Err(FrameHeaderError::FieldOutOfRange("end_pts_us"))
+        );
+    }
+
+    #[test]
+    fn test_header_from_cue() {
+        let cue = TimedTextCue {
+            start_pts_us: 20_000,
+            end_pts_us: 40_000,
+            text: "Hi".to_string(),
+            language: "en".to_string(),
+        };
+        let header =
+            FrameHeader::from_timed_text_cue(&cue, EncodingFlag::PCMSigned, 48000, 2, 16).unwrap();
+        assert_eq!(header.samples_per_channel(), 960);
+        assert_eq!(header.pts(), Some(20_000));
+        assert_eq!(header.sample_rate(), 48000);
+
+        // Two seconds do not fit one frame.
+        let long = TimedTextCue {
+            end_pts_us: 2_020_000,
+            ..cue.clone()
+        };
+        assert!(
+            FrameHeader::from_timed_text_cue(&long, EncodingFlag::PCMSigned, 48000, 2, 16).is_err()
+        );
+        let backwards = TimedTextCue {
+            end_pts_us: 0,
+            ..cue.clone()
+        };
+        assert_eq!(
+            FrameHeader::from_timed_text_cue(&backwards, EncodingFlag::PCMSigned, 48000, 2, 16),
+            Err(FrameHeaderError::FieldOutOfRange("end_pts_us"))
+        );
+        assert_eq!(
+            FrameHeader::from_timed_text_cue(&cue, EncodingFlag::PCMSigned, 22050, 2, 16),
+            Err(FrameHeaderError::FieldOutOfRange("sample_rate"))
+        );
+    }
+}
@@ -0,0 +1,124 @@
+use crate::FrameHeader;
+use xxhash_rust::xxh3::xxh3_64;
+
+impl FrameHeader {
+    /// XXH3-64 over the same fields as `config_fingerprint`, so headers of
+    /// one stream share it. Stable across processes and releases.
+    pub fn stream_fingerprint_xxh3(&self) -> u64 {
+        xxh3_64(&self.config_key().to_bytes())
+    }
+
+    /// XXH3-64 over every field, so it tells frames of one stream apart. A
+    /// missing ID or PTS hashes differently from one that is zero.
+    pub fn frame_fingerprint_xxh3(&self) -> u64 {
+        let mut bytes = [0u8; 28];
+        bytes[..8].copy_from_slice(&self.config_key().to_bytes());
+        bytes[8..10].copy_from_slice(&self.sample_size.to_be_bytes());
+        for (slot, value) in bytes[10..].chunks_exact_mut(9).zip([self.id, self.pts]) {
+            if let Some(value) = value {
+                slot[0] = 1;
+                slot[1..].copy_from_slice(&value.to_be_bytes());
+            }
+        }
+        xxh3_64(&bytes)
+    }
+
+    /// XOR of every header's `stream_fingerprint_xxh3`, independent of
+    /// order. Configurations that occur an even number of times cancel out,
+    /// so this identifies the set of configurations with odd counts, not the
+    /// headers themselves.
+    pub fn bulk_fingerprint(headers: &[FrameHeader]) -> u64 {
+        headers
+            .iter()
+            .fold(0, |hash, header| hash ^ header.stream_fingerprint_xxh3())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EncodingFlag, Endianness};
+
+    fn header(encoding: EncodingFlag, id: Option<u64>, pts: Option<u64>) -> FrameHeader {
+        FrameHeader::new(
+            encoding,
+            960,
+            48000,
+            2,
+            16,
+            Endianness::LittleEndian,
+            id,
+            pts,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_stream_fingerprint_ignores_frame_fields() {
+        let a = header(EncodingFlag::Opus, Some(1), Some(20_000));
+        let b = header(EncodingFlag::Opus, None, Some(40_000));
+        assert_eq!(a.stream_fingerprint_xxh3(), b.stream_fingerprint_xxh3());
+        assert_ne!(
+            a.stream_fingerprint_xxh3(),
+            header(EncodingFlag::FLAC, Some(1), Some(20_000)).stream_fingerprint_xxh3()
+        );
+        // Pinned so a change to the hashed bytes is caught.
+        assert_eq!(
+            a.stream_fingerprint_xxh3(),
+            xxh3_64(&[2, 0, 0, 0xBB, 0x80, 2, 16, 1])
+        );
+    }
+
+    #[test]
+    fn test_frame_fingerprint_covers_every_field() {
+        let base = header(EncodingFlag::PCMSigned, Some(1), Some(2));
+        let variants = [
+            header(EncodingFlag::PCMSigned, Some(1), Some(3)),
+            header(EncodingFlag::PCMSigned, Some(2), Some(2)),
+            header(EncodingFlag::PCMSigned, None, Some(2)),
+            header(EncodingFlag::PCMSigned, Some(1), None),
+            header(EncodingFlag::PCMSigned, Some(0), Some(2)),
+            header(EncodingFlag::PCMFloat, Some(1), Some(2)),
+            FrameHeader {
+                sample_size: 480,
+                ..base.clone()
+            },
+            FrameHeader {
+                endianness: Endianness::BigEndian,
+                ..base.clone()
+            },
+        ];
+        for variant in &variants {
+            assert_ne!(
+                variant.frame_fingerprint_xxh3(),
+                base.frame_fingerprint_xxh3(),
+                "{:?}",
+                variant
+            );
+        }
+        assert_eq!(
+            base.frame_fingerprint_xxh3(),
+            base.clone().frame_fingerprint_xxh3()
+        );
+        // Swapping ID and PTS does not collide.
+        assert_ne!(
+            header(EncodingFlag::Opus, Some(5), None).frame_fingerprint_xxh3(),
+            header(EncodingFlag::Opus, None, Some(5)).frame_fingerprint_xxh3()
+        );
+    }
+
+    #[test]
+    fn test_bulk_fingerprint() {
+        let opus = header(EncodingFlag::Opus, None, None);
+        let flac = header(EncodingFlag::FLAC, None, None);
+        assert_eq!(FrameHeader::bulk_fingerprint(&[]), 0);
+        assert_eq!(
+            FrameHeader::bulk_fingerprint(&[opus.clone(), flac.clone()]),
+            FrameHeader::bulk_fingerprint(&[flac.clone(), opus.clone()])
+        );
+        assert_eq!(
+            FrameHeader::bulk_fingerprint(&[opus.clone(), flac.clone(), opus.clone()]),
+            flac.stream_fingerprint_xxh3()
+        );
+    }
+}